@@ -1,4 +1,63 @@
-const COMMANDS: &[&str] = &["check", "download", "execute", "remove"];
+const COMMANDS: &[&str] = &[
+    "check",
+    "download",
+    "cancel_download",
+    "execute",
+    "enqueue",
+    "restore_queue",
+    "cancel",
+    "send_command",
+    "list_jobs",
+    "remove",
+    "draw_text",
+    "render_waveform_video",
+    "spectrogram",
+    "channel_ops",
+    "resample",
+    "denoise_audio",
+    "transcode",
+    "detect_crop",
+    "detect_interlacing",
+    "detect_black",
+    "detect_freeze",
+    "measure_sync_offset",
+    "replace_audio",
+    "analyze_complexity",
+    "estimate_output_size",
+    "list_builtin_profiles",
+    "probe",
+    "validate_for",
+    "list_platform_presets",
+    "transcode_multi",
+    "generate_proxies",
+    "trim_silence",
+    "prepare_for_transcription",
+    "media_hash",
+    "verify_file",
+    "faststart",
+    "smart_thumbnail",
+    "record_rtsp",
+    "start_level_meter",
+    "record_audio",
+    "encode_from_bytes",
+    "decode_frames",
+    "run_pipeline",
+    "purge_temp",
+    "get_quota_status",
+    "run_template",
+    "get_audit_log",
+    "reinstall",
+    "execute_str",
+    "recheck",
+    "is_at_least",
+    "list_formats",
+    "list_protocols",
+    "list_pix_fmts",
+    "list_sample_fmts",
+    "filter_help",
+    "benchmark",
+    "ready_report",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
@@ -0,0 +1,435 @@
+/**
+ * 面向对象的 API：`FfmpegManager`、`Job`、`Download`
+ *
+ * 在函数式 API 之上封装任务和下载句柄，句柄可以直接 `await`，并提供按任务或下载过滤的类型化事件订阅，
+ * 不需要自行调用 `invoke` 或按 ID 过滤 `listen` 的事件。
+ *
+ * @module tauri-plugin-use-ffmpeg-api/client
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+import {
+  cancel,
+  cancelDownload,
+  check,
+  download,
+  enqueue,
+  execute,
+  executeStr,
+  getAuditLog,
+  getQuotaStatus,
+  isAtLeast,
+  listJobs,
+  purgeTemp,
+  readyReport,
+  recheck,
+  reinstall,
+  remove,
+  restoreQueue,
+  sendCommand,
+  type AuditEntry,
+  type AuditLogOptions,
+  type BatchFinished,
+  type BatchProgress,
+  type CheckOptions,
+  type CheckResponse,
+  type CheckSlow,
+  type DeleteResponse,
+  type DownloadConfig,
+  type DownloadFinished,
+  type DownloadOptions,
+  type DownloadProgress,
+  type DownloadResponse,
+  type DownloadResumed,
+  type DownloadStarted,
+  type DownloadStarting,
+  type EncodeProgress,
+  type EnqueueOptions,
+  type EventPayload,
+  type ExecuteOptions,
+  type ExecuteResponse,
+  type JobFinished,
+  type JobInfo,
+  type JobStatsSample,
+  type PurgeTempResponse,
+  type QueueDrained,
+  type QueueJobRejected,
+  type QuotaStatus,
+  type ReadyReport,
+  type RemoveOptions,
+  type ThrottleState
+} from './index'
+import {
+  runTemplate,
+  type AudioLevel,
+  type HelperOptions,
+  type LevelMeterStopped,
+  type RecordingReconnecting,
+  type RecordingSegment
+} from './helpers'
+
+/**
+ * 面向对象 API 的版本
+ *
+ * 类的方法和属性只增不减，删除或改变已有成员的含义时才会提升版本号。
+ */
+export const CLIENT_API_VERSION = 1
+
+/**
+ * 插件事件名称（不含 `use-ffmpeg://` 前缀）与事件内容的对应关系
+ */
+export interface FfmpegEventMap {
+  ready: ReadyReport
+  'check-slow': CheckSlow
+  'download-started': DownloadStarted
+  'download-starting': DownloadStarting
+  'download-progress': DownloadProgress
+  'download-resumed': DownloadResumed
+  'download-finished': DownloadFinished
+  'job-started': JobInfo
+  'job-progress': EncodeProgress
+  'job-stats': JobStatsSample
+  'job-finished': JobFinished
+  'recording-segment': RecordingSegment
+  'recording-reconnecting': RecordingReconnecting
+  'audio-level': AudioLevel
+  'level-meter-stopped': LevelMeterStopped
+  'batch-progress': BatchProgress
+  'batch-finished': BatchFinished
+  'queue-drained': QueueDrained
+  'queue-job-rejected': QueueJobRejected
+  'throttle-changed': ThrottleState
+}
+
+/**
+ * 插件事件名称
+ */
+export type FfmpegEvent = keyof FfmpegEventMap
+
+/**
+ * 订阅插件事件
+ *
+ * @param event 事件名称，如 `job-progress`
+ * @param handler 事件回调
+ * @param filter 可选的过滤条件，返回 `false` 的事件被忽略
+ * @returns 取消订阅的函数
+ */
+export async function on<E extends FfmpegEvent>(
+  event: E,
+  handler: (payload: EventPayload<FfmpegEventMap[E]>) => void,
+  filter?: (payload: EventPayload<FfmpegEventMap[E]>) => boolean
+): Promise<UnlistenFn> {
+  return await listen<EventPayload<FfmpegEventMap[E]>>(`use-ffmpeg://${event}`, (e) => {
+    if (!filter || filter(e.payload)) {
+      handler(e.payload)
+    }
+  })
+}
+
+/**
+ * 事件回调的集合，第一次添加回调时开始监听，句柄结束时停止
+ */
+class Subscriptions<E extends FfmpegEvent> {
+  private handlers = new Set<(payload: EventPayload<FfmpegEventMap[E]>) => void>()
+  private unlisten?: Promise<UnlistenFn>
+  private stopped = false
+
+  constructor(
+    private readonly event: E,
+    private readonly filter: (payload: EventPayload<FfmpegEventMap[E]>) => boolean
+  ) {}
+
+  /** 开始监听，返回的 Promise 在监听生效后 resolve */
+  start(): Promise<UnlistenFn> {
+    this.unlisten ??= on(this.event, (payload) => this.handlers.forEach((handler) => handler(payload)), this.filter)
+    return this.unlisten
+  }
+
+  add(handler: (payload: EventPayload<FfmpegEventMap[E]>) => void): () => void {
+    this.handlers.add(handler)
+    if (!this.stopped) {
+      void this.start()
+    }
+    return () => {
+      this.handlers.delete(handler)
+    }
+  }
+
+  async stop(): Promise<void> {
+    this.stopped = true
+    if (this.unlisten) {
+      ;(await this.unlisten)()
+    }
+  }
+}
+
+/**
+ * FFmpeg 任务句柄
+ *
+ * 可以直接 `await`：{@link FfmpegManager.execute} 启动的任务得到 {@link ExecuteResponse}，失败或被终止时抛出异常；
+ * {@link FfmpegManager.enqueue} 加入的后台任务和 {@link FfmpegManager.jobs} 返回的已有任务得到
+ * {@link JobFinished}，无论成功与否都会 resolve，需要检查 `success` 和 `cancelled`。
+ *
+ * 进度和资源占用回调在任务结束后自动停止。
+ */
+export class Job<T = JobFinished> implements PromiseLike<T> {
+  /** 任务 ID */
+  readonly id: string
+  /** 任务结果 */
+  readonly result: Promise<T>
+
+  private readonly progress: Subscriptions<'job-progress'>
+  private readonly stats: Subscriptions<'job-stats'>
+
+  /**
+   * @param id 任务 ID
+   * @param run 启动任务的函数，在进度监听生效后调用；未提供时等待该任务的 `job-finished` 事件
+   */
+  constructor(id: string, run?: (jobId: string) => Promise<T>) {
+    this.id = id
+    this.progress = new Subscriptions('job-progress', (payload) => payload.jobId === id)
+    this.stats = new Subscriptions('job-stats', (payload) => payload.jobId === id)
+
+    const started = this.progress.start()
+    this.result = run
+      ? started.then(() => run(id))
+      : new Promise<T>((resolve) => {
+          const unlisten = on('job-finished', (payload) => {
+            void unlisten.then((fn) => fn())
+            resolve(payload as T)
+          }, (payload) => payload.jobId === id)
+        })
+
+    const stop = () => {
+      void this.progress.stop()
+      void this.stats.stop()
+    }
+    this.result.then(stop, stop)
+  }
+
+  /**
+   * 订阅任务进度
+   *
+   * @returns 取消订阅的函数
+   */
+  onProgress(handler: (progress: EncodeProgress) => void): () => void {
+    return this.progress.add(handler)
+  }
+
+  /**
+   * 订阅任务的资源占用采样（每秒一次）
+   *
+   * @returns 取消订阅的函数
+   */
+  onStats(handler: (sample: JobStatsSample) => void): () => void {
+    return this.stats.add(handler)
+  }
+
+  /** 终止任务 */
+  async cancel(): Promise<void> {
+    await cancel(this.id)
+  }
+
+  /** 向交互式任务（`interactive: true`）的标准输入发送命令 */
+  async sendCommand(text: string): Promise<void> {
+    await sendCommand(this.id, text)
+  }
+
+  then<R1 = T, R2 = never>(
+    onfulfilled?: ((value: T) => R1 | PromiseLike<R1>) | null,
+    onrejected?: ((reason: unknown) => R2 | PromiseLike<R2>) | null
+  ): Promise<R1 | R2> {
+    return this.result.then(onfulfilled, onrejected)
+  }
+}
+
+/**
+ * FFmpeg 下载句柄，可以直接 `await` 得到 {@link DownloadResponse}，失败或被取消时抛出异常
+ */
+export class Download implements PromiseLike<DownloadResponse> {
+  /** 下载 ID */
+  readonly id: string
+  /** 下载结果 */
+  readonly result: Promise<DownloadResponse>
+
+  private readonly progress: Subscriptions<'download-progress'>
+
+  /**
+   * @param id 下载 ID
+   * @param run 开始下载的函数，在进度监听生效后调用
+   */
+  constructor(id: string, run: (downloadId: string) => Promise<DownloadResponse>) {
+    this.id = id
+    this.progress = new Subscriptions('download-progress', (payload) => payload.downloadId === id)
+
+    this.result = this.progress.start().then(() => run(id))
+
+    const stop = () => void this.progress.stop()
+    this.result.then(stop, stop)
+  }
+
+  /**
+   * 订阅下载进度
+   *
+   * @returns 取消订阅的函数
+   */
+  onProgress(handler: (progress: DownloadProgress) => void): () => void {
+    return this.progress.add(handler)
+  }
+
+  /** 取消下载 */
+  async cancel(): Promise<void> {
+    await cancelDownload(this.id)
+  }
+
+  then<R1 = DownloadResponse, R2 = never>(
+    onfulfilled?: ((value: DownloadResponse) => R1 | PromiseLike<R1>) | null,
+    onrejected?: ((reason: unknown) => R2 | PromiseLike<R2>) | null
+  ): Promise<R1 | R2> {
+    return this.result.then(onfulfilled, onrejected)
+  }
+}
+
+/**
+ * 插件的面向对象入口
+ *
+ * @example
+ * ```typescript
+ * import { FfmpegManager, transcode } from 'tauri-plugin-use-ffmpeg-api'
+ *
+ * const ffmpeg = new FfmpegManager()
+ *
+ * if (!(await ffmpeg.check()).available) {
+ *   const install = ffmpeg.download()
+ *   install.onProgress((progress) => console.log(`下载进度: ${progress.percentage}%`))
+ *   await install
+ * }
+ *
+ * const job = ffmpeg.execute(['-i', 'input.mp4', 'output.webm'])
+ * job.onProgress((progress) => console.log(`${job.id}: ${progress.percentage}%`))
+ * const result = await job
+ *
+ * // 辅助函数同样可以得到任务句柄
+ * const converted = ffmpeg.track((jobId) => transcode('input.mp4', 'output.mp4', { jobId }))
+ * await converted
+ *
+ * const unlisten = await ffmpeg.on('queue-drained', ({ queued }) => console.log('队列剩余', queued))
+ * ```
+ */
+export class FfmpegManager {
+  /** 订阅插件事件，见 {@link FfmpegEventMap} */
+  on<E extends FfmpegEvent>(
+    event: E,
+    handler: (payload: EventPayload<FfmpegEventMap[E]>) => void
+  ): Promise<UnlistenFn> {
+    return on(event, handler)
+  }
+
+  /** 插件的就绪报告，内容与 `ready` 事件相同 */
+  readyReport(): Promise<ReadyReport> {
+    return readyReport()
+  }
+
+  /** 检查 FFmpeg 是否可用 */
+  check(options?: CheckOptions): Promise<CheckResponse> {
+    return check(options)
+  }
+
+  /** 清除缓存的检查结果后重新检查 */
+  recheck(options?: CheckOptions): Promise<CheckResponse> {
+    return recheck(options)
+  }
+
+  /** 已安装的 FFmpeg 是否不低于指定版本 */
+  isAtLeast(version: string): Promise<boolean> {
+    return isAtLeast(version)
+  }
+
+  /** 下载 FFmpeg */
+  download(config?: DownloadConfig, options?: DownloadOptions): Download {
+    return new Download(options?.downloadId ?? crypto.randomUUID(), (downloadId) =>
+      download(config, undefined, { ...options, downloadId })
+    )
+  }
+
+  /** 删除已安装的可执行文件后重新下载 */
+  reinstall(config?: DownloadConfig, options?: DownloadOptions): Download {
+    return new Download(options?.downloadId ?? crypto.randomUUID(), (downloadId) =>
+      reinstall(config, undefined, { ...options, downloadId })
+    )
+  }
+
+  /** 删除已下载的 FFmpeg */
+  remove(options?: RemoveOptions): Promise<DeleteResponse> {
+    return remove(options)
+  }
+
+  /** 执行 FFmpeg 命令 */
+  execute(args: string[], options?: Omit<ExecuteOptions, 'onProgress'>): Job<ExecuteResponse> {
+    return new Job(options?.jobId ?? crypto.randomUUID(), (jobId) => execute(args, { ...options, jobId }))
+  }
+
+  /** 以 shell 风格的命令行执行 FFmpeg 命令 */
+  executeStr(command: string, options?: Omit<ExecuteOptions, 'onProgress'>): Job<ExecuteResponse> {
+    return new Job(options?.jobId ?? crypto.randomUUID(), (jobId) => executeStr(command, { ...options, jobId }))
+  }
+
+  /** 运行宿主应用注册的命令模板 */
+  runTemplate(
+    name: string,
+    params?: Record<string, string | number>,
+    options?: Omit<HelperOptions, 'onProgress'>
+  ): Job<ExecuteResponse> {
+    return new Job(options?.jobId ?? crypto.randomUUID(), (jobId) => runTemplate(name, params, { ...options, jobId }))
+  }
+
+  /**
+   * 为辅助函数等接受 `jobId` 的调用创建任务句柄
+   *
+   * @param run 以句柄的任务 ID 启动任务的函数
+   * @param jobId 可选的任务 ID，未提供时自动生成
+   */
+  track<T>(run: (jobId: string) => Promise<T>, jobId?: string): Job<T> {
+    return new Job(jobId ?? crypto.randomUUID(), run)
+  }
+
+  /** 将任务加入后台队列，句柄在任务结束时 resolve */
+  async enqueue(args: string[], options?: EnqueueOptions): Promise<Job> {
+    const jobId = options?.jobId ?? crypto.randomUUID()
+    // 先开始监听，避免错过很快结束的任务
+    const job = new Job(jobId)
+    await enqueue(args, { ...options, jobId })
+    return job
+  }
+
+  /** 恢复上次保存的、尚未开始的后台任务 */
+  async restoreQueue(): Promise<Job[]> {
+    return (await restoreQueue()).map((info) => new Job(info.id))
+  }
+
+  /** 计划中、排队中和正在运行的任务的句柄 */
+  async jobs(): Promise<Job[]> {
+    return (await listJobs()).map((info) => new Job(info.id))
+  }
+
+  /** 计划中、排队中和正在运行的任务的信息 */
+  listJobs(): Promise<JobInfo[]> {
+    return listJobs()
+  }
+
+  /** 删除插件临时目录中所有未在使用的中间文件 */
+  purgeTemp(): Promise<PurgeTempResponse> {
+    return purgeTemp()
+  }
+
+  /** 获取存储配额状态 */
+  getQuotaStatus(): Promise<QuotaStatus> {
+    return getQuotaStatus()
+  }
+
+  /** 读取审计日志 */
+  getAuditLog(options?: AuditLogOptions): Promise<AuditEntry[]> {
+    return getAuditLog(options)
+  }
+}
@@ -0,0 +1,2058 @@
+/**
+ * 常用 FFmpeg 操作的封装
+ * 
+ * 参数由插件生成，任务仍通过 `execute` 运行，同样支持排队、进度事件和取消。
+ * FFmpeg 以非零状态退出时会抛出包含错误原因的异常。
+ * 
+ * @module tauri-plugin-use-ffmpeg-api/helpers
+ */
+
+import { Channel, invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+import type { EncodeProgress, EventPayload, ExecuteResponse, JobTags, OverwritePolicy } from './index'
+
+/**
+ * 辅助函数共用的任务选项
+ */
+export interface HelperOptions extends JobTags {
+  /** 可选的任务 ID，未提供时自动生成 */
+  jobId?: string
+  /** 输出文件已存在时的处理策略 */
+  overwrite?: OverwritePolicy
+  /** 下载正在进行时是否等待其完成 */
+  waitForInstall?: boolean
+  /** 复制第一个输入的容器元数据（包括 `creation_time`），并将输出文件的修改时间设为与输入相同 */
+  preserveTimestamps?: boolean
+  /** 所属批次 */
+  batchId?: string
+  /** 可选的任务进度回调 */
+  onProgress?: (progress: EncodeProgress) => void
+}
+
+/**
+ * 调用辅助命令，设置了 `onProgress` 时监听对应任务的进度
+ */
+async function runHelper<T>(
+  command: string,
+  payload: Record<string, unknown>,
+  options?: HelperOptions
+): Promise<T> {
+  const { onProgress, ...rest } = options ?? {}
+  const jobId = rest.jobId ?? (onProgress ? crypto.randomUUID() : undefined)
+  let unlisten: UnlistenFn | undefined
+
+  if (onProgress) {
+    unlisten = await listen<EventPayload<EncodeProgress>>('use-ffmpeg://job-progress', (event) => {
+      if (event.payload.jobId === jobId) {
+        onProgress(event.payload)
+      }
+    })
+  }
+
+  try {
+    return await invoke<T>(`plugin:use-ffmpeg|${command}`, {
+      payload: {
+        ...payload,
+        ...rest,
+        jobId
+      }
+    })
+  } finally {
+    if (unlisten) {
+      unlisten()
+    }
+  }
+}
+
+/**
+ * 生成单个文件的辅助函数的结果
+ */
+export interface HelperOutput {
+  jobId: string
+  /** 输出文件路径 */
+  path: string
+}
+
+/**
+ * 文字位置
+ */
+export type TextPosition =
+  | 'top-left'
+  | 'top'
+  | 'top-right'
+  | 'center'
+  | 'bottom-left'
+  | 'bottom'
+  | 'bottom-right'
+
+/**
+ * 文字叠加选项
+ */
+export interface DrawTextOptions extends HelperOptions {
+  /** 要绘制的文字，不会展开 `%{...}` 等占位符 */
+  text: string
+  /** 字体文件路径或字体名称，未设置时使用插件配置的 `defaultFont` 或系统默认字体 */
+  font?: string
+  /** 字号，默认 48 */
+  size?: number
+  /** 文字颜色，默认 `white` */
+  color?: string
+  /** 预设位置，默认 `bottom` */
+  position?: TextPosition
+  /** 与画面边缘的距离（像素），默认 20 */
+  margin?: number
+  /** 自定义横坐标表达式（如 `w-text_w-10`），覆盖 `position` */
+  x?: string
+  /** 自定义纵坐标表达式，覆盖 `position` */
+  y?: string
+  /** 背景框 */
+  box?: {
+    /** 背景颜色，默认 `black@0.5` */
+    color?: string
+    /** 文字与边框的间距（像素），默认 10 */
+    padding?: number
+  }
+}
+
+/**
+ * 在视频上叠加文字
+ * 
+ * 插件会自动查找各平台的默认字体并正确转义字体路径和文字，音频直接复制。
+ * 
+ * @param input 输入文件
+ * @param output 输出文件
+ * @param options 文字选项
+ * 
+ * @example
+ * ```typescript
+ * import { drawText } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await drawText('input.mp4', 'output.mp4', {
+ *   text: "It's 10:30",
+ *   position: 'top-right',
+ *   box: {}
+ * })
+ * ```
+ */
+export async function drawText(
+  input: string,
+  output: string,
+  options: DrawTextOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('draw_text', { input, output }, options)
+}
+
+/**
+ * 波形视频样式
+ * 
+ * `line` / `point` / `p2p` / `centered` 使用 showwaves 滤镜，`spectrum` 使用 showspectrum 滤镜
+ */
+export type WaveformStyle = 'line' | 'point' | 'p2p' | 'centered' | 'spectrum'
+
+/**
+ * 波形视频选项
+ */
+export interface WaveformVideoOptions extends HelperOptions {
+  /** 样式，默认 `centered` */
+  style?: WaveformStyle
+  /** 分辨率，默认 `1280x720` */
+  resolution?: string
+  /** 波形颜色，默认 `white`（`spectrum` 样式不使用） */
+  color?: string
+  /** 帧率，默认 30 */
+  frameRate?: number
+}
+
+/**
+ * 将音频渲染为带波形或频谱动画的视频（音频图）
+ * 
+ * 输出视频为 H.264、音频为 AAC，适合分享播客片段。
+ * 
+ * @param audio 输入音频
+ * @param output 输出视频
+ * @param options 可选的样式选项
+ * 
+ * @example
+ * ```typescript
+ * import { renderWaveformVideo } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await renderWaveformVideo('episode.mp3', 'audiogram.mp4', {
+ *   style: 'centered',
+ *   resolution: '1080x1080',
+ *   color: '#ff6600'
+ * })
+ * ```
+ */
+export async function renderWaveformVideo(
+  audio: string,
+  output: string,
+  options?: WaveformVideoOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('render_waveform_video', { audio, output }, options)
+}
+
+/**
+ * 频谱图幅度刻度
+ */
+export type SpectrogramScale = 'lin' | 'sqrt' | 'cbrt' | 'log' | '4thrt' | '5thrt'
+
+/**
+ * 频谱图选项
+ */
+export interface SpectrogramOptions extends HelperOptions {
+  /** 图片尺寸（不含坐标轴），默认 `1024x512` */
+  size?: string
+  /** 幅度刻度，默认 `log` */
+  scale?: SpectrogramScale
+  /** 配色方案（如 `intensity`、`viridis`、`magma`），默认 `intensity` */
+  colorScheme?: string
+  /** 是否绘制坐标轴和图例，默认 `true` */
+  legend?: boolean
+}
+
+/**
+ * 生成整段音频的频谱图
+ * 
+ * @param input 输入文件
+ * @param output 输出图片（如 `.png`）
+ * @param options 可选的频谱图选项
+ * @returns 图片路径
+ * 
+ * @example
+ * ```typescript
+ * import { spectrogram } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { path } = await spectrogram('track.flac', 'spectrogram.png', { colorScheme: 'magma' })
+ * ```
+ */
+export async function spectrogram(
+  input: string,
+  output: string,
+  options?: SpectrogramOptions
+): Promise<HelperOutput> {
+  return await runHelper<HelperOutput>('spectrogram', { input, output }, options)
+}
+
+/**
+ * 声道操作
+ * 
+ * - `split-mono`：每个声道输出为单独的单声道文件（`<输出>_FL.wav` 等）
+ * - `downmix-stereo`：缩混为立体声，中置和环绕声道按 -3 dB 混入，LFE 丢弃
+ * - `downmix-mono`：缩混为单声道
+ * - `swap`：交换左右声道
+ * - `extract`：提取单个声道（如 `FL`、`FC`）为单声道
+ */
+export type ChannelOp =
+  | { type: 'split-mono' }
+  | { type: 'downmix-stereo' }
+  | { type: 'downmix-mono' }
+  | { type: 'swap' }
+  | { type: 'extract'; channel: string }
+
+/**
+ * 声道操作选项
+ */
+export interface ChannelOpsOptions extends HelperOptions {
+  /** 输入的声道布局（如 `stereo`、`5.1`、`7.1`），默认 `stereo`，`downmix-stereo` 默认 `5.1` */
+  sourceLayout?: string
+}
+
+/**
+ * 声道操作结果
+ */
+export interface ChannelOpsResponse {
+  jobId: string
+  /** 生成的文件，`split-mono` 时每个声道一个 */
+  outputs: string[]
+}
+
+/**
+ * 对音频声道进行拆分、缩混、交换或提取
+ * 
+ * 插件根据声道布局生成 `pan` / `channelsplit` 滤镜，视频流（如果有）直接复制。
+ * 
+ * @param input 输入文件
+ * @param output 输出文件，`split-mono` 时作为文件名模板
+ * @param op 声道操作
+ * @param options 可选的声道布局和任务选项
+ * 
+ * @example
+ * ```typescript
+ * import { channelOps } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * // 5.1 缩混为立体声
+ * await channelOps('movie.mkv', 'stereo.mkv', { type: 'downmix-stereo' })
+ * 
+ * // 拆分为 voice_FL.wav / voice_FR.wav
+ * const { outputs } = await channelOps('voice.wav', 'voice.wav', { type: 'split-mono' })
+ * ```
+ */
+export async function channelOps(
+  input: string,
+  output: string,
+  op: ChannelOp,
+  options?: ChannelOpsOptions
+): Promise<ChannelOpsResponse> {
+  return await runHelper<ChannelOpsResponse>('channel_ops', { input, output, op }, options)
+}
+
+/**
+ * 抖动算法
+ */
+export type DitherMethod =
+  | 'none'
+  | 'rectangular'
+  | 'triangular'
+  | 'triangular_hp'
+  | 'lipshitz'
+  | 'shibata'
+  | 'low_shibata'
+  | 'high_shibata'
+  | 'f_weighted'
+  | 'e_weighted'
+  | 'modified_e_weighted'
+  | 'improved_e_weighted'
+
+/**
+ * 重采样选项
+ */
+export interface ResampleOptions extends HelperOptions {
+  /** 目标采样率，未设置时保持不变 */
+  sampleRate?: number
+  /** 目标位深（16、24 或 32），未设置时由编码器决定 */
+  bitDepth?: 16 | 24 | 32
+  /** 降低位深时的抖动算法，默认 `triangular` */
+  dither?: DitherMethod
+}
+
+/**
+ * 重采样结果
+ */
+export interface ResampleResponse {
+  jobId: string
+  /** 实际使用的重采样器 */
+  resampler: 'soxr' | 'swr'
+}
+
+/**
+ * 转换采样率和位深
+ * 
+ * FFmpeg 以 `--enable-libsoxr` 编译时使用 soxr 高质量重采样，否则回退到 swresample。输出只包含音频。
+ * 
+ * @param input 输入文件
+ * @param output 输出文件
+ * @param options 采样率、位深和抖动选项
+ * 
+ * @example
+ * ```typescript
+ * import { resample } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { resampler } = await resample('master.wav', 'cd.wav', {
+ *   sampleRate: 44100,
+ *   bitDepth: 16,
+ *   dither: 'shibata'
+ * })
+ * ```
+ */
+export async function resample(
+  input: string,
+  output: string,
+  options?: ResampleOptions
+): Promise<ResampleResponse> {
+  return await runHelper<ResampleResponse>('resample', { input, output }, options)
+}
+
+/**
+ * 降噪选项
+ */
+export interface DenoiseAudioOptions extends HelperOptions {
+  /** 降噪强度，默认 `medium` */
+  strength?: 'light' | 'medium' | 'strong'
+  /** 高通滤波截止频率（Hz），去除低频隆隆声，默认 80，设为 0 关闭 */
+  highpass?: number
+  /** 低通滤波截止频率（Hz），去除高频嘶声，默认关闭 */
+  lowpass?: number
+}
+
+/**
+ * 一键清理录音
+ * 
+ * 组合 highpass、afftdn 和 lowpass 滤镜，视频流（如果有）直接复制。
+ * 
+ * @param input 输入文件
+ * @param output 输出文件
+ * @param options 可选的降噪选项
+ * 
+ * @example
+ * ```typescript
+ * import { denoiseAudio } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await denoiseAudio('memo.m4a', 'memo-clean.m4a', { strength: 'strong', lowpass: 8000 })
+ * ```
+ */
+export async function denoiseAudio(
+  input: string,
+  output: string,
+  options?: DenoiseAudioOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('denoise_audio', { input, output }, options)
+}
+
+/**
+ * 全景视频元数据（Spherical Video V2）
+ */
+export interface SphericalMetadata {
+  /** 投影方式，默认 `equirectangular` */
+  projection?: 'equirectangular' | 'cubemap'
+  /** 立体模式，默认 `mono` */
+  stereo?: 'mono' | 'top-bottom' | 'left-right'
+  /** 初始视角的偏航角（度） */
+  yaw?: number
+  /** 初始视角的俯仰角（度） */
+  pitch?: number
+  /** 初始视角的翻滚角（度） */
+  roll?: number
+}
+
+/**
+ * 转码选项
+ */
+export interface TranscodeOptions extends HelperOptions {
+  /** 内置配置的名称（见 `listBuiltinProfiles`），显式设置的选项优先于配置 */
+  profile?: string
+  /** 视频编码器（如 `libx264`、`libx265`、`libsvtav1`），`copy` 表示直接复制 */
+  videoCodec?: string
+  /** 音频编码器（如 `aac`、`libopus`），`copy` 表示直接复制 */
+  audioCodec?: string
+  /** 恒定质量（`-crf`） */
+  crf?: number
+  /** 编码器预设（`-preset`） */
+  preset?: string
+  /** 视频码率（如 `5M`） */
+  videoBitrate?: string
+  /** 音频码率（如 `192k`） */
+  audioBitrate?: string
+  /** 输出宽度，只设置宽或高时按比例缩放 */
+  width?: number
+  /** 输出高度 */
+  height?: number
+  /** 同时设置宽高时的缩放方式：`stretch`（默认）、`contain`（补黑边）、`cover`（裁掉超出部分） */
+  fit?: 'stretch' | 'contain' | 'cover'
+  /** 输出帧率 */
+  fps?: number
+  /** 像素格式（如 `yuv420p`） */
+  pixelFormat?: string
+  /** 去隔行：`yadif`（快）或 `bwdif`（质量更好） */
+  deinterlace?: 'yadif' | 'bwdif'
+  /** 降噪：`hqdn3d`（快）或 `nlmeans`（质量高但很慢） */
+  denoise?: 'hqdn3d' | 'nlmeans'
+  /** 去色带 */
+  deband?: boolean
+  /** 自动检测并裁掉黑边（见 {@link detectCrop}） */
+  autoCrop?: boolean
+  /** 将 HDR（PQ / HLG）色调映射为 SDR BT.709，需要 FFmpeg 编译了 libzimg */
+  tonemapToSdr?: boolean
+  /** 保留 HDR：沿用输入的色彩标记，使用 `libx265` 时同时写入 HDR10 元数据 */
+  preserveHdr?: boolean
+  /** 输出的色彩原色标记（如 `bt709`、`bt2020`） */
+  colorPrimaries?: string
+  /** 输出的传递特性标记（如 `bt709`、`smpte2084`、`arib-std-b67`） */
+  colorTrc?: string
+  /** 输出的色彩矩阵标记（如 `bt709`、`bt2020nc`） */
+  colorspace?: string
+  /** 保留输入中的全景视频元数据 */
+  preserveSpherical?: boolean
+  /** 编码完成后写入全景视频元数据，只支持 MP4 / MOV 输出 */
+  spherical?: SphericalMetadata
+  /** MP4 / MOV 的 `-movflags` 预设：`faststart`、`fragmented`（MSE）或 `cmaf` */
+  movflags?: 'faststart' | 'fragmented' | 'cmaf'
+  /** 分片时长（秒），配合 `fragmented` / `cmaf` 使用 */
+  fragDuration?: number
+}
+
+/**
+ * 按选项转码
+ * 
+ * 预处理滤镜按去隔行、裁剪、降噪、去色带、缩放的顺序应用。
+ * 
+ * @param input 输入文件
+ * @param output 输出文件
+ * @param options 可选的转码选项
+ * 
+ * @example
+ * ```typescript
+ * import { transcode } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await transcode('tape.avi', 'tape.mp4', {
+ *   videoCodec: 'libx264',
+ *   crf: 20,
+ *   deinterlace: 'bwdif',
+ *   denoise: 'hqdn3d',
+ *   height: 720
+ * })
+ * ```
+ */
+export async function transcode(
+  input: string,
+  output: string,
+  options?: TranscodeOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('transcode', { input, output }, options)
+}
+
+/**
+ * 多输出转码中的一个输出
+ */
+export interface TranscodeOutput extends Omit<TranscodeOptions, keyof HelperOptions> {
+  /** 输出文件 */
+  output: string
+}
+
+/**
+ * 在一次 FFmpeg 调用中生成多个输出，输入只解码一次
+ * 
+ * @param input 输入文件
+ * @param outputs 各个输出及其转码选项
+ * @param options 可选的任务选项
+ * 
+ * @example
+ * ```typescript
+ * import { transcodeMulti } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await transcodeMulti('master.mov', [
+ *   { output: 'full.mp4', profile: 'web-1080p-h264' },
+ *   { output: 'proxy.mp4', profile: 'web-720p-h264', crf: 28 },
+ *   { output: 'preview.webm', videoCodec: 'libvpx-vp9', height: 360 }
+ * ])
+ * ```
+ */
+export async function transcodeMulti(
+  input: string,
+  outputs: TranscodeOutput[],
+  options?: HelperOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('transcode_multi', { input, outputs }, options)
+}
+
+/**
+ * 裁剪区域
+ */
+export interface CropRect {
+  width: number
+  height: number
+  x: number
+  y: number
+}
+
+/**
+ * 黑边检测选项
+ */
+export interface DetectCropOptions extends HelperOptions {
+  /** 分析的时长（秒），默认 60 */
+  sampleSeconds?: number
+}
+
+/**
+ * 使用 cropdetect 分析黑边
+ * 
+ * 时长已知时跳过开头 10% 的片头，返回出现次数最多的裁剪区域。
+ * 也可以在 {@link transcode} 中设置 `autoCrop: true` 自动应用。
+ * 
+ * @param input 输入文件
+ * @param options 可选的分析选项
+ * @returns 建议的裁剪区域
+ * 
+ * @example
+ * ```typescript
+ * import { detectCrop } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const crop = await detectCrop('movie.mkv', { sampleSeconds: 120 })
+ * console.log(`crop=${crop.width}:${crop.height}:${crop.x}:${crop.y}`)
+ * ```
+ */
+export async function detectCrop(input: string, options?: DetectCropOptions): Promise<CropRect> {
+  return await runHelper<CropRect>('detect_crop', { input }, options)
+}
+
+/**
+ * 场序
+ */
+export type FieldOrder = 'tff' | 'bff' | 'progressive' | 'undetermined'
+
+/**
+ * idet 的逐帧统计
+ */
+export interface FieldOrderCounts {
+  tff: number
+  bff: number
+  progressive: number
+  undetermined: number
+}
+
+/**
+ * 隔行检测结果
+ */
+export interface InterlacingReport {
+  /** 根据多帧检测得出的结论 */
+  fieldOrder: FieldOrder
+  /** 是否建议去隔行（隔行帧超过已判定帧的 10%） */
+  interlaced: boolean
+  /** 单帧检测统计 */
+  singleFrame: FieldOrderCounts
+  /** 多帧检测统计（更可靠） */
+  multiFrame: FieldOrderCounts
+  /** 重复场统计（用于识别 3:2 下拉） */
+  repeatedFields: { neither: number; top: number; bottom: number }
+}
+
+/**
+ * 隔行检测选项
+ */
+export interface DetectInterlacingOptions extends HelperOptions {
+  /** 分析的时长（秒），默认 60 */
+  sampleSeconds?: number
+}
+
+/**
+ * 使用 idet 检测隔行和场序
+ * 
+ * @param input 输入文件
+ * @param options 可选的分析选项
+ * @returns 顶场优先、底场优先和逐行帧的统计
+ * 
+ * @example
+ * ```typescript
+ * import { detectInterlacing, transcode } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const report = await detectInterlacing('tape.avi')
+ * await transcode('tape.avi', 'tape.mp4', {
+ *   deinterlace: report.interlaced ? 'bwdif' : undefined
+ * })
+ * ```
+ */
+export async function detectInterlacing(
+  input: string,
+  options?: DetectInterlacingOptions
+): Promise<InterlacingReport> {
+  return await runHelper<InterlacingReport>('detect_interlacing', { input }, options)
+}
+
+/**
+ * 检测到的时间区间（秒）
+ */
+export interface TimeInterval {
+  start: number
+  /** 持续到文件结尾时为空 */
+  end?: number
+  duration?: number
+}
+
+/**
+ * 黑场检测选项
+ */
+export interface DetectBlackOptions extends HelperOptions {
+  /** 最短黑场时长（秒），默认 2 */
+  minDuration?: number
+  /** 黑色像素占比阈值（0-1），默认 0.98 */
+  pictureThreshold?: number
+  /** 像素亮度阈值（0-1），默认 0.1 */
+  pixelThreshold?: number
+}
+
+/**
+ * 使用 blackdetect 找出黑场区间
+ * 
+ * @param input 输入文件
+ * @param options 可选的检测阈值
+ * @returns 黑场区间列表
+ * 
+ * @example
+ * ```typescript
+ * import { detectBlack } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const intervals = await detectBlack('render.mp4', { minDuration: 0.5 })
+ * if (intervals.length > 0) {
+ *   console.warn('发现黑场:', intervals)
+ * }
+ * ```
+ */
+export async function detectBlack(
+  input: string,
+  options?: DetectBlackOptions
+): Promise<TimeInterval[]> {
+  return await runHelper<TimeInterval[]>('detect_black', { input }, options)
+}
+
+/**
+ * 静帧检测选项
+ */
+export interface DetectFreezeOptions extends HelperOptions {
+  /** 最短静帧时长（秒），默认 2 */
+  minDuration?: number
+  /** 噪声容差（dB），默认 -60 */
+  noiseDb?: number
+}
+
+/**
+ * 使用 freezedetect 找出画面静止的区间
+ * 
+ * @param input 输入文件
+ * @param options 可选的检测阈值
+ * @returns 静帧区间列表
+ * 
+ * @example
+ * ```typescript
+ * import { detectFreeze } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const intervals = await detectFreeze('stream-recording.mp4')
+ * ```
+ */
+export async function detectFreeze(
+  input: string,
+  options?: DetectFreezeOptions
+): Promise<TimeInterval[]> {
+  return await runHelper<TimeInterval[]>('detect_freeze', { input }, options)
+}
+
+/**
+ * 同步偏移测量选项
+ */
+export interface MeasureSyncOptions extends HelperOptions {
+  /** 从第几秒开始分析，默认 0 */
+  startSeconds?: number
+  /** 分析的时长（秒），默认 60 */
+  windowSeconds?: number
+  /** 搜索的最大偏移（毫秒），默认 10000 */
+  maxOffsetMs?: number
+}
+
+/**
+ * 同步偏移测量结果
+ */
+export interface SyncOffset {
+  /** 需要施加在 `target` 上的偏移（毫秒），正数表示延后 */
+  offsetMs: number
+  /** 相关系数（0-1），低于 0.3 时结果通常不可靠 */
+  confidence: number
+}
+
+/**
+ * 对两个输入的音频做互相关，估算两者之间的同步偏移
+ * 
+ * @param reference 参考文件（通常是带现场声音的视频）
+ * @param target 需要对齐的文件（通常是单独录制的音频）
+ * @param options 可选的分析范围
+ * @returns 同步偏移和可信度
+ * 
+ * @example
+ * ```typescript
+ * import { measureSyncOffset } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { offsetMs, confidence } = await measureSyncOffset('camera.mp4', 'recorder.wav')
+ * if (confidence > 0.3) {
+ *   console.log(`外录音频需要偏移 ${offsetMs} ms`)
+ * }
+ * ```
+ */
+export async function measureSyncOffset(
+  reference: string,
+  target: string,
+  options?: MeasureSyncOptions
+): Promise<SyncOffset> {
+  return await runHelper<SyncOffset>('measure_sync_offset', { reference, target }, options)
+}
+
+/**
+ * 替换音轨选项
+ */
+export interface ReplaceAudioOptions extends HelperOptions {
+  /** 音频偏移（毫秒），正数表示延后，负数表示提前，可以直接使用 `measureSyncOffset` 的结果 */
+  offsetMs?: number
+  /** 以较短的一路为准结束输出 */
+  trimToShortest?: boolean
+  /** 音频编码器，默认 `aac`，传入 `copy` 可直接复制 */
+  audioCodec?: string
+}
+
+/**
+ * 用外部音频替换视频的音轨，视频流直接复制
+ * 
+ * @param video 视频文件，其中的音轨会被丢弃
+ * @param audio 新的音频文件
+ * @param output 输出文件
+ * @param options 可选的偏移和编码选项
+ * 
+ * @example
+ * ```typescript
+ * import { measureSyncOffset, replaceAudio } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { offsetMs } = await measureSyncOffset('camera.mp4', 'recorder.wav')
+ * await replaceAudio('camera.mp4', 'recorder.wav', 'synced.mp4', {
+ *   offsetMs,
+ *   trimToShortest: true
+ * })
+ * ```
+ */
+export async function replaceAudio(
+  video: string,
+  audio: string,
+  output: string,
+  options?: ReplaceAudioOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('replace_audio', { video, audio, output }, options)
+}
+
+/**
+ * 复杂度分析选项
+ */
+export interface AnalyzeComplexityOptions extends HelperOptions {
+  /** 目标画质（SSIM，0-1），默认 0.98 */
+  targetSsim?: number
+  /** 视频编码器，默认 `libx264` */
+  videoCodec?: string
+  /** 试编码使用的预设，默认 `veryfast` */
+  preset?: string
+  /** 采样片段数，默认 3 */
+  samples?: number
+  /** 每个片段的时长（秒），默认 4 */
+  sampleSeconds?: number
+}
+
+/**
+ * 单个 CRF 的试编码结果
+ */
+export interface ComplexityProbe {
+  crf: number
+  /** 平均视频码率（kbps） */
+  bitrateKbps: number
+  /** 与原片相比的平均 SSIM */
+  ssim: number
+}
+
+/**
+ * 复杂度分析结果
+ */
+export interface ComplexityReport {
+  /** 达到目标画质的最大 CRF */
+  crf: number
+  /** 该 CRF 下预计的视频码率（kbps） */
+  bitrateKbps: number
+  /** 该 CRF 下预计的 SSIM */
+  ssim: number
+  /** 各 CRF 的试编码结果 */
+  probes: ComplexityProbe[]
+}
+
+/**
+ * 对采样片段用不同 CRF 试编码，推荐达到目标画质的 CRF 和码率
+ * 
+ * @param input 输入文件
+ * @param options 可选的目标画质和采样设置
+ * @returns 推荐的 CRF、码率和试编码结果
+ * 
+ * @example
+ * ```typescript
+ * import { analyzeComplexity, transcode } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { crf } = await analyzeComplexity('talk.mov', { targetSsim: 0.97 })
+ * await transcode('talk.mov', 'talk.mp4', { videoCodec: 'libx264', crf })
+ * ```
+ */
+export async function analyzeComplexity(
+  input: string,
+  options?: AnalyzeComplexityOptions
+): Promise<ComplexityReport> {
+  return await runHelper<ComplexityReport>('analyze_complexity', { input }, options)
+}
+
+/**
+ * 体积估算选项
+ */
+export interface EstimateOutputSizeOptions extends TranscodeOptions {
+  /** 采样片段数，默认 5 */
+  samples?: number
+  /** 每个片段的时长（秒），默认 5 */
+  sampleSeconds?: number
+}
+
+/**
+ * 体积估算结果
+ */
+export interface SizeEstimate {
+  /** 预计大小（字节） */
+  bytes: number
+  /** 下限，按码率最低的片段推算 */
+  lowBytes: number
+  /** 上限，按码率最高的片段推算 */
+  highBytes: number
+  /** 输入时长（秒） */
+  duration: number
+  /** 预计的平均码率（kbps） */
+  bitrateKbps: number
+}
+
+/**
+ * 按转码选项编码几个采样片段，推算完整输出的大小
+ * 
+ * @param input 输入文件
+ * @param options 与 `transcode` 相同的转码选项，以及采样设置
+ * @returns 预计大小和上下限
+ * 
+ * @example
+ * ```typescript
+ * import { estimateOutputSize } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { bytes } = await estimateOutputSize('raw.mov', { videoCodec: 'libx265', crf: 24 })
+ * console.log(`预计输出约 ${(bytes / 1024 ** 3).toFixed(1)} GB`)
+ * ```
+ */
+export async function estimateOutputSize(
+  input: string,
+  options?: EstimateOutputSizeOptions
+): Promise<SizeEstimate> {
+  return await runHelper<SizeEstimate>('estimate_output_size', { input }, options)
+}
+
+/**
+ * 编码器性能测试选项
+ */
+export interface BenchmarkOptions extends HelperOptions {
+  /** 每个编码器编码的时长（秒），默认 5 */
+  seconds?: number
+  /** 测试画面的分辨率，默认 `1920x1080` */
+  size?: string
+  /** 测试画面的帧率，默认 30 */
+  frameRate?: number
+}
+
+/**
+ * 单个编码器的测试结果
+ */
+export interface EncoderBenchmark {
+  encoder: string
+  /** 平均编码帧率 */
+  fps: number | null
+  /** 相对实时的编码速度倍数 */
+  speed: number | null
+  /** 包括初始化在内的耗时（秒） */
+  elapsedSecs: number
+  /** 编码器不可用或初始化失败（如缺少硬件或驱动）时的原因 */
+  error: string | null
+}
+
+/**
+ * 编码器性能测试结果
+ */
+export interface BenchmarkReport {
+  /** 按请求顺序排列的各编码器结果 */
+  results: EncoderBenchmark[]
+  /** 编码帧率最高的编码器，全部失败时为 null */
+  fastest: string | null
+}
+
+/**
+ * 用合成的测试画面依次测试各个编码器的编码速度，在用户的机器上选择最快的硬件编码器
+ * 
+ * 编码器逐个运行，单个编码器失败只记录在对应结果的 `error` 中。
+ * 
+ * @param encoders 要测试的视频编码器
+ * @param options 可选的测试时长和画面设置
+ * @returns 各编码器的帧率和速度，以及最快的编码器
+ * 
+ * @example
+ * ```typescript
+ * import { benchmark } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { fastest } = await benchmark(['h264_nvenc', 'h264_qsv', 'h264_amf', 'libx264'], { seconds: 3 })
+ * ```
+ */
+export async function benchmark(
+  encoders: string[],
+  options?: BenchmarkOptions
+): Promise<BenchmarkReport> {
+  return await runHelper<BenchmarkReport>('benchmark', { encoders }, options)
+}
+
+/**
+ * 内置转码配置
+ */
+export interface BuiltinProfile {
+  /** 配置名称，用于 `TranscodeOptions.profile` */
+  name: string
+  /** 用途说明 */
+  description: string
+  /** 推荐的输出扩展名 */
+  extension: string
+  /** 配置对应的转码选项，`extraArgs` 为配置附加的参数（如 `-tag:v hvc1`） */
+  options: TranscodeOptions & { extraArgs: string[] }
+}
+
+/**
+ * 列出插件内置的转码配置
+ * 
+ * @returns 内置配置列表
+ * 
+ * @example
+ * ```typescript
+ * import { listBuiltinProfiles, transcode } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const profiles = await listBuiltinProfiles()
+ * await transcode('raw.mov', 'web.mp4', { profile: profiles[0].name })
+ * ```
+ */
+export async function listBuiltinProfiles(): Promise<BuiltinProfile[]> {
+  return await invoke<BuiltinProfile[]>('plugin:use-ffmpeg|list_builtin_profiles')
+}
+
+/**
+ * 单个流的信息
+ */
+export interface ProbeStream {
+  index: number
+  /** `video`、`audio`、`subtitle`、`data` 等 */
+  codecType: string
+  codecName?: string
+  width?: number
+  height?: number
+  /** 像素格式（如 `yuv420p10le`） */
+  pixelFormat?: string
+  /** 色彩原色（如 `bt709`、`bt2020`） */
+  colorPrimaries?: string
+  /** 传递特性（`smpte2084` 表示 PQ，`arib-std-b67` 表示 HLG） */
+  colorTransfer?: string
+  /** 色彩矩阵（如 `bt2020nc`） */
+  colorSpace?: string
+  /** 平均帧率 */
+  frameRate?: number
+  /** 码率（bps） */
+  bitRate?: number
+  sampleRate?: number
+  channels?: number
+  /** 时长（秒） */
+  duration?: number
+  tags: Record<string, string>
+}
+
+/**
+ * 媒体信息
+ */
+export interface ProbeResult {
+  /** 封装格式，ffprobe 以逗号分隔的别名列表（如 `mov,mp4,m4a,3gp,3g2,mj2`） */
+  formatName: string
+  /** 时长（秒） */
+  duration?: number
+  /** 文件大小（字节） */
+  size?: number
+  /** 总码率（bps） */
+  bitRate?: number
+  /** 容器级元数据（如 `creation_time`） */
+  tags: Record<string, string>
+  streams: ProbeStream[]
+}
+
+/**
+ * 使用 ffprobe 读取封装格式和各个流的信息
+ * 
+ * @param input 输入文件
+ * @returns 媒体信息
+ * 
+ * @example
+ * ```typescript
+ * import { probe } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const info = await probe('clip.mp4')
+ * const video = info.streams.find(stream => stream.codecType === 'video')
+ * console.log(`${video?.width}x${video?.height}, ${info.duration} s`)
+ * ```
+ */
+export async function probe(input: string): Promise<ProbeResult> {
+  return await invoke<ProbeResult>('plugin:use-ffmpeg|probe', { payload: { input } })
+}
+
+/**
+ * 封装格式
+ */
+export interface FormatInfo {
+  /** 格式名，可以传给 `-f`；部分分离器有多个名称，以逗号分隔，如 `matroska,webm` */
+  name: string
+  /** 说明，如 `Matroska / WebM` */
+  description: string
+  /** 是否可以读取（分离器） */
+  demux: boolean
+  /** 是否可以写入（复用器） */
+  mux: boolean
+  /** 是否为采集设备（如 `avfoundation`、`dshow`），仅 FFmpeg 7.0 及以上会标出 */
+  device: boolean
+}
+
+/**
+ * 列出已安装的 FFmpeg 支持的封装格式（`ffmpeg -formats`）
+ * 
+ * @returns 格式列表，包括能否读取、写入
+ * 
+ * @example
+ * ```typescript
+ * import { listFormats } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const formats = await listFormats()
+ * const exportChoices = formats.filter(format => format.mux && !format.device)
+ * ```
+ */
+export async function listFormats(): Promise<FormatInfo[]> {
+  return await invoke<FormatInfo[]>('plugin:use-ffmpeg|list_formats')
+}
+
+/**
+ * 支持的协议
+ */
+export interface ProtocolsInfo {
+  /** 可以用于输入的协议，如 `file`、`https`、`rtmp`、`srt` */
+  input: string[]
+  /** 可以用于输出的协议 */
+  output: string[]
+}
+
+/**
+ * 列出已安装的 FFmpeg 支持的输入和输出协议（`ffmpeg -protocols`）
+ * 
+ * 不同的构建支持的协议不同，可以在启动任务前判断能否使用网络来源。
+ * 
+ * @example
+ * ```typescript
+ * import { listProtocols } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { input } = await listProtocols()
+ * if (!input.includes('srt')) {
+ *   showWarning('当前的 FFmpeg 不支持 SRT')
+ * }
+ * ```
+ */
+export async function listProtocols(): Promise<ProtocolsInfo> {
+  return await invoke<ProtocolsInfo>('plugin:use-ffmpeg|list_protocols')
+}
+
+/**
+ * 像素格式
+ */
+export interface PixFmtInfo {
+  /** 格式名，可以传给 `-pix_fmt`，如 `yuv420p` */
+  name: string
+  /** 分量数 */
+  components: number
+  /** 每个像素的平均位数 */
+  bitsPerPixel: number
+  /** 各分量的位深，FFmpeg 6.0 之前的版本为空 */
+  bitDepths: number[]
+  /** 是否支持作为转换的输入 */
+  input: boolean
+  /** 是否支持作为转换的输出，编码前 `-pix_fmt` 转换到的格式需要支持 */
+  output: boolean
+  /** 是否为硬件加速格式（如 `vaapi`、`cuda`），帧数据位于显存中 */
+  hardware: boolean
+  /** 是否为调色板格式 */
+  paletted: boolean
+  /** 是否为位流格式 */
+  bitstream: boolean
+}
+
+/**
+ * 音频采样格式
+ */
+export interface SampleFmtInfo {
+  /** 格式名，可以传给 `-sample_fmt`，如 `s16`、`fltp` */
+  name: string
+  /** 每个采样的位数 */
+  depth: number
+  /** 是否为平面格式（各声道分开存储） */
+  planar: boolean
+}
+
+/**
+ * 列出已安装的 FFmpeg 支持的像素格式（`ffmpeg -pix_fmts`）
+ * 
+ * @example
+ * ```typescript
+ * import { listPixFmts } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const tenBit = (await listPixFmts()).filter(
+ *   format => format.output && !format.hardware && format.bitDepths.every(depth => depth === 10)
+ * )
+ * ```
+ */
+export async function listPixFmts(): Promise<PixFmtInfo[]> {
+  return await invoke<PixFmtInfo[]>('plugin:use-ffmpeg|list_pix_fmts')
+}
+
+/**
+ * 列出已安装的 FFmpeg 支持的音频采样格式（`ffmpeg -sample_fmts`）
+ */
+export async function listSampleFmts(): Promise<SampleFmtInfo[]> {
+  return await invoke<SampleFmtInfo[]>('plugin:use-ffmpeg|list_sample_fmts')
+}
+
+/**
+ * 滤镜选项的具名常量
+ */
+export interface FilterOptionConstant {
+  name: string
+  /** 对应的数值，`flags` 类型的常量没有 */
+  value: string | null
+  description: string
+}
+
+/**
+ * 滤镜选项
+ */
+export interface FilterOption {
+  name: string
+  /** 类型，如 `int`、`string`、`boolean`、`flags`、`color`、`duration` */
+  type: string
+  /** 说明，不含取值范围和默认值 */
+  description: string
+  /** 默认值，按 FFmpeg 的输出原样保留（去掉外层引号） */
+  default: string | null
+  min: string | null
+  max: string | null
+  /** 是否可以在运行时通过 `sendcmd` 或交互命令修改 */
+  runtime: boolean
+  /** 具名常量，如 `eval` 的 `init` 和 `frame` */
+  constants: FilterOptionConstant[]
+}
+
+/**
+ * 滤镜的说明和选项
+ */
+export interface FilterHelp {
+  name: string
+  description: string
+  /** 选项，别名（如 `scale` 的 `w` 和 `width`）各自单独列出 */
+  options: FilterOption[]
+  /** 是否支持通过 `enable` 选项按时间启用 */
+  timeline: boolean
+}
+
+/**
+ * 读取滤镜的说明和选项（`ffmpeg -h filter=<名称>`），用于为任意滤镜生成参数表单
+ * 
+ * 已安装的 FFmpeg 没有该滤镜时以 `invalid_argument` 拒绝。
+ * 
+ * @param name 滤镜名，如 `'scale'`
+ * 
+ * @example
+ * ```typescript
+ * import { filterHelp } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { options } = await filterHelp('eq')
+ * for (const option of options) {
+ *   console.log(option.name, option.type, option.min, option.max, option.default)
+ * }
+ * ```
+ */
+export async function filterHelp(name: string): Promise<FilterHelp> {
+  return await invoke<FilterHelp>('plugin:use-ffmpeg|filter_help', { payload: { name } })
+}
+
+/**
+ * 目标平台
+ */
+export type Platform = 'youtube' | 'youtube-shorts' | 'instagram-reels' | 'tiktok' | 'x'
+
+/**
+ * 平台的上传约束，未设置的字段表示不限制
+ */
+export interface PlatformConstraints {
+  /** 最短时长（秒） */
+  minDuration?: number
+  /** 最长时长（秒） */
+  maxDuration?: number
+  /** 最大文件大小（字节） */
+  maxSize?: number
+  /** 长边的最大像素数 */
+  maxLongEdge?: number
+  /** 短边的最大像素数 */
+  maxShortEdge?: number
+  /** 宽高比（宽 / 高）的允许范围 */
+  aspectRatio?: [number, number]
+  /** 最大帧率 */
+  maxFrameRate?: number
+  /** 最大总码率（kbps） */
+  maxBitrateKbps?: number
+  /** 允许的封装格式 */
+  containers: string[]
+  /** 允许的视频编码，为空时不限制 */
+  videoCodecs: string[]
+  /** 允许的音频编码，为空时不限制 */
+  audioCodecs: string[]
+}
+
+/**
+ * 平台导出预设
+ */
+export interface PlatformPreset {
+  platform: Platform
+  /** 推荐使用的内置转码配置 */
+  profile: string
+  constraints: PlatformConstraints
+}
+
+/**
+ * 违反的约束
+ */
+export interface ConstraintViolation {
+  constraint:
+    | 'duration'
+    | 'size'
+    | 'resolution'
+    | 'aspect-ratio'
+    | 'frame-rate'
+    | 'bitrate'
+    | 'container'
+    | 'video-codec'
+    | 'audio-codec'
+  /** 可直接展示给用户的说明 */
+  message: string
+}
+
+/**
+ * 约束检查结果
+ */
+export interface ValidationReport {
+  platform: Platform
+  /** 没有任何违规时为 true */
+  valid: boolean
+  violations: ConstraintViolation[]
+}
+
+/**
+ * 列出各平台的导出预设和上传约束
+ * 
+ * @returns 平台预设列表
+ */
+export async function listPlatformPresets(): Promise<PlatformPreset[]> {
+  return await invoke<PlatformPreset[]>('plugin:use-ffmpeg|list_platform_presets')
+}
+
+/**
+ * 检查文件是否满足平台的上传约束
+ * 
+ * @param platform 目标平台
+ * @param probeResult `probe` 的结果
+ * @returns 违反的约束
+ * 
+ * @example
+ * ```typescript
+ * import { probe, transcode, validateFor } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const report = await validateFor('tiktok', await probe('edit.mov'))
+ * if (!report.valid) {
+ *   report.violations.forEach(v => console.warn(v.message))
+ *   await transcode('edit.mov', 'edit-tiktok.mp4', { profile: 'social-vertical-9x16' })
+ * }
+ * ```
+ */
+export async function validateFor(
+  platform: Platform,
+  probeResult: ProbeResult
+): Promise<ValidationReport> {
+  return await invoke<ValidationReport>('plugin:use-ffmpeg|validate_for', {
+    payload: { platform, probe: probeResult }
+  })
+}
+
+/**
+ * 代理生成选项
+ */
+export interface ProxyOptions extends Omit<HelperOptions, 'jobId'> {
+  /** 代理编码：`prores_proxy`（默认）、`dnxhr` 或 `h264` */
+  codec?: 'prores_proxy' | 'dnxhr' | 'h264'
+  /** 相对原片的缩放比例，默认 0.5 */
+  scale?: number
+}
+
+/**
+ * 清单中的一项
+ */
+export interface ProxyEntry {
+  /** 原始文件 */
+  original: string
+  /** 代理文件 */
+  proxy: string
+  /** 生成失败时的原因 */
+  error?: string
+}
+
+/**
+ * 代理清单
+ */
+export interface ProxyManifest {
+  codec: 'prores_proxy' | 'dnxhr' | 'h264'
+  scale: number
+  /** 生成时间（Unix 毫秒） */
+  createdAt: number
+  entries: ProxyEntry[]
+}
+
+/**
+ * 批量生成剪辑代理，并在输出目录写入 `proxy-manifest.json`
+ * 
+ * @param inputs 原始文件
+ * @param outputDir 输出目录，不存在时自动创建
+ * @param options 可选的编码和缩放设置
+ * @returns 原始文件与代理文件的对应关系
+ * 
+ * @example
+ * ```typescript
+ * import { generateProxies } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const manifest = await generateProxies(['A001.mov', 'A002.mov'], '/project/proxies', {
+ *   codec: 'prores_proxy',
+ *   scale: 0.5
+ * })
+ * const failed = manifest.entries.filter(entry => entry.error)
+ * ```
+ */
+export async function generateProxies(
+  inputs: string[],
+  outputDir: string,
+  options?: ProxyOptions
+): Promise<ProxyManifest> {
+  return await runHelper<ProxyManifest>('generate_proxies', { inputs, outputDir }, options)
+}
+
+/**
+ * 静音裁剪选项
+ */
+export interface TrimSilenceOptions extends HelperOptions {
+  /** 低于该音量视为静音（dB），默认 -50 */
+  thresholdDb?: number
+  /** 裁剪后在首尾保留的静音时长（秒），默认 0.25 */
+  padding?: number
+  /** 最短静音时长（秒），默认 0.5 */
+  minDuration?: number
+}
+
+/**
+ * 静音裁剪结果
+ */
+export interface TrimSilenceResponse {
+  /** 任务 ID */
+  jobId: string
+  /** 保留部分在输入中的开始时间（秒） */
+  start: number
+  /** 保留部分在输入中的结束时间（秒），没有裁掉结尾时为空 */
+  end?: number
+}
+
+/**
+ * 裁掉录音开头和结尾的静音，中间的停顿保持不变
+ * 
+ * @param input 输入文件
+ * @param output 输出文件
+ * @param options 可选的静音阈值和保留时长
+ * @returns 保留部分在输入中的时间范围
+ * 
+ * @example
+ * ```typescript
+ * import { trimSilence } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { start, end } = await trimSilence('take1.wav', 'take1-trimmed.wav', { thresholdDb: -45 })
+ * ```
+ */
+export async function trimSilence(
+  input: string,
+  output: string,
+  options?: TrimSilenceOptions
+): Promise<TrimSilenceResponse> {
+  return await runHelper<TrimSilenceResponse>('trim_silence', { input, output }, options)
+}
+
+/**
+ * 转写预处理选项
+ */
+export interface PrepareForTranscriptionOptions extends HelperOptions {
+  /** 响度目标（LUFS），默认 -16 */
+  loudness?: number
+}
+
+/**
+ * 转换为 16 kHz 单声道 16 位 WAV / FLAC 并做响度标准化，可直接交给 Whisper 等转写模型
+ * 
+ * @param input 输入文件，可以是任意音视频
+ * @param output 输出文件，扩展名为 `.wav` 或 `.flac`
+ * @param options 可选的响度目标
+ * 
+ * @example
+ * ```typescript
+ * import { prepareForTranscription } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await prepareForTranscription('meeting.mp4', 'meeting-16k.wav')
+ * ```
+ */
+export async function prepareForTranscription(
+  input: string,
+  output: string,
+  options?: PrepareForTranscriptionOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('prepare_for_transcription', { input, output }, options)
+}
+
+/**
+ * 哈希算法
+ */
+export type HashAlgorithm = 'md5' | 'sha1' | 'sha256' | 'sha512' | 'crc32'
+
+/**
+ * 内容哈希选项
+ */
+export interface MediaHashOptions extends HelperOptions {
+  /** 哈希算法，默认 `sha256` */
+  algorithm?: HashAlgorithm
+  /** 参与计算的流：`all`（默认，视频和音频）、`video` 或 `audio` */
+  streams?: 'all' | 'video' | 'audio'
+}
+
+/**
+ * 内容哈希结果
+ */
+export interface MediaHash {
+  algorithm: HashAlgorithm
+  /** 十六进制哈希值 */
+  hash: string
+}
+
+/**
+ * 对解码后的画面和声音计算哈希，与封装格式无关
+ * 
+ * @param input 输入文件
+ * @param options 可选的算法和流选择
+ * @returns 哈希值
+ * 
+ * @example
+ * ```typescript
+ * import { mediaHash } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const [a, b] = await Promise.all([mediaHash('clip.mkv'), mediaHash('clip.mp4')])
+ * if (a.hash === b.hash) {
+ *   console.log('内容相同')
+ * }
+ * ```
+ */
+export async function mediaHash(input: string, options?: MediaHashOptions): Promise<MediaHash> {
+  return await runHelper<MediaHash>('media_hash', { input }, options)
+}
+
+/**
+ * 完整性检查选项
+ */
+export interface VerifyFileOptions extends HelperOptions {
+  /** 同时检查 CRC 和码流语法，更严格但会报告更多轻微问题 */
+  strict?: boolean
+}
+
+/**
+ * 单条解码错误
+ */
+export interface DecodeError {
+  /** 出错时的大致解码位置（秒） */
+  time?: number
+  /** FFmpeg 输出的错误信息 */
+  message: string
+}
+
+/**
+ * 完整性检查结果
+ */
+export interface CorruptionReport {
+  /** 任务 ID */
+  jobId: string
+  /** 完整解码且没有任何错误 */
+  ok: boolean
+  /** FFmpeg 是否正常退出，无法打开或读取中断时为 false */
+  completed: boolean
+  /** 错误总数 */
+  errorCount: number
+  /** 错误列表，最多保留 500 条 */
+  errors: DecodeError[]
+  /** 成功解码到的位置（秒） */
+  decodedDuration?: number
+}
+
+/**
+ * 完整解码文件，收集解码错误及其大致位置
+ * 
+ * 文件损坏不会抛出异常，而是体现在报告中。
+ * 
+ * @param input 输入文件
+ * @param options 可选的检查选项
+ * @returns 损坏报告
+ * 
+ * @example
+ * ```typescript
+ * import { verifyFile } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const report = await verifyFile('download.mkv')
+ * if (!report.ok) {
+ *   console.warn(`发现 ${report.errorCount} 处错误`, report.errors[0])
+ * }
+ * ```
+ */
+export async function verifyFile(
+  input: string,
+  options?: VerifyFileOptions
+): Promise<CorruptionReport> {
+  return await runHelper<CorruptionReport>('verify_file', { input }, options)
+}
+
+/**
+ * 不重新编码，将 `moov` 移到文件开头，使 MP4 / MOV 可以边下载边播放
+ * 
+ * 未指定输出时原地替换输入文件，失败时输入文件保持不变。
+ * 
+ * @param input 输入文件
+ * @param output 输出文件，未设置时原地处理
+ * @param options 可选的任务选项
+ * @returns 处理后的文件路径
+ * 
+ * @example
+ * ```typescript
+ * import { faststart } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await faststart('upload.mp4')
+ * ```
+ */
+export async function faststart(
+  input: string,
+  output?: string,
+  options?: HelperOptions
+): Promise<HelperOutput> {
+  return await runHelper<HelperOutput>('faststart', { input, output }, options)
+}
+
+/** 智能缩略图选项 */
+export interface SmartThumbnailOptions extends HelperOptions {
+  /** 候选画面数量，默认 20 */
+  samples?: number
+  /** 输出宽度（像素），高度按比例计算，未设置时使用原始尺寸 */
+  width?: number
+  /** 跳过开头和结尾的比例（0-0.5），避开片头和片尾，默认 0.05 */
+  margin?: number
+}
+
+/** 智能缩略图结果 */
+export interface SmartThumbnail {
+  jobId: string
+  /** 输出图片路径 */
+  path: string
+  /** 选中画面的时间（秒） */
+  time: number
+}
+
+/**
+ * 自动挑选清晰、非黑屏的画面作为缩略图
+ * 
+ * @param input 输入视频
+ * @param output 输出图片（如 `.jpg`、`.png`、`.webp`）
+ * @param options 可选的缩略图选项
+ * @returns 图片路径和选中画面的时间
+ * 
+ * @example
+ * ```typescript
+ * import { smartThumbnail } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { path, time } = await smartThumbnail('movie.mp4', 'poster.jpg', { width: 640 })
+ * ```
+ */
+export async function smartThumbnail(
+  input: string,
+  output: string,
+  options?: SmartThumbnailOptions
+): Promise<SmartThumbnail> {
+  return await runHelper<SmartThumbnail>('smart_thumbnail', { input, output }, options)
+}
+
+/** 分段录制选项 */
+export interface RecordRtspOptions extends HelperOptions {
+  /** 每个分段的时长（分钟），默认 10 */
+  segmentMinutes?: number
+  /** RTSP 传输协议，默认 `tcp` */
+  transport?: 'tcp' | 'udp'
+  /** 连接断开或 FFmpeg 退出后是否自动重连，默认 true */
+  reconnect?: boolean
+  /** 重连前等待的秒数，默认 5 */
+  reconnectDelaySecs?: number
+  /** 分段文件的扩展名，决定容器格式，默认 `mkv` */
+  container?: string
+}
+
+/**
+ * 已写完的录制分段（`use-ffmpeg://recording-segment` 事件）
+ */
+export interface RecordingSegment extends JobTags {
+  jobId: string
+  path: string
+  /** 分段序号，从 0 开始，重连后继续递增 */
+  index: number
+}
+
+/**
+ * 录制中断并即将重连（`use-ffmpeg://recording-reconnecting` 事件）
+ */
+export interface RecordingReconnecting extends JobTags {
+  jobId: string
+  /** 第几次重连，从 1 开始 */
+  attempt: number
+  /** 中断原因（FFmpeg 的最后一行输出） */
+  reason: string
+}
+
+/** 录制结果 */
+export interface RecordingSummary {
+  jobId: string
+  /** 所有已写完的分段 */
+  segments: string[]
+  /** 重连次数 */
+  reconnects: number
+}
+
+/**
+ * 录制 RTSP / IP 摄像头的流，按固定时长分段保存，断开后自动重连
+ * 
+ * 录制一直持续到通过 `cancel(jobId)` 取消为止，取消后等待当前分段写完再返回。
+ * 
+ * @param url 流地址
+ * @param outputDir 分段文件的输出目录，文件以开始录制的本地时间命名
+ * @param options 可选的录制选项
+ * @returns 录制结果
+ * 
+ * @example
+ * ```typescript
+ * import { listen } from '@tauri-apps/api/event'
+ * import { cancel, recordRtsp, type RecordingSegment } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await listen<RecordingSegment>('use-ffmpeg://recording-segment', (event) => {
+ *   console.log('分段已保存', event.payload.path)
+ * })
+ * const recording = recordRtsp('rtsp://192.168.1.10/stream1', '/data/camera1', {
+ *   jobId: 'camera1',
+ *   segmentMinutes: 15
+ * })
+ * // 停止录制
+ * await cancel('camera1')
+ * const { segments } = await recording
+ * ```
+ */
+export async function recordRtsp(
+  url: string,
+  outputDir: string,
+  options?: RecordRtspOptions
+): Promise<RecordingSummary> {
+  return await runHelper<RecordingSummary>('record_rtsp', { url, outputDir }, options)
+}
+
+/**
+ * 音频采集源
+ */
+export interface AudioCaptureOptions {
+  /**
+   * 采集系统正在播放的声音而不是麦克风
+   * 
+   * - Windows：DirectShow 设备 `virtual-audio-capturer`（由 screen-capture-recorder 提供，内部使用 WASAPI loopback）
+   * - Linux：PulseAudio / PipeWire 的 monitor source，默认为 `@DEFAULT_MONITOR@`
+   * - macOS：系统不提供 loopback 设备，需要安装 BlackHole 等虚拟声卡，默认使用 `BlackHole 2ch`
+   */
+  loopback?: boolean
+}
+
+/** 电平表选项 */
+export interface LevelMeterOptions extends Omit<HelperOptions, 'onProgress'>, AudioCaptureOptions {
+  /** 电平事件的间隔（毫秒），默认 100 */
+  intervalMs?: number
+}
+
+/** 已启动的电平表 */
+export interface LevelMeter {
+  /** 任务 ID，通过 `cancel(jobId)` 停止 */
+  jobId: string
+}
+
+/**
+ * 输入电平（`use-ffmpeg://audio-level` 事件）
+ */
+export interface AudioLevel extends JobTags {
+  jobId: string
+  /** 本次间隔内的 RMS 电平（dBFS），静音时为 -100 */
+  rmsDb: number
+  /** 本次间隔内的峰值电平（dBFS），静音时为 -100 */
+  peakDb: number
+}
+
+/**
+ * 电平表停止（`use-ffmpeg://level-meter-stopped` 事件）
+ */
+export interface LevelMeterStopped extends JobTags {
+  jobId: string
+  /** 设备断开等原因导致 FFmpeg 退出时的错误信息，被取消时不存在 */
+  error?: string
+}
+
+/**
+ * 从输入设备采集音频并持续发送 `use-ffmpeg://audio-level` 事件，用于录制前的输入电平显示
+ * 
+ * 确认设备可以打开后立即返回，电平表在后台运行，直到通过 `cancel(jobId)` 停止。
+ * 
+ * @param device 输入设备，未设置时使用系统默认输入设备（Windows 上必须设置）：
+ *   macOS 为 AVFoundation 音频设备序号或名称，Windows 为 DirectShow 设备名称，Linux 为 PulseAudio / PipeWire 的 source 名称
+ * @param options 可选的电平表选项
+ * @returns 电平表的任务 ID
+ * 
+ * @example
+ * ```typescript
+ * import { listen } from '@tauri-apps/api/event'
+ * import { cancel, startLevelMeter, type AudioLevel } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { jobId } = await startLevelMeter()
+ * const unlisten = await listen<AudioLevel>('use-ffmpeg://audio-level', (event) => {
+ *   if (event.payload.jobId === jobId) {
+ *     meter.value = event.payload.peakDb
+ *   }
+ * })
+ * // 开始录制前停止
+ * await cancel(jobId)
+ * unlisten()
+ * ```
+ */
+export async function startLevelMeter(
+  device?: string,
+  options?: LevelMeterOptions
+): Promise<LevelMeter> {
+  return await runHelper<LevelMeter>('start_level_meter', { device }, options)
+}
+
+/** 录音选项 */
+export interface RecordAudioOptions extends Omit<HelperOptions, 'onProgress'>, AudioCaptureOptions {
+  /** 输入设备，含义与 {@link startLevelMeter} 的 `device` 相同 */
+  device?: string
+  /** 录制时长（秒），未设置时一直录制到任务被取消 */
+  durationSecs?: number
+}
+
+/**
+ * 从麦克风或系统声音（`loopback: true`）录音
+ * 
+ * 达到 `durationSecs` 或通过 `cancel(jobId)` 取消时结束。取消时等待 FFmpeg 写完文件再返回，已录制的内容会被保留。
+ * 
+ * @param output 输出文件，格式由扩展名决定
+ * @param options 可选的录音选项
+ * @returns 输出文件路径
+ * 
+ * @example
+ * ```typescript
+ * import { cancel, recordAudio } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const recording = recordAudio('meeting.m4a', { jobId: 'meeting', loopback: true })
+ * // 停止录音
+ * await cancel('meeting')
+ * const { path } = await recording
+ * ```
+ */
+export async function recordAudio(
+  output: string,
+  options?: RecordAudioOptions
+): Promise<HelperOutput> {
+  return await runHelper<HelperOutput>('record_audio', { output }, options)
+}
+
+/** 图像帧编码选项 */
+export interface EncodeFromBytesOptions extends Omit<HelperOptions, 'onProgress'> {
+  /** 帧率，默认 10 */
+  fps?: number
+  /** 输出格式（`-f`），未设置时由扩展名决定 */
+  format?: string
+}
+
+/**
+ * 将内存中的图像帧（如 canvas 截图）编码为 GIF 或视频，不写入临时图片
+ * 
+ * 图像通过标准输入（`image2pipe`）传给 FFmpeg。GIF 会先生成调色板以保证画质，
+ * 其他格式输出 `yuv420p`，并将尺寸裁剪为偶数以兼容 H.264。
+ * 
+ * @param frames 按顺序排列的图像帧：PNG / JPEG 等格式的字节，或 base64 字符串（可以直接使用 `canvas.toDataURL()` 的结果）。
+ *   字节需要转换为 JSON 数组传输，大量帧时 base64 更紧凑
+ * @param output 输出文件
+ * @param options 可选的编码选项
+ * @returns 输出文件路径
+ * 
+ * @example
+ * ```typescript
+ * import { encodeFromBytes } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const frames = captures.map((canvas) => canvas.toDataURL('image/png'))
+ * await encodeFromBytes(frames, 'animation.gif', { fps: 15 })
+ * ```
+ */
+export async function encodeFromBytes(
+  frames: Array<string | Uint8Array | ArrayBuffer | number[]>,
+  output: string,
+  options?: EncodeFromBytesOptions
+): Promise<HelperOutput> {
+  const payloadFrames = frames.map((frame) =>
+    typeof frame === 'string' || Array.isArray(frame)
+      ? frame
+      : Array.from(frame instanceof ArrayBuffer ? new Uint8Array(frame) : frame)
+  )
+  return await runHelper<HelperOutput>('encode_from_bytes', { frames: payloadFrames, output }, options)
+}
+
+/** 帧解码输出的像素格式 */
+export type RawPixelFormat = 'rgb24' | 'bgr24' | 'rgba' | 'bgra' | 'gray' | 'yuv420p' | 'nv12'
+
+/** 帧解码选项 */
+export interface DecodeFramesOptions extends Omit<HelperOptions, 'onProgress' | 'overwrite'> {
+  /** 输出帧率，未设置时输出每一帧 */
+  fps?: number
+  /** 像素格式，默认 `rgb24` */
+  pixFmt?: RawPixelFormat
+  /** 输出宽度，只设置宽高之一时按比例计算另一个，都未设置时使用原始尺寸 */
+  width?: number
+  /** 输出高度 */
+  height?: number
+}
+
+/** 解码出的一帧 */
+export interface DecodedFrame {
+  /** 帧序号，从 0 开始 */
+  index: number
+  width: number
+  height: number
+  /** 紧密排列的像素数据（没有行填充） */
+  data: Uint8Array
+}
+
+/** 帧解码结果 */
+export interface DecodeFramesSummary {
+  /** 任务 ID */
+  jobId: string
+  /** 发送的帧数 */
+  frames: number
+  width: number
+  height: number
+  pixFmt: RawPixelFormat
+  /** 每帧的字节数 */
+  frameSize: number
+}
+
+/**
+ * 解码视频并逐帧返回原始像素，用于在前端运行计算机视觉或机器学习模型
+ * 
+ * 帧通过 Channel 以二进制传输，不经过 JSON 编码。所有帧发送完后返回；
+ * 传入 `jobId` 后可以通过 `cancel` 提前停止，此时抛出 `cancelled` 错误。
+ * 
+ * @param input 输入文件
+ * @param onFrame 每一帧的回调，`data` 在回调返回后仍然有效
+ * @param options 可选的解码选项
+ * @returns 解码结果
+ * 
+ * @example
+ * ```typescript
+ * import { decodeFrames } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await decodeFrames('clip.mp4', ({ data, width, height }) => {
+ *   const image = new ImageData(new Uint8ClampedArray(data.buffer, data.byteOffset, data.byteLength), width, height)
+ *   model.run(image)
+ * }, { fps: 2, width: 224, height: 224, pixFmt: 'rgba' })
+ * ```
+ */
+export async function decodeFrames(
+  input: string,
+  onFrame: (frame: DecodedFrame) => void,
+  options?: DecodeFramesOptions
+): Promise<DecodeFramesSummary> {
+  const channel = new Channel<ArrayBuffer>()
+  channel.onmessage = (message) => {
+    // 头部为 16 字节小端序：帧序号（u64）、宽度（u32）、高度（u32）
+    const header = new DataView(message, 0, 16)
+    onFrame({
+      index: Number(header.getBigUint64(0, true)),
+      width: header.getUint32(8, true),
+      height: header.getUint32(12, true),
+      data: new Uint8Array(message, 16)
+    })
+  }
+
+  return await invoke<DecodeFramesSummary>('plugin:use-ffmpeg|decode_frames', {
+    payload: { input, ...options },
+    onFrame: channel
+  })
+}
+
+/** 管道中的一个阶段 */
+export interface PipelineStage {
+  /** FFmpeg 参数，通过 `pipe:0` 读取上一阶段的输出，通过 `pipe:1` 输出到下一阶段 */
+  args: string[]
+  /** 该阶段输出文件已存在时的处理策略 */
+  overwrite?: OverwritePolicy
+}
+
+/** 管道选项 */
+export type PipelineOptions = Omit<HelperOptions, 'overwrite' | 'preserveTimestamps' | 'batchId'>
+
+/** 管道结果 */
+export interface PipelineOutput {
+  jobId: string
+  /** 各阶段的退出码，被终止的阶段为 null */
+  exitCodes: Array<number | null>
+}
+
+/**
+ * 运行多个 FFmpeg 进程，前一阶段的标准输出通过操作系统管道连接到后一阶段的标准输入
+ * 
+ * 整个管道是一个任务，`cancel(jobId)` 会终止所有阶段，进度由第一个阶段报告。
+ * 任一阶段失败时终止其余阶段，并抛出包含阶段序号和错误原因的异常。
+ * 
+ * @param stages 按顺序连接的各个阶段
+ * @param options 可选的任务选项
+ * @returns 各阶段的退出码
+ * 
+ * @example
+ * ```typescript
+ * import { runPipeline } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await runPipeline([
+ *   { args: ['-i', 'input.mkv', '-f', 'yuv4mpegpipe', 'pipe:1'] },
+ *   { args: ['-f', 'yuv4mpegpipe', '-i', 'pipe:0', '-vf', 'hqdn3d', '-f', 'yuv4mpegpipe', 'pipe:1'] },
+ *   { args: ['-f', 'yuv4mpegpipe', '-i', 'pipe:0', '-c:v', 'libx264', 'output.mp4'], overwrite: 'overwrite' }
+ * ], { onProgress: (progress) => console.log(progress.percentage) })
+ * ```
+ */
+export async function runPipeline(
+  stages: PipelineStage[],
+  options?: PipelineOptions
+): Promise<PipelineOutput> {
+  return await runHelper<PipelineOutput>('run_pipeline', { stages }, options)
+}
+
+/**
+ * 运行宿主应用在插件构建器中注册的命令模板
+ * 
+ * 只授予 `use-ffmpeg:templates` 权限集时，前端只能通过模板运行命令。参数按模板声明的类型校验，
+ * 未注册的模板、未声明或缺少的参数、类型不符的参数都会抛出 `invalid_argument` 异常。
+ * 
+ * @param name 模板名称
+ * @param params 模板参数，路径和文本为字符串，数字为 number
+ * @param options 可选的任务选项
+ * 
+ * @example
+ * ```typescript
+ * import { runTemplate } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await runTemplate('thumbnail', { time: 12.5, input: 'input.mp4', output: 'thumb.jpg' })
+ * ```
+ */
+export async function runTemplate(
+  name: string,
+  params: Record<string, string | number> = {},
+  options?: HelperOptions
+): Promise<ExecuteResponse> {
+  return await runHelper<ExecuteResponse>('run_template', { name, params }, options)
+}
@@ -12,8 +12,8 @@
  * FFmpeg 会被下载到: app_data_dir/bin/{platform}/ffmpeg[.exe]
  * 
  * 默认下载地址：
- * - macOS: https://evermeet.cx/ffmpeg/ffmpeg-8.0.zip
- * - Windows: https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-n8.0-latest-win64-gpl-8.0.zip
+ * - macOS: evermeet.cx 当前的正式版本（或快照），下载前通过 `https://evermeet.cx/ffmpeg/info/ffmpeg/release` 解析下载地址
+ * - Windows: BtbN/FFmpeg-Builds 发布中最新版本的 GPL 构建，下载前通过 GitHub API 解析具体文件并校验 SHA-256
  * 
  * 使用示例：
  * 
@@ -40,12 +40,91 @@
 import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 
+export * from './helpers'
+export * from './client'
+
 /**
  * 下载配置接口
  */
 export interface DownloadConfig {
   url: string
   executable_path: string
+  /** `executable_path` 对应的工具，默认 `ffmpeg`（同时解压同目录下的 ffprobe、ffplay），设置为其他工具时只安装该工具 */
+  tool?: Tool
+  /** 从已安装版本升级的增量补丁，没有匹配的补丁或打补丁失败时自动改为完整下载 */
+  patches?: DownloadPatch[]
+  /** 下载文件的 SHA-256（十六进制），设置后下载完成时校验，不一致时安装失败 */
+  sha256?: string
+}
+
+/**
+ * 增量补丁（`zstd --patch-from=<旧可执行文件> <新可执行文件>` 生成）
+ */
+export interface DownloadPatch {
+  /** 补丁适用的已安装版本，与 `<tool> -version` 输出中 `version` 之后的版本号比较（如 `n7.1`） */
+  fromVersion: string
+  /** 补丁 URL */
+  url: string
+  /** 补丁对应的工具，默认 `ffmpeg` */
+  tool?: Tool
+  /** 解码得到的新可执行文件的 SHA-256（十六进制），不一致时不运行该文件并改为完整下载 */
+  sha256: string
+}
+
+/**
+ * FFmpeg 套件中的工具
+ */
+export type Tool = 'ffmpeg' | 'ffprobe' | 'ffplay'
+
+/**
+ * 单个工具的检查结果
+ */
+export interface ToolStatus {
+  available: boolean
+  path?: string
+  version?: string
+  /** 可执行文件是否被杀毒软件拦截而无法运行，加入排除列表后可以调用 {@link reinstall} */
+  blockedByAntivirus?: boolean
+  /** `-version` 是否在 `timeoutMs` 内没有完成，此时 `available` 为 false，稍后可以重新检查 */
+  timedOut?: boolean
+  /** Authenticode 签名信息，仅 Windows，文件没有签名时为 null */
+  signature?: SignatureInfo | null
+}
+
+/**
+ * 可执行文件的 Authenticode 签名信息
+ */
+export interface SignatureInfo {
+  /** 签名是否有效且证书链到受信任的根证书 */
+  trusted: boolean
+  /** 签名者证书的名称 */
+  signer: string | null
+  /** 签名者证书的颁发者 */
+  issuer: string | null
+  /** `WinVerifyTrust` 的结果（HRESULT），有效时为 0 */
+  status: number
+}
+
+/**
+ * 检查选项
+ */
+export interface CheckOptions {
+  /** 等待每个工具 `-version` 完成的最长时间（毫秒），默认不限制 */
+  timeoutMs?: number
+  /** 超过该时间（毫秒）仍未完成时发送 `use-ffmpeg://check-slow` 事件，默认为 1000 */
+  slowThresholdMs?: number
+}
+
+/**
+ * 检查较慢（`use-ffmpeg://check-slow` 事件）
+ * 
+ * 安装后首次运行时杀毒软件扫描、磁盘唤醒可能让检查耗时数秒，超过 `slowThresholdMs` 仍未完成时发送一次。
+ */
+export interface CheckSlow {
+  /** 已经等待的时间（毫秒） */
+  elapsedMs: number
+  /** 本次检查的超时时间（毫秒） */
+  timeoutMs?: number
 }
 
 /**
@@ -55,44 +134,608 @@ export interface CheckResponse {
   available: boolean
   path?: string
   version?: string
+  tools: Record<Tool, ToolStatus>
 }
 
 /**
  * 下载响应接口
  */
 export interface DownloadResponse {
+  downloadId: string
   success: boolean
+  /** 是否通过增量补丁完成升级 */
+  patched: boolean
+  /** 消息代码，不随语言变化 */
+  messageCode?: MessageCode
   path?: string
   message?: string
+  /** 跟随重定向后实际下载的地址，通过增量补丁升级时为空 */
+  finalUrl?: string
+  /** 实际提供文件的服务器（响应头 `Server`） */
+  server?: string
+}
+
+/**
+ * 输出文件已存在时的处理策略
+ * 
+ * - `always` - 直接覆盖（`-y`）
+ * - `never` - 不覆盖，任务以失败结束（`-n`）
+ * - `error` - 不覆盖，并抛出 `Output file already exists: <path>` 错误
+ */
+export type OverwritePolicy = 'always' | 'never' | 'error'
+
+/**
+ * 调用方附加在任务上的标签，原样出现在该任务的所有进度和生命周期事件中
+ */
+export interface JobTags {
+  /** 任务标签 */
+  tag?: string
+  /** 任意 JSON 元数据 */
+  meta?: unknown
+}
+
+/**
+ * 任务的来源，与 `JobTags` 一起出现在任务信息和事件中，由插件设置
+ */
+export interface JobOrigin {
+  /** 发起任务的窗口标签，Rust 端发起的任务为空 */
+  window?: string
+}
+
+/**
+ * 执行选项
+ */
+export interface ExecuteOptions extends JobTags {
+  /** 可选的任务 ID，未提供时自动生成 */
+  jobId?: string
+  /** 下载正在进行时是否等待其完成，否则会抛出 `FFmpeg download is in progress` 错误 */
+  waitForInstall?: boolean
+  /** 任务失败或被取消时是否删除写了一半的输出文件（任务开始前已存在且未被修改的文件会被保留） */
+  cleanupOutputsOnFailure?: boolean
+  /** 输出文件已存在时的处理策略，未设置时不注入 `-y` / `-n`，与 `args` 中的标志矛盾时抛出错误 */
+  overwrite?: OverwritePolicy
+  /** 编码线程数（`-threads`），覆盖插件配置中的 `defaultThreads` */
+  threads?: number
+  /** 任务结束时将 {@link JobFinished} 以 JSON POST 到该地址（在插件配置的 `jobFinishedWebhook` 之外），源必须在插件配置的 `allowedWebhookOrigins` 中 */
+  webhook?: string
+  /** 任务结束时发送系统通知，需要在 Rust 端启用 `notification` feature 并注册 `tauri-plugin-notification` */
+  notifyOnComplete?: boolean
+  /** 所属批次，同一批次的任务会汇总发送 `use-ffmpeg://batch-progress` 和 `use-ffmpeg://batch-finished` 事件 */
+  batchId?: string
+  /** 将标准输入保持为管道，以便通过 {@link sendCommand} 发送交互命令 */
+  interactive?: boolean
+  /** 可选的任务进度回调 */
+  onProgress?: (progress: EncodeProgress) => void
+}
+
+/**
+ * 任务进度接口
+ * 
+ * 输入没有时长（直播源、管道）时 `percentage` 为空、`indeterminate` 为 `true`，
+ * 此时可以使用 `sizeBytes` / `outTimeSecs` 展示已处理的数据量。
+ */
+export interface EncodeProgress extends JobTags, JobOrigin {
+  jobId: string
+  frame?: number
+  fps?: number
+  sizeBytes?: number
+  outTimeSecs?: number
+  durationSecs?: number
+  /** 进度百分比（如果已知，单调不减） */
+  percentage?: number
+  /** 平滑后的处理速度（相对实时的倍数） */
+  speed?: number
+  elapsedSecs: number
+  etaSecs?: number
+  indeterminate: boolean
 }
 
 /**
  * 执行响应接口
  */
 export interface ExecuteResponse {
+  jobId: string
   success: boolean
   stdout: string
   stderr: string
   exitCode?: number
+  /** 资源占用汇总 */
+  stats?: JobResourceStats
+  /** 不影响运行、但很可能有误的参数（如没有 `-i` 输入） */
+  warnings: string[]
+}
+
+/**
+ * 后台任务选项
+ */
+export interface EnqueueOptions extends Omit<ExecuteOptions, 'onProgress' | 'waitForInstall'> {
+  /** 延迟开始的毫秒数 */
+  startAfter?: number
+  /** 最早开始时间（Unix 时间戳，毫秒） */
+  notBefore?: number
+  /** 只在没有其他任务运行时开始 */
+  onlyWhenIdle?: boolean
 }
 
 /**
  * 下载进度接口
  */
 export interface DownloadProgress {
+  downloadId: string
   downloaded: number
   total?: number
   percentage?: number
 }
 
+/**
+ * 任务状态
+ */
+export type JobStatus = 'scheduled' | 'queued' | 'running'
+
+/**
+ * 任务信息
+ */
+export interface JobInfo extends JobTags, JobOrigin {
+  id: string
+  args: string[]
+  /** 等待计划时间、排队等待运行名额（`maxConcurrentJobs`）或正在运行 */
+  status: JobStatus
+  /** 提交时间（Unix 时间戳，毫秒） */
+  queuedAt: number
+  /** 开始运行时间（Unix 时间戳，毫秒） */
+  startedAt?: number
+  /** 计划的最早开始时间（Unix 时间戳，毫秒） */
+  notBefore?: number
+  /** 是否只在没有其他任务运行时开始 */
+  onlyWhenIdle: boolean
+  /** 所属批次 */
+  batchId?: string
+}
+
+/**
+ * 任务结束事件
+ */
+export interface JobFinished extends JobTags, JobOrigin {
+  jobId: string
+  success: boolean
+  exitCode?: number
+  /** 是否被取消 */
+  cancelled: boolean
+  /** 资源占用汇总 */
+  stats?: JobResourceStats
+  /** 任务未能运行或运行出错时的错误信息 */
+  error?: string
+  /** 任务未能运行或运行出错时的错误代码，不随语言变化 */
+  errorCode?: ErrorCode
+  /** 所属批次 */
+  batchId?: string
+}
+
+/**
+ * 批次进度（`use-ffmpeg://batch-progress` 事件）
+ */
+export interface BatchProgress {
+  batchId: string
+  /** 已提交的任务数 */
+  total: number
+  /** 已结束的任务数 */
+  completed: number
+  /** 失败或被取消的任务数 */
+  failed: number
+  /** 所有任务的平均进度百分比，已结束的任务按 100 计算，进度未知的任务按 0 计算 */
+  percentage: number
+}
+
+/**
+ * 批次中第一个失败的任务
+ */
+export interface BatchFailure {
+  jobId: string
+  exitCode?: number
+  error?: string
+  errorCode?: ErrorCode
+}
+
+/**
+ * 批次结束（`use-ffmpeg://batch-finished` 事件），批次中所有已提交的任务都结束后发送
+ */
+export interface BatchFinished {
+  batchId: string
+  total: number
+  succeeded: number
+  failed: number
+  cancelled: number
+  /** 是否所有任务都成功 */
+  success: boolean
+  /** 第一个失败（不包括被取消）的任务 */
+  firstFailure?: BatchFailure
+}
+
+/**
+ * 任务资源占用采样（`use-ffmpeg://job-stats` 事件，每秒一次）
+ */
+export interface JobStatsSample extends JobTags, JobOrigin {
+  jobId: string
+  /** CPU 占用百分比（多核时可能超过 100） */
+  cpuPercent: number
+  /** 常驻内存（字节） */
+  rssBytes: number
+}
+
+/**
+ * 任务资源占用汇总
+ */
+export interface JobResourceStats {
+  samples: number
+  avgCpuPercent: number
+  peakCpuPercent: number
+  peakRssBytes: number
+}
+
+/**
+ * 事件内容的结构版本
+ * 
+ * 所有 `use-ffmpeg://` 事件都带有 `schemaVersion` 字段。字段只增不减，删除或改变已有字段的含义时才会提升版本号，
+ * 可以据此判断插件升级后事件结构是否仍兼容。
+ */
+export const EVENT_SCHEMA_VERSION = 1
+
+/**
+ * 带有结构版本的事件内容，原有字段保持在顶层
+ */
+export type EventPayload<T> = T & { schemaVersion: number }
+
+/**
+ * 响应中的消息代码
+ */
+export type MessageCode = 'downloaded' | 'patched' | 'not_installed' | 'removed' | 'cleared'
+
+/**
+ * 错误代码
+ */
+export type ErrorCode =
+  | 'io'
+  | 'download'
+  | 'ffmpeg_not_found'
+  | 'extraction'
+  | 'not_an_archive'
+  | 'blocked_by_antivirus'
+  | 'untrusted_binary'
+  | 'http'
+  | 'zip'
+  | 'command_execution'
+  | 'unsupported_platform'
+  | 'no_default_source'
+  | 'system_ffmpeg_mode'
+  | 'provisioned_mode'
+  | 'jobs_running'
+  | 'install_in_progress'
+  | 'no_install_in_progress'
+  | 'download_exists'
+  | 'download_not_found'
+  | 'download_pending'
+  | 'cancelled'
+  | 'job_exists'
+  | 'queue_full'
+  | 'quota_exceeded'
+  | 'job_not_found'
+  | 'job_not_interactive'
+  | 'invalid_config'
+  | 'invalid_argument'
+  | 'output_exists'
+  | 'unknown_version'
+
+/**
+ * 插件配置了 `locale` 时抛出的错误对象，未配置时错误为英文字符串
+ */
+export interface PluginError {
+  code: ErrorCode
+  /** 对应语言的错误信息 */
+  message: string
+}
+
+/**
+ * 取消操作时 `execute` / `download` 抛出的错误信息
+ */
+export const CANCELLED_ERROR = 'Operation cancelled'
+
+/**
+ * 获取错误代码，错误不是插件抛出的对象形式（未配置 `locale`）时返回 `undefined`
+ */
+export function errorCode(error: unknown): ErrorCode | undefined {
+  if (typeof error === 'object' && error !== null && 'code' in error) {
+    return (error as PluginError).code
+  }
+  return undefined
+}
+
+/**
+ * 判断错误是否由用户取消引起
+ * 
+ * @example
+ * ```typescript
+ * import { execute, isCancelled } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * try {
+ *   await execute(['-i', 'input.mp4', 'output.mp4'], { jobId: 'convert' })
+ * } catch (error) {
+ *   if (!isCancelled(error)) {
+ *     console.error('转换失败:', error)
+ *   }
+ * }
+ * ```
+ */
+export function isCancelled(error: unknown): boolean {
+  return error === CANCELLED_ERROR || errorCode(error) === 'cancelled'
+}
+
+/**
+ * 内置下载源的构建渠道：`release` 为正式发布版本，`git-master` 为 master 分支的构建（包含最新的编码器，但未经充分测试）
+ */
+export type BuildChannel = 'release' | 'git-master'
+
+/**
+ * 下载选项
+ */
+export interface DownloadOptions {
+  /** 可选的下载 ID，未提供时自动生成，用于区分同时进行的下载 */
+  downloadId?: string
+  /** 未传入下载配置时使用的构建渠道，覆盖插件配置中的 `provider.channel` */
+  channel?: BuildChannel
+  /**
+   * 要安装的工具，默认安装 `config.tool` 指定的工具（`ffmpeg` 时同时安装同目录下的 ffprobe、ffplay）
+   *
+   * 例如 `['ffprobe']` 只安装 ffprobe，已安装的其他工具保持不变。
+   */
+  tools?: Tool[]
+  /** 有任务正在运行时是否先终止它们再覆盖安装 */
+  force?: boolean
+  /**
+   * 因网络未连接而失败时，是否在网络恢复后自动重试
+   *
+   * 此时抛出代码为 `download_pending` 的错误，重试开始时触发 `use-ffmpeg://download-resumed` 事件。
+   */
+  retryWhenOnline?: boolean
+}
+
+/**
+ * 下载开始事件（`use-ffmpeg://download-started`）
+ */
+export interface DownloadStarted {
+  downloadId: string
+  url: string
+}
+
+/**
+ * 下载即将开始传输事件（`use-ffmpeg://download-starting`），在确认地址可以访问之后发送
+ */
+export interface DownloadStarting {
+  downloadId: string
+  url: string
+  /** 文件总大小（字节），服务器未提供时为 null */
+  totalBytes: number | null
+}
+
+/**
+ * 等待网络恢复的下载开始重试事件（`use-ffmpeg://download-resumed`）
+ */
+export interface DownloadResumed {
+  downloadId: string
+}
+
+/**
+ * 下载结束事件（`use-ffmpeg://download-finished`）
+ */
+export interface DownloadFinished {
+  downloadId: string
+  success: boolean
+  cancelled: boolean
+  error?: string
+  /** 错误代码，不随语言变化 */
+  errorCode?: ErrorCode
+}
+
+/**
+ * 队列排空（`use-ffmpeg://queue-drained` 事件）
+ * 
+ * 后台队列从 `queueLowWatermark` 以上减少到以下时发送，需要在插件配置中设置 `maxQueueLength`。
+ */
+export interface QueueDrained {
+  /** 队列中剩余的尚未开始的任务数 */
+  queued: number
+  maxQueueLength?: number
+}
+
+/**
+ * 恢复后台队列时被拒绝的任务（`use-ffmpeg://queue-job-rejected` 事件）
+ * 
+ * 保存的任务因配置变化（如 `allowedProtocols`、`allowedWebhookOrigins`）等原因无法恢复时发送，
+ * 该任务同时从 `queue.json` 中移除。
+ */
+export interface QueueJobRejected extends JobTags, JobOrigin {
+  jobId: string
+  /** 被拒绝的原因 */
+  error: string
+  /** 错误代码，不随语言变化 */
+  errorCode: ErrorCode
+}
+
+/**
+ * FFmpeg 的来源
+ * 
+ * - `managed` - 由插件下载和管理
+ * - `system` - 使用 PATH 中的系统 FFmpeg（`systemFfmpeg`）
+ * - `provisioned` - 外部部署的 FFmpeg（`ffmpegPath`）
+ * - `override` - 开发调试用的路径（`TAURI_FFMPEG_PATH` 或 `pathOverride`）
+ */
+export type FfmpegSource = 'managed' | 'system' | 'provisioned' | 'override'
+
+/**
+ * 就绪报告中的策略配置，与插件配置中的同名字段对应
+ */
+export interface ReadyPolicies {
+  maxConcurrentJobs?: number
+  maxQueueLength?: number
+  allowedProtocols?: string[]
+  storageQuotaMb?: number
+  requireSignedBinaries: boolean
+  killJobsOnWindowClose: boolean
+  auditLog: boolean
+  /** 是否配置了 `powerAware` */
+  powerAware: boolean
+  shared: boolean
+  compressBinaries: boolean
+  simulate: boolean
+}
+
+/**
+ * 就绪报告（`use-ffmpeg://ready` 事件）
+ * 
+ * 插件初始化后发送一次，`install` 为初始化时的检查结果。
+ */
+export interface ReadyReport {
+  pluginVersion: string
+  /** 平台（`macos`、`windows`、`linux` 等），不支持的平台为 null */
+  platform: string | null
+  /** CPU 架构（`x86_64`、`aarch64` 等） */
+  arch: string
+  source: FfmpegSource
+  /** 初始化时的检查结果，检查失败时为 null */
+  install: CheckResponse | null
+  /** 插件配置中的 `defaultDownload.url`，未设置时使用内置下载源 */
+  defaultDownloadUrl: string | null
+  /** 内置下载源的选项（插件配置中的 `provider`） */
+  provider: {
+    channel?: BuildChannel
+    btbnTag?: string
+    evermeetChannel?: 'release' | 'snapshot'
+  }
+  policies: ReadyPolicies
+}
+
+/**
+ * 电源感知的限流状态（`use-ffmpeg://throttle-changed` 事件）
+ * 
+ * 需要在插件配置中启用 `powerAware`。
+ */
+export interface ThrottleState {
+  onBattery: boolean
+  thermalPressure: boolean
+  /** 队列是否暂停（正在运行的任务不受影响） */
+  paused: boolean
+  /** 限流后的最大并发数 */
+  maxConcurrentJobs?: number
+}
+
+/**
+ * 删除选项
+ */
+export interface RemoveOptions {
+  /** 有任务正在运行时是否先终止它们再删除 */
+  force?: boolean
+  /** 只删除指定的工具，`target` 为 `all` 时有效，默认删除整个安装目录 */
+  tools?: Tool[]
+  /** 要删除的内容，默认 `all` */
+  target?: RemoveTarget
+}
+
+/**
+ * `remove` 删除的内容
+ *
+ * - `ffmpeg` / `ffprobe` - 只删除对应的可执行文件
+ * - `cache` - 临时文件和压缩存储模式下解压的可执行文件
+ * - `logs` - FFmpeg 的 `-report` 日志
+ * - `all` - 安装目录、缓存和日志
+ */
+export type RemoveTarget = 'ffmpeg' | 'ffprobe' | 'cache' | 'logs' | 'all'
+
 /**
  * 删除响应接口
  */
 export interface DeleteResponse {
   success: boolean
+  /** 消息代码，不随语言变化 */
+  messageCode?: MessageCode
   message?: string
 }
 
+/**
+ * 临时文件清理结果
+ */
+export interface PurgeTempResponse {
+  /** 删除的文件数 */
+  removed: number
+  /** 释放的字节数 */
+  freedBytes: number
+}
+
+/**
+ * 存储配额状态
+ */
+export interface QuotaStatus {
+  /** 配额（字节），未设置 `storageQuotaMb` 时为 null */
+  limitBytes: number | null
+  /** 已使用的字节数 */
+  usedBytes: number
+  /** 文件数 */
+  files: number
+}
+
+/**
+ * 审计日志中的操作，`action` 为操作类型
+ */
+export type AuditAction =
+  | {
+      /** 下载并安装可执行文件 */
+      action: 'download'
+      downloadId: string
+      url: string
+      /** 是否为请求中提供的地址，而不是默认下载地址 */
+      customUrl: boolean
+      sha256: string | null
+      tools: Tool[] | null
+    }
+  | {
+      /** 删除可执行文件、缓存或日志 */
+      action: 'remove'
+      target: RemoveTarget
+      tools: Tool[] | null
+    }
+  | {
+      /** 以任意参数运行 FFmpeg */
+      action: 'execute'
+      /** `execute`、`enqueue` 或 `run_pipeline` */
+      command: string
+      jobId: string
+      args: string[]
+    }
+  | {
+      /** 越过插件默认策略的选项，如 `force`、`webhook` */
+      action: 'policy-override'
+      policy: string
+      detail: string
+    }
+
+/**
+ * 审计日志中的一条记录
+ */
+export type AuditEntry = AuditAction & {
+  /** 记录时间（Unix 毫秒） */
+  time: number
+  /** 发起操作的窗口，由 Rust 端发起时为 null */
+  window: string | null
+}
+
+/**
+ * 读取审计日志的选项
+ */
+export interface AuditLogOptions {
+  /** 只返回该时间（Unix 毫秒）之后的记录 */
+  since?: number
+  /** 最多返回的记录数，超出时返回最新的部分 */
+  limit?: number
+}
+
 /**
  * 默认下载配置
  */
@@ -110,6 +753,10 @@ export const DEFAULT_CONFIGS: Record<string, DownloadConfig> = {
 /**
  * 检查 FFmpeg 是否已安装并可用
  * 
+ * 结果会被缓存，之后的调用立即返回而不再运行可执行文件，下载、重新安装、删除后缓存失效，
+ * 也可以调用 {@link recheck} 重新检查。有工具超时的结果不会被缓存。
+ * 
+ * @param options 可选的超时时间和 `check-slow` 事件的阈值
  * @returns 检查结果，包括是否可用、路径和版本信息
  * 
  * @example
@@ -120,6 +767,7 @@ export const DEFAULT_CONFIGS: Record<string, DownloadConfig> = {
  * console.log('是否可用:', result.available)
  * console.log('路径:', result.path)
  * console.log('版本:', result.version)
+ * console.log('ffprobe 是否可用:', result.tools.ffprobe.available)
  * 
  * if (result.available) {
  *   console.log('FFmpeg 已安装，可以使用')
@@ -128,8 +776,68 @@ export const DEFAULT_CONFIGS: Record<string, DownloadConfig> = {
  * }
  * ```
  */
-export async function check(): Promise<CheckResponse> {
-  return await invoke<CheckResponse>('plugin:use-ffmpeg|check')
+export async function check(options?: CheckOptions): Promise<CheckResponse> {
+  return await invoke<CheckResponse>('plugin:use-ffmpeg|check', {
+    payload: options
+  })
+}
+
+/**
+ * 清除缓存的检查结果后重新检查
+ * 
+ * {@link check} 的结果会被缓存，下载、重新安装、删除后自动失效；FFmpeg 被插件之外的程序修改
+ * （例如共享目录中的其他应用、用户手动替换文件）后可以调用该函数。
+ * 
+ * @param options 与 {@link check} 相同
+ */
+export async function recheck(options?: CheckOptions): Promise<CheckResponse> {
+  return await invoke<CheckResponse>('plugin:use-ffmpeg|recheck', {
+    payload: options
+  })
+}
+
+/**
+ * 已安装的 FFmpeg 是否不低于指定版本
+ * 
+ * 版本号按数值比较（`n6.1.1`、`7.0.2-static` 等都取前面的数字部分），master 分支构建的开发版本
+ * （如 `N-117676-g...`）满足任何版本要求。FFmpeg 不可用时以 `ffmpeg_not_found` 拒绝，
+ * 无法识别已安装的版本号时以 `unknown_version` 拒绝。
+ * 
+ * @param version 要求的最低版本，例如 `'6.0'`
+ * 
+ * @example
+ * ```typescript
+ * import { isAtLeast } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * if (await isAtLeast('6.0')) {
+ *   enableVulkanFilters()
+ * }
+ * ```
+ */
+export async function isAtLeast(version: string): Promise<boolean> {
+  return await invoke<boolean>('plugin:use-ffmpeg|is_at_least', {
+    payload: { version }
+  })
+}
+
+/**
+ * 获取插件的就绪报告：平台、架构、FFmpeg 来源、初始化时的安装状态、下载源和策略
+ * 
+ * 内容与 `use-ffmpeg://ready` 事件相同。插件初始化时发送该事件，通常早于页面加载，
+ * 页面启动时调用该函数即可一次获得初始状态。
+ * 
+ * @example
+ * ```typescript
+ * import { readyReport } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { platform, source, install } = await readyReport()
+ * if (source === 'managed' && !install?.available) {
+ *   showInstallPrompt(platform)
+ * }
+ * ```
+ */
+export async function readyReport(): Promise<ReadyReport> {
+  return await invoke<ReadyReport>('plugin:use-ffmpeg|ready_report')
 }
 
 /**
@@ -137,6 +845,7 @@ export async function check(): Promise<CheckResponse> {
  * 
  * @param config 可选的下载配置，如果不提供则使用默认配置
  * @param onProgress 可选的进度回调函数
+ * @param options 可选的下载选项
  * @returns 下载结果
  * 
  * @example
@@ -159,6 +868,12 @@ export async function check(): Promise<CheckResponse> {
  *   }
  * })
  * 
+ * // 同时下载 ffmpeg 和单独发布的 ffprobe
+ * await Promise.all([
+ *   download(ffmpegConfig, undefined, { downloadId: 'ffmpeg' }),
+ *   download({ ...ffprobeConfig, tool: 'ffprobe' }, undefined, { downloadId: 'ffprobe' })
+ * ])
+ * 
  * // 使用自定义配置下载
  * await download({
  *   url: 'https://your-custom-url.com/ffmpeg.zip',
@@ -170,20 +885,66 @@ export async function check(): Promise<CheckResponse> {
  */
 export async function download(
   config?: DownloadConfig,
-  onProgress?: (progress: DownloadProgress) => void
+  onProgress?: (progress: DownloadProgress) => void,
+  options?: DownloadOptions
+): Promise<DownloadResponse> {
+  return await runDownload('download', config, onProgress, options)
+}
+
+/**
+ * 删除已安装的可执行文件后重新下载
+ * 
+ * 用于杀毒软件隔离或锁定了可执行文件（错误代码 `blocked_by_antivirus`）、用户将安装目录加入排除列表之后恢复。
+ * 只删除 `options.tools` 指定的工具（默认全部），不尝试增量补丁，其余参数与 {@link download} 相同。
+ * 
+ * @example
+ * ```typescript
+ * import { download, errorCode, reinstall } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * try {
+ *   await download()
+ * } catch (error) {
+ *   if (errorCode(error) === 'blocked_by_antivirus') {
+ *     await showAntivirusHelp()
+ *     await reinstall(undefined, undefined, { force: true })
+ *   }
+ * }
+ * ```
+ */
+export async function reinstall(
+  config?: DownloadConfig,
+  onProgress?: (progress: DownloadProgress) => void,
+  options?: DownloadOptions
+): Promise<DownloadResponse> {
+  return await runDownload('reinstall', config, onProgress, options)
+}
+
+/**
+ * 调用下载命令，设置了 `onProgress` 时监听本次下载的进度
+ */
+async function runDownload(
+  command: 'download' | 'reinstall',
+  config?: DownloadConfig,
+  onProgress?: (progress: DownloadProgress) => void,
+  options?: DownloadOptions
 ): Promise<DownloadResponse> {
   let unlisten: UnlistenFn | undefined
+  const downloadId = options?.downloadId ?? crypto.randomUUID()
 
   if (onProgress) {
-    unlisten = await listen<DownloadProgress>('use-ffmpeg://download-progress', (event) => {
-      onProgress(event.payload)
+    unlisten = await listen<EventPayload<DownloadProgress>>('use-ffmpeg://download-progress', (event) => {
+      if (event.payload.downloadId === downloadId) {
+        onProgress(event.payload)
+      }
     })
   }
 
   try {
-    return await invoke<DownloadResponse>('plugin:use-ffmpeg|download', {
+    return await invoke<DownloadResponse>(`plugin:use-ffmpeg|${command}`, {
       payload: {
-        config
+        config,
+        ...options,
+        downloadId
       }
     })
   } finally {
@@ -193,10 +954,22 @@ export async function download(
   }
 }
 
+/**
+ * 取消正在进行的下载
+ * 
+ * 被取消的 `download` 调用会抛出 {@link CANCELLED_ERROR}。
+ * 
+ * @param downloadId 要取消的下载 ID，未提供时取消所有正在进行的下载
+ */
+export async function cancelDownload(downloadId?: string): Promise<void> {
+  await invoke('plugin:use-ffmpeg|cancel_download', { payload: { downloadId } })
+}
+
 /**
  * 执行 FFmpeg 命令
  * 
  * @param args FFmpeg 命令参数数组（不包含 `ffmpeg` 本身）
+ * @param options 可选的执行选项
  * @returns 执行结果
  * 
  * @example
@@ -224,6 +997,17 @@ export async function download(
  *   console.error('退出码:', result.exitCode)
  * }
  * 
+ * // 带进度回调的转换
+ * await execute(['-i', 'input.mp4', 'output.webm'], {
+ *   onProgress: (progress) => {
+ *     if (progress.indeterminate) {
+ *       console.log(`已处理: ${progress.outTimeSecs} 秒`)
+ *     } else {
+ *       console.log(`进度: ${progress.percentage?.toFixed(1)}%`)
+ *     }
+ *   }
+ * })
+ * 
  * // 提取音频
  * await execute([
  *   '-i', 'video.mp4',
@@ -233,17 +1017,270 @@ export async function download(
  * ])
  * ```
  */
-export async function execute(args: string[]): Promise<ExecuteResponse> {
-  return await invoke<ExecuteResponse>('plugin:use-ffmpeg|execute', {
+export async function execute(args: string[], options?: ExecuteOptions): Promise<ExecuteResponse> {
+  const { onProgress, ...rest } = options ?? {}
+  const jobId = rest.jobId ?? (onProgress ? crypto.randomUUID() : undefined)
+  let unlisten: UnlistenFn | undefined
+
+  if (onProgress) {
+    unlisten = await listen<EventPayload<EncodeProgress>>('use-ffmpeg://job-progress', (event) => {
+      if (event.payload.jobId === jobId) {
+        onProgress(event.payload)
+      }
+    })
+  }
+
+  try {
+    return await invoke<ExecuteResponse>('plugin:use-ffmpeg|execute', {
+      payload: {
+        args,
+        ...rest,
+        jobId
+      }
+    })
+  } finally {
+    if (unlisten) {
+      unlisten()
+    }
+  }
+}
+
+/**
+ * 以 shell 风格的命令行执行 FFmpeg 命令，适合直接使用从教程中复制的命令
+ * 
+ * 命令可以以 `ffmpeg` 开头，支持单双引号、反斜杠转义和以 `\` 结尾的续行，不展开环境变量和通配符。
+ * 管道（`|`）和重定向（`>`、`2>&1` 等）无法执行，包含时抛出 `invalid_argument` 异常。
+ * 
+ * @param command 命令行
+ * @param options 可选的执行选项，与 {@link execute} 相同
+ * @returns 执行结果
+ * 
+ * @example
+ * ```typescript
+ * import { executeStr } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * await executeStr('ffmpeg -i "my video.mp4" -vf "scale=1280:-2" -c:a copy out.mp4')
+ * ```
+ */
+export async function executeStr(command: string, options?: ExecuteOptions): Promise<ExecuteResponse> {
+  const { onProgress, ...rest } = options ?? {}
+  const jobId = rest.jobId ?? (onProgress ? crypto.randomUUID() : undefined)
+  let unlisten: UnlistenFn | undefined
+
+  if (onProgress) {
+    unlisten = await listen<EventPayload<EncodeProgress>>('use-ffmpeg://job-progress', (event) => {
+      if (event.payload.jobId === jobId) {
+        onProgress(event.payload)
+      }
+    })
+  }
+
+  try {
+    return await invoke<ExecuteResponse>('plugin:use-ffmpeg|execute_str', {
+      payload: {
+        command,
+        ...rest,
+        jobId
+      }
+    })
+  } finally {
+    if (unlisten) {
+      unlisten()
+    }
+  }
+}
+
+/**
+ * 将 FFmpeg 任务加入后台队列
+ * 
+ * 任务由插件调度，立即返回任务信息，WebView 重新加载后任务仍会按计划运行。
+ * 结果通过 `use-ffmpeg://job-finished` 事件（{@link JobFinished}）通知。
+ * 
+ * @param args FFmpeg 命令参数
+ * @param options 可选的计划时间和执行选项
+ * @returns 任务信息
+ * 
+ * @example
+ * ```typescript
+ * import { enqueue } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * // 一小时后在空闲时转码
+ * const job = await enqueue(['-i', 'input.mp4', 'output.webm'], {
+ *   startAfter: 60 * 60 * 1000,
+ *   onlyWhenIdle: true
+ * })
+ * console.log(job.id, job.status)
+ * ```
+ */
+export async function enqueue(args: string[], options?: EnqueueOptions): Promise<JobInfo> {
+  return await invoke<JobInfo>('plugin:use-ffmpeg|enqueue', {
     payload: {
-      args
+      args,
+      ...options
     }
   })
 }
 
+/**
+ * 恢复上次保存的、尚未开始的后台任务
+ * 
+ * 通过 {@link enqueue} 加入、尚未开始的任务会保存到磁盘，应用崩溃或更新重启后可以恢复。
+ * 也可以在插件配置中设置 `restoreQueue: true` 在启动时自动恢复。
+ * 
+ * @returns 恢复的任务列表
+ * 
+ * @example
+ * ```typescript
+ * import { restoreQueue } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const jobs = await restoreQueue()
+ * console.log('恢复了', jobs.length, '个任务')
+ * ```
+ */
+export async function restoreQueue(): Promise<JobInfo[]> {
+  return await invoke<JobInfo[]>('plugin:use-ffmpeg|restore_queue')
+}
+
+/**
+ * 终止正在运行的 FFmpeg 任务
+ * 
+ * 被终止任务的 `execute` 调用会抛出 {@link CANCELLED_ERROR}。
+ * 
+ * @param jobId 任务 ID
+ * 
+ * @example
+ * ```typescript
+ * import { execute, cancel } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const job = execute(['-i', 'input.mp4', 'output.mp4'], { jobId: 'convert' })
+ * await cancel('convert')
+ * ```
+ */
+export async function cancel(jobId: string): Promise<void> {
+  await invoke('plugin:use-ffmpeg|cancel', {
+    payload: {
+      jobId
+    }
+  })
+}
+
+/**
+ * 向交互式任务的标准输入发送命令
+ * 
+ * 任务需要以 `interactive: true` 启动。文本未以换行结尾时会自动补上。
+ * FFmpeg 的交互命令以 `c` 开头，格式为 `c<目标> <时间> <命令> <参数>`，`q` 可以让 FFmpeg 正常结束。
+ * 
+ * @param jobId 任务 ID
+ * @param text 写入标准输入的文本
+ * 
+ * @example
+ * ```typescript
+ * import { execute, sendCommand } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const job = execute(['-i', 'input.mp4', '-af', 'volume=1', 'output.mp4'], {
+ *   jobId: 'live',
+ *   interactive: true
+ * })
+ * await sendCommand('live', 'cvolume -1 volume 0.5')
+ * ```
+ */
+export async function sendCommand(jobId: string, text: string): Promise<void> {
+  await invoke('plugin:use-ffmpeg|send_command', {
+    payload: {
+      jobId,
+      text
+    }
+  })
+}
+
+/**
+ * 列出计划中、排队中和正在运行的 FFmpeg 任务
+ * 
+ * 任务开始和结束时会分别触发 `use-ffmpeg://job-started`（{@link JobInfo}）
+ * 和 `use-ffmpeg://job-finished`（{@link JobFinished}）事件。
+ * 
+ * @returns 正在运行的任务列表
+ * 
+ * @example
+ * ```typescript
+ * import { listJobs } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const jobs = await listJobs()
+ * console.log('正在运行的任务数:', jobs.length)
+ * ```
+ */
+export async function listJobs(): Promise<JobInfo[]> {
+  return await invoke<JobInfo[]>('plugin:use-ffmpeg|list_jobs')
+}
+
+/**
+ * 立即删除插件临时目录中所有未在使用的中间文件
+ * 
+ * 辅助函数遗留的中间文件平时会在超过 `tempTtlHours`（默认 24 小时）后自动清理。
+ * 
+ * @returns 删除的文件数和释放的字节数
+ * 
+ * @example
+ * ```typescript
+ * import { purgeTemp } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { removed, freedBytes } = await purgeTemp()
+ * console.log(`已删除 ${removed} 个文件，释放 ${freedBytes} 字节`)
+ * ```
+ */
+export async function purgeTemp(): Promise<PurgeTempResponse> {
+  return await invoke<PurgeTempResponse>('plugin:use-ffmpeg|purge_temp')
+}
+
+/**
+ * 获取插件临时目录和解压的可执行文件合计占用的磁盘空间，以及 `storageQuotaMb` 配额
+ * 
+ * @returns 配额状态
+ * 
+ * @example
+ * ```typescript
+ * import { getQuotaStatus } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const { usedBytes, limitBytes } = await getQuotaStatus()
+ * if (limitBytes !== null && usedBytes > limitBytes * 0.9) {
+ *   console.warn('插件存储空间即将用完')
+ * }
+ * ```
+ */
+export async function getQuotaStatus(): Promise<QuotaStatus> {
+  return await invoke<QuotaStatus>('plugin:use-ffmpeg|get_quota_status')
+}
+
+/**
+ * 读取审计日志，按时间顺序排列
+ * 
+ * 需要在插件配置中启用 `auditLog`，关闭后仍可读取之前的记录。
+ * 
+ * @param options 可选的筛选条件
+ * @returns 审计记录
+ * 
+ * @example
+ * ```typescript
+ * import { getAuditLog } from 'tauri-plugin-use-ffmpeg-api'
+ * 
+ * const entries = await getAuditLog({ limit: 100 })
+ * for (const entry of entries.filter((entry) => entry.action === 'download')) {
+ *   console.log(new Date(entry.time), entry.url, entry.sha256)
+ * }
+ * ```
+ */
+export async function getAuditLog(options?: AuditLogOptions): Promise<AuditEntry[]> {
+  return await invoke<AuditEntry[]>('plugin:use-ffmpeg|get_audit_log', {
+    payload: options ?? {}
+  })
+}
+
 /**
  * 删除已下载的 FFmpeg
  * 
+ * 有任务正在运行时会拒绝删除，除非设置 `force: true`，此时会先终止所有任务。
+ * 
+ * @param options 可选的删除选项
  * @returns 删除结果
  * 
  * @example
@@ -268,6 +1305,8 @@ export async function execute(args: string[]): Promise<ExecuteResponse> {
  * }
  * ```
  */
-export async function remove(): Promise<DeleteResponse> {
-  return await invoke<DeleteResponse>('plugin:use-ffmpeg|remove')
+export async function remove(options?: RemoveOptions): Promise<DeleteResponse> {
+  return await invoke<DeleteResponse>('plugin:use-ffmpeg|remove', {
+    payload: options
+  })
 }
@@ -0,0 +1,78 @@
+//! # Archive
+//!
+//! 根据文件开头的字节识别下载的压缩包格式，在解压之前发现下载到的其实是错误页面等情况。
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// 读取的文件开头字节数，同时用于错误信息中的摘录
+const SNIFF_LEN: usize = 256;
+
+/// 错误信息中摘录的最大字符数
+const EXCERPT_CHARS: usize = 120;
+
+/// 可以识别的压缩包格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveFormat {
+    Zip,
+    Xz,
+    Gzip,
+    SevenZip,
+}
+
+impl ArchiveFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::Xz => "xz",
+            ArchiveFormat::Gzip => "gzip",
+            ArchiveFormat::SevenZip => "7z",
+        }
+    }
+
+    fn of(bytes: &[u8]) -> Option<Self> {
+        // 空的 zip 以中央目录结束标记开头
+        if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+            Some(ArchiveFormat::Zip)
+        } else if bytes.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(ArchiveFormat::Xz)
+        } else if bytes.starts_with(&[0x1F, 0x8B]) {
+            Some(ArchiveFormat::Gzip)
+        } else if bytes.starts_with(&[b'7', b'z', 0xBC, 0xAF, 0x27, 0x1C]) {
+            Some(ArchiveFormat::SevenZip)
+        } else {
+            None
+        }
+    }
+}
+
+/// 识别文件的压缩包格式，不是已知格式时返回带有文件开头摘录的 [`Error::NotAnArchive`]
+pub(crate) fn detect(path: &Path) -> Result<ArchiveFormat> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    fs::File::open(path)?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+
+    ArchiveFormat::of(&head).ok_or_else(|| Error::NotAnArchive(excerpt(&head)))
+}
+
+/// 文件开头的可读摘录，控制字符替换为空格，例如 `<!DOCTYPE html> <html> <head>...`
+fn excerpt(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "empty file".to_string();
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let mut excerpt = String::new();
+    for (index, c) in text.chars().enumerate() {
+        if index == EXCERPT_CHARS {
+            excerpt.push_str("...");
+            break;
+        }
+        excerpt.push(if c.is_control() { ' ' } else { c });
+    }
+    excerpt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,1314 @@
+//! # Args
+//!
+//! FFmpeg 命令参数的解析工具。
+
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+use crate::models::OverwritePolicy;
+
+/// 不带值的 FFmpeg 选项，布尔选项加上 `no` 前缀（如 `-nostdin`）同样不带值
+const FLAG_OPTIONS: &[&str] = &[
+    "-y",
+    "-n",
+    "-stdin",
+    "-hide_banner",
+    "-stats",
+    "-vn",
+    "-an",
+    "-sn",
+    "-dn",
+    "-shortest",
+    "-re",
+    "-copyts",
+    "-start_at_zero",
+    "-xerror",
+    "-benchmark",
+    "-benchmark_all",
+    "-report",
+    "-ignore_unknown",
+    "-copy_unknown",
+    "-recast_media",
+    "-autorotate",
+    "-autoscale",
+    "-accurate_seek",
+    "-find_stream_info",
+    "-bitexact",
+    "-copyinkf",
+    "-fix_sub_duration",
+    "-fix_sub_duration_heartbeat",
+    "-auto_conversion_filters",
+    "-force_fps",
+    "-display_hflip",
+    "-display_vflip",
+    "-vstats",
+    "-qphist",
+    "-psnr",
+    "-print_graphs",
+    "-dump",
+    "-hex",
+    "-debug_ts",
+    "-version",
+    "-buildconf",
+    "-formats",
+    "-muxers",
+    "-demuxers",
+    "-devices",
+    "-codecs",
+    "-decoders",
+    "-encoders",
+    "-bsfs",
+    "-protocols",
+    "-filters",
+    "-pix_fmts",
+    "-layouts",
+    "-sample_fmts",
+    "-colors",
+    "-hwaccels",
+    "-L",
+    "-h",
+    "-?",
+    "-help",
+    "--help",
+];
+
+/// 带值的 FFmpeg 选项：FFmpeg 自身的选项，以及常用的编码器、封装格式、协议和设备选项
+///
+/// 配置了 `allowedProtocols` 时，不在此列表和 [`FLAG_OPTIONS`] 中的选项会被拒绝，
+/// 否则无法确定之后的参数是选项的值还是输出。
+const VALUE_OPTIONS: &[&str] = &[
+    // FFmpeg 自身的选项
+    "-i",
+    "-f",
+    "-c",
+    "-codec",
+    "-vcodec",
+    "-acodec",
+    "-scodec",
+    "-dcodec",
+    "-map",
+    "-map_metadata",
+    "-map_chapters",
+    "-t",
+    "-to",
+    "-ss",
+    "-sseof",
+    "-fs",
+    "-itsoffset",
+    "-itsscale",
+    "-isync",
+    "-timestamp",
+    "-metadata",
+    "-program",
+    "-stream_group",
+    "-target",
+    "-vsync",
+    "-fps_mode",
+    "-frame_drop_threshold",
+    "-adrift_threshold",
+    "-copytb",
+    "-shortest_buf_duration",
+    "-apad",
+    "-dts_delta_threshold",
+    "-dts_error_threshold",
+    "-abort_on",
+    "-copypriorss",
+    "-frames",
+    "-vframes",
+    "-aframes",
+    "-dframes",
+    "-discard",
+    "-disposition",
+    "-thread_queue_size",
+    "-bits_per_raw_sample",
+    "-stats_enc_pre",
+    "-stats_enc_post",
+    "-stats_mux_pre",
+    "-stats_enc_pre_fmt",
+    "-stats_enc_post_fmt",
+    "-stats_mux_pre_fmt",
+    "-filter",
+    "-vf",
+    "-af",
+    "-filter_threads",
+    "-filter_complex",
+    "-filter_complex_threads",
+    "-filter_complex_nbthreads",
+    "-lavfi",
+    "-reinit_filter",
+    "-stats_period",
+    "-progress",
+    "-attach",
+    "-dump_attachment",
+    "-stream_loop",
+    "-max_error_rate",
+    "-top",
+    "-vstats_file",
+    "-vstats_version",
+    "-sdp_file",
+    "-print_graphs_file",
+    "-print_graphs_format",
+    "-hwaccel",
+    "-hwaccel_device",
+    "-hwaccel_output_format",
+    "-init_hw_device",
+    "-filter_hw_device",
+    "-canvas_size",
+    "-pass",
+    "-passlogfile",
+    "-r",
+    "-fpsmax",
+    "-s",
+    "-aspect",
+    "-pix_fmt",
+    "-display_rotation",
+    "-q",
+    "-qscale",
+    "-profile",
+    "-intra_matrix",
+    "-inter_matrix",
+    "-chroma_intra_matrix",
+    "-tag",
+    "-vtag",
+    "-atag",
+    "-stag",
+    "-rc_override",
+    "-force_key_frames",
+    "-enc_time_base",
+    "-time_base",
+    "-streamid",
+    "-ar",
+    "-ac",
+    "-ch_layout",
+    "-channel_layout",
+    "-sample_fmt",
+    "-guess_layout_max",
+    "-spre",
+    "-apre",
+    "-vpre",
+    "-fpre",
+    "-b",
+    "-bsf",
+    "-absf",
+    "-vbsf",
+    "-max_muxing_queue_size",
+    "-muxing_queue_data_threshold",
+    "-timelimit",
+    "-readrate",
+    "-readrate_initial_burst",
+    "-readrate_catchup",
+    "-loglevel",
+    "-v",
+    "-cpuflags",
+    "-cpucount",
+    "-max_alloc",
+    "-threads",
+    "-protocol_whitelist",
+    "-protocol_blacklist",
+    // 编解码器
+    "-g",
+    "-bf",
+    "-flags",
+    "-flags2",
+    "-maxrate",
+    "-minrate",
+    "-bufsize",
+    "-qmin",
+    "-qmax",
+    "-qdiff",
+    "-global_quality",
+    "-compression_level",
+    "-strict",
+    "-sc_threshold",
+    "-keyint_min",
+    "-refs",
+    "-level",
+    "-field_order",
+    "-color_primaries",
+    "-color_trc",
+    "-colorspace",
+    "-color_range",
+    "-chroma_sample_location",
+    "-frame_size",
+    "-cutoff",
+    "-err_detect",
+    "-skip_frame",
+    "-lowres",
+    "-thread_type",
+    "-timecode",
+    "-sws_flags",
+    "-sub_charenc",
+    "-crf",
+    "-preset",
+    "-tune",
+    "-x264-params",
+    "-x264opts",
+    "-x265-params",
+    "-svtav1-params",
+    "-aom-params",
+    "-cpu-used",
+    "-deadline",
+    "-row-mt",
+    "-tile-columns",
+    "-tile-rows",
+    "-lag-in-frames",
+    "-cq",
+    "-qp",
+    "-rc",
+    "-rc-lookahead",
+    "-b_ref_mode",
+    "-spatial-aq",
+    "-temporal-aq",
+    "-aq-mode",
+    "-aq-strength",
+    "-lossless",
+    "-quality",
+    "-usage",
+    "-speed",
+    "-vbr",
+    "-application",
+    "-look_ahead",
+    "-realtime",
+    "-allow_sw",
+    "-a53cc",
+    // 封装格式
+    "-fflags",
+    "-avioflags",
+    "-probesize",
+    "-analyzeduration",
+    "-fpsprobesize",
+    "-max_delay",
+    "-max_interleave_delta",
+    "-avoid_negative_ts",
+    "-use_wallclock_as_timestamps",
+    "-rtbufsize",
+    "-output_ts_offset",
+    "-skip_initial_bytes",
+    "-flush_packets",
+    "-muxdelay",
+    "-muxpreload",
+    "-movflags",
+    "-frag_duration",
+    "-frag_size",
+    "-min_frag_duration",
+    "-brand",
+    "-use_editlist",
+    "-write_tmcd",
+    "-ignore_editlist",
+    "-hls_time",
+    "-hls_list_size",
+    "-hls_flags",
+    "-hls_segment_filename",
+    "-hls_playlist_type",
+    "-hls_segment_type",
+    "-hls_base_url",
+    "-hls_init_time",
+    "-hls_fmp4_init_filename",
+    "-hls_allow_cache",
+    "-hls_delete_threshold",
+    "-master_pl_name",
+    "-var_stream_map",
+    "-start_number",
+    "-live_start_index",
+    "-allowed_extensions",
+    "-http_persistent",
+    "-seg_duration",
+    "-window_size",
+    "-extra_window_size",
+    "-use_template",
+    "-use_timeline",
+    "-adaptation_sets",
+    "-init_seg_name",
+    "-media_seg_name",
+    "-dash_segment_type",
+    "-ldash",
+    "-streaming",
+    "-segment_time",
+    "-segment_format",
+    "-segment_list",
+    "-segment_list_type",
+    "-segment_list_size",
+    "-segment_times",
+    "-segment_frames",
+    "-segment_wrap",
+    "-segment_start_number",
+    "-break_non_keyframes",
+    "-reset_timestamps",
+    "-strftime",
+    "-update",
+    "-atomic_writing",
+    "-hash",
+    "-framerate",
+    "-video_size",
+    "-pixel_format",
+    "-input_format",
+    "-pattern_type",
+    "-loop",
+    "-safe",
+    "-sample_rate",
+    "-channels",
+    "-id3v2_version",
+    "-write_id3v1",
+    "-write_xing",
+    "-flvflags",
+    "-mpegts_flags",
+    "-mpegts_service_id",
+    "-muxrate",
+    "-service_name",
+    "-service_provider",
+    "-cluster_size_limit",
+    "-cluster_time_limit",
+    "-reserve_index_space",
+    // 协议
+    "-rtsp_transport",
+    "-rtsp_flags",
+    "-stimeout",
+    "-timeout",
+    "-rw_timeout",
+    "-listen_timeout",
+    "-reconnect",
+    "-reconnect_streamed",
+    "-reconnect_at_eof",
+    "-reconnect_on_network_error",
+    "-reconnect_on_http_error",
+    "-reconnect_delay_max",
+    "-user_agent",
+    "-headers",
+    "-referer",
+    "-cookies",
+    "-method",
+    "-multiple_requests",
+    "-seekable",
+    "-listen",
+    "-buffer_size",
+    "-fifo_size",
+    "-overrun_nonfatal",
+    "-pkt_size",
+    "-tls_verify",
+    "-ca_file",
+    "-http_proxy",
+    // 采集设备
+    "-list_devices",
+    "-list_options",
+    "-audio_buffer_size",
+    "-sample_size",
+    "-capture_cursor",
+    "-capture_mouse_clicks",
+    "-draw_mouse",
+    "-show_region",
+    "-offset_x",
+    "-offset_y",
+    "-follow_mouse",
+    "-video_device_index",
+    "-audio_device_index",
+    "-window_id",
+    "-select_region",
+];
+
+/// FFmpeg 自行打开的文件或 URL 作为值的选项，不受注入的 `-protocol_whitelist` 限制
+const URL_OPTIONS: &[&str] = &[
+    "-progress",
+    "-attach",
+    "-dump_attachment",
+    "-sdp_file",
+    "-vstats_file",
+    "-passlogfile",
+    "-stats_enc_pre",
+    "-stats_enc_post",
+    "-stats_mux_pre",
+    "-print_graphs_file",
+];
+
+/// 各协议在内部打开的底层协议，`-protocol_whitelist` 同样作用于这些嵌套打开的协议
+const PROTOCOL_DEPENDENCIES: &[(&str, &[&str])] = &[
+    ("http", &["tcp"]),
+    ("https", &["tls", "tcp"]),
+    ("tls", &["tcp"]),
+    ("ftp", &["tcp"]),
+    ("rtmp", &["tcp"]),
+    ("rtmps", &["tls", "tcp"]),
+    ("rtsp", &["rtp", "udp", "tcp"]),
+    ("rtp", &["udp"]),
+];
+
+/// 始终允许的协议，`pipe` 只能读写 FFmpeg 进程自身的标准输入输出
+const ALWAYS_ALLOWED_PROTOCOLS: &[&str] = &["pipe"];
+
+/// 只输出信息、不需要输入的选项
+const INFO_OPTIONS: &[&str] = &[
+    "-version",
+    "-buildconf",
+    "-formats",
+    "-muxers",
+    "-demuxers",
+    "-devices",
+    "-codecs",
+    "-decoders",
+    "-encoders",
+    "-bsfs",
+    "-protocols",
+    "-filters",
+    "-pix_fmts",
+    "-layouts",
+    "-sample_fmts",
+    "-colors",
+    "-hwaccels",
+    "-sources",
+    "-sinks",
+    "-dispositions",
+    "-L",
+    "-h",
+    "-?",
+    "-help",
+    "--help",
+];
+
+/// 命令行中不支持的 shell 操作符
+const SHELL_OPERATORS: &[&str] = &[
+    "|", "||", "&", "&&", ";", "<", ">", ">>", "1>", "2>", "2>>", "&>", "2>&1",
+];
+
+/// 选项去掉流说明符后的名称（如 `-c:v` 为 `-c`）
+fn option_name(option: &str) -> &str {
+    option.split(':').next().unwrap_or(option)
+}
+
+/// 判断选项是否不带值
+fn is_flag(option: &str) -> bool {
+    let name = option_name(option);
+    FLAG_OPTIONS.contains(&name)
+        || name
+            .strip_prefix("-no")
+            .is_some_and(|rest| FLAG_OPTIONS.contains(&format!("-{rest}").as_str()))
+}
+
+/// 判断选项是否带值
+fn takes_value(option: &str) -> bool {
+    !is_flag(option)
+}
+
+/// 判断是否为已知的选项
+fn is_known_option(option: &str) -> bool {
+    is_flag(option) || VALUE_OPTIONS.contains(&option_name(option))
+}
+
+/// 按 shell 规则拆分命令行（引号、转义、续行），开头的 `ffmpeg` 留给 [`normalize_args`] 去掉
+///
+/// 管道和重定向无法由插件执行，包含这些操作符时返回 [`Error::InvalidArgument`]。
+pub(crate) fn split_command(command: &str) -> Result<Vec<String>> {
+    let args = shell_words::split(command)
+        .map_err(|e| Error::InvalidArgument(format!("Cannot parse command line: {e}")))?;
+    if let Some(operator) = args
+        .iter()
+        .find(|arg| SHELL_OPERATORS.contains(&arg.as_str()))
+    {
+        return Err(Error::InvalidArgument(format!(
+            "Shell operator `{operator}` is not supported, run a single FFmpeg command"
+        )));
+    }
+    Ok(args)
+}
+
+/// 检查并规范化参数，在其他处理之前调用
+///
+/// 去掉开头误加的 `ffmpeg` / `ffmpeg.exe`（包括完整路径），以下情况返回 [`Error::InvalidArgument`]，
+/// 而不是让 FFmpeg 输出难以理解的用法说明：
+///
+/// - 参数列表为空
+/// - 参数中包含 NUL 字符（无法传给进程）
+/// - 选项名称、输入或输出中包含换行（通常是复制粘贴时带入的）
+/// - 选项名称中包含空格（如 `"-i input.mp4"`，选项和值需要分成两个参数）
+///
+/// 滤镜、元数据等选项的值可以包含换行和空格，不做检查。
+pub(crate) fn normalize_args(args: &[String]) -> Result<Vec<String>> {
+    let skip = args.iter().take_while(|arg| is_ffmpeg_program(arg)).count();
+    if args.len() == skip {
+        return Err(Error::InvalidArgument(
+            "No FFmpeg arguments given".to_string(),
+        ));
+    }
+
+    let invalid = |index: usize, arg: &str, reason: &str| {
+        Error::InvalidArgument(format!(
+            "Argument {index} `{}` {reason}",
+            arg.escape_debug()
+        ))
+    };
+
+    let mut index = skip;
+    while index < args.len() {
+        let arg = &args[index];
+        if arg.contains('\0') {
+            return Err(invalid(index, arg, "contains a NUL byte"));
+        }
+
+        if arg.len() > 1 && arg.starts_with('-') {
+            if arg.contains(['\n', '\r']) {
+                return Err(invalid(index, arg, "contains a line break"));
+            }
+            if arg.contains(char::is_whitespace) {
+                return Err(invalid(
+                    index,
+                    arg,
+                    "contains spaces, pass each option and its value as separate arguments",
+                ));
+            }
+            if takes_value(arg) {
+                if let Some(value) = args.get(index + 1) {
+                    if value.contains('\0') {
+                        return Err(invalid(index + 1, value, "contains a NUL byte"));
+                    }
+                    if arg == "-i" && value.contains(['\n', '\r']) {
+                        return Err(invalid(index + 1, value, "contains a line break"));
+                    }
+                }
+                index += 1;
+            }
+        } else if arg.contains(['\n', '\r']) {
+            return Err(invalid(index, arg, "contains a line break"));
+        }
+        index += 1;
+    }
+
+    Ok(args[skip..].to_vec())
+}
+
+/// 参数是否为 FFmpeg 程序本身（`ffmpeg`、`ffmpeg.exe` 或以其结尾的路径）
+fn is_ffmpeg_program(arg: &str) -> bool {
+    // 同时识别 Windows 路径，参数可能来自其他平台上编写的命令
+    let name = arg.rsplit(['/', '\\']).next().unwrap_or(arg);
+    name.eq_ignore_ascii_case("ffmpeg") || name.eq_ignore_ascii_case("ffmpeg.exe")
+}
+
+/// 不影响运行、但很可能有误的参数，作为警告返回给调用方
+pub(crate) fn arg_warnings(args: &[String]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    // `-filter_complex` 中的源滤镜（如 `testsrc`）不需要输入
+    let has_input = args.iter().any(|arg| {
+        matches!(arg.as_str(), "-i" | "-filter_complex" | "-lavfi")
+            || INFO_OPTIONS.contains(&arg.as_str())
+    });
+    if !has_input {
+        warnings.push("No input given, add `-i <input>` before the output".to_string());
+    }
+    warnings
+}
+
+/// 从参数中找出输出目标（不属于任何选项的位置参数）
+pub(crate) fn output_targets(args: &[String]) -> Vec<String> {
+    let mut outputs = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg.len() > 1 && arg.starts_with('-') {
+            if takes_value(arg) {
+                iter.next();
+            }
+            continue;
+        }
+        outputs.push(arg.clone());
+    }
+
+    outputs
+}
+
+/// 从参数中找出输出文件路径，排除管道、URL 和图片序列模板
+pub(crate) fn output_paths(args: &[String]) -> Vec<PathBuf> {
+    output_targets(args)
+        .into_iter()
+        .filter(|target| is_local_file(target))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// 判断输出目标是否为普通本地文件
+fn is_local_file(target: &str) -> bool {
+    if target == "-" || target.contains('%') {
+        return false;
+    }
+
+    let target = target.strip_prefix("file:").unwrap_or(target);
+
+    // 带协议的 URL（如 pipe:1、rtmp://）不是本地文件，Windows 盘符除外
+    !matches!(target.find(':'), Some(index) if index > 1)
+}
+
+/// 输入或输出目标使用的协议，没有协议前缀的本地路径为 `file`，`-` 为 `pipe`
+///
+/// 与 FFmpeg 相同，`:` 之前只包含字母、数字和 `+-.` 时为协议名。`:` 之前包含其他字符
+/// （如 `[f=flv]rtmp:`、`a.mp4|rtmp:`、`subfile,,start,0,:`）且不是路径时无法确定协议，返回 None。
+pub(crate) fn target_protocol(target: &str) -> Option<&str> {
+    if target == "-" {
+        return Some("pipe");
+    }
+    let Some(index) = target.find(':') else {
+        return Some("file");
+    };
+    let prefix = &target[..index];
+    // Windows 盘符（`C:`）不是协议
+    if index == 1 && prefix.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Some("file");
+    }
+    if !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    {
+        return Some(prefix);
+    }
+    // `./a:b.mp4`、`dir\a:b.mp4` 这样 `:` 之前包含路径分隔符的是本地路径
+    if prefix.contains(['/', '\\']) {
+        return Some("file");
+    }
+    None
+}
+
+/// 参数中的一个输入或输出目标
+struct Target<'a> {
+    input: bool,
+    /// 目标之前的 `-f` 格式
+    format: Option<&'a str>,
+    url: &'a str,
+}
+
+/// 按顺序找出所有输入（`-i` 的值）和输出目标，以及各自使用的 `-f` 格式
+fn targets(args: &[String]) -> Vec<Target<'_>> {
+    let mut targets = Vec::new();
+    let mut format = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg.len() > 1 && arg.starts_with('-') {
+            if !takes_value(arg) {
+                continue;
+            }
+            let Some(value) = iter.next() else {
+                break;
+            };
+            match arg.as_str() {
+                "-f" => format = Some(value.as_str()),
+                "-i" => targets.push(Target {
+                    input: true,
+                    format: format.take(),
+                    url: value,
+                }),
+                _ => {}
+            }
+            continue;
+        }
+        targets.push(Target {
+            input: false,
+            format: format.take(),
+            url: arg,
+        });
+    }
+
+    targets
+}
+
+/// 值为滤镜图的选项，其中的 `movie` / `amovie` 源滤镜会直接打开文件或 URL
+fn is_filtergraph_option(option: &str) -> bool {
+    matches!(
+        option,
+        "-filter_complex" | "-lavfi" | "-vf" | "-af" | "-filter"
+    ) || option.starts_with("-filter:")
+}
+
+/// 从文件读取滤镜图或选项值的选项，内容无法检查
+fn is_script_option(option: &str) -> bool {
+    option.starts_with("-/") || option.ends_with("_script") || option.contains("_script:")
+}
+
+/// tee 格式输出中的各个目标：按 `|` 分隔，去掉开头的 `[选项]`
+///
+/// 包含转义或引号时无法可靠地拆分，返回 None。
+fn tee_slaves(url: &str) -> Option<Vec<&str>> {
+    if url.contains(['\\', '\'']) {
+        return None;
+    }
+    url.split('|')
+        .map(|slave| {
+            let slave = slave.trim();
+            match slave.strip_prefix('[') {
+                Some(rest) => rest.find(']').map(|end| rest[end + 1..].trim()),
+                None => Some(slave),
+            }
+        })
+        .collect()
+}
+
+/// 滤镜图中 `movie` / `amovie` 源滤镜打开的文件或 URL
+///
+/// 滤镜图包含转义或引号时无法可靠地解析，返回 None。
+fn movie_sources(graph: &str) -> Option<Vec<&str>> {
+    if graph.contains(['\\', '\'']) {
+        return None;
+    }
+
+    let mut sources = Vec::new();
+    for filter in graph.split([',', ';']) {
+        let mut filter = filter.trim();
+        // 去掉开头的输入标签 `[in]`
+        while let Some(rest) = filter.strip_prefix('[') {
+            filter = rest[rest.find(']')? + 1..].trim_start();
+        }
+        let (name, options) = filter.split_once('=').unwrap_or((filter, ""));
+        let name = name.trim().split('@').next().unwrap_or(name);
+        if name != "movie" && name != "amovie" {
+            continue;
+        }
+        // 第一个不带键名的选项或 `filename=` 为文件名
+        let source = options.split(':').enumerate().find_map(|(index, option)| {
+            match option.split_once('=') {
+                Some(("filename", value)) => Some(value),
+                None if index == 0 => Some(option),
+                _ => None,
+            }
+        });
+        sources.push(source.unwrap_or_default().trim());
+    }
+    Some(sources)
+}
+
+/// 检查目标使用的协议是否允许
+fn check_protocol(target: &str, allowed: &[String]) -> Result<()> {
+    let Some(protocol) = target_protocol(target) else {
+        return Err(Error::InvalidArgument(format!(
+            "Cannot determine the protocol of `{target}`, add `file:` before local paths"
+        )));
+    };
+    if !ALWAYS_ALLOWED_PROTOCOLS.contains(&protocol)
+        && !allowed.iter().any(|allowed| allowed == protocol)
+    {
+        return Err(Error::InvalidArgument(format!(
+            "Protocol `{protocol}` is not allowed: {target}"
+        )));
+    }
+    Ok(())
+}
+
+/// 检查滤镜图中 `movie` / `amovie` 打开的目标使用的协议是否允许
+fn check_filtergraph(graph: &str, allowed: &[String]) -> Result<()> {
+    let sources = movie_sources(graph).ok_or_else(|| {
+        Error::InvalidArgument(format!(
+            "Filter graph with escapes or quotes cannot be checked against `allowedProtocols`: {graph}"
+        ))
+    })?;
+    sources
+        .into_iter()
+        .try_for_each(|source| check_protocol(source, allowed))
+}
+
+/// 检查输入、输出和滤镜图使用的协议，并在每个输入和输出之前注入 `-protocol_whitelist`
+///
+/// 未配置 `allowedProtocols` 时保持不变。tee 格式的输出逐个检查其中的目标，lavfi 输入和滤镜选项
+/// 检查其中 `movie` / `amovie` 打开的目标，`-progress` 等选项检查其值。参数中自带 `-protocol_whitelist`
+/// 或 `-protocol_blacklist`、使用了从文件读取的滤镜脚本或未知的选项，或者使用了未允许或无法确定的协议时
+/// 返回 [`Error::InvalidArgument`]。
+pub(crate) fn apply_protocol_whitelist(
+    args: &[String],
+    allowed: Option<&[String]>,
+) -> Result<Vec<String>> {
+    let Some(allowed) = allowed else {
+        return Ok(args.to_vec());
+    };
+
+    if let Some(option) = args
+        .iter()
+        .find(|arg| *arg == "-protocol_whitelist" || *arg == "-protocol_blacklist")
+    {
+        return Err(Error::InvalidArgument(format!(
+            "`{option}` cannot be set when `allowedProtocols` is configured"
+        )));
+    }
+
+    for (option, value) in options(args) {
+        if is_script_option(option) {
+            return Err(Error::InvalidArgument(format!(
+                "`{option}` cannot be used when `allowedProtocols` is configured"
+            )));
+        }
+        if !is_known_option(option) {
+            return Err(Error::InvalidArgument(format!(
+                "Unknown option `{option}` cannot be checked against `allowedProtocols`"
+            )));
+        }
+        if is_filtergraph_option(option) {
+            check_filtergraph(value.unwrap_or_default(), allowed)?;
+        }
+        if URL_OPTIONS.contains(&option_name(option)) {
+            check_protocol(value.unwrap_or_default(), allowed)?;
+        }
+    }
+
+    for target in targets(args) {
+        match target.format {
+            Some("lavfi") if target.input => check_filtergraph(target.url, allowed)?,
+            Some("tee") if !target.input => {
+                let slaves = tee_slaves(target.url).ok_or_else(|| {
+                    Error::InvalidArgument(format!(
+                        "tee output with escapes or quotes cannot be checked against `allowedProtocols`: {}",
+                        target.url
+                    ))
+                })?;
+                for slave in slaves {
+                    check_protocol(slave, allowed)?;
+                }
+            }
+            _ => check_protocol(target.url, allowed)?,
+        }
+    }
+
+    let mut whitelist: Vec<&str> = ALWAYS_ALLOWED_PROTOCOLS.to_vec();
+    for protocol in allowed {
+        let dependencies = PROTOCOL_DEPENDENCIES
+            .iter()
+            .find(|(name, _)| name == protocol)
+            .map_or(&[][..], |(_, dependencies)| dependencies);
+        for protocol in std::iter::once(protocol.as_str()).chain(dependencies.iter().copied()) {
+            if !whitelist.contains(&protocol) {
+                whitelist.push(protocol);
+            }
+        }
+    }
+    let whitelist = whitelist.join(",");
+
+    // 输出选项中的 `-protocol_whitelist` 同样作用于打开输出的 URL
+    let mut result = Vec::with_capacity(args.len() + 4);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let is_option = arg.len() > 1 && arg.starts_with('-');
+        if arg == "-i" || !is_option {
+            result.extend(["-protocol_whitelist".to_string(), whitelist.clone()]);
+        }
+        result.push(arg.clone());
+        if is_option && takes_value(arg) {
+            result.extend(iter.next().cloned());
+        }
+    }
+
+    Ok(result)
+}
+
+/// 参数中的选项及其值
+fn options(args: &[String]) -> Vec<(&str, Option<&str>)> {
+    let mut options = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg.len() > 1 && arg.starts_with('-') {
+            let value = if takes_value(arg) { iter.next() } else { None };
+            options.push((arg.as_str(), value.map(String::as_str)));
+        }
+    }
+    options
+}
+
+/// 根据覆盖策略在参数前注入 `-y` / `-n`
+///
+/// 参数中已包含相同的标志时保持不变，包含相反的标志时返回 [`Error::InvalidArgument`]。
+pub(crate) fn apply_overwrite_policy(
+    args: &[String],
+    policy: Option<OverwritePolicy>,
+) -> Result<Vec<String>> {
+    let flag = match policy {
+        Some(OverwritePolicy::Always) => "-y",
+        Some(OverwritePolicy::Never) | Some(OverwritePolicy::Error) => "-n",
+        None => return Ok(args.to_vec()),
+    };
+
+    let existing = options(args)
+        .into_iter()
+        .map(|(option, _)| option)
+        .find(|option| matches!(*option, "-y" | "-n"));
+    match existing {
+        Some(existing) if existing == flag => Ok(args.to_vec()),
+        Some(existing) => Err(Error::InvalidArgument(format!(
+            "`{existing}` in args conflicts with the overwrite policy, which requires `{flag}`"
+        ))),
+        None => Ok(std::iter::once(flag.to_string())
+            .chain(args.iter().cloned())
+            .collect()),
+    }
+}
+
+/// 从 stderr 中识别因 `-n` 拒绝覆盖的输出文件
+pub(crate) fn refused_overwrite(stderr: &str) -> Option<String> {
+    stderr.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("File '")?;
+        let end = rest.find("' already exists")?;
+        Some(rest[..end].to_string())
+    })
+}
+
+/// 在最后一个输出之前注入 `-threads`，参数中已包含 `-threads` 时保持不变
+pub(crate) fn apply_threads(args: &[String], threads: Option<u32>) -> Vec<String> {
+    let Some(threads) = threads else {
+        return args.to_vec();
+    };
+    if args.iter().any(|arg| arg == "-threads") {
+        return args.to_vec();
+    }
+
+    let mut args = args.to_vec();
+    let index = last_output_index(&args).unwrap_or(args.len());
+    args.splice(index..index, ["-threads".to_string(), threads.to_string()]);
+    args
+}
+
+/// 在每个输出之前注入 `-map_metadata 0`，参数中已包含 `-map_metadata` 时保持不变
+pub(crate) fn apply_map_metadata(args: &[String]) -> Vec<String> {
+    if args.iter().any(|arg| arg.starts_with("-map_metadata")) {
+        return args.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(args.len() + 2);
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg.len() > 1 && arg.starts_with('-') {
+            result.push(arg.clone());
+            if takes_value(arg) {
+                result.extend(iter.next().cloned());
+            }
+            continue;
+        }
+        result.extend(["-map_metadata".to_string(), "0".to_string(), arg.clone()]);
+    }
+
+    result
+}
+
+/// 第一个输入（`-i` 的值）
+pub(crate) fn first_input(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|arg| arg == "-i")
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+/// 最后一个输出目标在参数中的位置
+fn last_output_index(args: &[String]) -> Option<usize> {
+    let mut last = None;
+    let mut index = 0;
+
+    while index < args.len() {
+        let arg = &args[index];
+        if arg.len() > 1 && arg.starts_with('-') {
+            index += if takes_value(arg) { 2 } else { 1 };
+            continue;
+        }
+        last = Some(index);
+        index += 1;
+    }
+
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn whitelist(args: &[String], allowed: &[&str]) -> Result<Vec<String>> {
+        apply_protocol_whitelist(args, Some(&self::args(allowed)))
+    }
+
+    fn is_rejected(result: Result<Vec<String>>) -> bool {
+        matches!(result, Err(Error::InvalidArgument(_)))
+    }
+
+    #[test]
+    fn overwrite_flag_is_injected_once() {
+        let input = args(&["-i", "in.mp4", "out.mp4"]);
+        assert_eq!(
+            apply_overwrite_policy(&input, Some(OverwritePolicy::Always)).unwrap(),
+            args(&["-y", "-i", "in.mp4", "out.mp4"])
+        );
+        assert_eq!(apply_overwrite_policy(&input, None).unwrap(), input);
+
+        let input = args(&["-n", "-i", "in.mp4", "out.mp4"]);
+        assert_eq!(
+            apply_overwrite_policy(&input, Some(OverwritePolicy::Error)).unwrap(),
+            input
+        );
+        assert!(is_rejected(apply_overwrite_policy(
+            &input,
+            Some(OverwritePolicy::Always)
+        )));
+
+        // 选项的值不是标志
+        let input = args(&["-i", "in.mp4", "-metadata", "-y", "out.mp4"]);
+        assert_eq!(
+            apply_overwrite_policy(&input, Some(OverwritePolicy::Never)).unwrap()[0],
+            "-n"
+        );
+    }
+
+    #[test]
+    fn command_line_is_split_like_a_shell() {
+        let split = |command: &str| split_command(command).unwrap();
+        assert_eq!(
+            split(r#"ffmpeg -i 'my file.mp4' -vf "scale=1280:-1" out.mp4"#),
+            args(&[
+                "ffmpeg",
+                "-i",
+                "my file.mp4",
+                "-vf",
+                "scale=1280:-1",
+                "out.mp4"
+            ])
+        );
+        assert_eq!(split(r"-i my\ file.mp4"), args(&["-i", "my file.mp4"]));
+        // 双引号中可以转义双引号，单引号中的反斜杠原样保留
+        assert_eq!(
+            split(r#"-metadata "title=He said \"hi\"" -metadata comment='a\b'"#),
+            args(&[
+                "-metadata",
+                r#"title=He said "hi""#,
+                "-metadata",
+                r"comment=a\b"
+            ])
+        );
+        assert_eq!(
+            split(r"-i 'C:\Videos\in.mp4' out.mp4"),
+            args(&["-i", r"C:\Videos\in.mp4", "out.mp4"])
+        );
+        assert_eq!(
+            split("ffmpeg -i in.mp4 \\\n  -c copy \\\n  out.mp4"),
+            args(&["ffmpeg", "-i", "in.mp4", "-c", "copy", "out.mp4"])
+        );
+        assert_eq!(
+            split(r#"-metadata title="" out.mp4"#),
+            args(&["-metadata", "title=", "out.mp4"])
+        );
+        assert_eq!(
+            split(r#"-metadata "" out.mp4"#),
+            args(&["-metadata", "", "out.mp4"])
+        );
+        // 引号中的操作符是普通字符
+        assert_eq!(
+            split("-f tee 'a.mp4|b.mp4'"),
+            args(&["-f", "tee", "a.mp4|b.mp4"])
+        );
+    }
+
+    #[test]
+    fn unsupported_command_lines_are_rejected() {
+        for command in [
+            "ffmpeg -i 'unterminated.mp4",
+            r#"ffmpeg -i "unterminated.mp4"#,
+            "ffmpeg -i in.mp4 out.mp4 | tee log",
+            "ffmpeg -i in.mp4 out.mp4 2>&1",
+            "ffmpeg -i in.mp4 out.mp4 > log",
+            "ffmpeg -i a.mp4 a.gif && open a.gif",
+            "ffmpeg -i in.mp4 out.mp4 ; rm in.mp4",
+        ] {
+            assert!(
+                matches!(split_command(command), Err(Error::InvalidArgument(_))),
+                "{command}"
+            );
+        }
+    }
+
+    #[test]
+    fn target_protocol_matches_ffmpeg() {
+        assert_eq!(target_protocol("out.mp4"), Some("file"));
+        assert_eq!(target_protocol("-"), Some("pipe"));
+        assert_eq!(target_protocol("pipe:1"), Some("pipe"));
+        assert_eq!(target_protocol("C:\\Videos\\out.mp4"), Some("file"));
+        assert_eq!(target_protocol("./a:b.mp4"), Some("file"));
+        assert_eq!(target_protocol("rtmp://host/app"), Some("rtmp"));
+        assert_eq!(target_protocol("async+http://host/a"), Some("async+http"));
+        assert_eq!(target_protocol("[f=flv]rtmp://evil/x"), None);
+        assert_eq!(target_protocol("a.mp4|rtmp://evil/x"), None);
+        assert_eq!(target_protocol("subfile,,start,0,end,0,,:a.mp4"), None);
+    }
+
+    #[test]
+    fn whitelist_is_injected_before_inputs_and_outputs() {
+        let result = whitelist(&args(&["-i", "in.mp4", "-c", "copy", "out.mp4"]), &["file"]);
+        assert_eq!(
+            result.unwrap(),
+            args(&[
+                "-protocol_whitelist",
+                "pipe,file",
+                "-i",
+                "in.mp4",
+                "-c",
+                "copy",
+                "-protocol_whitelist",
+                "pipe,file",
+                "out.mp4",
+            ])
+        );
+        let result = whitelist(&args(&["-i", "https://host/a.mp4", "out.mp4"]), &["file"]);
+        assert!(is_rejected(result));
+    }
+
+    #[test]
+    fn unknown_prefix_is_rejected() {
+        for target in ["[f=flv]rtmp://evil/x", "a.mp4|rtmp://evil/x"] {
+            let result = whitelist(&args(&["-i", "in.mp4", target]), &["file"]);
+            assert!(is_rejected(result), "{target}");
+        }
+        let result = whitelist(&args(&["-i", "in.mp4", "file:My Video: 1.mp4"]), &["file"]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn tee_slaves_are_checked() {
+        let tee = |output: &str| {
+            whitelist(
+                &args(&["-i", "in.mp4", "-f", "tee", "-map", "0", output]),
+                &["file"],
+            )
+        };
+        assert!(tee("a.mp4|[f=mpegts]b.ts").is_ok());
+        assert!(is_rejected(tee("a.mp4|[f=flv]rtmp://evil/x")));
+        assert!(is_rejected(tee("[f=flv:onfail=ignore]rtmp://evil/x")));
+        assert!(is_rejected(tee("a.mp4|[f=flv]'rtmp://evil/x'")));
+        // 其他格式的输出中，`|` 是文件名的一部分
+        let result = whitelist(&args(&["-i", "in.mp4", "a.mp4|b.mp4"]), &["file"]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn movie_sources_are_checked() {
+        for option in ["-filter_complex", "-lavfi", "-vf", "-filter:v"] {
+            let graph = |graph: &str| {
+                whitelist(
+                    &args(&["-i", "in.mp4", option, graph, "out.mp4"]),
+                    &["file"],
+                )
+            };
+            assert!(graph("movie=logo.png[wm];[0:v][wm]overlay").is_ok());
+            assert!(is_rejected(graph(
+                "movie=rtmp\\://evil/x[wm];[0:v][wm]overlay"
+            )));
+            assert!(is_rejected(graph(
+                "[0:v]scale=640:-1[v];amovie=filename='http://evil/a.mp3'[a]"
+            )));
+            assert!(is_rejected(graph("movie@wm='http://evil/a.png'")));
+        }
+        let result = whitelist(
+            &args(&[
+                "-f",
+                "lavfi",
+                "-i",
+                "amovie=https\\://evil/a.mp3",
+                "out.wav",
+            ]),
+            &["file"],
+        );
+        assert!(is_rejected(result));
+        let result = whitelist(
+            &args(&["-f", "lavfi", "-i", "testsrc2=size=640x360", "out.mp4"]),
+            &["file"],
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn filter_scripts_are_rejected() {
+        for option in [
+            "-filter_complex_script",
+            "-/filter_complex",
+            "-filter_script:v",
+        ] {
+            let result = whitelist(
+                &args(&["-i", "in.mp4", option, "graph.txt", "out.mp4"]),
+                &["file"],
+            );
+            assert!(is_rejected(result), "{option}");
+        }
+    }
+
+    #[test]
+    fn url_options_are_checked() {
+        let result = whitelist(
+            &args(&[
+                "-i",
+                "a.mp4",
+                "-progress",
+                "http://169.254.169.254/",
+                "out.mp4",
+            ]),
+            &["file"],
+        );
+        assert!(is_rejected(result));
+        let result = whitelist(
+            &args(&["-i", "a.mp4", "-attach", "[f=flv]rtmp://evil/x", "out.mkv"]),
+            &["file"],
+        );
+        assert!(is_rejected(result));
+        let result = whitelist(
+            &args(&[
+                "-i",
+                "a.mp4",
+                "-progress",
+                "pipe:1",
+                "-vstats_file",
+                "vstats.log",
+                "out.mp4",
+            ]),
+            &["file"],
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn boolean_flags_do_not_take_values() {
+        for flag in [
+            "-vstats",
+            "-bitexact",
+            "-copyinkf",
+            "-qphist",
+            "-noautoscale",
+            "-nostdin",
+        ] {
+            let result = whitelist(
+                &args(&["-i", "a.mp4", flag, "http://internal/x"]),
+                &["file"],
+            );
+            assert!(is_rejected(result), "{flag}");
+        }
+        assert_eq!(
+            output_targets(&args(&["-i", "a.mp4", "-fix_sub_duration", "out.mp4"])),
+            ["out.mp4"]
+        );
+        // `-copytb` 带值
+        assert_eq!(
+            output_targets(&args(&["-i", "a.mp4", "-copytb", "1", "out.mp4"])),
+            ["out.mp4"]
+        );
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        let result = whitelist(
+            &args(&[
+                "-i",
+                "a.mp4",
+                "-made_up_option",
+                "http://internal/x",
+                "out.mp4",
+            ]),
+            &["file"],
+        );
+        assert!(is_rejected(result));
+        let result = whitelist(
+            &args(&[
+                "-nostdin", "-i", "a.mp4", "-c:v", "libx264", "-crf", "23", "-b:a:0", "128k",
+                "out.mp4",
+            ]),
+            &["file"],
+        );
+        assert!(result.is_ok());
+        // 未配置 `allowedProtocols` 时不限制
+        let input = args(&["-i", "a.mp4", "-made_up_option", "1", "out.mp4"]);
+        assert_eq!(apply_protocol_whitelist(&input, None).unwrap(), input);
+    }
+}
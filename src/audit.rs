@@ -0,0 +1,142 @@
+//! # Audit
+//!
+//! 特权操作的审计日志：安装可执行文件、删除、以任意参数运行 FFmpeg，以及越过插件默认策略的选项。
+//! 记录以 JSON Lines 追加写入应用数据目录中的 `audit.jsonl`，插件只追加、从不修改或删除已有记录。
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::jobs::now_millis;
+use crate::models::{RemoveTarget, Tool};
+
+/// 审计日志中的一条记录
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// 记录时间（Unix 毫秒）
+    pub time: u64,
+    /// 发起操作的窗口，由 Rust 端发起时为 None
+    pub window: Option<String>,
+    #[serde(flatten)]
+    pub action: AuditAction,
+}
+
+/// 被审计的操作
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum AuditAction {
+    /// 下载并安装可执行文件
+    #[serde(rename_all = "camelCase")]
+    Download {
+        download_id: String,
+        /// 下载地址
+        url: String,
+        /// 是否为请求中提供的地址，而不是插件配置或内置下载源的默认地址
+        custom_url: bool,
+        /// 预期的 SHA-256，未提供时为 None
+        sha256: Option<String>,
+        tools: Option<Vec<Tool>>,
+    },
+    /// 删除可执行文件、缓存或日志
+    #[serde(rename_all = "camelCase")]
+    Remove {
+        target: RemoveTarget,
+        tools: Option<Vec<Tool>>,
+    },
+    /// 以任意参数运行 FFmpeg（`execute`、`execute_str`、`enqueue`、`run_pipeline`）
+    #[serde(rename_all = "camelCase")]
+    Execute {
+        /// 命令名称
+        command: String,
+        job_id: String,
+        args: Vec<String>,
+    },
+    /// 越过插件默认策略的选项，如 `force` 终止正在运行的任务、单个任务的 `webhook`
+    #[serde(rename_all = "camelCase")]
+    PolicyOverride {
+        /// 选项名称
+        policy: String,
+        /// 被覆盖的内容
+        detail: String,
+    },
+}
+
+/// 读取审计日志的请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogRequest {
+    /// 只返回该时间（Unix 毫秒）之后的记录
+    pub since: Option<u64>,
+    /// 最多返回的记录数，超出时返回最新的部分
+    pub limit: Option<usize>,
+}
+
+/// 审计日志
+pub(crate) struct AuditLog {
+    /// 保存位置，无法获取应用数据目录时为 None（不记录）
+    path: Option<PathBuf>,
+    enabled: bool,
+    /// 串行写入，避免多行记录交错
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(path: Option<PathBuf>, enabled: bool) -> Self {
+        Self {
+            path,
+            enabled,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 追加一条记录，未启用审计日志时忽略，写入失败不影响操作本身
+    pub fn record(&self, window: Option<&str>, action: AuditAction) {
+        let Some(path) = self.path.as_ref().filter(|_| self.enabled) else {
+            return;
+        };
+        let entry = AuditEntry {
+            time: now_millis(),
+            window: window.map(str::to_string),
+            action,
+        };
+        let Ok(mut line) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        line.push(b'\n');
+
+        let _lock = self.lock.lock().unwrap();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+            let _ = file.write_all(&line);
+        }
+    }
+
+    /// 按时间顺序读取记录，无法解析的行被跳过
+    pub fn read(&self, request: &AuditLogRequest) -> Result<Vec<AuditEntry>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries: Vec<AuditEntry> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter(|entry: &AuditEntry| request.since.map_or(true, |since| entry.time > since))
+            .collect();
+        if let Some(limit) = request.limit {
+            entries.drain(..entries.len().saturating_sub(limit));
+        }
+        Ok(entries)
+    }
+}
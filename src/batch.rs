@@ -0,0 +1,128 @@
+//! # Batch
+//!
+//! 将相关任务（例如同一次上传的所有清晰度）归入同一批次，汇总进度并在所有任务结束后通知。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::models::{
+    BatchFailure, BatchFinished, BatchProgress, EncodeProgress, JobFinished, JobInfo,
+};
+
+/// 批次跟踪器
+#[derive(Default)]
+pub(crate) struct BatchTracker {
+    state: Mutex<BatchState>,
+}
+
+#[derive(Default)]
+struct BatchState {
+    batches: HashMap<String, Batch>,
+    /// 任务 ID 到批次 ID 的映射，仅包含尚未结束的任务
+    members: HashMap<String, String>,
+}
+
+/// 进行中的批次
+#[derive(Default)]
+struct Batch {
+    /// 各任务的进度百分比
+    progress: HashMap<String, f64>,
+    succeeded: usize,
+    failed: usize,
+    cancelled: usize,
+    first_failure: Option<BatchFailure>,
+}
+
+impl Batch {
+    fn completed(&self) -> usize {
+        self.succeeded + self.failed + self.cancelled
+    }
+
+    fn snapshot(&self, batch_id: &str) -> BatchProgress {
+        let total = self.progress.len();
+        BatchProgress {
+            batch_id: batch_id.to_string(),
+            total,
+            completed: self.completed(),
+            failed: self.failed + self.cancelled,
+            percentage: if total == 0 {
+                0.0
+            } else {
+                self.progress.values().sum::<f64>() / total as f64
+            },
+        }
+    }
+}
+
+impl BatchTracker {
+    /// 将已注册的任务加入其所属批次，批次不存在时创建
+    ///
+    /// 批次在所有已加入的任务结束后结束，之后再提交同一批次 ID 的任务会开始新的批次。
+    pub fn join(&self, info: &JobInfo) {
+        let Some(batch_id) = &info.batch_id else {
+            return;
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state
+            .batches
+            .entry(batch_id.clone())
+            .or_default()
+            .progress
+            .insert(info.id.clone(), 0.0);
+        state.members.insert(info.id.clone(), batch_id.clone());
+    }
+
+    /// 记录任务进度，返回任务所属批次的汇总进度
+    pub fn progress(&self, progress: &EncodeProgress) -> Option<BatchProgress> {
+        let percentage = progress.percentage?;
+
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let batch_id = state.members.get(&progress.job_id)?;
+        let batch = state.batches.get_mut(batch_id)?;
+        batch
+            .progress
+            .insert(progress.job_id.clone(), percentage.clamp(0.0, 100.0));
+        Some(batch.snapshot(batch_id))
+    }
+
+    /// 记录任务结束，返回批次的汇总进度，以及批次中所有任务都已结束时的结果
+    pub fn finish(&self, finished: &JobFinished) -> Option<(BatchProgress, Option<BatchFinished>)> {
+        let mut state = self.state.lock().unwrap();
+        let batch_id = state.members.remove(&finished.job_id)?;
+        let batch = state.batches.get_mut(&batch_id)?;
+
+        batch.progress.insert(finished.job_id.clone(), 100.0);
+        if finished.success {
+            batch.succeeded += 1;
+        } else if finished.cancelled {
+            batch.cancelled += 1;
+        } else {
+            batch.failed += 1;
+            batch.first_failure.get_or_insert_with(|| BatchFailure {
+                job_id: finished.job_id.clone(),
+                exit_code: finished.exit_code,
+                error: finished.error.clone(),
+                error_code: finished.error_code.clone(),
+            });
+        }
+
+        let progress = batch.snapshot(&batch_id);
+        if batch.completed() < batch.progress.len() {
+            return Some((progress, None));
+        }
+
+        let batch = state.batches.remove(&batch_id)?;
+        let done = BatchFinished {
+            batch_id,
+            total: progress.total,
+            succeeded: batch.succeeded,
+            failed: batch.failed,
+            cancelled: batch.cancelled,
+            success: batch.failed == 0 && batch.cancelled == 0,
+            first_failure: batch.first_failure,
+        };
+        Some((progress, Some(done)))
+    }
+}
@@ -0,0 +1,67 @@
+//! # Child
+//!
+//! 直接暴露 FFmpeg 子进程，供需要自定义管道的 Rust 代码使用。
+
+use std::process::ExitStatus;
+
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout};
+
+use crate::error::{Error, Result};
+
+/// 通过 `Ffmpeg::spawn_raw` 启动的 FFmpeg 进程
+///
+/// 进程不经过任务队列，也不会出现在 `list_jobs` 中，由调用方负责等待或终止。
+/// 句柄被释放时进程会被终止。
+#[derive(Debug)]
+pub struct FfmpegChild {
+    child: Child,
+}
+
+impl FfmpegChild {
+    pub(crate) fn new(child: Child) -> Self {
+        Self { child }
+    }
+
+    /// 进程 ID，进程已退出时为 None
+    pub fn id(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// 取出标准输入，只有第一次调用返回 Some
+    pub fn stdin(&mut self) -> Option<ChildStdin> {
+        self.child.stdin.take()
+    }
+
+    /// 取出标准输出，只有第一次调用返回 Some
+    pub fn stdout(&mut self) -> Option<ChildStdout> {
+        self.child.stdout.take()
+    }
+
+    /// 取出标准错误输出，只有第一次调用返回 Some
+    pub fn stderr(&mut self) -> Option<ChildStderr> {
+        self.child.stderr.take()
+    }
+
+    /// 等待进程退出
+    ///
+    /// 标准输入未被取出时会先关闭，避免 FFmpeg 一直等待输入。
+    pub async fn wait(&mut self) -> Result<ExitStatus> {
+        self.child
+            .wait()
+            .await
+            .map_err(|e| Error::CommandExecution(e.to_string()))
+    }
+
+    /// 终止进程并等待退出
+    pub async fn kill(&mut self) -> Result<()> {
+        self.child
+            .kill()
+            .await
+            .map_err(|e| Error::CommandExecution(e.to_string()))
+    }
+
+    /// 取出底层的 [`tokio::process::Child`]
+    pub fn into_inner(self) -> Child {
+        self.child
+    }
+}
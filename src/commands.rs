@@ -1,12 +1,20 @@
-use tauri::{command, AppHandle, Runtime};
+use tauri::ipc::Channel;
+use tauri::{command, AppHandle, Runtime, Window};
 
+use crate::audit::{AuditAction, AuditEntry, AuditLogRequest};
+use crate::helpers::*;
+use crate::jobs::new_job_id;
 use crate::models::*;
+use crate::template::RunTemplateRequest;
 use crate::FfmpegExt;
 use crate::Result;
 
 #[command]
-pub(crate) async fn check<R: Runtime>(app: AppHandle<R>) -> Result<CheckResponse> {
-    app.ffmpeg().check()
+pub(crate) async fn check<R: Runtime>(
+    app: AppHandle<R>,
+    payload: Option<CheckRequest>,
+) -> Result<CheckResponse> {
+    app.ffmpeg().check(payload.unwrap_or_default()).await
 }
 
 #[command]
@@ -20,12 +28,498 @@ pub(crate) async fn download<R: Runtime>(
 #[command]
 pub(crate) async fn execute<R: Runtime>(
     app: AppHandle<R>,
-    payload: ExecuteRequest,
+    window: Window<R>,
+    mut payload: ExecuteRequest,
+) -> Result<ExecuteResponse> {
+    payload.tags.window = Some(window.label().to_string());
+    app.ffmpeg().audit_execute("execute", &mut payload);
+    app.ffmpeg().execute(payload).await
+}
+
+#[command]
+pub(crate) async fn enqueue<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: EnqueueRequest,
+) -> Result<JobInfo> {
+    payload.execute.tags.window = Some(window.label().to_string());
+    app.ffmpeg().audit_execute("enqueue", &mut payload.execute);
+    app.ffmpeg().enqueue(payload)
+}
+
+#[command]
+pub(crate) async fn restore_queue<R: Runtime>(app: AppHandle<R>) -> Result<Vec<JobInfo>> {
+    Ok(app.ffmpeg().restore_queue())
+}
+
+#[command]
+pub(crate) async fn cancel<R: Runtime>(app: AppHandle<R>, payload: CancelRequest) -> Result<()> {
+    app.ffmpeg().cancel(&payload.job_id)
+}
+
+#[command]
+pub(crate) async fn send_command<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SendCommandRequest,
+) -> Result<()> {
+    app.ffmpeg()
+        .send_command(&payload.job_id, &payload.text)
+        .await
+}
+
+#[command]
+pub(crate) async fn cancel_download<R: Runtime>(
+    app: AppHandle<R>,
+    payload: Option<CancelDownloadRequest>,
+) -> Result<()> {
+    let payload = payload.unwrap_or_default();
+    app.ffmpeg().cancel_download(payload.download_id.as_deref())
+}
+
+#[command]
+pub(crate) async fn list_jobs<R: Runtime>(app: AppHandle<R>) -> Result<Vec<JobInfo>> {
+    Ok(app.ffmpeg().list_jobs())
+}
+
+#[command]
+pub(crate) async fn remove<R: Runtime>(
+    app: AppHandle<R>,
+    payload: Option<RemoveRequest>,
+) -> Result<DeleteResponse> {
+    app.ffmpeg().remove(payload.unwrap_or_default()).await
+}
+
+#[command]
+pub(crate) async fn draw_text<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DrawTextRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().draw_text(payload).await
+}
+
+#[command]
+pub(crate) async fn render_waveform_video<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: WaveformVideoRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().render_waveform_video(payload).await
+}
+
+#[command]
+pub(crate) async fn spectrogram<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: SpectrogramRequest,
+) -> Result<HelperOutput> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().spectrogram(payload).await
+}
+
+#[command]
+pub(crate) async fn channel_ops<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: ChannelOpsRequest,
+) -> Result<ChannelOpsResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().channel_ops(payload).await
+}
+
+#[command]
+pub(crate) async fn resample<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: ResampleRequest,
+) -> Result<ResampleResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().resample(payload).await
+}
+
+#[command]
+pub(crate) async fn denoise_audio<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DenoiseAudioRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().denoise_audio(payload).await
+}
+
+#[command]
+pub(crate) async fn transcode<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: TranscodeRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().transcode(payload).await
+}
+
+#[command]
+pub(crate) async fn detect_crop<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DetectCropRequest,
+) -> Result<CropRect> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().detect_crop(payload).await
+}
+
+#[command]
+pub(crate) async fn detect_interlacing<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DetectInterlacingRequest,
+) -> Result<InterlacingReport> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().detect_interlacing(payload).await
+}
+
+#[command]
+pub(crate) async fn detect_black<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DetectBlackRequest,
+) -> Result<Vec<TimeInterval>> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().detect_black(payload).await
+}
+
+#[command]
+pub(crate) async fn detect_freeze<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DetectFreezeRequest,
+) -> Result<Vec<TimeInterval>> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().detect_freeze(payload).await
+}
+
+#[command]
+pub(crate) async fn measure_sync_offset<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: MeasureSyncRequest,
+) -> Result<SyncOffset> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().measure_sync_offset(payload).await
+}
+
+#[command]
+pub(crate) async fn replace_audio<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: ReplaceAudioRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().replace_audio(payload).await
+}
+
+#[command]
+pub(crate) async fn analyze_complexity<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: AnalyzeComplexityRequest,
+) -> Result<ComplexityReport> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().analyze_complexity(payload).await
+}
+
+#[command]
+pub(crate) async fn estimate_output_size<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: EstimateOutputSizeRequest,
+) -> Result<SizeEstimate> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().estimate_output_size(payload).await
+}
+
+#[command]
+pub(crate) async fn list_builtin_profiles() -> Result<Vec<BuiltinProfile>> {
+    Ok(builtin_profiles())
+}
+
+#[command]
+pub(crate) async fn probe<R: Runtime>(
+    app: AppHandle<R>,
+    payload: ProbeRequest,
+) -> Result<ProbeResult> {
+    app.ffmpeg().probe(payload).await
+}
+
+#[command]
+pub(crate) async fn validate_for(payload: ValidateForRequest) -> Result<ValidationReport> {
+    Ok(crate::helpers::validate_for(
+        payload.platform,
+        &payload.probe,
+    ))
+}
+
+#[command]
+pub(crate) async fn list_platform_presets() -> Result<Vec<PlatformPreset>> {
+    Ok(platform_presets())
+}
+
+#[command]
+pub(crate) async fn transcode_multi<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: TranscodeMultiRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().transcode_multi(payload).await
+}
+
+#[command]
+pub(crate) async fn generate_proxies<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: GenerateProxiesRequest,
+) -> Result<ProxyManifest> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().generate_proxies(payload).await
+}
+
+#[command]
+pub(crate) async fn trim_silence<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: TrimSilenceRequest,
+) -> Result<TrimSilenceResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().trim_silence(payload).await
+}
+
+#[command]
+pub(crate) async fn prepare_for_transcription<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: PrepareForTranscriptionRequest,
+) -> Result<ExecuteResponse> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().prepare_for_transcription(payload).await
+}
+
+#[command]
+pub(crate) async fn media_hash<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: MediaHashRequest,
+) -> Result<MediaHash> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().media_hash(payload).await
+}
+
+#[command]
+pub(crate) async fn verify_file<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: VerifyFileRequest,
+) -> Result<CorruptionReport> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().verify_file(payload).await
+}
+
+#[command]
+pub(crate) async fn faststart<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: FaststartRequest,
+) -> Result<HelperOutput> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().faststart(payload).await
+}
+
+#[command]
+pub(crate) async fn smart_thumbnail<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: SmartThumbnailRequest,
+) -> Result<SmartThumbnail> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().smart_thumbnail(payload).await
+}
+
+#[command]
+pub(crate) async fn record_rtsp<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: RecordRtspRequest,
+) -> Result<RecordingSummary> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().record_rtsp(payload).await
+}
+
+#[command]
+pub(crate) async fn start_level_meter<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: LevelMeterRequest,
+) -> Result<LevelMeter> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().start_level_meter(payload).await
+}
+
+#[command]
+pub(crate) async fn record_audio<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: RecordAudioRequest,
+) -> Result<HelperOutput> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().record_audio(payload).await
+}
+
+#[command]
+pub(crate) async fn encode_from_bytes<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: EncodeFromBytesRequest,
+) -> Result<HelperOutput> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().encode_from_bytes(payload).await
+}
+
+#[command]
+pub(crate) async fn decode_frames<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: DecodeFramesRequest,
+    on_frame: Channel,
+) -> Result<DecodeFramesSummary> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().send_frames(payload, on_frame).await
+}
+
+#[command]
+pub(crate) async fn run_pipeline<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: PipelineRequest,
+) -> Result<PipelineOutput> {
+    payload.job.tags.window = Some(window.label().to_string());
+    let job_id = payload.job.job_id.get_or_insert_with(new_job_id).clone();
+    for stage in &payload.stages {
+        app.ffmpeg().audit(
+            Some(window.label()),
+            AuditAction::Execute {
+                command: "run_pipeline".to_string(),
+                job_id: job_id.clone(),
+                args: stage.args.clone(),
+            },
+        );
+    }
+    app.ffmpeg().run_pipeline(payload).await
+}
+
+#[command]
+pub(crate) async fn purge_temp<R: Runtime>(app: AppHandle<R>) -> Result<PurgeTempResponse> {
+    Ok(app.ffmpeg().purge_temp())
+}
+
+#[command]
+pub(crate) async fn get_quota_status<R: Runtime>(app: AppHandle<R>) -> Result<QuotaStatus> {
+    Ok(app.ffmpeg().get_quota_status())
+}
+
+#[command]
+pub(crate) async fn run_template<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: RunTemplateRequest,
 ) -> Result<ExecuteResponse> {
-    app.ffmpeg().execute(payload)
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().run_template(payload).await
+}
+
+#[command]
+pub(crate) async fn get_audit_log<R: Runtime>(
+    app: AppHandle<R>,
+    payload: AuditLogRequest,
+) -> Result<Vec<AuditEntry>> {
+    app.ffmpeg().get_audit_log(payload)
+}
+
+#[command]
+pub(crate) async fn reinstall<R: Runtime>(
+    app: AppHandle<R>,
+    payload: DownloadRequest,
+) -> Result<DownloadResponse> {
+    app.ffmpeg().reinstall(payload).await
+}
+
+#[command]
+pub(crate) async fn execute_str<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    payload: ExecuteStrRequest,
+) -> Result<ExecuteResponse> {
+    let mut request = payload.into_request()?;
+    request.tags.window = Some(window.label().to_string());
+    app.ffmpeg().audit_execute("execute_str", &mut request);
+    app.ffmpeg().execute(request).await
+}
+
+#[command]
+pub(crate) async fn recheck<R: Runtime>(
+    app: AppHandle<R>,
+    payload: Option<CheckRequest>,
+) -> Result<CheckResponse> {
+    app.ffmpeg().recheck(payload.unwrap_or_default()).await
+}
+
+#[command]
+pub(crate) async fn is_at_least<R: Runtime>(
+    app: AppHandle<R>,
+    payload: IsAtLeastRequest,
+) -> Result<bool> {
+    app.ffmpeg().is_at_least(&payload.version).await
+}
+
+#[command]
+pub(crate) async fn list_formats<R: Runtime>(app: AppHandle<R>) -> Result<Vec<FormatInfo>> {
+    app.ffmpeg().list_formats().await
+}
+
+#[command]
+pub(crate) async fn list_protocols<R: Runtime>(app: AppHandle<R>) -> Result<ProtocolsInfo> {
+    app.ffmpeg().list_protocols().await
+}
+
+#[command]
+pub(crate) async fn list_pix_fmts<R: Runtime>(app: AppHandle<R>) -> Result<Vec<PixFmtInfo>> {
+    app.ffmpeg().list_pix_fmts().await
+}
+
+#[command]
+pub(crate) async fn list_sample_fmts<R: Runtime>(app: AppHandle<R>) -> Result<Vec<SampleFmtInfo>> {
+    app.ffmpeg().list_sample_fmts().await
+}
+
+#[command]
+pub(crate) async fn filter_help<R: Runtime>(
+    app: AppHandle<R>,
+    payload: FilterHelpRequest,
+) -> Result<FilterHelp> {
+    app.ffmpeg().filter_help(&payload.name).await
+}
+
+#[command]
+pub(crate) async fn benchmark<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    mut payload: BenchmarkRequest,
+) -> Result<BenchmarkReport> {
+    payload.job.tags.window = Some(window.label().to_string());
+    app.ffmpeg().benchmark(payload).await
 }
 
 #[command]
-pub(crate) async fn remove<R: Runtime>(app: AppHandle<R>) -> Result<DeleteResponse> {
-    app.ffmpeg().remove()
+pub(crate) async fn ready_report<R: Runtime>(app: AppHandle<R>) -> Result<ReadyReport> {
+    Ok(app.ffmpeg().ready_report().await)
 }
@@ -0,0 +1,385 @@
+//! # Config
+//!
+//! 插件配置，对应 `tauri.conf.json` 中的 `plugins.use-ffmpeg` 字段。
+//!
+//! ```json
+//! {
+//!   "plugins": {
+//!     "use-ffmpeg": {
+//!       "defaultDownload": {
+//!         "url": "https://example.com/ffmpeg-freebsd.zip",
+//!         "executablePath": "ffmpeg"
+//!       },
+//!       "systemFfmpeg": false,
+//!       "maxConcurrentJobs": 2,
+//!       "maxQueueLength": 500,
+//!       "defaultThreads": 4,
+//!       "powerAware": {
+//!         "onBattery": "reduce",
+//!         "batteryMaxConcurrentJobs": 1
+//!       },
+//!       "restoreQueue": true,
+//!       "compressBinaries": false,
+//!       "shared": false,
+//!       "jobFinishedWebhook": "http://localhost:8080/jobs",
+//!       "allowedWebhookOrigins": ["https://hooks.example.com"],
+//!       "tempTtlHours": 24,
+//!       "storageQuotaMb": 2048,
+//!       "allowedProtocols": ["file", "https"],
+//!       "auditLog": true
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::messages::Locale;
+use crate::models::{BuildChannel, DownloadConfig};
+
+/// 插件配置
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    /// 覆盖内置的默认下载配置，对没有内置下载源的平台（如 FreeBSD、OpenBSD）必须提供
+    pub default_download: Option<DownloadConfig>,
+    /// 是否仅使用系统 `PATH` 中的 FFmpeg（不下载、不删除）
+    ///
+    /// 未设置时，BSD 平台在没有配置 `defaultDownload` 的情况下默认启用，其余平台默认关闭。
+    pub system_ffmpeg: Option<bool>,
+    /// 外部管理的 FFmpeg 可执行文件路径（例如由 IT 通过 MDM 部署），ffprobe、ffplay 在同一目录中查找
+    ///
+    /// 设置后优先于 `systemFfmpeg`，`download` 和 `remove` 被禁用，其余功能不受影响。
+    pub ffmpeg_path: Option<String>,
+    /// 开发调试用的 FFmpeg 路径，跳过常规的路径查找，但不禁用下载和删除
+    ///
+    /// 环境变量 `TAURI_FFMPEG_PATH` 优先于该配置。
+    pub path_override: Option<String>,
+    /// 同时运行的 FFmpeg 进程数上限，超出的任务排队等待，未设置时不限制
+    pub max_concurrent_jobs: Option<usize>,
+    /// 后台队列中尚未开始的任务数上限，达到上限时 `enqueue` 返回 [`Error::QueueFull`](crate::Error::QueueFull)，未设置时不限制
+    pub max_queue_length: Option<usize>,
+    /// 队列从该长度以上减少到该长度以下时发送 `use-ffmpeg://queue-drained` 事件，默认为 `maxQueueLength` 的一半
+    pub queue_low_watermark: Option<usize>,
+    /// 默认注入到每个任务的 `-threads` 值，参数中已包含 `-threads` 时不注入
+    pub default_threads: Option<u32>,
+    /// 使用电池或系统过热时限制任务并发，未设置时不启用
+    pub power_aware: Option<PowerAwareConfig>,
+    /// 启动时是否自动恢复上次保存的、尚未开始的后台任务
+    pub restore_queue: Option<bool>,
+    /// 每个任务结束时将 `JobFinished` 以 JSON POST 到该地址
+    pub job_finished_webhook: Option<String>,
+    /// 允许单个任务通过 `webhook` 选项 POST 结果的源（如 `["https://hooks.example.com"]`）
+    ///
+    /// 未设置时不接受单个任务的 `webhook`，防止 WebView 让插件向任意地址（包括本机和局域网）发送请求。
+    pub allowed_webhook_origins: Option<Vec<String>>,
+    /// `draw_text` 等辅助函数的默认字体文件，可以指向随应用分发的字体资源
+    pub default_font: Option<String>,
+    /// 是否以 zstd 压缩保存已安装的可执行文件，首次使用时再解压到应用缓存目录
+    ///
+    /// 适合很少调用 FFmpeg 的应用，可以显著减少常驻的磁盘占用，代价是首次使用时需要解压。
+    #[serde(default)]
+    pub compress_binaries: bool,
+    /// 是否安装到所有应用共用的 `<用户缓存目录>/tauri-ffmpeg/<平台>-<下载源>`，避免每个应用各保存一份
+    ///
+    /// `<下载源>` 由 `defaultDownload` 和 `provider` 计算，配置相同的应用共用一份，固定了不同版本的应用互不影响；
+    /// `download` 不能再通过 `config` 或 `channel` 改变下载源。安装和删除时通过锁文件与其他应用互斥。
+    #[serde(default)]
+    pub shared: bool,
+    /// 辅助函数遗留的中间文件（应用异常退出时未能删除）的保留时间（小时），默认 24，超过后自动清理
+    pub temp_ttl_hours: Option<u64>,
+    /// 内置下载源的选项
+    #[serde(default)]
+    pub provider: ProviderConfig,
+    /// 下载时最多跟随的重定向次数，默认 10，设为 0 时不跟随重定向
+    pub max_redirects: Option<usize>,
+    /// 窗口关闭时是否终止该窗口发起的所有任务（包括排队中的任务），避免关闭的编辑窗口留下无人关心的编码
+    #[serde(default)]
+    pub kill_jobs_on_window_close: bool,
+    /// 是否只接受带有有效 Authenticode 签名的可执行文件（仅 Windows）
+    ///
+    /// 下载解压后验证签名，未签名或签名无效的文件被删除，下载返回
+    /// [`Error::UntrustedBinary`](crate::Error::UntrustedBinary)。内置的 BtbN 构建没有签名，需要配合提供签名文件的
+    /// `defaultDownload` 使用。
+    #[serde(default)]
+    pub require_signed_binaries: bool,
+    /// `requireSignedBinaries` 启用时允许的签名者证书名称，未设置时接受任何受信任的签名
+    pub trusted_signers: Option<Vec<String>>,
+    /// 是否将下载、删除、任意参数的命令等特权操作追加记录到应用数据目录中的 `audit.jsonl`
+    #[serde(default)]
+    pub audit_log: bool,
+    /// 允许 FFmpeg 使用的输入输出协议（如 `["file", "pipe", "https"]`），未设置时不限制
+    ///
+    /// 设置后每个输入和输出前注入 `-protocol_whitelist`，`https`、`rtsp` 等依赖的 `tls`、`tcp` 等底层协议自动加入；
+    /// 参数中的输入、输出（包括 tee 输出中的各个目标）或 `movie` / `amovie` 滤镜使用了其他协议，
+    /// 或者无法确定协议时任务在启动前被拒绝，防止 WebView 传入的参数让 FFmpeg 访问内网地址。
+    /// `-progress`、`-attach` 等由 FFmpeg 自行打开的值、`probe` 等辅助函数直接读取的输入同样检查，
+    /// 参数中包含未知的选项时同样拒绝。本地文件需要 `file`，`pipe` 始终允许。
+    pub allowed_protocols: Option<Vec<String>>,
+    /// 插件临时目录和解压的可执行文件合计占用的磁盘空间上限（MB），未设置时不限制
+    ///
+    /// 超出时删除最久未用的文件，仍然放不下时返回 [`Error::QuotaExceeded`](crate::Error::QuotaExceeded)。
+    pub storage_quota_mb: Option<u64>,
+    /// 前端开发用的模拟模式，任务不运行 FFmpeg，只发送模拟的进度事件，需要启用 `simulate` feature，仅限调试构建
+    #[serde(default)]
+    pub simulate: bool,
+    /// 响应消息和错误信息的语言，支持 `en` 和 `zh-CN`
+    ///
+    /// 设置后错误以 `{ code, message }` 对象返回，可以根据 `code` 自行翻译；未设置时错误为英文字符串。
+    pub locale: Option<String>,
+}
+
+/// 内置下载源的选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderConfig {
+    /// 构建渠道，默认 `release`，可以被 `download` 请求中的 `channel` 覆盖
+    pub channel: Option<BuildChannel>,
+    /// Windows 上使用的 BtbN/FFmpeg-Builds 发布标签，默认 `latest`
+    ///
+    /// 可以固定为某次自动构建（如 `autobuild-2025-01-01-12-55`），使所有用户安装完全相同的文件。
+    pub btbn_tag: Option<String>,
+    /// macOS 上使用的 evermeet.cx 构建，设置后优先于 `channel`
+    pub evermeet_channel: Option<EvermeetChannel>,
+}
+
+/// evermeet.cx 的构建类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvermeetChannel {
+    /// 正式发布版本
+    #[default]
+    Release,
+    /// 每日构建的 git master 快照
+    Snapshot,
+}
+
+/// 使用电池时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatteryAction {
+    /// 降低并发数到 `batteryMaxConcurrentJobs`
+    #[default]
+    Reduce,
+    /// 暂停队列，正在运行的任务不受影响
+    Pause,
+    /// 不做处理
+    Ignore,
+}
+
+/// 电源感知的限流配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerAwareConfig {
+    /// 使用电池时的处理方式
+    #[serde(default)]
+    pub on_battery: BatteryAction,
+    /// 使用电池时的最大并发数，默认 1
+    pub battery_max_concurrent_jobs: Option<usize>,
+    /// 系统报告过热（目前仅 macOS）时是否暂停队列
+    #[serde(default = "default_true")]
+    pub pause_on_thermal_pressure: bool,
+    /// 检查电源状态的间隔（秒），默认 30
+    pub poll_interval_secs: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Config {
+    /// 是否启用系统 FFmpeg 模式
+    pub fn use_system_ffmpeg(&self) -> bool {
+        self.system_ffmpeg.unwrap_or(
+            cfg!(any(
+                target_os = "freebsd",
+                target_os = "openbsd",
+                target_os = "netbsd"
+            )) && self.default_download.is_none(),
+        )
+    }
+
+    /// 检查配置是否有效，所有问题合并为一个 [`Error::InvalidConfig`]
+    ///
+    /// 只检查不依赖应用路径的部分，安装目录是否可写在插件初始化时检查。
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if let Some(download) = &self.default_download {
+            check_url("defaultDownload.url", &download.url, &mut problems);
+            if download.executable_path.trim().is_empty() {
+                problems.push("`defaultDownload.executablePath` must not be empty".to_string());
+            }
+            for (index, patch) in download.patches.iter().enumerate() {
+                check_url(
+                    &format!("defaultDownload.patches[{index}].url"),
+                    &patch.url,
+                    &mut problems,
+                );
+            }
+        }
+
+        if let Some(url) = &self.job_finished_webhook {
+            check_url("jobFinishedWebhook", url, &mut problems);
+        }
+        for (index, origin) in self.allowed_webhook_origins.iter().flatten().enumerate() {
+            let key = format!("allowedWebhookOrigins[{index}]");
+            check_url(&key, origin, &mut problems);
+            if reqwest::Url::parse(origin)
+                .is_ok_and(|url| url.path() != "/" || url.query().is_some())
+            {
+                problems.push(format!(
+                    "`{key}` must be an origin such as `https://hooks.example.com`, got `{origin}`"
+                ));
+            }
+        }
+
+        if self.max_concurrent_jobs == Some(0) {
+            problems
+                .push("`maxConcurrentJobs` must be at least 1, omit it for no limit".to_string());
+        }
+
+        if self.max_queue_length == Some(0) {
+            problems.push("`maxQueueLength` must be at least 1, omit it for no limit".to_string());
+        }
+
+        match (self.max_queue_length, self.queue_low_watermark) {
+            (_, Some(0)) => problems.push("`queueLowWatermark` must be at least 1".to_string()),
+            (Some(max), Some(low)) if low > max => problems.push(format!(
+                "`queueLowWatermark` ({low}) must not exceed `maxQueueLength` ({max})"
+            )),
+            (None, Some(_)) => {
+                problems.push("`queueLowWatermark` requires `maxQueueLength`".to_string())
+            }
+            _ => {}
+        }
+
+        if self
+            .provider
+            .btbn_tag
+            .as_deref()
+            .is_some_and(|tag| tag.trim().is_empty() || tag.contains('/'))
+        {
+            problems.push("`provider.btbnTag` must be a release tag name".to_string());
+        }
+
+        if self.storage_quota_mb == Some(0) {
+            problems.push("`storageQuotaMb` must be at least 1, omit it for no limit".to_string());
+        }
+
+        if self.temp_ttl_hours == Some(0) {
+            problems.push("`tempTtlHours` must be at least 1".to_string());
+        }
+
+        if let Some(power_aware) = &self.power_aware {
+            if power_aware.battery_max_concurrent_jobs == Some(0) {
+                problems
+                    .push("`powerAware.batteryMaxConcurrentJobs` must be at least 1".to_string());
+            }
+            if power_aware.poll_interval_secs == Some(0) {
+                problems.push("`powerAware.pollIntervalSecs` must be at least 1".to_string());
+            }
+        }
+
+        for (key, path) in [
+            ("ffmpegPath", &self.ffmpeg_path),
+            ("pathOverride", &self.path_override),
+        ] {
+            if let Some(path) = path {
+                if !Path::new(path).is_file() {
+                    problems.push(format!("`{key}` does not point to a file: {path}"));
+                }
+            }
+        }
+
+        if self.trusted_signers.is_some() && !self.require_signed_binaries {
+            problems.push("`trustedSigners` requires `requireSignedBinaries`".to_string());
+        }
+        if self
+            .trusted_signers
+            .as_ref()
+            .is_some_and(|signers| signers.is_empty())
+        {
+            problems.push("`trustedSigners` must not be empty".to_string());
+        }
+
+        if let Some(protocols) = &self.allowed_protocols {
+            if protocols.is_empty() {
+                problems
+                    .push("`allowedProtocols` must not be empty, omit it for no limit".to_string());
+            }
+            for protocol in protocols {
+                if protocol.is_empty()
+                    || !protocol
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                {
+                    problems.push(format!(
+                        "`allowedProtocols` contains an invalid protocol name `{protocol}`"
+                    ));
+                }
+            }
+        }
+
+        if self.simulate && !cfg!(feature = "simulate") {
+            problems.push("`simulate` requires the `simulate` feature".to_string());
+        }
+        if self.simulate && !cfg!(debug_assertions) {
+            problems.push("`simulate` is only available in debug builds".to_string());
+        }
+
+        if let Some(locale) = &self.locale {
+            if Locale::parse(locale).is_none() {
+                problems.push(format!(
+                    "`locale` `{locale}` is not supported, use `en` or `zh-CN`"
+                ));
+            }
+        }
+
+        // 互相冲突的选项
+        let managed = if self.ffmpeg_path.is_some() {
+            Some("ffmpegPath")
+        } else if self.system_ffmpeg == Some(true) {
+            Some("systemFfmpeg")
+        } else {
+            None
+        };
+        if self.ffmpeg_path.is_some() && self.system_ffmpeg == Some(true) {
+            problems.push("`ffmpegPath` and `systemFfmpeg` cannot be used together".to_string());
+        }
+        if let Some(managed) = managed {
+            for (key, enabled) in [
+                ("shared", self.shared),
+                ("compressBinaries", self.compress_binaries),
+                ("defaultDownload", self.default_download.is_some()),
+            ] {
+                if enabled {
+                    problems.push(format!(
+                        "`{key}` has no effect when `{managed}` is set, FFmpeg is never downloaded"
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidConfig(problems.join("; ")))
+        }
+    }
+}
+
+/// 检查 URL 是否为有效的 http(s) 地址
+fn check_url(key: &str, url: &str, problems: &mut Vec<String>) {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        Ok(parsed) => problems.push(format!(
+            "`{key}` must be an http or https URL, got `{}`",
+            parsed.scheme()
+        )),
+        Err(e) => problems.push(format!("`{key}` is not a valid URL ({e}): {url}")),
+    }
+}
@@ -1,40 +1,367 @@
 use futures_util::StreamExt;
-use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use tauri::{plugin::PluginApi, AppHandle, Emitter, Manager, Runtime};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{plugin::PluginApi, AppHandle, Manager, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+use crate::archive::{self, ArchiveFormat};
+use crate::args::{
+    apply_overwrite_policy, apply_protocol_whitelist, apply_threads, arg_warnings, normalize_args,
+    output_paths, refused_overwrite,
+};
+use crate::audit::{AuditAction, AuditEntry, AuditLog, AuditLogRequest};
+use crate::batch::BatchTracker;
+use crate::child::FfmpegChild;
+use crate::config::Config;
 use crate::error::{Error, Result};
+use crate::events;
+use crate::hooks::Hooks;
+use crate::install::{InstallGuard, InstallState, ProgressCallback};
+use crate::jobs::{new_job_id, now_millis, JobGuard, JobRegistry};
+use crate::lock::DirLock;
+use crate::messages::{self, MessageCode};
+use crate::metrics::{DownloadMetrics, FailureCategory, JobMetrics};
 use crate::models::*;
+use crate::patch;
+use crate::progress::ProgressParser;
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use crate::providers;
+use crate::queue::QueueStore;
+use crate::quota;
+use crate::scheduler::Scheduler;
+use crate::signature;
+use crate::stats::StatsSampler;
+use crate::storage;
+use crate::temp::{self, TempFile, TempRegistry};
+use crate::template::CommandTemplate;
+use crate::version::{self, FfmpegVersion};
+use crate::FfmpegExt;
 
-pub fn init<R: Runtime, C: DeserializeOwned>(
+/// 共享安装目录的名称
+const SHARED_DIR: &str = "tauri-ffmpeg";
+
+/// 覆盖 FFmpeg 路径的环境变量
+const PATH_OVERRIDE_ENV: &str = "TAURI_FFMPEG_PATH";
+
+/// 下载前确认地址可以访问的超时时间
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 等待网络恢复的下载检查网络的间隔
+const ONLINE_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 检查网络时连接服务器的超时时间
+const ONLINE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `check` 未设置 `slow_threshold_ms` 时发送 `check-slow` 事件前等待的时间
+const CHECK_SLOW_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// 安装后等待杀毒软件完成扫描的时间
+const QUARANTINE_SETTLE: Duration = Duration::from_millis(500);
+
+/// Windows 的 `ERROR_VIRUS_INFECTED` 和 `ERROR_VIRUS_DELETED`
+const ERROR_VIRUS_CODES: [i32; 2] = [225, 226];
+
+/// 下载时默认最多跟随的重定向次数，与 reqwest 的默认值相同
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+pub fn init<R: Runtime>(
     app: &AppHandle<R>,
-    _api: PluginApi<R, C>,
+    api: PluginApi<R, Option<Config>>,
+    hooks: Hooks<R>,
+    templates: HashMap<String, CommandTemplate>,
 ) -> crate::Result<Ffmpeg<R>> {
-    Ok(Ffmpeg(app.clone()))
+    let config: Config = api.config().clone().unwrap_or_default();
+    config.validate()?;
+    for (name, template) in &templates {
+        let undeclared = template.undeclared();
+        if !undeclared.is_empty() {
+            return Err(Error::InvalidConfig(format!(
+                "Command template `{name}` uses undeclared parameters: {}",
+                undeclared.join(", ")
+            )));
+        }
+    }
+    messages::init(config.locale.as_deref());
+    let queue_path = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("queue.json"));
+    let temp_dir = app
+        .path()
+        .app_cache_dir()
+        .map(|dir| dir.join("temp"))
+        .unwrap_or_else(|_| std::env::temp_dir().join("use-ffmpeg"));
+    let temp_ttl = config.temp_ttl_hours.unwrap_or(temp::DEFAULT_TTL_HOURS);
+    let audit_path = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("audit.jsonl"));
+
+    let ffmpeg = Ffmpeg {
+        app: app.clone(),
+        queue: QueueStore::load(
+            queue_path,
+            config.max_queue_length,
+            config.queue_low_watermark,
+        ),
+        hooks,
+        templates,
+        audit: AuditLog::new(audit_path, config.audit_log),
+        scheduler: Scheduler::new(config.max_concurrent_jobs),
+        config,
+        jobs: Arc::new(JobRegistry::default()),
+        batches: BatchTracker::default(),
+        temp: TempRegistry::new(temp_dir, Duration::from_secs(temp_ttl * 60 * 60)),
+        install: InstallState::default(),
+        check_cache: Mutex::new(CheckCache::default()),
+        ready: tokio::sync::OnceCell::new(),
+    };
+    ffmpeg.check_install_dir()?;
+
+    Ok(ffmpeg)
 }
 
 /// Access to the ffmpeg APIs.
-pub struct Ffmpeg<R: Runtime>(AppHandle<R>);
+///
+/// 通过 [`FfmpegExt::ffmpeg`](crate::FfmpegExt::ffmpeg) 从 `App`、`AppHandle` 或 `Window` 获取。
+/// 前端命令都是对这些方法的封装，Rust 端可以直接调用，不需要经过 WebView：
+///
+/// - 安装管理：[`check`](Self::check)、[`download`](Self::download)、[`reinstall`](Self::reinstall)、[`remove`](Self::remove)
+/// - 运行任务：[`execute`](Self::execute)、[`spawn_raw`](Self::spawn_raw)、[`run_template`](Self::run_template)
+/// - 后台队列：[`enqueue`](Self::enqueue)、[`restore_queue`](Self::restore_queue)、[`list_jobs`](Self::list_jobs)、[`cancel`](Self::cancel)
+/// - 辅助函数：[`transcode`](Self::transcode)、[`probe`](Self::probe)、[`smart_thumbnail`](Self::smart_thumbnail) 等，
+///   参数与前端相同，结构体字段为 snake_case
+///
+/// Rust 端发起的任务同样发送进度和生命周期事件，任务信息中没有 `window`。
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use tauri::Manager;
+/// use tauri_plugin_use_ffmpeg::{FfmpegExt, HelperOptions, TranscodeOptions, TranscodeRequest};
+///
+/// tauri::Builder::default()
+///     .plugin(tauri_plugin_use_ffmpeg::init())
+///     .setup(|app| {
+///         let handle = app.handle().clone();
+///         tauri::async_runtime::spawn(async move {
+///             let ffmpeg = handle.ffmpeg();
+///             let info = ffmpeg.probe_typed("input.mov").await?;
+///             if info.video().is_some() {
+///                 ffmpeg
+///                     .transcode(TranscodeRequest {
+///                         input: "input.mov".to_string(),
+///                         output: "output.mp4".to_string(),
+///                         options: TranscodeOptions {
+///                             video_codec: Some("libx264".to_string()),
+///                             crf: Some(23),
+///                             ..Default::default()
+///                         },
+///                         job: HelperOptions {
+///                             wait_for_install: true,
+///                             ..Default::default()
+///                         },
+///                     })
+///                     .await?;
+///             }
+///             Ok::<_, tauri_plugin_use_ffmpeg::Error>(())
+///         });
+///         Ok(())
+///     });
+/// ```
+pub struct Ffmpeg<R: Runtime> {
+    app: AppHandle<R>,
+    config: Config,
+    jobs: Arc<JobRegistry>,
+    batches: BatchTracker,
+    temp: TempRegistry,
+    install: InstallState,
+    scheduler: Scheduler,
+    queue: QueueStore,
+    hooks: Hooks<R>,
+    templates: HashMap<String, CommandTemplate>,
+    audit: AuditLog,
+    /// 最近一次完整的检查结果，下载、删除或调用 [`Ffmpeg::recheck`] 后失效
+    check_cache: Mutex<CheckCache>,
+    /// 初始化时生成的就绪报告
+    ready: tokio::sync::OnceCell<ReadyReport>,
+}
 
 impl<R: Runtime> Ffmpeg<R> {
+    /// 插件配置
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 应用句柄，供辅助函数发送自己的事件
+    pub(crate) fn app(&self) -> &AppHandle<R> {
+        &self.app
+    }
+
+    /// 是否处于模拟模式（`simulate` feature 且配置了 `simulate: true`）
+    fn simulating(&self) -> bool {
+        cfg!(feature = "simulate") && self.config.simulate
+    }
+
+    /// 宿主应用注册的命令模板
+    pub(crate) fn template(&self, name: &str) -> Option<&CommandTemplate> {
+        self.templates.get(name)
+    }
+
+    /// 追加一条审计记录，未启用 `auditLog` 时忽略
+    pub(crate) fn audit(&self, window: Option<&str>, action: AuditAction) {
+        self.audit.record(window, action);
+    }
+
+    /// 记录前端以任意参数发起的命令，未指定任务 ID 时先分配一个，使记录可以与任务事件对应
+    pub(crate) fn audit_execute(&self, command: &str, request: &mut ExecuteRequest) {
+        let job_id = request.job_id.get_or_insert_with(new_job_id).clone();
+        let window = request.tags.window.as_deref();
+        if let Some(webhook) = &request.webhook {
+            self.audit(
+                window,
+                AuditAction::PolicyOverride {
+                    policy: "webhook".to_string(),
+                    detail: format!("Job {job_id} posts its result to {webhook}"),
+                },
+            );
+        }
+        self.audit(
+            window,
+            AuditAction::Execute {
+                command: command.to_string(),
+                job_id,
+                args: request.args.clone(),
+            },
+        );
+    }
+
+    /// 临时文件登记表
+    pub(crate) fn temp(&self) -> &TempRegistry {
+        &self.temp
+    }
+
+    /// 在插件的临时目录中登记一个中间文件，返回的 [`TempFile`] 被释放时删除
+    ///
+    /// 设置了 `storageQuotaMb` 时先检查配额。
+    pub(crate) fn temp_file(&self, prefix: &str, extension: &str) -> Result<TempFile> {
+        self.reserve_storage(0, None)?;
+        self.temp.create(prefix, extension)
+    }
+
+    /// 确保受配额限制的目录还能写入 `incoming` 字节，必要时删除最久未用的文件
+    ///
+    /// 正在使用的临时文件和 `protected` 不会被删除。未设置 `storageQuotaMb` 时不做任何事。
+    pub(crate) fn reserve_storage(&self, incoming: u64, protected: Option<&Path>) -> Result<()> {
+        let Some(limit) = self.quota_limit() else {
+            return Ok(());
+        };
+
+        let mut active = self.temp.active();
+        active.extend(protected.map(Path::to_path_buf));
+        quota::reserve(&self.quota_dirs(), limit, incoming, &active)
+    }
+
+    /// 存储配额（字节）
+    fn quota_limit(&self) -> Option<u64> {
+        self.config
+            .storage_quota_mb
+            .map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+
+    /// 共享安装目录中区分下载源的部分：`defaultDownload` 和 `provider` 的 SHA-256 的前 12 位
+    ///
+    /// 固定了不同版本或下载源的应用使用不同的目录，不会互相覆盖对方的可执行文件。
+    fn shared_source_key(&self) -> String {
+        let source = serde_json::to_vec(&(&self.config.default_download, &self.config.provider))
+            .unwrap_or_default();
+        let digest = format!("{:x}", Sha256::digest(source));
+        digest[..12].to_string()
+    }
+
+    /// 受配额限制的目录：临时目录，以及压缩存储模式下解压的可执行文件
+    fn quota_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.temp.dir().to_path_buf()];
+        if self.config.compress_binaries {
+            dirs.extend(self.get_runtime_dir().ok());
+        }
+        dirs
+    }
+
+    /// 应用电源感知的限流状态
+    pub(crate) fn apply_throttle(&self, state: &ThrottleState) {
+        self.scheduler
+            .set_throttle(state.paused, state.max_concurrent_jobs);
+    }
+
     /// 获取 FFmpeg 二进制文件的存储路径
+    ///
+    /// 共享模式下为下载源相同的应用共用的 `<用户缓存目录>/tauri-ffmpeg/<平台>-<下载源>`，
+    /// 见 [`Ffmpeg::shared_source_key`]。
     fn get_ffmpeg_dir(&self) -> Result<PathBuf> {
-        let app_data_dir = self.0.path().app_data_dir().map_err(|e| {
+        let platform = self.get_platform()?;
+
+        if self.config.shared {
+            let cache_dir = self.app.path().cache_dir().map_err(|e| {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    e.to_string(),
+                ))
+            })?;
+            let name = format!("{platform}-{}", self.shared_source_key());
+            return Ok(cache_dir.join(SHARED_DIR).join(name));
+        }
+
+        let app_data_dir = self.app.path().app_data_dir().map_err(|e| {
             Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 e.to_string(),
             ))
         })?;
 
-        let platform = self.get_platform()?;
         let ffmpeg_dir = app_data_dir.join("bin").join(platform);
 
         Ok(ffmpeg_dir)
     }
 
+    /// 检查安装目录是否可写，避免到第一次下载时才失败
+    fn check_install_dir(&self) -> Result<()> {
+        if self.config.ffmpeg_path.is_some() || self.config.use_system_ffmpeg() {
+            return Ok(());
+        }
+
+        // 目录尚未创建时检查最近的已存在的上级目录，不提前创建
+        let ffmpeg_dir = self.get_ffmpeg_dir()?;
+        let Some(existing) = ffmpeg_dir.ancestors().find(|dir| dir.exists()) else {
+            return Ok(());
+        };
+        let probe = existing.join(format!(".write-test-{}", new_job_id()));
+        fs::write(&probe, b"")
+            .and_then(|_| fs::remove_file(&probe))
+            .map_err(|e| {
+                Error::InvalidConfig(format!(
+                    "install directory {} is not writable: {e}",
+                    ffmpeg_dir.display()
+                ))
+            })
+    }
+
+    /// 共享模式下获取安装目录的跨进程锁
+    async fn lock_ffmpeg_dir(&self, ffmpeg_dir: &Path) -> Result<Option<DirLock>> {
+        if !self.config.shared {
+            return Ok(None);
+        }
+        Ok(Some(DirLock::acquire(ffmpeg_dir).await?))
+    }
+
     /// 获取当前平台名称
     fn get_platform(&self) -> Result<&'static str> {
         #[cfg(target_os = "macos")]
@@ -46,97 +373,729 @@ impl<R: Runtime> Ffmpeg<R> {
         #[cfg(target_os = "linux")]
         return Ok("linux");
 
-        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        #[cfg(target_os = "freebsd")]
+        return Ok("freebsd");
+
+        #[cfg(target_os = "openbsd")]
+        return Ok("openbsd");
+
+        #[cfg(target_os = "netbsd")]
+        return Ok("netbsd");
+
+        #[cfg(not(any(
+            target_os = "macos",
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd"
+        )))]
         return Err(Error::UnsupportedPlatform);
     }
 
     /// 获取默认下载配置
-    fn get_default_config(&self) -> Result<DownloadConfig> {
+    ///
+    /// macOS 上通过 evermeet.cx 的 API 解析当前版本的下载地址，
+    /// Windows 上通过 GitHub API 解析 BtbN 发布中的具体文件和校验和。
+    /// `channel` 未设置时使用插件配置中的 `provider.channel`。
+    ///
+    /// 只安装一个工具时，macOS 直接下载该工具单独的压缩包，其余平台的压缩包包含所有工具。
+    async fn get_default_config(
+        &self,
+        channel: Option<BuildChannel>,
+        tools: Option<&[Tool]>,
+    ) -> Result<DownloadConfig> {
+        if let Some(config) = &self.config.default_download {
+            return Ok(config.clone());
+        }
+
+        let provider = &self.config.provider;
+        let channel = channel.or(provider.channel).unwrap_or_default();
+
         #[cfg(target_os = "macos")]
-        return Ok(DownloadConfig {
-            url: "https://evermeet.cx/ffmpeg/ffmpeg-8.0.zip".to_string(),
-            executable_path: "ffmpeg".to_string(),
-        });
+        return {
+            let tool = match tools {
+                Some([tool]) => *tool,
+                _ => Tool::Ffmpeg,
+            };
+            providers::evermeet(&providers::api_client()?, provider, channel, tool).await
+        };
 
         #[cfg(target_os = "windows")]
-    return Ok(DownloadConfig {
-      url: "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-n8.0-latest-win64-gpl-8.0.zip".to_string(),
-      executable_path: "bin/ffmpeg.exe".to_string(),
-    });
+        return {
+            let _ = tools;
+            providers::btbn(&providers::api_client()?, provider, channel).await
+        };
 
         #[cfg(target_os = "linux")]
-        return Ok(DownloadConfig {
-            url: "https://johnvansickle.com/ffmpeg/releases/ffmpeg-release-amd64-static.tar.xz"
-                .to_string(),
-            executable_path: "ffmpeg".to_string(),
-        });
+        return {
+            let _ = tools;
+            Ok(providers::johnvansickle(channel))
+        };
 
+        // BSD 等平台没有官方静态构建，需要通过插件配置提供下载源
         #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
-        return Err(Error::UnsupportedPlatform);
+        return {
+            let _ = (provider, channel, tools);
+            Err(Error::NoDefaultSource(self.get_platform()?.to_string()))
+        };
     }
 
     /// 获取 FFmpeg 可执行文件路径
-    fn get_ffmpeg_executable_path(&self) -> Result<PathBuf> {
-        let ffmpeg_dir = self.get_ffmpeg_dir()?;
+    pub(crate) fn get_ffmpeg_executable_path(&self) -> Result<PathBuf> {
+        self.get_tool_executable_path(Tool::Ffmpeg)
+    }
 
-        #[cfg(target_os = "windows")]
-        let executable_name = "ffmpeg.exe";
+    /// 跳过常规查找的 FFmpeg 路径，依次为环境变量 `TAURI_FFMPEG_PATH`、`pathOverride` 和 `ffmpegPath`
+    fn pinned_ffmpeg_path(&self) -> Option<PathBuf> {
+        std::env::var_os(PATH_OVERRIDE_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| self.config.path_override.as_ref().map(PathBuf::from))
+            .or_else(|| self.config.ffmpeg_path.as_ref().map(PathBuf::from))
+    }
+
+    /// 获取指定工具的可执行文件路径
+    pub(crate) fn get_tool_executable_path(&self, tool: Tool) -> Result<PathBuf> {
+        // 开发时的覆盖路径或外部部署的 FFmpeg，其余工具与其位于同一目录
+        if let Some(ffmpeg_path) = self.pinned_ffmpeg_path() {
+            return Ok(match tool {
+                Tool::Ffmpeg => ffmpeg_path,
+                _ => ffmpeg_path.with_file_name(tool.executable_name()),
+            });
+        }
+
+        if self.config.use_system_ffmpeg() {
+            return find_in_path(tool.executable_name()).ok_or(Error::FfmpegNotFound);
+        }
+
+        let installed = self.get_ffmpeg_dir()?.join(tool.executable_name());
+        if !self.config.compress_binaries {
+            return Ok(installed);
+        }
+
+        // 压缩存储模式：首次使用时解压到缓存目录
+        let stored = storage::stored_path(&installed);
+        if !stored.exists() {
+            // 启用压缩存储之前安装的文件仍然可以直接使用
+            return Ok(installed);
+        }
+
+        // 共享模式下其他应用可能已更新压缩文件，解压副本比压缩文件旧时重新解压
+        let runtime = self.get_runtime_dir()?.join(tool.executable_name());
+        if !storage::is_fresh(&stored, &runtime) {
+            // 解压后的大小未知，至少需要与压缩文件相同的空间
+            self.reserve_storage(fs::metadata(&stored)?.len(), Some(&runtime))?;
+            storage::decompress(&stored, &runtime)?;
+        }
+        Ok(runtime)
+    }
+
+    /// 安装目录中保存的文件，压缩存储模式下为 `.zst` 文件
+    fn installed_tool_path(&self, ffmpeg_dir: &Path, tool: Tool) -> PathBuf {
+        let installed = ffmpeg_dir.join(tool.executable_name());
+        if self.config.compress_binaries {
+            storage::stored_path(&installed)
+        } else {
+            installed
+        }
+    }
+
+    /// 压缩存储模式下解压后的可执行文件所在目录
+    fn get_runtime_dir(&self) -> Result<PathBuf> {
+        let cache_dir = self.app.path().app_cache_dir().map_err(|e| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                e.to_string(),
+            ))
+        })?;
+
+        Ok(cache_dir.join("bin").join(self.get_platform()?))
+    }
+
+    /// 插件的日志目录，FFmpeg 的 `-report` 日志写入此处
+    fn get_log_dir(&self) -> Result<PathBuf> {
+        let log_dir = self.app.path().app_log_dir().map_err(|e| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                e.to_string(),
+            ))
+        })?;
+
+        Ok(log_dir.join("ffmpeg"))
+    }
+
+    /// 参数中带有 `-report` 时通过 `FFREPORT` 把日志写入插件的日志目录，而不是不确定的工作目录
+    fn report_env(&self, args: &[String]) -> Option<String> {
+        if !args.iter().any(|arg| arg == "-report") {
+            return None;
+        }
+        let log_dir = self.get_log_dir().ok()?;
+        fs::create_dir_all(&log_dir).ok()?;
 
-        #[cfg(not(target_os = "windows"))]
-        let executable_name = "ffmpeg";
+        // `FFREPORT` 中 `:` 分隔选项、`\` 转义，文件名中的 `%` 需要写成 `%%`
+        let mut file = String::new();
+        for c in log_dir.to_string_lossy().chars() {
+            match c {
+                '\\' | ':' | '\'' | '=' => {
+                    file.push('\\');
+                    file.push(c);
+                }
+                '%' => file.push_str("%%"),
+                c => file.push(c),
+            }
+        }
+        Some(format!("file={file}/%p-%t.log"))
+    }
+
+    /// 安装完成后按存储模式保存可执行文件
+    ///
+    /// 压缩存储模式下压缩安装目录中的可执行文件，并删除缓存目录中旧版本的解压副本。
+    fn store_binaries(&self, ffmpeg_dir: &Path) -> Result<()> {
+        if !self.config.compress_binaries {
+            return Ok(());
+        }
 
-        Ok(ffmpeg_dir.join(executable_name))
+        let runtime_dir = self.get_runtime_dir()?;
+        for tool in Tool::ALL {
+            let installed = ffmpeg_dir.join(tool.executable_name());
+            if !installed.exists() {
+                continue;
+            }
+            storage::compress(&installed)?;
+            let _ = fs::remove_file(runtime_dir.join(tool.executable_name()));
+        }
+        Ok(())
     }
 
     /// 检查 FFmpeg 是否可用
-    pub fn check(&self) -> Result<CheckResponse> {
-        let ffmpeg_path = self.get_ffmpeg_executable_path()?;
+    ///
+    /// 结果会被缓存，之后的调用直接返回缓存的结果而不再运行可执行文件；下载、重新安装、删除后
+    /// 缓存失效，由其他应用或用户在插件之外修改了文件时可以调用 [`Ffmpeg::recheck`]。
+    /// 有工具超时的结果不会被缓存。
+    ///
+    /// 各工具的 `-version` 同时运行。安装后首次运行时杀毒软件扫描、磁盘唤醒可能让检查耗时数秒，
+    /// 超过 `slow_threshold_ms` 仍未完成时发送一次 `use-ffmpeg://check-slow` 事件，界面可以据此显示加载状态；
+    /// 设置了 `timeout_ms` 时超时的工具视为不可用，并设置 `timed_out`。
+    pub async fn check(&self, request: CheckRequest) -> Result<CheckResponse> {
+        let generation = {
+            let cache = self.check_cache.lock().unwrap();
+            if let Some(cached) = &cache.response {
+                return Ok(cached.clone());
+            }
+            cache.generation
+        };
 
-        if !ffmpeg_path.exists() {
-            return Ok(CheckResponse {
-                available: false,
-                path: None,
-                version: None,
-            });
+        let timeout = request.timeout_ms.map(Duration::from_millis);
+        let threshold = request
+            .slow_threshold_ms
+            .map_or(CHECK_SLOW_THRESHOLD, Duration::from_millis);
+
+        let tools = async {
+            tokio::try_join!(
+                self.check_tool(Tool::Ffmpeg, timeout),
+                self.check_tool(Tool::Ffprobe, timeout),
+                self.check_tool(Tool::Ffplay, timeout),
+            )
+        };
+        tokio::pin!(tools);
+        let (ffmpeg, ffprobe, ffplay) = tokio::select! {
+            result = &mut tools => result?,
+            _ = tokio::time::sleep(threshold) => {
+                let slow = CheckSlow {
+                    elapsed_ms: threshold.as_millis() as u64,
+                    timeout_ms: request.timeout_ms,
+                };
+                events::emit(&self.app, events::CHECK_SLOW, &slow);
+                tools.await?
+            }
+        };
+
+        let timed_out = ffmpeg.timed_out || ffprobe.timed_out || ffplay.timed_out;
+        let response = CheckResponse {
+            available: ffmpeg.available,
+            path: ffmpeg.path.clone(),
+            version: ffmpeg.version.clone(),
+            tools: ToolsStatus {
+                ffmpeg,
+                ffprobe,
+                ffplay,
+            },
+        };
+        // 检查期间缓存被清除（如下载刚好完成）时结果可能已经过时，不写入缓存
+        let mut cache = self.check_cache.lock().unwrap();
+        if !timed_out && cache.generation == generation {
+            cache.response = Some(response.clone());
+        }
+        Ok(response)
+    }
+
+    /// 清除缓存的检查结果后重新检查
+    pub async fn recheck(&self, request: CheckRequest) -> Result<CheckResponse> {
+        self.invalidate_check();
+        self.check(request).await
+    }
+
+    /// 插件的就绪报告：平台、安装状态、下载源和策略
+    ///
+    /// 第一次调用时检查安装状态并生成报告，之后返回相同的内容。插件初始化时生成报告并发送
+    /// `use-ffmpeg://ready` 事件，页面加载晚于该事件时可以通过该方法获取同样的内容。
+    pub async fn ready_report(&self) -> ReadyReport {
+        self.ready
+            .get_or_init(|| async {
+                let config = &self.config;
+                ReadyReport {
+                    plugin_version: env!("CARGO_PKG_VERSION").to_string(),
+                    platform: self.get_platform().ok().map(str::to_string),
+                    arch: std::env::consts::ARCH.to_string(),
+                    source: self.source(),
+                    install: self.check(CheckRequest::default()).await.ok(),
+                    default_download_url: config
+                        .default_download
+                        .as_ref()
+                        .map(|download| download.url.clone()),
+                    provider: config.provider.clone(),
+                    policies: ReadyPolicies {
+                        max_concurrent_jobs: config.max_concurrent_jobs,
+                        max_queue_length: config.max_queue_length,
+                        allowed_protocols: config.allowed_protocols.clone(),
+                        storage_quota_mb: config.storage_quota_mb,
+                        require_signed_binaries: config.require_signed_binaries,
+                        kill_jobs_on_window_close: config.kill_jobs_on_window_close,
+                        audit_log: config.audit_log,
+                        power_aware: config.power_aware.is_some(),
+                        shared: config.shared,
+                        compress_binaries: config.compress_binaries,
+                        simulate: self.simulating(),
+                    },
+                }
+            })
+            .await
+            .clone()
+    }
+
+    /// FFmpeg 的来源，优先级与 [`Ffmpeg::get_tool_executable_path`] 的查找顺序相同
+    fn source(&self) -> FfmpegSource {
+        let env_override = std::env::var_os(PATH_OVERRIDE_ENV).is_some_and(|path| !path.is_empty());
+        if env_override || self.config.path_override.is_some() {
+            FfmpegSource::Override
+        } else if self.config.ffmpeg_path.is_some() {
+            FfmpegSource::Provisioned
+        } else if self.config.use_system_ffmpeg() {
+            FfmpegSource::System
+        } else {
+            FfmpegSource::Managed
+        }
+    }
+
+    /// 已安装的 FFmpeg 是否不低于指定版本（如 `6.0`、`7.1.1`）
+    ///
+    /// 版本号取自 [`Ffmpeg::check`] 的结果并按数值比较，master 分支构建的开发版本满足任何版本要求。
+    /// FFmpeg 不可用时返回 [`Error::FfmpegNotFound`]，无法识别已安装的版本号时返回 [`Error::UnknownVersion`]。
+    pub async fn is_at_least(&self, version: &str) -> Result<bool> {
+        let required = version::parse_required(version)?;
+        let check = self.check(CheckRequest::default()).await?;
+        let line = check
+            .version
+            .filter(|_| check.available)
+            .ok_or(Error::FfmpegNotFound)?;
+        let installed = patch::version_number(&line)
+            .and_then(FfmpegVersion::parse)
+            .ok_or_else(|| Error::UnknownVersion(line.clone()))?;
+        Ok(installed.is_at_least(required))
+    }
+
+    /// 清除缓存的检查结果，下次检查时重新运行可执行文件
+    fn invalidate_check(&self) {
+        let mut cache = self.check_cache.lock().unwrap();
+        cache.generation += 1;
+        cache.response = None;
+    }
+
+    /// 检查单个工具是否可用
+    async fn check_tool(&self, tool: Tool, timeout: Option<Duration>) -> Result<ToolStatus> {
+        let tool_path = match self.get_tool_executable_path(tool) {
+            Ok(path) => path,
+            Err(Error::FfmpegNotFound) => return Ok(ToolStatus::default()),
+            Err(e) => return Err(e),
+        };
+
+        if !tool_path.exists() {
+            return Ok(ToolStatus::default());
         }
 
-        // 尝试执行 ffmpeg -version 获取版本信息
-        let output = Command::new(&ffmpeg_path).arg("-version").output();
+        // 尝试执行 <tool> -version 获取版本信息，超时时结束进程
+        let output = tokio::process::Command::new(&tool_path)
+            .arg("-version")
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, output).await.ok(),
+            None => Some(output.await),
+        };
+        let signature = {
+            let tool_path = tool_path.clone();
+            tokio::task::spawn_blocking(move || signature::verify(&tool_path))
+                .await
+                .ok()
+                .flatten()
+        };
+        let path = Some(tool_path.to_string_lossy().to_string());
 
         match output {
-            Ok(output) if output.status.success() => {
+            Some(Ok(output)) if output.status.success() => {
                 let version_info = String::from_utf8_lossy(&output.stdout);
                 let version = version_info.lines().next().map(|s| s.to_string());
 
-                Ok(CheckResponse {
+                Ok(ToolStatus {
                     available: true,
-                    path: Some(ffmpeg_path.to_string_lossy().to_string()),
+                    path,
                     version,
+                    blocked_by_antivirus: false,
+                    timed_out: false,
+                    signature,
                 })
             }
-            _ => Ok(CheckResponse {
+            output => Ok(ToolStatus {
                 available: false,
-                path: Some(ffmpeg_path.to_string_lossy().to_string()),
+                path,
                 version: None,
+                blocked_by_antivirus: matches!(&output, Some(Err(e)) if is_blocked_by_antivirus(e)),
+                timed_out: output.is_none(),
+                signature,
             }),
         }
     }
 
     /// 下载 FFmpeg
+    ///
+    /// 多个下载可以同时进行（例如从不同地址分别下载 ffmpeg 和 ffprobe），进度和生命周期事件中
+    /// 带有各自的下载 ID，可以通过 [`Ffmpeg::cancel_download`] 单独取消。
+    /// 设置了 `retry_when_online` 时，因网络未连接而失败的下载会在网络恢复后自动重试。
     pub async fn download(&self, request: DownloadRequest) -> Result<DownloadResponse> {
-        let config = request
-            .config
-            .unwrap_or_else(|| self.get_default_config().unwrap());
+        self.start_download(request, None).await
+    }
+
+    /// 下载 FFmpeg，并在每次发送进度事件时调用 `on_progress`
+    ///
+    /// 供后端发起的安装（如在 `setup` 中）直接获取进度，无需监听插件自己的事件。
+    /// 其余行为与 [`Ffmpeg::download`] 相同；网络恢复后自动重试的下载不再调用 `on_progress`。
+    pub async fn download_with<F>(
+        &self,
+        request: DownloadRequest,
+        on_progress: F,
+    ) -> Result<DownloadResponse>
+    where
+        F: Fn(&DownloadProgress) + Send + Sync,
+    {
+        self.start_download(request, Some(&on_progress)).await
+    }
+
+    async fn start_download(
+        &self,
+        request: DownloadRequest,
+        on_progress: Option<ProgressCallback<'_>>,
+    ) -> Result<DownloadResponse> {
+        if self.config.ffmpeg_path.is_some() {
+            return Err(Error::ProvisionedMode);
+        }
+        if self.config.use_system_ffmpeg() {
+            return Err(Error::SystemFfmpegMode);
+        }
+
+        // 共享目录按插件配置的下载源区分，安装其他来源或渠道的构建会覆盖其他应用的副本
+        if self.config.shared {
+            let channel = self.config.provider.channel.unwrap_or_default();
+            if request.config.is_some() || request.channel.is_some_and(|c| c != channel) {
+                return Err(Error::InvalidArgument(
+                    "`config` and `channel` cannot be overridden with `shared`, configure `defaultDownload` or `provider` instead"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // 在登记下载、写入审计日志和发送事件之前检查
+        if request.tools.as_ref().is_some_and(|tools| tools.is_empty()) {
+            return Err(Error::InvalidArgument(
+                "`tools` must not be empty".to_string(),
+            ));
+        }
+
+        let download_id = request.download_id.clone().unwrap_or_else(new_job_id);
+
+        // 安装期间 execute 会被拒绝或等待安装完成
+        let install = self.install.begin(&download_id, on_progress)?;
+
+        let started = std::time::Instant::now();
+        let config = match request.config.clone() {
+            Some(config) => Ok(config),
+            None => {
+                self.get_default_config(request.channel, request.tools.as_deref())
+                    .await
+            }
+        };
+        let url = config.as_ref().ok().map(|config| config.url.clone());
+        let retry = request.retry_when_online.then(|| request.clone());
+        let result = match config {
+            Ok(config) => {
+                self.run_download(&download_id, request, config, &install)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        self.hooks.download_metrics(&DownloadMetrics {
+            download_id: download_id.clone(),
+            url: url.clone(),
+            bytes: install.downloaded(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            patched: matches!(&result, Ok(response) if response.patched),
+            success: result.is_ok(),
+            failure: result.as_ref().err().map(FailureCategory::of),
+        });
+
+        let result = match (result, retry) {
+            (Err(e), Some(request)) if is_offline(&e) => self
+                .retry_when_online(download_id.clone(), request, url)
+                .and(Err(Error::DownloadPending(download_id.clone()))),
+            (result, _) => result,
+        };
+
+        let finished = DownloadFinished {
+            download_id: download_id.clone(),
+            success: result.is_ok(),
+            cancelled: matches!(result, Err(Error::Cancelled)),
+            error_code: result.as_ref().err().map(|e| e.code().to_string()),
+            error: result.as_ref().err().map(messages::error_message),
+        };
+        // 失败的下载也可能已经替换了部分文件
+        self.invalidate_check();
+        events::emit(&self.app, events::DOWNLOAD_FINISHED, &finished);
+
+        result
+    }
+
+    /// 登记等待网络恢复的下载，定期检查网络，恢复后以相同的下载 ID 重新下载
+    ///
+    /// `url` 为已知的下载地址，检查能否连接到它的服务器；解析默认下载配置时就已失败的，
+    /// 以能否重新解析默认下载配置为准。
+    fn retry_when_online(
+        &self,
+        download_id: String,
+        mut request: DownloadRequest,
+        url: Option<String>,
+    ) -> Result<()> {
+        let cancel = self.install.add_pending(&download_id)?;
+        request.download_id = Some(download_id.clone());
+
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(ONLINE_PROBE_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // 第一次立即触发，跳过
+            interval.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = cancel.cancelled() => {
+                        app.ffmpeg().install.remove_pending(&download_id);
+                        events::emit(
+                            &app,
+                            events::DOWNLOAD_FINISHED,
+                            &DownloadFinished {
+                                download_id,
+                                success: false,
+                                cancelled: true,
+                                error_code: Some(Error::Cancelled.code().to_string()),
+                                error: Some(messages::error_message(&Error::Cancelled)),
+                            },
+                        );
+                        return;
+                    }
+                }
+
+                let ffmpeg = app.ffmpeg();
+                let online = match &url {
+                    Some(url) => can_connect(url).await,
+                    None => ffmpeg
+                        .get_default_config(request.channel, request.tools.as_deref())
+                        .await
+                        .is_ok(),
+                };
+                if online {
+                    break;
+                }
+            }
+
+            app.ffmpeg().install.remove_pending(&download_id);
+            events::emit(
+                &app,
+                events::DOWNLOAD_RESUMED,
+                &DownloadResumed {
+                    download_id: download_id.clone(),
+                },
+            );
+            // 结果通过 `download-finished` 事件通知，仍然离线时会再次等待
+            let _ = app.ffmpeg().download(request).await;
+        });
+
+        Ok(())
+    }
+
+    async fn run_download(
+        &self,
+        download_id: &str,
+        request: DownloadRequest,
+        config: DownloadConfig,
+        install: &InstallGuard<'_>,
+    ) -> Result<DownloadResponse> {
+        // 下载会覆盖正在被使用的可执行文件
+        if self.jobs.has_running() {
+            if !request.force {
+                return Err(Error::JobsRunning);
+            }
+            self.audit(
+                None,
+                AuditAction::PolicyOverride {
+                    policy: "force".to_string(),
+                    detail: format!("Killed running jobs to install download {download_id}"),
+                },
+            );
+            self.jobs.kill_running().await;
+        }
 
         let ffmpeg_dir = self.get_ffmpeg_dir()?;
         fs::create_dir_all(&ffmpeg_dir)?;
 
-        // 下载文件
+        self.audit(
+            None,
+            AuditAction::Download {
+                download_id: download_id.to_string(),
+                url: config.url.clone(),
+                custom_url: request.config.is_some(),
+                sha256: config.sha256.clone(),
+                tools: request.tools.clone(),
+            },
+        );
+
+        events::emit(
+            &self.app,
+            events::DOWNLOAD_STARTED,
+            &DownloadStarted {
+                download_id: download_id.to_string(),
+                url: config.url.clone(),
+            },
+        );
+
+        let main_tool = config.tool.unwrap_or(Tool::Ffmpeg);
+        let tools = request.tools.as_deref();
+        // 响应中报告的工具
+        let tool = match tools {
+            Some(tools) if !tools.contains(&main_tool) => tools[0],
+            _ => main_tool,
+        };
+
+        if tool == main_tool {
+            if let Some(response) = self
+                .try_patch(download_id, &config, tool, &ffmpeg_dir, install)
+                .await?
+            {
+                return Ok(response);
+            }
+        }
+
+        // 保存到临时文件，同时进行的下载使用不同的文件
+        let temp_file_path = ffmpeg_dir.join(format!("ffmpeg_download_{}.tmp", new_job_id()));
+        let origin = self
+            .fetch_to_file(
+                download_id,
+                &config.url,
+                config.sha256.as_deref(),
+                &temp_file_path,
+                install,
+            )
+            .await?;
+
+        // 共享模式下其他应用可能在同时安装
+        let _lock = self.lock_ffmpeg_dir(&ffmpeg_dir).await?;
+
+        // 解压文件
+        let extracted = self.extract_archive(
+            &temp_file_path,
+            &ffmpeg_dir,
+            &config.executable_path,
+            main_tool,
+            tools,
+        );
+
+        // 删除临时文件，杀毒软件可能锁定或隔离了它，删除失败不掩盖解压的结果
+        let _ = fs::remove_file(&temp_file_path);
+        extracted.map_err(antivirus_error)?;
+
+        // 在 Unix 系统上设置执行权限
+        let mut installed = Vec::new();
+        for tool in Tool::ALL {
+            let tool_path = ffmpeg_dir.join(tool.executable_name());
+            if tool_path.exists() {
+                set_executable(&tool_path)?;
+                installed.push(tool);
+            }
+        }
+        self.check_signatures(&ffmpeg_dir, &installed)?;
+        self.store_binaries(&ffmpeg_dir).map_err(antivirus_error)?;
+        self.check_not_quarantined(&ffmpeg_dir, &installed).await?;
+
+        let tool_path = self.installed_tool_path(&ffmpeg_dir, tool);
+
+        Ok(DownloadResponse {
+            download_id: download_id.to_string(),
+            success: true,
+            patched: false,
+            path: Some(tool_path.to_string_lossy().to_string()),
+            message_code: Some(MessageCode::Downloaded),
+            message: Some(messages::message(MessageCode::Downloaded, tool)),
+            final_url: Some(origin.final_url),
+            server: origin.server,
+        })
+    }
+
+    /// 下载文件并发送进度事件，失败、被取消或校验和不一致时删除写了一半的文件
+    async fn fetch_to_file(
+        &self,
+        download_id: &str,
+        url: &str,
+        sha256: Option<&str>,
+        path: &Path,
+        install: &InstallGuard<'_>,
+    ) -> Result<DownloadOrigin> {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(300))
+            .redirect(reqwest::redirect::Policy::limited(
+                self.config.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS),
+            ))
             .build()?;
 
-        let response = client.get(&config.url).send().await?;
+        // 先确认地址可以访问，404、403 等错误立即返回，不必等到传输开始
+        let total_bytes = tokio::select! {
+            total_bytes = preflight(&client, url) => total_bytes?,
+            _ = install.cancelled() => return Err(Error::Cancelled),
+        };
+        events::emit(
+            &self.app,
+            events::DOWNLOAD_STARTING,
+            &DownloadStarting {
+                download_id: download_id.to_string(),
+                url: url.to_string(),
+                total_bytes,
+            },
+        );
+
+        let response = tokio::select! {
+            response = client.get(url).send() => response?,
+            _ = install.cancelled() => return Err(Error::Cancelled),
+        };
 
         if !response.status().is_success() {
             return Err(Error::Download(format!(
@@ -145,133 +1104,1295 @@ impl<R: Runtime> Ffmpeg<R> {
             )));
         }
 
-        let total_size = response.content_length();
-
-        // 保存到临时文件
-        let temp_file_path = ffmpeg_dir.join("ffmpeg_download.tmp");
-        let mut file = fs::File::create(&temp_file_path)?;
+        let total_size = response.content_length().or(total_bytes);
+        let origin = DownloadOrigin {
+            final_url: response.url().to_string(),
+            server: response
+                .headers()
+                .get(reqwest::header::SERVER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        };
+        let mut file = fs::File::create(path)?;
         let mut stream = response.bytes_stream();
         let mut downloaded: u64 = 0;
+        let mut hasher = Sha256::new();
 
         // 发送进度事件
-        let app_handle = self.0.clone();
+        let app_handle = self.app.clone();
 
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result?;
+        loop {
+            let chunk_result = tokio::select! {
+                chunk_result = stream.next() => chunk_result,
+                _ = install.cancelled() => {
+                    drop(file);
+                    let _ = fs::remove_file(path);
+                    return Err(Error::Cancelled);
+                }
+            };
+            let Some(chunk_result) = chunk_result else {
+                break;
+            };
+            let chunk = match chunk_result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    drop(file);
+                    let _ = fs::remove_file(path);
+                    return Err(e.into());
+                }
+            };
             file.write_all(&chunk)?;
+            hasher.update(&chunk);
             downloaded += chunk.len() as u64;
+            install.add_downloaded(chunk.len() as u64);
 
             // 计算进度并发送事件
             let progress = DownloadProgress {
+                download_id: download_id.to_string(),
                 downloaded,
                 total: total_size,
                 percentage: total_size.map(|total| (downloaded as f64 / total as f64) * 100.0),
             };
 
-            let _ = app_handle.emit("use-ffmpeg://download-progress", &progress);
+            events::emit(&app_handle, events::DOWNLOAD_PROGRESS, &progress);
+            install.report_progress(&progress);
         }
 
-        drop(file);
-
-        // 解压文件
-        self.extract_archive(&temp_file_path, &ffmpeg_dir, &config.executable_path)?;
+        if let Some(expected) = sha256 {
+            let actual = format!("{:x}", hasher.finalize());
+            if !actual.eq_ignore_ascii_case(expected.trim()) {
+                drop(file);
+                let _ = fs::remove_file(path);
+                return Err(Error::Download(format!(
+                    "Checksum mismatch: expected {expected}, got {actual}"
+                )));
+            }
+        }
 
-        // 删除临时文件
-        fs::remove_file(&temp_file_path)?;
+        Ok(origin)
+    }
 
-        let ffmpeg_path = self.get_ffmpeg_executable_path()?;
+    /// 尝试通过增量补丁从已安装版本升级
+    ///
+    /// 没有与已安装版本匹配的补丁，或任一补丁下载、解码、SHA-256 或签名校验失败时返回 None，由调用方改为完整下载；
+    /// 只有全部补丁都成功后才替换已安装的文件。
+    async fn try_patch(
+        &self,
+        download_id: &str,
+        config: &DownloadConfig,
+        tool: Tool,
+        ffmpeg_dir: &Path,
+        install: &InstallGuard<'_>,
+    ) -> Result<Option<DownloadResponse>> {
+        let installed = self.check_tool(tool, None).await?.version;
+        let Some(installed) = installed.as_deref().and_then(patch::version_number) else {
+            return Ok(None);
+        };
 
-        // 在 Unix 系统上设置执行权限
-        #[cfg(unix)]
+        let patches: Vec<_> = config
+            .patches
+            .iter()
+            .filter(|patch| patch.from_version == installed)
+            .collect();
+        if !patches
+            .iter()
+            .any(|patch| patch.tool.unwrap_or(Tool::Ffmpeg) == tool)
         {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&ffmpeg_path)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&ffmpeg_path, perms)?;
+            return Ok(None);
         }
 
-        Ok(DownloadResponse {
+        let mut patched = Vec::new();
+        let mut result = Ok(());
+        for patch in patches {
+            let patch_tool = patch.tool.unwrap_or(Tool::Ffmpeg);
+            let tool_path = self.get_tool_executable_path(patch_tool)?;
+            let id = new_job_id();
+            let patch_path = ffmpeg_dir.join(format!("ffmpeg_patch_{id}.tmp"));
+            let new_path = ffmpeg_dir.join(format!("ffmpeg_patched_{id}.tmp"));
+
+            result = self
+                .apply_download_patch(
+                    download_id,
+                    patch,
+                    &tool_path,
+                    &patch_path,
+                    &new_path,
+                    install,
+                )
+                .await;
+            let _ = fs::remove_file(&patch_path);
+            patched.push((new_path, patch_tool));
+            if result.is_err() {
+                break;
+            }
+        }
+
+        match result {
+            Ok(()) => {}
+            Err(e) => {
+                for (new_path, _) in &patched {
+                    let _ = fs::remove_file(new_path);
+                }
+                return match e {
+                    Error::Cancelled => Err(Error::Cancelled),
+                    _ => Ok(None),
+                };
+            }
+        }
+
+        let _lock = self.lock_ffmpeg_dir(ffmpeg_dir).await?;
+        for (new_path, patch_tool) in &patched {
+            fs::rename(new_path, ffmpeg_dir.join(patch_tool.executable_name()))?;
+        }
+        self.store_binaries(ffmpeg_dir)?;
+
+        let tool_path = self.installed_tool_path(ffmpeg_dir, tool);
+        Ok(Some(DownloadResponse {
+            download_id: download_id.to_string(),
             success: true,
-            path: Some(ffmpeg_path.to_string_lossy().to_string()),
-            message: Some("FFmpeg downloaded successfully".to_string()),
+            patched: true,
+            path: Some(tool_path.to_string_lossy().to_string()),
+            message_code: Some(MessageCode::Patched),
+            message: Some(messages::message(MessageCode::Patched, tool)),
+            final_url: None,
+            server: None,
+        }))
+    }
+
+    /// 下载并解码单个补丁，确认得到的可执行文件可以运行
+    async fn apply_download_patch(
+        &self,
+        download_id: &str,
+        patch: &DownloadPatch,
+        tool_path: &Path,
+        patch_path: &Path,
+        new_path: &Path,
+        install: &InstallGuard<'_>,
+    ) -> Result<()> {
+        if !tool_path.exists() {
+            return Err(Error::FfmpegNotFound);
+        }
+
+        self.fetch_to_file(download_id, &patch.url, None, patch_path, install)
+            .await?;
+
+        let (old, patch_file, output) = (
+            tool_path.to_path_buf(),
+            patch_path.to_path_buf(),
+            new_path.to_path_buf(),
+        );
+        let actual = tokio::task::spawn_blocking(move || {
+            patch::apply_patch(&old, &patch_file, &output)?;
+            patch::sha256_file(&output)
         })
+        .await
+        .map_err(|e| Error::Extraction(e.to_string()))??;
+        // 补丁不在 `config.sha256` 的校验范围内，运行解码得到的文件之前先校验
+        if !actual.eq_ignore_ascii_case(patch.sha256.trim()) {
+            return Err(Error::Download(format!(
+                "Checksum mismatch for patched {}: expected {}, got {actual}",
+                patch.tool.unwrap_or(Tool::Ffmpeg).executable_name(),
+                patch.sha256
+            )));
+        }
+        set_executable(new_path)?;
+        self.check_signature(new_path).map_err(|reason| {
+            Error::UntrustedBinary(format!("{}: {reason}", new_path.display()))
+        })?;
+
+        let output = tokio::process::Command::new(new_path)
+            .arg("-version")
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(Error::Extraction(
+                "Patched executable failed to run".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 启用了 `requireSignedBinaries` 时验证刚解压的可执行文件的签名，不符合要求的文件被删除
+    fn check_signatures(&self, ffmpeg_dir: &Path, tools: &[Tool]) -> Result<()> {
+        let mut rejected = Vec::new();
+        for tool in tools {
+            let path = ffmpeg_dir.join(tool.executable_name());
+            if let Err(reason) = self.check_signature(&path) {
+                rejected.push(format!("{}: {reason}", tool.executable_name()));
+                let _ = fs::remove_file(&path);
+            }
+        }
+
+        if rejected.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UntrustedBinary(rejected.join(", ")))
+        }
+    }
+
+    /// 检查单个文件的签名是否符合 `requireSignedBinaries` 和 `trustedSigners`，不符合时返回原因
+    fn check_signature(&self, path: &Path) -> std::result::Result<(), String> {
+        if !self.config.require_signed_binaries || !cfg!(windows) {
+            return Ok(());
+        }
+
+        match signature::verify(path) {
+            None => Err("not signed".to_string()),
+            Some(info) if info.accepted_by(self.config.trusted_signers.as_deref()) => Ok(()),
+            Some(info) if !info.trusted => {
+                Err(format!("invalid signature (0x{:08X})", info.status as u32))
+            }
+            Some(info) => Err(format!(
+                "signed by untrusted signer `{}`",
+                info.signer.as_deref().unwrap_or("unknown")
+            )),
+        }
+    }
+
+    /// 确认刚安装的可执行文件没有被杀毒软件隔离或锁定
+    ///
+    /// Windows Defender 等软件通常在文件写入后很快将其删除或拒绝访问，先等待片刻再检查。
+    async fn check_not_quarantined(&self, ffmpeg_dir: &Path, tools: &[Tool]) -> Result<()> {
+        if cfg!(windows) {
+            tokio::time::sleep(QUARANTINE_SETTLE).await;
+        }
+
+        for tool in tools {
+            let path = self.installed_tool_path(ffmpeg_dir, *tool);
+            match fs::File::open(&path) {
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(Error::BlockedByAntivirus(format!(
+                        "{} disappeared right after extraction",
+                        path.display()
+                    )));
+                }
+                Err(e) if is_blocked_by_antivirus(&e) => {
+                    return Err(Error::BlockedByAntivirus(format!(
+                        "{}: {e}",
+                        path.display()
+                    )));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
     }
 
     /// 解压归档文件
+    ///
+    /// 安装 FFmpeg 时，归档中与其位于同一目录的 ffprobe、ffplay 也会一并解压。
+    /// 指定 `tools` 时只解压其中的工具，归档中缺少任何一个都视为失败。
     fn extract_archive(
         &self,
         archive_path: &Path,
         target_dir: &Path,
         executable_path: &str,
+        main_tool: Tool,
+        tools: Option<&[Tool]>,
     ) -> Result<()> {
-        let file = fs::File::open(archive_path)?;
-        let mut archive = zip::ZipArchive::new(file)?;
+        // 先确认下载到的确实是压缩包，而不是错误页面
+        let format = archive::detect(archive_path)?;
+        // 未指定工具时安装主工具和同目录下存在的配套工具，只有主工具是必需的
+        let (wanted, required) = match tools {
+            Some(tools) => (tools.to_vec(), tools.to_vec()),
+            None if main_tool == Tool::Ffmpeg => {
+                let mut wanted = vec![main_tool];
+                wanted.extend(Tool::COMPANIONS);
+                (wanted, vec![main_tool])
+            }
+            None => (vec![main_tool], vec![main_tool]),
+        };
+        let mut found = Vec::new();
 
-        // 查找可执行文件
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i)?;
-            let file_path = file.name();
+        // 检查是否是我们需要的可执行文件
+        let select = |file_path: &str, main_matches: bool, found: &[Tool]| {
+            let tool = if !found.contains(&main_tool) && main_matches {
+                main_tool
+            } else {
+                *wanted.iter().find(|tool| {
+                    **tool != main_tool
+                        && !found.contains(*tool)
+                        && file_path.ends_with(&companion_path(executable_path, **tool))
+                })?
+            };
+            wanted.contains(&tool).then_some(tool)
+        };
 
-            // 检查是否是我们需要的可执行文件
-            if file_path.ends_with(executable_path) || file_path.contains(executable_path) {
-                let output_path = target_dir.join(
-                    #[cfg(target_os = "windows")]
-                    "ffmpeg.exe",
-                    #[cfg(not(target_os = "windows"))]
-                    "ffmpeg",
-                );
+        match format {
+            ArchiveFormat::Zip => {
+                let file = fs::File::open(archive_path)?;
+                let mut archive = zip::ZipArchive::new(file)?;
+                for i in 0..archive.len() {
+                    let mut file = archive.by_index(i)?;
+                    let file_path = file.name().to_string();
+                    let main_matches =
+                        file_path.ends_with(executable_path) || file_path.contains(executable_path);
+                    let Some(tool) = select(&file_path, main_matches, &found) else {
+                        continue;
+                    };
 
-                let mut outfile = fs::File::create(&output_path)?;
-                std::io::copy(&mut file, &mut outfile)?;
+                    let output_path = target_dir.join(tool.executable_name());
+                    let mut outfile = fs::File::create(&output_path)?;
+                    std::io::copy(&mut file, &mut outfile)?;
+                    found.push(tool);
+                }
+            }
+            // johnvansickle.com 的 Linux 构建为 tar.xz
+            ArchiveFormat::Xz => {
+                let file = std::io::BufReader::new(fs::File::open(archive_path)?);
+                let mut archive = tar::Archive::new(xz2::read::XzDecoder::new(file));
+                for entry in archive.entries()? {
+                    let mut entry = entry?;
+                    if !entry.header().entry_type().is_file() {
+                        continue;
+                    }
+                    let file_path = entry.path()?.to_string_lossy().to_string();
+                    // 压缩包中还有 `GPLv3.txt`、`manpages/ffmpeg.1` 等文件，只按完整的路径部分匹配
+                    let main_matches = file_path == executable_path
+                        || file_path.ends_with(&format!("/{executable_path}"));
+                    let Some(tool) = select(&file_path, main_matches, &found) else {
+                        continue;
+                    };
 
-                return Ok(());
+                    let output_path = target_dir.join(tool.executable_name());
+                    let mut outfile = fs::File::create(&output_path)?;
+                    std::io::copy(&mut entry, &mut outfile)?;
+                    found.push(tool);
+                }
+            }
+            format => {
+                return Err(Error::Extraction(format!(
+                    "Unsupported archive format: {}",
+                    format.name()
+                )));
             }
         }
 
-        Err(Error::Extraction(format!(
-            "Could not find executable at path: {}",
-            executable_path
-        )))
+        match required.iter().find(|tool| !found.contains(*tool)) {
+            None => Ok(()),
+            Some(tool) if *tool == main_tool => Err(Error::Extraction(format!(
+                "Could not find executable at path: {}",
+                executable_path
+            ))),
+            Some(tool) => Err(Error::Extraction(format!(
+                "Could not find {} next to {}",
+                tool.executable_name(),
+                executable_path
+            ))),
+        }
     }
 
     /// 执行 FFmpeg 命令
-    pub fn execute(&self, request: ExecuteRequest) -> Result<ExecuteResponse> {
+    ///
+    /// 下载正在进行时返回 [`Error::InstallInProgress`]，设置了 `wait_for_install` 时则等待下载结束。
+    pub async fn execute(&self, request: ExecuteRequest) -> Result<ExecuteResponse> {
+        if self.install.is_installing() && !request.wait_for_install {
+            return Err(Error::InstallInProgress);
+        }
+
+        let job = self
+            .jobs
+            .register(self.new_job_info(&request, None, false)?)?;
+        self.batches.join(job.info());
+        self.run_job(job, request).await
+    }
+
+    /// 解析 shell 风格的命令行并执行，命令可以以 `ffmpeg` 开头
+    ///
+    /// 支持单双引号、反斜杠转义和以 `\` 结尾的续行，不展开环境变量和通配符。
+    /// 命令行无法解析或包含管道、重定向等 shell 操作符时返回 [`Error::InvalidArgument`]。
+    pub async fn execute_str(&self, request: ExecuteStrRequest) -> Result<ExecuteResponse> {
+        self.execute(request.into_request()?).await
+    }
+
+    /// 将任务加入后台队列，立即返回任务信息
+    ///
+    /// 任务由插件调度，不受 WebView 重新加载的影响，结果通过 `use-ffmpeg://job-finished` 事件通知。
+    /// 下载正在进行时任务会等待下载结束。尚未开始的任务会保存到磁盘，可以通过
+    /// [`Ffmpeg::restore_queue`] 在应用重启后恢复。队列达到 `maxQueueLength` 时返回 [`Error::QueueFull`]。
+    pub fn enqueue(&self, mut request: EnqueueRequest) -> Result<JobInfo> {
+        request.not_before = match (request.not_before, request.start_after.take()) {
+            (Some(not_before), Some(delay)) => Some(not_before.max(now_millis() + delay)),
+            (not_before, delay) => not_before.or(delay.map(|delay| now_millis() + delay)),
+        };
+        request.execute.wait_for_install = true;
+
+        let job = self.jobs.register(self.new_job_info(
+            &request.execute,
+            request.not_before,
+            request.only_when_idle,
+        )?)?;
+        let info = job.info().clone();
+
+        request.execute.job_id = Some(info.id.clone());
+        self.queue.insert(request.clone())?;
+        self.batches.join(&info);
+
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = app.ffmpeg().run_job(job, request.execute).await;
+        });
+
+        Ok(info)
+    }
+
+    /// 恢复上次保存的、尚未开始的后台任务
+    ///
+    /// 已在队列中的任务会被跳过，计划时间已过的任务会立即排队。因配置变化（如 `allowedProtocols`、
+    /// `allowedWebhookOrigins`）等原因无法恢复的任务从队列中移除，并发送 `use-ffmpeg://queue-job-rejected` 事件。
+    pub fn restore_queue(&self) -> Vec<JobInfo> {
+        let mut restored = Vec::new();
+        for request in self.queue.snapshot() {
+            let job_id = request.execute.job_id.clone().unwrap_or_default();
+            let tags = request.execute.tags.clone();
+            match self.enqueue(request) {
+                Ok(info) => restored.push(info),
+                Err(Error::JobExists(_)) => {}
+                Err(e) => {
+                    self.dequeue(&job_id);
+                    let rejected = QueueJobRejected {
+                        job_id,
+                        error_code: e.code().to_string(),
+                        error: messages::error_message(&e),
+                        tags,
+                    };
+                    events::emit(&self.app, events::QUEUE_JOB_REJECTED, &rejected);
+                }
+            }
+        }
+        restored
+    }
+
+    /// 从后台队列中移除已开始或已结束的任务，队列降到低水位以下时发送 `use-ffmpeg://queue-drained` 事件
+    fn dequeue(&self, job_id: &str) {
+        if let Some(queued) = self.queue.remove(job_id) {
+            let drained = QueueDrained {
+                queued,
+                max_queue_length: self.config.max_queue_length,
+            };
+            events::emit(&self.app, events::QUEUE_DRAINED, &drained);
+        }
+    }
+
+    /// 单个任务的 `webhook` 只能发送到 `allowedWebhookOrigins` 中的源，未配置时不接受
+    fn check_webhook(&self, request: &ExecuteRequest) -> Result<()> {
+        let Some(webhook) = &request.webhook else {
+            return Ok(());
+        };
+        let url = reqwest::Url::parse(webhook)
+            .map_err(|e| Error::InvalidArgument(format!("`webhook` is not a valid URL ({e})")))?;
+        let origin = url.origin();
+        let allowed = self
+            .config
+            .allowed_webhook_origins
+            .iter()
+            .flatten()
+            .filter_map(|allowed| reqwest::Url::parse(allowed).ok())
+            .any(|allowed| allowed.origin() == origin);
+        if !allowed {
+            return Err(Error::InvalidArgument(format!(
+                "Webhook origin `{}` is not in `allowedWebhookOrigins`",
+                origin.ascii_serialization()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 根据请求生成任务信息，配置了 `allowedProtocols` 时参数中的协议不符合要求，
+    /// 或者 `webhook` 不在 `allowedWebhookOrigins` 中时返回错误
+    fn new_job_info(
+        &self,
+        request: &ExecuteRequest,
+        not_before: Option<u64>,
+        only_when_idle: bool,
+    ) -> Result<JobInfo> {
+        self.check_webhook(request)?;
+        let args = normalize_args(&request.args)?;
+        let args = apply_protocol_whitelist(&args, self.config.allowed_protocols.as_deref())?;
+        let args = apply_overwrite_policy(&args, request.overwrite)?;
+        let args = apply_threads(&args, request.threads.or(self.config.default_threads));
+        let status = if not_before.is_some_and(|time| time > now_millis()) {
+            JobStatus::Scheduled
+        } else {
+            JobStatus::Queued
+        };
+
+        Ok(JobInfo {
+            id: request.job_id.clone().unwrap_or_else(new_job_id),
+            args,
+            status,
+            queued_at: now_millis(),
+            started_at: None,
+            not_before,
+            only_when_idle,
+            batch_id: request.batch_id.clone(),
+            tags: request.tags.clone(),
+        })
+    }
+
+    /// 运行已注册的任务，无论成功与否都会发送 `use-ffmpeg://job-finished` 事件
+    async fn run_job(&self, mut job: JobGuard, request: ExecuteRequest) -> Result<ExecuteResponse> {
+        let mut result = self.run_job_inner(&mut job, &request).await;
+        self.dequeue(&job.info().id);
+        if let Ok(response) = &mut result {
+            response.warnings = arg_warnings(&job.info().args);
+        }
+
+        if matches!(&result, Ok(response) if response.success) {
+            let outputs = output_paths(&job.info().args)
+                .into_iter()
+                .filter(|path| path.is_file())
+                .collect();
+            self.hooks.job_success(job.info(), outputs).await;
+        }
+
+        let finished = match &result {
+            Ok(response) => JobFinished {
+                job_id: response.job_id.clone(),
+                success: response.success,
+                exit_code: response.exit_code,
+                cancelled: false,
+                stats: response.stats.clone(),
+                error_code: None,
+                batch_id: job.info().batch_id.clone(),
+                tags: job.info().tags.clone(),
+                error: None,
+            },
+            Err(e) => JobFinished {
+                job_id: job.info().id.clone(),
+                success: false,
+                exit_code: None,
+                cancelled: matches!(e, Error::Cancelled),
+                stats: None,
+                error_code: Some(e.code().to_string()),
+                batch_id: job.info().batch_id.clone(),
+                tags: job.info().tags.clone(),
+                error: Some(messages::error_message(e)),
+            },
+        };
+        events::emit(&self.app, events::JOB_FINISHED, &finished);
+        if let Some((progress, done)) = self.batches.finish(&finished) {
+            events::emit(&self.app, events::BATCH_PROGRESS, &progress);
+            if let Some(done) = done {
+                events::emit(&self.app, events::BATCH_FINISHED, &done);
+            }
+        }
+
+        let info = job.info();
+        let now = now_millis();
+        self.hooks.job_metrics(&JobMetrics {
+            job: info.clone(),
+            wait_ms: info.started_at.map(|started| {
+                started.saturating_sub(info.queued_at.max(info.not_before.unwrap_or(0)))
+            }),
+            duration_ms: info.started_at.map(|started| now.saturating_sub(started)),
+            success: finished.success,
+            exit_code: finished.exit_code,
+            stats: finished.stats.clone(),
+            failure: match &result {
+                Ok(response) if !response.success => Some(FailureCategory::Ffmpeg),
+                Ok(_) => None,
+                Err(e) => Some(FailureCategory::of(e)),
+            },
+        });
+
+        let webhooks = self
+            .config
+            .job_finished_webhook
+            .iter()
+            .chain(&request.webhook)
+            .cloned()
+            .collect();
+        self.hooks.job_finished(&self.app, &finished, webhooks);
+
+        #[cfg(feature = "notification")]
+        if request.notify_on_complete {
+            crate::notification::job_finished(&self.app, &finished, &job.info().args);
+        }
+
+        result
+    }
+
+    async fn run_job_inner(
+        &self,
+        job: &mut JobGuard,
+        request: &ExecuteRequest,
+    ) -> Result<ExecuteResponse> {
+        // 等待计划的开始时间
+        if let Some(not_before) = job.info().not_before {
+            let delay = not_before.saturating_sub(now_millis());
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_millis(delay)) => {}
+                _ = job.cancelled() => return Err(Error::Cancelled),
+            }
+            job.set_status(JobStatus::Queued);
+        }
+
+        if self.install.is_installing() {
+            if !request.wait_for_install {
+                return Err(Error::InstallInProgress);
+            }
+            tokio::select! {
+                _ = self.install.wait() => {}
+                _ = job.cancelled() => return Err(Error::Cancelled),
+            }
+        }
+
+        let ffmpeg_path = self.get_ffmpeg_executable_path()?;
+
+        if !ffmpeg_path.exists() && !self.simulating() {
+            return Err(Error::FfmpegNotFound);
+        }
+
+        // 等待运行名额，排队期间也可以被取消
+        let _permit = tokio::select! {
+            permit = self.scheduler.acquire(job.info().only_when_idle) => permit,
+            _ = job.cancelled() => return Err(Error::Cancelled),
+        };
+        job.set_status(JobStatus::Running);
+
+        let job_id = job.info().id.clone();
+        self.dequeue(&job_id);
+        let args = job.info().args.clone();
+
+        // 记录输出文件在任务开始前的状态，以便失败时只清理本次写入的文件
+        let outputs = if request.cleanup_outputs_on_failure {
+            snapshot_outputs(&args)
+        } else {
+            Vec::new()
+        };
+
+        events::emit(&self.app, events::JOB_STARTED, job.info());
+
+        #[cfg(feature = "simulate")]
+        if self.simulating() {
+            return crate::simulate::run(&self.app, job).await;
+        }
+
+        let mut command = tokio::process::Command::new(&ffmpeg_path);
+        if let Some(report) = self.report_env(&args) {
+            command.env("FFREPORT", report);
+        }
+        let mut child = command
+            .args(&args)
+            .stdin(if request.interactive {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| spawn_error(&ffmpeg_path, e))?;
+
+        if let Some(stdin) = child.stdin.take() {
+            job.set_stdin(stdin);
+        }
+
+        let sampler = child.id().map(|pid| {
+            StatsSampler::spawn(
+                self.app.clone(),
+                job_id.clone(),
+                job.info().tags.clone(),
+                pid,
+            )
+        });
+        let stdout = child.stdout.take().map(read_to_end);
+        let stderr = child.stderr.take().map(|stderr| {
+            let parser = ProgressParser::new(job_id.clone(), job.info().tags.clone(), &args);
+            read_stderr_with_progress(self.app.clone(), parser, stderr)
+        });
+
+        let mut cancelled = false;
+        let status = tokio::select! {
+            status = child.wait() => status,
+            _ = job.cancelled() => {
+                cancelled = true;
+                let _ = child.start_kill();
+                child.wait().await
+            }
+        }
+        .map_err(|e| Error::CommandExecution(e.to_string()))?;
+
+        let stats = match sampler {
+            Some(sampler) => Some(sampler.finish().await),
+            None => None,
+        };
+
+        let stdout = match stdout {
+            Some(task) => task.await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+        let stderr = match stderr {
+            Some(task) => task.await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        if cancelled || !status.success() {
+            cleanup_outputs(&outputs);
+        }
+
+        if cancelled {
+            return Err(Error::Cancelled);
+        }
+
+        let response = ExecuteResponse {
+            job_id,
+            success: status.success(),
+            stdout: String::from_utf8_lossy(&stdout).to_string(),
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+            exit_code: status.code(),
+            stats,
+            warnings: Vec::new(),
+        };
+
+        if request.overwrite == Some(OverwritePolicy::Error) && !response.success {
+            if let Some(path) = refused_overwrite(&response.stderr) {
+                return Err(Error::OutputExists(path));
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// 向交互式任务的标准输入写入文本
+    ///
+    /// 例如 `c` 加上 `all -1 volume 0.5` 可以在运行时调整滤镜参数，`q` 可以让 FFmpeg 正常结束。
+    pub async fn send_command(&self, job_id: &str, text: &str) -> Result<()> {
+        let stdin = self.jobs.stdin(job_id)?;
+
+        let mut text = text.to_string();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+
+        let mut stdin = stdin.lock().await;
+        stdin.write_all(text.as_bytes()).await?;
+        stdin.flush().await?;
+        Ok(())
+    }
+
+    /// 直接启动 FFmpeg 进程，标准输入、输出和错误输出均为管道
+    ///
+    /// 会应用覆盖策略和线程数选项，但不经过任务队列、不发送任务事件，
+    /// 进程的生命周期由返回的 [`FfmpegChild`] 管理。
+    pub async fn spawn_raw(&self, request: ExecuteRequest) -> Result<FfmpegChild> {
+        self.spawn_with_stdin(request, Stdio::piped()).await
+    }
+
+    /// 与 [`Ffmpeg::spawn_raw`] 相同，但由调用方指定标准输入（如上一个进程的标准输出）
+    pub(crate) async fn spawn_with_stdin(
+        &self,
+        request: ExecuteRequest,
+        stdin: Stdio,
+    ) -> Result<FfmpegChild> {
+        if self.install.is_installing() {
+            if !request.wait_for_install {
+                return Err(Error::InstallInProgress);
+            }
+            self.install.wait().await;
+        }
+
         let ffmpeg_path = self.get_ffmpeg_executable_path()?;
 
         if !ffmpeg_path.exists() {
             return Err(Error::FfmpegNotFound);
         }
 
-        let output = Command::new(&ffmpeg_path)
-            .args(&request.args)
-            .output()
-            .map_err(|e| Error::CommandExecution(e.to_string()))?;
+        let args = self.new_job_info(&request, None, false)?.args;
+        let mut command = tokio::process::Command::new(&ffmpeg_path);
+        if let Some(report) = self.report_env(&args) {
+            command.env("FFREPORT", report);
+        }
+        let child = command
+            .args(&args)
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| spawn_error(&ffmpeg_path, e))?;
 
-        Ok(ExecuteResponse {
-            success: output.status.success(),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            exit_code: output.status.code(),
-        })
+        Ok(FfmpegChild::new(child))
+    }
+
+    /// 注册由辅助函数自行管理进程的长时间任务（如录制），状态直接为运行中
+    ///
+    /// 任务会出现在 `list_jobs` 中并可以通过 `cancel` 终止，但不经过调度器，也不发送任务事件，
+    /// 返回的 [`JobGuard`] 被释放时注销。
+    pub(crate) fn register_job(&self, request: &ExecuteRequest) -> Result<JobGuard> {
+        let mut job = self
+            .jobs
+            .register(self.new_job_info(request, None, false)?)?;
+        job.set_status(JobStatus::Running);
+        Ok(job)
+    }
+
+    /// 读取审计日志，按时间顺序排列
+    ///
+    /// 只有启用了 `auditLog` 时才会记录，关闭后仍可读取之前的记录。
+    pub fn get_audit_log(&self, request: AuditLogRequest) -> Result<Vec<AuditEntry>> {
+        self.audit.read(&request)
+    }
+
+    /// 立即删除插件临时目录中所有未在使用的中间文件
+    ///
+    /// 遗留文件平时会在超过 `tempTtlHours` 后自动清理，正在运行的任务使用的文件不会被删除。
+    pub fn purge_temp(&self) -> PurgeTempResponse {
+        self.temp.purge()
+    }
+
+    /// 受 `storageQuotaMb` 限制的目录当前的占用
+    pub fn get_quota_status(&self) -> QuotaStatus {
+        quota::status(&self.quota_dirs(), self.quota_limit())
+    }
+
+    /// 终止正在运行的任务
+    ///
+    /// 被终止任务的 `execute` 调用返回 [`Error::Cancelled`]。
+    pub fn cancel(&self, job_id: &str) -> Result<()> {
+        self.jobs.cancel(job_id)
+    }
+
+    /// 取消指定的下载，未指定下载 ID 时取消所有正在进行的下载
+    ///
+    /// 被取消的 `download` 调用返回 [`Error::Cancelled`]。
+    pub fn cancel_download(&self, download_id: Option<&str>) -> Result<()> {
+        self.install.cancel(download_id)
+    }
+
+    /// 列出计划中、排队中和正在运行的 FFmpeg 任务
+    ///
+    /// 前端发起的任务带有发起窗口的标签（`window`）。
+    pub fn list_jobs(&self) -> Vec<JobInfo> {
+        self.jobs.list()
+    }
+
+    /// 终止指定窗口发起的所有任务，返回被终止的任务数
+    pub fn cancel_window_jobs(&self, window: &str) -> usize {
+        self.jobs
+            .list()
+            .into_iter()
+            .filter(|job| job.tags.window.as_deref() == Some(window))
+            .filter(|job| self.jobs.cancel(&job.id).is_ok())
+            .count()
+    }
+
+    /// 删除已安装的可执行文件后重新下载，用于杀毒软件隔离或损坏了部分文件后的恢复
+    ///
+    /// 只删除 `tools` 指定的工具（默认全部），不尝试增量补丁。有任务正在运行时返回
+    /// [`Error::JobsRunning`]，除非设置了 `force`，此时会先终止所有任务。
+    pub async fn reinstall(&self, request: DownloadRequest) -> Result<DownloadResponse> {
+        if self.config.ffmpeg_path.is_some() {
+            return Err(Error::ProvisionedMode);
+        }
+        if self.config.use_system_ffmpeg() {
+            return Err(Error::SystemFfmpegMode);
+        }
+        if self.install.is_installing() {
+            return Err(Error::InstallInProgress);
+        }
+
+        if self.jobs.has_running() {
+            if !request.force {
+                return Err(Error::JobsRunning);
+            }
+            self.audit(
+                None,
+                AuditAction::PolicyOverride {
+                    policy: "force".to_string(),
+                    detail: "Killed running jobs to reinstall FFmpeg".to_string(),
+                },
+            );
+            self.jobs.kill_running().await;
+        }
+
+        let ffmpeg_dir = self.get_ffmpeg_dir()?;
+        if ffmpeg_dir.exists() {
+            let tools = request.tools.clone().unwrap_or_else(|| Tool::ALL.to_vec());
+            self.audit(
+                None,
+                AuditAction::Remove {
+                    target: RemoveTarget::All,
+                    tools: Some(tools.clone()),
+                },
+            );
+            let removed = self.remove_tools(&ffmpeg_dir, &tools).await;
+            self.invalidate_check();
+            removed.map_err(antivirus_error)?;
+        }
+
+        self.download(request).await
     }
 
     /// 删除 FFmpeg
-    pub fn remove(&self) -> Result<DeleteResponse> {
+    ///
+    /// 有任务正在运行时返回 [`Error::JobsRunning`]，除非设置了 `force`，此时会先终止所有任务。
+    /// 设置了 `tools` 时只删除这些工具，其余工具保持可用。`target` 为 `cache` 或 `logs` 时
+    /// 只清除缓存或日志，不影响已安装的可执行文件，此时 `ffmpegPath` 和系统 FFmpeg 模式下也可以使用。
+    pub async fn remove(&self, request: RemoveRequest) -> Result<DeleteResponse> {
+        let result = self.remove_inner(request).await;
+        self.invalidate_check();
+        result
+    }
+
+    async fn remove_inner(&self, request: RemoveRequest) -> Result<DeleteResponse> {
+        let target = request.target.unwrap_or_default();
+        let tools = match target {
+            RemoveTarget::Ffmpeg => Some(vec![Tool::Ffmpeg]),
+            RemoveTarget::Ffprobe => Some(vec![Tool::Ffprobe]),
+            RemoveTarget::All => request.tools.clone(),
+            RemoveTarget::Cache | RemoveTarget::Logs => None,
+        };
+        let binaries = !matches!(target, RemoveTarget::Cache | RemoveTarget::Logs);
+
+        if binaries {
+            if self.config.ffmpeg_path.is_some() {
+                return Err(Error::ProvisionedMode);
+            }
+            if self.config.use_system_ffmpeg() {
+                return Err(Error::SystemFfmpegMode);
+            }
+        }
+
+        // 日志不会被正在运行的任务使用
+        if target != RemoveTarget::Logs && self.jobs.has_running() {
+            if !request.force {
+                return Err(Error::JobsRunning);
+            }
+            self.audit(
+                None,
+                AuditAction::PolicyOverride {
+                    policy: "force".to_string(),
+                    detail: "Killed running jobs to remove FFmpeg".to_string(),
+                },
+            );
+            self.jobs.kill_running().await;
+        }
+
+        self.audit(
+            None,
+            AuditAction::Remove {
+                target,
+                tools: tools.clone(),
+            },
+        );
+
+        match target {
+            RemoveTarget::Cache => {
+                self.clear_cache();
+                return Ok(cleared());
+            }
+            RemoveTarget::Logs => {
+                self.clear_logs()?;
+                return Ok(cleared());
+            }
+            RemoveTarget::All if tools.is_none() => {
+                self.clear_cache();
+                self.clear_logs()?;
+            }
+            _ => {}
+        }
+
         let ffmpeg_dir = self.get_ffmpeg_dir()?;
 
         if !ffmpeg_dir.exists() {
             return Ok(DeleteResponse {
                 success: true,
-                message: Some("FFmpeg directory does not exist".to_string()),
+                message_code: Some(MessageCode::NotInstalled),
+                message: Some(messages::message(MessageCode::NotInstalled, Tool::Ffmpeg)),
             });
         }
 
+        if let Some(tools) = &tools {
+            return self.remove_tools(&ffmpeg_dir, tools).await;
+        }
+
         // 删除整个 FFmpeg 目录
+        let _lock = self.lock_ffmpeg_dir(&ffmpeg_dir).await?;
         fs::remove_dir_all(&ffmpeg_dir)?;
 
         Ok(DeleteResponse {
             success: true,
-            message: Some("FFmpeg deleted successfully".to_string()),
+            message_code: Some(MessageCode::Removed),
+            message: Some(messages::message(MessageCode::Removed, Tool::Ffmpeg)),
+        })
+    }
+
+    /// 删除未在使用的临时文件，以及压缩存储模式下解压的可执行文件（下次使用时重新解压）
+    fn clear_cache(&self) {
+        self.temp.purge();
+        if let Ok(runtime_dir) = self.get_runtime_dir() {
+            let _ = fs::remove_dir_all(runtime_dir);
+        }
+    }
+
+    /// 删除 FFmpeg 的 `-report` 日志
+    fn clear_logs(&self) -> Result<()> {
+        match fs::remove_dir_all(self.get_log_dir()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 删除安装目录中的指定工具，以及压缩存储模式下的压缩文件和解压副本
+    async fn remove_tools(&self, ffmpeg_dir: &Path, tools: &[Tool]) -> Result<DeleteResponse> {
+        let Some(first) = tools.first().copied() else {
+            return Err(Error::InvalidArgument(
+                "`tools` must not be empty".to_string(),
+            ));
+        };
+
+        let _lock = self.lock_ffmpeg_dir(ffmpeg_dir).await?;
+        let runtime_dir = self.get_runtime_dir().ok();
+        let mut removed = false;
+        for tool in tools {
+            let installed = ffmpeg_dir.join(tool.executable_name());
+            for path in [storage::stored_path(&installed), installed] {
+                if path.exists() {
+                    fs::remove_file(&path)?;
+                    removed = true;
+                }
+            }
+            if let Some(runtime_dir) = &runtime_dir {
+                let _ = fs::remove_file(runtime_dir.join(tool.executable_name()));
+            }
+        }
+
+        let code = if removed {
+            MessageCode::Removed
+        } else {
+            MessageCode::NotInstalled
+        };
+        Ok(DeleteResponse {
+            success: true,
+            message_code: Some(code),
+            message: Some(messages::message(code, first)),
+        })
+    }
+}
+
+/// 在系统 `PATH` 中查找可执行文件
+fn find_in_path(executable_name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(executable_name))
+        .find(|path| path.is_file())
+}
+
+/// 在 Unix 系统上设置执行权限
+fn set_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// 错误是否像是杀毒软件拦截了可执行文件
+///
+/// 文件被判定为病毒时 Windows 返回 `ERROR_VIRUS_INFECTED` / `ERROR_VIRUS_DELETED`，正在扫描或已隔离的文件
+/// 则通常返回拒绝访问。其他平台上没有可靠的特征，始终为 false。
+fn is_blocked_by_antivirus(error: &std::io::Error) -> bool {
+    cfg!(windows)
+        && (error
+            .raw_os_error()
+            .is_some_and(|code| ERROR_VIRUS_CODES.contains(&code))
+            || error.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
+/// 将疑似被杀毒软件拦截的文件读写错误转换为 [`Error::BlockedByAntivirus`]
+fn antivirus_error(error: Error) -> Error {
+    match error {
+        Error::Io(e) if is_blocked_by_antivirus(&e) => Error::BlockedByAntivirus(e.to_string()),
+        e => e,
+    }
+}
+
+/// 启动 FFmpeg 失败时的错误
+fn spawn_error(path: &Path, error: std::io::Error) -> Error {
+    if is_blocked_by_antivirus(&error) {
+        Error::BlockedByAntivirus(format!("{}: {error}", path.display()))
+    } else {
+        Error::CommandExecution(error.to_string())
+    }
+}
+
+/// 错误是否由网络未连接（无法建立连接或连接超时）引起
+fn is_offline(error: &Error) -> bool {
+    matches!(error, Error::Http(e) if e.is_connect() || e.is_timeout())
+}
+
+/// 能否与地址所在的服务器建立 TCP 连接
+async fn can_connect(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return false;
+    };
+    matches!(
+        tokio::time::timeout(
+            ONLINE_PROBE_TIMEOUT,
+            tokio::net::TcpStream::connect((host, port))
+        )
+        .await,
+        Ok(Ok(_))
+    )
+}
+
+/// 缓存的检查结果，每次清除时递增 `generation`
+#[derive(Default)]
+struct CheckCache {
+    generation: u64,
+    response: Option<CheckResponse>,
+}
+
+/// 实际提供下载文件的地址和服务器
+struct DownloadOrigin {
+    final_url: String,
+    server: Option<String>,
+}
+
+/// 下载前确认地址可以访问并取得文件大小
+///
+/// 先发送 HEAD 请求；服务器不支持 HEAD（部分 CDN 和预签名地址会返回 403 或 405）时，
+/// 改为只请求第一个字节的 GET 请求，从 `Content-Range` 中取得总大小。
+async fn preflight(client: &reqwest::Client, url: &str) -> Result<Option<u64>> {
+    let response = client.head(url).timeout(PREFLIGHT_TIMEOUT).send().await?;
+    if response.status().is_success() {
+        // HEAD 响应没有内容，`content_length()` 总是 0，需要直接读取响应头
+        return Ok(header_u64(&response, reqwest::header::CONTENT_LENGTH));
+    }
+
+    let response = client
+        .get(url)
+        .header(reqwest::header::RANGE, "bytes=0-0")
+        .timeout(PREFLIGHT_TIMEOUT)
+        .send()
+        .await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::PARTIAL_CONTENT {
+        // `bytes 0-0/<总大小>`，总大小未知时为 `*`
+        return Ok(response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit_once('/'))
+            .and_then(|(_, total)| total.parse().ok()));
+    }
+    if status.is_success() {
+        // 服务器忽略了 Range，丢弃响应，不读取内容
+        return Ok(response.content_length());
+    }
+
+    Err(Error::Download(format!(
+        "Failed to download: HTTP {status}"
+    )))
+}
+
+fn header_u64(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<u64> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// 清除缓存或日志后的响应
+fn cleared() -> DeleteResponse {
+    DeleteResponse {
+        success: true,
+        message_code: Some(MessageCode::Cleared),
+        message: Some(messages::message(MessageCode::Cleared, Tool::Ffmpeg)),
+    }
+}
+
+/// 根据 FFmpeg 在归档中的路径推断同目录下其他工具的路径
+fn companion_path(executable_path: &str, tool: Tool) -> String {
+    match executable_path.rfind('/') {
+        Some(index) => format!("{}/{}", &executable_path[..index], tool.executable_name()),
+        None => tool.executable_name().to_string(),
+    }
+}
+
+/// 在后台读取子进程的全部输出
+fn read_to_end<T>(mut reader: T) -> tokio::task::JoinHandle<Vec<u8>>
+where
+    T: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf).await;
+        buf
+    })
+}
+
+/// 记录输出文件当前的修改时间（不存在时为 None）
+fn snapshot_outputs(args: &[String]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    output_paths(args)
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
         })
+        .collect()
+}
+
+/// 删除任务写入的不完整输出文件，任务开始前已存在且未被修改的文件会被保留
+fn cleanup_outputs(outputs: &[(PathBuf, Option<SystemTime>)]) {
+    for (path, before) in outputs {
+        let after = fs::metadata(path).and_then(|m| m.modified()).ok();
+        if after.is_some() && after != *before {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// 在后台读取 stderr 的全部输出，同时解析并发送任务进度事件
+fn read_stderr_with_progress<R, T>(
+    app: AppHandle<R>,
+    mut parser: ProgressParser,
+    mut reader: T,
+) -> tokio::task::JoinHandle<Vec<u8>>
+where
+    R: Runtime,
+    T: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+
+        loop {
+            let n = match reader.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            buf.extend_from_slice(&chunk[..n]);
+
+            for progress in parser.feed(&chunk[..n]) {
+                emit_job_progress(&app, &progress);
+            }
+        }
+
+        buf
+    })
+}
+
+/// 发送任务进度事件，任务属于批次时同时发送批次进度
+pub(crate) fn emit_job_progress<R: Runtime>(app: &AppHandle<R>, progress: &EncodeProgress) {
+    events::emit(app, events::JOB_PROGRESS, progress);
+    if let Some(batch) = app.ffmpeg().batches.progress(progress) {
+        events::emit(app, events::BATCH_PROGRESS, &batch);
     }
 }
@@ -1,4 +1,9 @@
-use serde::{ser::Serializer, Serialize};
+use serde::{
+    ser::{SerializeStruct, Serializer},
+    Serialize,
+};
+
+use crate::messages;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -12,14 +17,98 @@ pub enum Error {
     FfmpegNotFound,
     #[error("Extraction error: {0}")]
     Extraction(String),
+    #[error("Downloaded file is not an archive, it starts with: {0}")]
+    NotAnArchive(String),
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
+    #[error("FFmpeg was blocked by antivirus software: {0}. Restore it from quarantine or add an exclusion for the install directory, then reinstall")]
+    BlockedByAntivirus(String),
+    #[error("Binary signature verification failed: {0}")]
+    UntrustedBinary(String),
     #[error("Zip error: {0}")]
     Zip(#[from] zip::result::ZipError),
     #[error("Command execution error: {0}")]
     CommandExecution(String),
     #[error("Unsupported platform")]
     UnsupportedPlatform,
+    #[error("No default download source for platform `{0}`, configure `defaultDownload` in the plugin config")]
+    NoDefaultSource(String),
+    #[error("FFmpeg is managed by the system in system-ffmpeg mode")]
+    SystemFfmpegMode,
+    #[error(
+        "FFmpeg is provisioned externally via `ffmpegPath` and cannot be downloaded or removed"
+    )]
+    ProvisionedMode,
+    #[error("FFmpeg jobs are still running, pass `force: true` to kill them first")]
+    JobsRunning,
+    #[error("FFmpeg download is in progress")]
+    InstallInProgress,
+    #[error("No FFmpeg download is in progress")]
+    NoInstallInProgress,
+    #[error("Download `{0}` already exists")]
+    DownloadExists(String),
+    #[error("Download `{0}` not found")]
+    DownloadNotFound(String),
+    #[error("No network connection, download `{0}` will start when the connection is restored")]
+    DownloadPending(String),
+    #[error("Operation cancelled")]
+    Cancelled,
+    #[error("Job `{0}` already exists")]
+    JobExists(String),
+    #[error("Job queue is full ({0} jobs waiting)")]
+    QueueFull(usize),
+    #[error("Storage quota exceeded ({used} of {limit} bytes in use)")]
+    QuotaExceeded { used: u64, limit: u64 },
+    #[error("Job `{0}` not found")]
+    JobNotFound(String),
+    #[error("Job `{0}` is not interactive, start it with `interactive: true`")]
+    JobNotInteractive(String),
+    #[error("Invalid plugin config: {0}")]
+    InvalidConfig(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Output file already exists: {0}")]
+    OutputExists(String),
+    #[error("Cannot determine the FFmpeg version from `{0}`")]
+    UnknownVersion(String),
+}
+
+impl Error {
+    /// 稳定的错误代码，不随语言变化
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::Download(_) => "download",
+            Error::FfmpegNotFound => "ffmpeg_not_found",
+            Error::Extraction(_) => "extraction",
+            Error::NotAnArchive(_) => "not_an_archive",
+            Error::Http(_) => "http",
+            Error::BlockedByAntivirus(_) => "blocked_by_antivirus",
+            Error::UntrustedBinary(_) => "untrusted_binary",
+            Error::Zip(_) => "zip",
+            Error::CommandExecution(_) => "command_execution",
+            Error::UnsupportedPlatform => "unsupported_platform",
+            Error::NoDefaultSource(_) => "no_default_source",
+            Error::SystemFfmpegMode => "system_ffmpeg_mode",
+            Error::ProvisionedMode => "provisioned_mode",
+            Error::JobsRunning => "jobs_running",
+            Error::InstallInProgress => "install_in_progress",
+            Error::NoInstallInProgress => "no_install_in_progress",
+            Error::DownloadExists(_) => "download_exists",
+            Error::DownloadNotFound(_) => "download_not_found",
+            Error::DownloadPending(_) => "download_pending",
+            Error::Cancelled => "cancelled",
+            Error::JobExists(_) => "job_exists",
+            Error::QueueFull(_) => "queue_full",
+            Error::QuotaExceeded { .. } => "quota_exceeded",
+            Error::JobNotFound(_) => "job_not_found",
+            Error::JobNotInteractive(_) => "job_not_interactive",
+            Error::InvalidConfig(_) => "invalid_config",
+            Error::InvalidArgument(_) => "invalid_argument",
+            Error::OutputExists(_) => "output_exists",
+            Error::UnknownVersion(_) => "unknown_version",
+        }
+    }
 }
 
 impl Serialize for Error {
@@ -27,6 +116,13 @@ impl Serialize for Error {
     where
         S: Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        if !messages::structured_errors() {
+            return serializer.serialize_str(self.to_string().as_ref());
+        }
+
+        let mut state = serializer.serialize_struct("Error", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &messages::error_message(self))?;
+        state.end()
     }
 }
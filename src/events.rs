@@ -0,0 +1,65 @@
+//! # Events
+//!
+//! 插件发送的事件。所有事件的内容都带有 `schemaVersion` 字段，字段只增不减，
+//! 删除或改变已有字段的含义时才会提升版本号。
+
+use serde::{Serialize, Serializer};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// 事件内容的结构版本
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+pub(crate) const READY: &str = "use-ffmpeg://ready";
+pub(crate) const CHECK_SLOW: &str = "use-ffmpeg://check-slow";
+pub(crate) const DOWNLOAD_STARTED: &str = "use-ffmpeg://download-started";
+pub(crate) const DOWNLOAD_STARTING: &str = "use-ffmpeg://download-starting";
+pub(crate) const DOWNLOAD_PROGRESS: &str = "use-ffmpeg://download-progress";
+pub(crate) const DOWNLOAD_FINISHED: &str = "use-ffmpeg://download-finished";
+pub(crate) const DOWNLOAD_RESUMED: &str = "use-ffmpeg://download-resumed";
+pub(crate) const JOB_STARTED: &str = "use-ffmpeg://job-started";
+pub(crate) const JOB_PROGRESS: &str = "use-ffmpeg://job-progress";
+pub(crate) const JOB_STATS: &str = "use-ffmpeg://job-stats";
+pub(crate) const JOB_FINISHED: &str = "use-ffmpeg://job-finished";
+pub(crate) const RECORDING_SEGMENT: &str = "use-ffmpeg://recording-segment";
+pub(crate) const RECORDING_RECONNECTING: &str = "use-ffmpeg://recording-reconnecting";
+pub(crate) const AUDIO_LEVEL: &str = "use-ffmpeg://audio-level";
+pub(crate) const LEVEL_METER_STOPPED: &str = "use-ffmpeg://level-meter-stopped";
+pub(crate) const BATCH_PROGRESS: &str = "use-ffmpeg://batch-progress";
+pub(crate) const BATCH_FINISHED: &str = "use-ffmpeg://batch-finished";
+pub(crate) const QUEUE_DRAINED: &str = "use-ffmpeg://queue-drained";
+pub(crate) const QUEUE_JOB_REJECTED: &str = "use-ffmpeg://queue-job-rejected";
+pub(crate) const THROTTLE_CHANGED: &str = "use-ffmpeg://throttle-changed";
+
+/// 带有结构版本的事件内容，原有字段保持在顶层，与加入版本号之前的结构兼容
+pub(crate) struct Versioned<'a, T>(pub &'a T);
+
+impl<T> Clone for Versioned<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Versioned<'_, T> {}
+
+impl<T: Serialize> Serialize for Versioned<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Payload<'a, T> {
+            schema_version: u32,
+            #[serde(flatten)]
+            payload: &'a T,
+        }
+
+        Payload {
+            schema_version: EVENT_SCHEMA_VERSION,
+            payload: self.0,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// 发送带有结构版本的事件，发送失败时忽略
+pub(crate) fn emit<R: Runtime, T: Serialize>(app: &AppHandle<R>, event: &str, payload: &T) {
+    let _ = app.emit(event, Versioned(payload));
+}
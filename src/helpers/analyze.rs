@@ -0,0 +1,383 @@
+//! 画面分析（黑边、隔行、黑场和静帧检测）
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+
+/// 裁剪区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CropRect {
+    pub width: u32,
+    pub height: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl CropRect {
+    /// 对应的 crop 滤镜
+    pub fn filter(&self) -> String {
+        format!("crop={}:{}:{}:{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+/// 黑边检测请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectCropRequest {
+    /// 输入文件
+    pub input: String,
+    /// 分析的时长（秒），默认 60
+    pub sample_seconds: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 使用 cropdetect 分析黑边，返回出现次数最多的裁剪区域
+    ///
+    /// 时长已知时跳过开头 10% 的片头，避免黑场影响结果。
+    pub async fn detect_crop(&self, request: DetectCropRequest) -> Result<CropRect> {
+        let sample = request.sample_seconds.unwrap_or(60.0).max(1.0);
+        let start = match self.media_duration(&request.input).await {
+            Some(duration) if duration > sample => (duration * 0.1).min(duration - sample),
+            _ => 0.0,
+        };
+
+        let args = to_args([
+            "-hide_banner".to_string(),
+            "-ss".to_string(),
+            start.to_string(),
+            "-t".to_string(),
+            sample.to_string(),
+            "-i".to_string(),
+            request.input.clone(),
+            "-vf".to_string(),
+            "cropdetect=limit=24:round=2:reset=0".to_string(),
+            "-an".to_string(),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ]);
+
+        let response = self.run_helper(args, &request.job).await?;
+
+        let mut counts: HashMap<CropRect, usize> = HashMap::new();
+        for rect in response.stderr.lines().filter_map(parse_crop_line) {
+            *counts.entry(rect).or_default() += 1;
+        }
+
+        counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(rect, _)| rect)
+            .ok_or_else(|| Error::CommandExecution("cropdetect produced no result".to_string()))
+    }
+}
+
+/// 场序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldOrder {
+    /// 顶场优先
+    Tff,
+    /// 底场优先
+    Bff,
+    /// 逐行
+    Progressive,
+    /// 无法判断
+    Undetermined,
+}
+
+/// idet 的逐帧统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldOrderCounts {
+    pub tff: u64,
+    pub bff: u64,
+    pub progressive: u64,
+    pub undetermined: u64,
+}
+
+/// idet 的重复场统计（用于识别 3:2 下拉）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepeatedFieldCounts {
+    pub neither: u64,
+    pub top: u64,
+    pub bottom: u64,
+}
+
+/// 隔行检测结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterlacingReport {
+    /// 根据多帧检测得出的结论
+    pub field_order: FieldOrder,
+    /// 是否建议去隔行
+    pub interlaced: bool,
+    /// 单帧检测统计
+    pub single_frame: FieldOrderCounts,
+    /// 多帧检测统计（更可靠）
+    pub multi_frame: FieldOrderCounts,
+    /// 重复场统计
+    pub repeated_fields: RepeatedFieldCounts,
+}
+
+/// 隔行检测请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectInterlacingRequest {
+    /// 输入文件
+    pub input: String,
+    /// 分析的时长（秒），默认 60
+    pub sample_seconds: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 使用 idet 统计顶场优先、底场优先和逐行帧的数量
+    pub async fn detect_interlacing(
+        &self,
+        request: DetectInterlacingRequest,
+    ) -> Result<InterlacingReport> {
+        let sample = request.sample_seconds.unwrap_or(60.0).max(1.0);
+        let args = to_args([
+            "-hide_banner".to_string(),
+            "-t".to_string(),
+            sample.to_string(),
+            "-i".to_string(),
+            request.input.clone(),
+            "-vf".to_string(),
+            "idet".to_string(),
+            "-an".to_string(),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ]);
+
+        let response = self.run_helper(args, &request.job).await?;
+        let stats = |label: &str| {
+            response
+                .stderr
+                .lines()
+                .rev()
+                .find_map(|line| line.split_once(label).map(|(_, rest)| parse_counts(rest)))
+                .ok_or_else(|| Error::CommandExecution("idet produced no result".to_string()))
+        };
+
+        let field_order_counts = |counts: HashMap<String, u64>| FieldOrderCounts {
+            tff: counts.get("TFF").copied().unwrap_or_default(),
+            bff: counts.get("BFF").copied().unwrap_or_default(),
+            progressive: counts.get("Progressive").copied().unwrap_or_default(),
+            undetermined: counts.get("Undetermined").copied().unwrap_or_default(),
+        };
+        let single_frame = field_order_counts(stats("Single frame detection:")?);
+        let multi_frame = field_order_counts(stats("Multi frame detection:")?);
+        let repeated = stats("Repeated Fields:")?;
+        let repeated_fields = RepeatedFieldCounts {
+            neither: repeated.get("Neither").copied().unwrap_or_default(),
+            top: repeated.get("Top").copied().unwrap_or_default(),
+            bottom: repeated.get("Bottom").copied().unwrap_or_default(),
+        };
+
+        let field_order = [
+            (FieldOrder::Tff, multi_frame.tff),
+            (FieldOrder::Bff, multi_frame.bff),
+            (FieldOrder::Progressive, multi_frame.progressive),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(_, count)| *count)
+        .map(|(order, _)| order)
+        .unwrap_or(FieldOrder::Undetermined);
+
+        // 隔行帧超过已判定帧的 10% 时建议去隔行，可以覆盖混合素材
+        let interlaced_frames = multi_frame.tff + multi_frame.bff;
+        let decided = interlaced_frames + multi_frame.progressive;
+        let interlaced = decided > 0 && interlaced_frames * 10 > decided;
+
+        Ok(InterlacingReport {
+            field_order,
+            interlaced,
+            single_frame,
+            multi_frame,
+            repeated_fields,
+        })
+    }
+}
+
+/// 检测到的时间区间（秒）
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeInterval {
+    pub start: f64,
+    /// 持续到文件结尾时为 None
+    pub end: Option<f64>,
+    pub duration: Option<f64>,
+}
+
+/// 黑场检测请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectBlackRequest {
+    /// 输入文件
+    pub input: String,
+    /// 最短黑场时长（秒），默认 2
+    pub min_duration: Option<f64>,
+    /// 黑色像素占比阈值（0-1），默认 0.98
+    pub picture_threshold: Option<f64>,
+    /// 像素亮度阈值（0-1），默认 0.1
+    pub pixel_threshold: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 静帧检测请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectFreezeRequest {
+    /// 输入文件
+    pub input: String,
+    /// 最短静帧时长（秒），默认 2
+    pub min_duration: Option<f64>,
+    /// 噪声容差（dB），默认 -60
+    pub noise_db: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 使用 blackdetect 找出黑场区间
+    pub async fn detect_black(&self, request: DetectBlackRequest) -> Result<Vec<TimeInterval>> {
+        let filter = format!(
+            "blackdetect=d={}:pic_th={}:pix_th={}",
+            request.min_duration.unwrap_or(2.0),
+            request.picture_threshold.unwrap_or(0.98),
+            request.pixel_threshold.unwrap_or(0.1),
+        );
+        let stderr = self
+            .run_video_filter(&request.input, &filter, &request.job)
+            .await?;
+
+        Ok(stderr
+            .lines()
+            .filter_map(|line| {
+                Some(TimeInterval {
+                    start: find_value(line, "black_start")?,
+                    end: find_value(line, "black_end"),
+                    duration: find_value(line, "black_duration"),
+                })
+            })
+            .collect())
+    }
+
+    /// 使用 freezedetect 找出画面静止的区间
+    pub async fn detect_freeze(&self, request: DetectFreezeRequest) -> Result<Vec<TimeInterval>> {
+        let filter = format!(
+            "freezedetect=n={}dB:d={}",
+            request.noise_db.unwrap_or(-60.0),
+            request.min_duration.unwrap_or(2.0),
+        );
+        let stderr = self
+            .run_video_filter(&request.input, &filter, &request.job)
+            .await?;
+
+        // freezedetect 将开始、时长和结束分别输出在不同的行
+        let mut intervals: Vec<TimeInterval> = Vec::new();
+        for line in stderr.lines() {
+            if let Some(start) = find_value(line, "freeze_start") {
+                intervals.push(TimeInterval {
+                    start,
+                    end: None,
+                    duration: None,
+                });
+            } else if let Some(last) = intervals.last_mut() {
+                if let Some(duration) = find_value(line, "freeze_duration") {
+                    last.duration = Some(duration);
+                } else if let Some(end) = find_value(line, "freeze_end") {
+                    last.end = Some(end);
+                }
+            }
+        }
+
+        Ok(intervals)
+    }
+
+    /// 将整个视频解码并通过滤镜，返回 stderr
+    async fn run_video_filter(
+        &self,
+        input: &str,
+        filter: &str,
+        job: &HelperOptions,
+    ) -> Result<String> {
+        let args = to_args([
+            "-hide_banner",
+            "-i",
+            input,
+            "-vf",
+            filter,
+            "-an",
+            "-f",
+            "null",
+            "-",
+        ]);
+
+        Ok(self.run_helper(args, job).await?.stderr)
+    }
+}
+
+/// 读取 `key:value` 或 `key: value` 形式的数值
+pub(super) fn find_value(line: &str, key: &str) -> Option<f64> {
+    let rest = &line[line.find(key)? + key.len()..];
+    rest.strip_prefix(':')?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// 解析 `TFF:  12 BFF:  0` 形式的统计
+fn parse_counts(text: &str) -> HashMap<String, u64> {
+    let mut counts = HashMap::new();
+    let mut key: Option<&str> = None;
+
+    for token in text.split_whitespace() {
+        if let Some(name) = token.strip_suffix(':') {
+            key = Some(name);
+        } else if let (Some(name), Ok(value)) = (key.take(), token.parse::<u64>()) {
+            counts.insert(name.to_string(), value);
+        }
+    }
+
+    counts
+}
+
+/// 解析 cropdetect 输出行末尾的 `crop=w:h:x:y`
+fn parse_crop_line(line: &str) -> Option<CropRect> {
+    let value = line.rsplit_once("crop=")?.1.split_whitespace().next()?;
+    let mut parts = value.split(':').map(|part| part.parse::<i64>().ok());
+
+    let width = parts.next()??;
+    let height = parts.next()??;
+    let x = parts.next()??;
+    let y = parts.next()??;
+
+    // 黑场会产生负数或零尺寸的结果
+    if width <= 0 || height <= 0 || x < 0 || y < 0 {
+        return None;
+    }
+
+    Some(CropRect {
+        width: width as u32,
+        height: height as u32,
+        x: x as u32,
+        y: y as u32,
+    })
+}
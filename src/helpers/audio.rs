@@ -0,0 +1,426 @@
+//! 音频处理（重采样、降噪、静音裁剪、转写预处理）
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::analyze::find_value;
+use super::{to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::ExecuteResponse;
+
+/// 抖动算法（aresample 的 `dither_method`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DitherMethod {
+    None,
+    Rectangular,
+    #[default]
+    Triangular,
+    TriangularHp,
+    Lipshitz,
+    Shibata,
+    LowShibata,
+    HighShibata,
+    FWeighted,
+    EWeighted,
+    ModifiedEWeighted,
+    ImprovedEWeighted,
+}
+
+impl DitherMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            DitherMethod::None => "none",
+            DitherMethod::Rectangular => "rectangular",
+            DitherMethod::Triangular => "triangular",
+            DitherMethod::TriangularHp => "triangular_hp",
+            DitherMethod::Lipshitz => "lipshitz",
+            DitherMethod::Shibata => "shibata",
+            DitherMethod::LowShibata => "low_shibata",
+            DitherMethod::HighShibata => "high_shibata",
+            DitherMethod::FWeighted => "f_weighted",
+            DitherMethod::EWeighted => "e_weighted",
+            DitherMethod::ModifiedEWeighted => "modified_e_weighted",
+            DitherMethod::ImprovedEWeighted => "improved_e_weighted",
+        }
+    }
+}
+
+/// 重采样器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resampler {
+    /// libsoxr 高质量重采样
+    Soxr,
+    /// FFmpeg 内置的 swresample
+    Swr,
+}
+
+/// 重采样选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResampleOptions {
+    /// 目标采样率，未设置时保持不变
+    pub sample_rate: Option<u32>,
+    /// 目标位深（16、24 或 32），未设置时由编码器决定
+    pub bit_depth: Option<u8>,
+    /// 降低位深时的抖动算法，默认 `triangular`
+    pub dither: Option<DitherMethod>,
+}
+
+/// 重采样请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResampleRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: ResampleOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 重采样结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResampleResponse {
+    /// 任务 ID
+    pub job_id: String,
+    /// 实际使用的重采样器
+    pub resampler: Resampler,
+}
+
+/// 降噪强度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DenoiseStrength {
+    Light,
+    #[default]
+    Medium,
+    Strong,
+}
+
+impl DenoiseStrength {
+    /// afftdn 的降噪量（dB）
+    fn noise_reduction(self) -> u32 {
+        match self {
+            DenoiseStrength::Light => 6,
+            DenoiseStrength::Medium => 12,
+            DenoiseStrength::Strong => 24,
+        }
+    }
+}
+
+/// 降噪选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DenoiseAudioOptions {
+    /// 降噪强度，默认 `medium`
+    pub strength: Option<DenoiseStrength>,
+    /// 高通滤波截止频率（Hz），去除低频隆隆声，默认 80，设为 0 关闭
+    pub highpass: Option<u32>,
+    /// 低通滤波截止频率（Hz），去除高频嘶声，默认关闭
+    pub lowpass: Option<u32>,
+}
+
+/// 降噪请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DenoiseAudioRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: DenoiseAudioOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 静音裁剪选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimSilenceOptions {
+    /// 低于该音量视为静音（dB），默认 -50
+    pub threshold_db: Option<f64>,
+    /// 裁剪后在首尾保留的静音时长（秒），默认 0.25
+    pub padding: Option<f64>,
+    /// 最短静音时长（秒），更短的停顿不会被裁掉，默认 0.5
+    pub min_duration: Option<f64>,
+}
+
+/// 静音裁剪请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimSilenceRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: TrimSilenceOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 静音裁剪结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimSilenceResponse {
+    /// 任务 ID
+    pub job_id: String,
+    /// 保留部分在输入中的开始时间（秒）
+    pub start: f64,
+    /// 保留部分在输入中的结束时间（秒），没有裁掉结尾时为 None
+    pub end: Option<f64>,
+}
+
+/// 转写预处理请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrepareForTranscriptionRequest {
+    /// 输入文件，可以是任意音视频
+    pub input: String,
+    /// 输出文件，扩展名为 `.wav` 或 `.flac`
+    pub output: String,
+    /// 响度目标（LUFS），默认 -16
+    pub loudness: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 转换采样率和位深，FFmpeg 支持时使用 soxr，否则回退到 swresample
+    pub async fn resample(&self, request: ResampleRequest) -> Result<ResampleResponse> {
+        let options = &request.options;
+        let resampler = if self.has_build_option("libsoxr").await {
+            Resampler::Soxr
+        } else {
+            Resampler::Swr
+        };
+
+        let mut params = Vec::new();
+        if let Some(rate) = options.sample_rate {
+            params.push(format!("osr={rate}"));
+        }
+        match resampler {
+            Resampler::Soxr => params.push("resampler=soxr:precision=28".to_string()),
+            Resampler::Swr => params.push("filter_size=64:phase_shift=10:cutoff=0.97".to_string()),
+        }
+        params.push(format!(
+            "dither_method={}",
+            options.dither.unwrap_or_default().as_str()
+        ));
+
+        let mut args = to_args([
+            "-i".to_string(),
+            request.input.clone(),
+            "-vn".to_string(),
+            "-af".to_string(),
+            format!("aresample={}", params.join(":")),
+        ]);
+        if let Some(bit_depth) = options.bit_depth {
+            args.extend(bit_depth_args(&request.output, bit_depth)?);
+        }
+        args.push(request.output.clone());
+
+        let response = self.run_helper(args, &request.job).await?;
+
+        Ok(ResampleResponse {
+            job_id: response.job_id,
+            resampler,
+        })
+    }
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 一键清理录音：高通、FFT 降噪、低通，视频流（如果有）直接复制
+    pub async fn denoise_audio(&self, request: DenoiseAudioRequest) -> Result<ExecuteResponse> {
+        let options = &request.options;
+        let mut filters = Vec::new();
+
+        match options.highpass.unwrap_or(80) {
+            0 => {}
+            frequency => filters.push(format!("highpass=f={frequency}")),
+        }
+        filters.push(format!(
+            "afftdn=nr={}:nf=-25:tn=1",
+            options.strength.unwrap_or_default().noise_reduction()
+        ));
+        if let Some(frequency) = options.lowpass.filter(|frequency| *frequency > 0) {
+            filters.push(format!("lowpass=f={frequency}"));
+        }
+
+        let args = to_args([
+            "-i",
+            &request.input,
+            "-af",
+            &filters.join(","),
+            "-c:v",
+            "copy",
+            &request.output,
+        ]);
+
+        self.run_helper(args, &request.job).await
+    }
+
+    /// 用 silencedetect 找出开头和结尾的静音，按计算出的时间点裁掉
+    ///
+    /// 中间的停顿保持不变。
+    pub async fn trim_silence(&self, request: TrimSilenceRequest) -> Result<TrimSilenceResponse> {
+        let options = &request.options;
+        let padding = options.padding.unwrap_or(0.25).max(0.0);
+        let filter = format!(
+            "silencedetect=noise={}dB:d={}",
+            options.threshold_db.unwrap_or(-50.0),
+            options.min_duration.unwrap_or(0.5).max(0.01)
+        );
+
+        let analysis = self
+            .run_helper(
+                to_args([
+                    "-hide_banner",
+                    "-i",
+                    &request.input,
+                    "-af",
+                    &filter,
+                    "-vn",
+                    "-f",
+                    "null",
+                    "-",
+                ]),
+                &HelperOptions {
+                    wait_for_install: request.job.wait_for_install,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let duration = self.media_duration(&request.input).await;
+
+        // (silence_start, silence_end)，持续到结尾的静音没有 end
+        let mut silences: Vec<(f64, Option<f64>)> = Vec::new();
+        for line in analysis.stderr.lines() {
+            if let Some(start) = find_value(line, "silence_start") {
+                silences.push((start, None));
+            } else if let Some(end) = find_value(line, "silence_end") {
+                if let Some(last) = silences.last_mut() {
+                    last.1 = Some(end);
+                }
+            }
+        }
+
+        // 误差范围内视为位于文件开头或结尾
+        let leading = silences.first().filter(|(start, _)| *start <= 0.05);
+        if let Some((_, None)) = leading {
+            return Err(Error::InvalidArgument(
+                "input contains only silence".to_string(),
+            ));
+        }
+        let leading_end = leading.and_then(|(_, end)| *end).unwrap_or(0.0);
+        let trailing_start = silences
+            .last()
+            .filter(|(start, end)| {
+                *start > leading_end
+                    && match (end, duration) {
+                        (None, _) => true,
+                        (Some(end), Some(duration)) => *end >= duration - 0.05,
+                        (Some(_), None) => false,
+                    }
+            })
+            .map(|(start, _)| *start);
+
+        let start = (leading_end - padding).max(0.0);
+        let end = trailing_start.map(|trailing| match duration {
+            Some(duration) => (trailing + padding).min(duration),
+            None => trailing + padding,
+        });
+
+        let mut args = to_args(["-i", &request.input]);
+        if start > 0.0 {
+            args.extend(to_args(["-ss".to_string(), start.to_string()]));
+        }
+        if let Some(end) = end {
+            args.extend(to_args(["-to".to_string(), end.to_string()]));
+        }
+        args.push(request.output.clone());
+
+        let response = self.run_helper(args, &request.job).await?;
+        Ok(TrimSilenceResponse {
+            job_id: response.job_id,
+            start,
+            end,
+        })
+    }
+
+    /// 转换为 16 kHz 单声道 16 位 WAV / FLAC 并做响度标准化，即 Whisper 等转写模型需要的输入
+    pub async fn prepare_for_transcription(
+        &self,
+        request: PrepareForTranscriptionRequest,
+    ) -> Result<ExecuteResponse> {
+        let codec = match Path::new(&request.output)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("wav") => "pcm_s16le",
+            Some("flac") => "flac",
+            _ => {
+                return Err(Error::InvalidArgument(
+                    "transcription output must be .wav or .flac".to_string(),
+                ))
+            }
+        };
+        let filter = format!(
+            "loudnorm=I={}:TP=-1.5:LRA=11",
+            request.loudness.unwrap_or(-16.0)
+        );
+
+        let args = to_args([
+            "-i",
+            &request.input,
+            "-map",
+            "0:a:0",
+            "-af",
+            &filter,
+            "-ar",
+            "16000",
+            "-ac",
+            "1",
+            "-sample_fmt",
+            "s16",
+            "-c:a",
+            codec,
+            &request.output,
+        ]);
+
+        self.run_helper(args, &request.job).await
+    }
+}
+
+/// 根据输出格式选择位深对应的编码器或采样格式
+fn bit_depth_args(output: &str, bit_depth: u8) -> Result<Vec<String>> {
+    if !matches!(bit_depth, 16 | 24 | 32) {
+        return Err(Error::InvalidArgument(format!(
+            "unsupported bit depth {bit_depth}, expected 16, 24 or 32"
+        )));
+    }
+
+    let ext = Path::new(output)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    Ok(match ext.as_str() {
+        "wav" | "w64" | "caf" => to_args(["-c:a".to_string(), format!("pcm_s{bit_depth}le")]),
+        "aif" | "aiff" => to_args(["-c:a".to_string(), format!("pcm_s{bit_depth}be")]),
+        // FLAC 没有 24 位采样格式，使用 s32 并声明有效位数
+        "flac" if bit_depth == 24 => to_args(["-sample_fmt", "s32", "-bits_per_raw_sample", "24"]),
+        _ if bit_depth == 16 => to_args(["-sample_fmt", "s16"]),
+        _ => to_args(["-sample_fmt", "s32"]),
+    })
+}
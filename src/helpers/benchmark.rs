@@ -0,0 +1,156 @@
+//! 编码器性能测试
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::progress::field;
+
+/// 性能测试请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRequest {
+    /// 要测试的视频编码器，如 `libx264`、`h264_nvenc`、`h264_videotoolbox`
+    pub encoders: Vec<String>,
+    /// 每个编码器编码的时长（秒），默认 5
+    pub seconds: Option<f64>,
+    /// 测试画面的分辨率，默认 `1920x1080`
+    pub size: Option<String>,
+    /// 测试画面的帧率，默认 30
+    pub frame_rate: Option<u32>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 单个编码器的测试结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderBenchmark {
+    /// 编码器
+    pub encoder: String,
+    /// 平均编码帧率
+    pub fps: Option<f64>,
+    /// 相对实时的编码速度倍数
+    pub speed: Option<f64>,
+    /// 包括初始化在内的耗时（秒）
+    pub elapsed_secs: f64,
+    /// 编码器不可用或初始化失败（如缺少硬件或驱动）时的原因，成功时为 None
+    pub error: Option<String>,
+}
+
+/// 性能测试结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    /// 按请求顺序排列的各编码器结果
+    pub results: Vec<EncoderBenchmark>,
+    /// 编码帧率最高的编码器，全部失败时为 None
+    pub fastest: Option<String>,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 用合成的 testsrc2 画面依次测试各个编码器的编码速度，用于在用户的机器上选择最快的硬件编码器
+    ///
+    /// 编码器逐个运行，避免互相争用资源；单个编码器失败只记录在对应结果的 `error` 中。
+    /// 每个编码器单独运行，`jobId` 不会用于这些任务。
+    pub async fn benchmark(&self, request: BenchmarkRequest) -> Result<BenchmarkReport> {
+        if request.encoders.is_empty() {
+            return Err(Error::InvalidArgument(
+                "At least one encoder is required".to_string(),
+            ));
+        }
+        if let Some(encoder) = request.encoders.iter().find(|encoder| {
+            encoder.is_empty()
+                || !encoder
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        }) {
+            return Err(Error::InvalidArgument(format!(
+                "`{encoder}` is not a valid encoder name"
+            )));
+        }
+
+        let seconds = request.seconds.unwrap_or(5.0).clamp(0.5, 60.0);
+        let source = format!(
+            "testsrc2=size={}:rate={}",
+            request.size.as_deref().unwrap_or("1920x1080"),
+            request.frame_rate.unwrap_or(30).max(1)
+        );
+        let job = HelperOptions {
+            job_id: None,
+            ..request.job.clone()
+        };
+
+        let mut results = Vec::with_capacity(request.encoders.len());
+        for encoder in &request.encoders {
+            let args = to_args([
+                "-hide_banner",
+                "-f",
+                "lavfi",
+                "-i",
+                &source,
+                "-t",
+                &seconds.to_string(),
+                "-an",
+                "-c:v",
+                encoder,
+                "-f",
+                "null",
+                "-",
+            ]);
+
+            let started = Instant::now();
+            let result = self.run_helper(args, &job).await;
+            let elapsed_secs = started.elapsed().as_secs_f64();
+            let benchmark = match result {
+                Ok(response) => {
+                    let (fps, speed) = final_stats(&response.stderr);
+                    EncoderBenchmark {
+                        encoder: encoder.clone(),
+                        fps,
+                        speed,
+                        elapsed_secs,
+                        error: None,
+                    }
+                }
+                Err(Error::Cancelled) => return Err(Error::Cancelled),
+                Err(e) => EncoderBenchmark {
+                    encoder: encoder.clone(),
+                    fps: None,
+                    speed: None,
+                    elapsed_secs,
+                    error: Some(crate::messages::error_message(&e)),
+                },
+            };
+            results.push(benchmark);
+        }
+
+        let fastest = results
+            .iter()
+            .filter(|result| result.error.is_none())
+            .filter_map(|result| Some((result.fps?, &result.encoder)))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, encoder)| encoder.clone());
+
+        Ok(BenchmarkReport { results, fastest })
+    }
+}
+
+/// 最后一行进度中的 `fps` 和 `speed`（去掉末尾的 `x`）
+fn final_stats(stderr: &str) -> (Option<f64>, Option<f64>) {
+    let Some(line) = stderr
+        .split(['\r', '\n'])
+        .rev()
+        .find(|line| line.contains("frame=") && line.contains("speed="))
+    else {
+        return (None, None);
+    };
+
+    let fps = field(line, "fps").and_then(|fps| fps.parse().ok());
+    let speed = field(line, "speed").and_then(|speed| speed.trim_end_matches('x').parse().ok());
+    (fps, speed)
+}
@@ -0,0 +1,800 @@
+//! 查询已安装的 FFmpeg 支持的功能
+//!
+//! 解析 `ffmpeg -formats` 等命令的文本输出，界面可以根据实际安装的构建动态生成选项，
+//! 而不是写死一份可能与用户的构建不一致的列表。
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::stderr_tail;
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+
+/// 封装格式
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatInfo {
+    /// 格式名，可以传给 `-f`；部分分离器有多个名称，以逗号分隔，如 `matroska,webm`
+    pub name: String,
+    /// 说明，如 `Matroska / WebM`
+    pub description: String,
+    /// 是否可以读取（分离器）
+    pub demux: bool,
+    /// 是否可以写入（复用器）
+    pub mux: bool,
+    /// 是否为采集设备（如 `avfoundation`、`dshow`），仅 FFmpeg 7.0 及以上会标出
+    pub device: bool,
+}
+
+/// 支持的协议
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolsInfo {
+    /// 可以用于输入的协议，如 `file`、`https`、`rtmp`、`srt`
+    pub input: Vec<String>,
+    /// 可以用于输出的协议
+    pub output: Vec<String>,
+}
+
+/// 像素格式
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PixFmtInfo {
+    /// 格式名，可以传给 `-pix_fmt`，如 `yuv420p`
+    pub name: String,
+    /// 分量数
+    pub components: u32,
+    /// 每个像素的平均位数
+    pub bits_per_pixel: u32,
+    /// 各分量的位深，FFmpeg 6.0 之前的版本不输出该列，此时为空
+    pub bit_depths: Vec<u32>,
+    /// 是否支持作为转换的输入
+    pub input: bool,
+    /// 是否支持作为转换的输出，编码前 `-pix_fmt` 转换到的格式需要支持
+    pub output: bool,
+    /// 是否为硬件加速格式（如 `vaapi`、`cuda`、`videotoolbox_vld`），帧数据位于显存中
+    pub hardware: bool,
+    /// 是否为调色板格式
+    pub paletted: bool,
+    /// 是否为位流格式
+    pub bitstream: bool,
+}
+
+/// 音频采样格式
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleFmtInfo {
+    /// 格式名，可以传给 `-sample_fmt`，如 `s16`、`fltp`
+    pub name: String,
+    /// 每个采样的位数
+    pub depth: u32,
+    /// 是否为平面格式（各声道分开存储）
+    pub planar: bool,
+}
+
+/// 滤镜说明查询请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterHelpRequest {
+    /// 滤镜名，如 `scale`
+    pub name: String,
+}
+
+/// 滤镜的说明和选项（`ffmpeg -h filter=<名称>`）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterHelp {
+    /// 滤镜名
+    pub name: String,
+    /// 说明
+    pub description: String,
+    /// 选项，别名（如 `scale` 的 `w` 和 `width`）各自单独列出
+    pub options: Vec<FilterOption>,
+    /// 是否支持通过 `enable` 选项按时间启用
+    pub timeline: bool,
+}
+
+/// 滤镜选项
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterOption {
+    /// 选项名
+    pub name: String,
+    /// 类型，如 `int`、`string`、`boolean`、`flags`、`color`、`duration`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// 说明，不含取值范围和默认值
+    pub description: String,
+    /// 默认值，按 FFmpeg 的输出原样保留（去掉外层引号）
+    pub default: Option<String>,
+    /// 最小值
+    pub min: Option<String>,
+    /// 最大值
+    pub max: Option<String>,
+    /// 是否可以在运行时通过 `sendcmd` 或交互命令修改
+    pub runtime: bool,
+    /// 具名常量，如 `eval` 的 `init` 和 `frame`
+    pub constants: Vec<FilterOptionConstant>,
+}
+
+/// 选项的具名常量
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterOptionConstant {
+    /// 常量名
+    pub name: String,
+    /// 对应的数值，`flags` 类型的常量没有
+    pub value: Option<String>,
+    /// 说明
+    pub description: String,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 列出已安装的 FFmpeg 支持的封装格式（`ffmpeg -formats`）
+    pub async fn list_formats(&self) -> Result<Vec<FormatInfo>> {
+        let output = self.ffmpeg_output(&["-formats"]).await?;
+        Ok(parse_formats(&output))
+    }
+
+    /// 列出已安装的 FFmpeg 支持的输入和输出协议（`ffmpeg -protocols`）
+    ///
+    /// 不同的构建支持的协议不同，例如 LGPL 构建可能没有 `srt`，可以在启动任务前判断能否使用网络来源。
+    pub async fn list_protocols(&self) -> Result<ProtocolsInfo> {
+        let output = self.ffmpeg_output(&["-protocols"]).await?;
+        Ok(parse_protocols(&output))
+    }
+
+    /// 列出已安装的 FFmpeg 支持的像素格式（`ffmpeg -pix_fmts`）
+    pub async fn list_pix_fmts(&self) -> Result<Vec<PixFmtInfo>> {
+        let output = self.ffmpeg_output(&["-pix_fmts"]).await?;
+        Ok(parse_pix_fmts(&output))
+    }
+
+    /// 列出已安装的 FFmpeg 支持的音频采样格式（`ffmpeg -sample_fmts`）
+    pub async fn list_sample_fmts(&self) -> Result<Vec<SampleFmtInfo>> {
+        let output = self.ffmpeg_output(&["-sample_fmts"]).await?;
+        Ok(parse_sample_fmts(&output))
+    }
+
+    /// 读取滤镜的说明和选项（`ffmpeg -h filter=<名称>`），供界面为任意滤镜生成参数表单
+    ///
+    /// 已安装的 FFmpeg 没有该滤镜时返回 [`Error::InvalidArgument`]。
+    pub async fn filter_help(&self, name: &str) -> Result<FilterHelp> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(Error::InvalidArgument(format!(
+                "`{name}` is not a valid filter name"
+            )));
+        }
+
+        let output = self
+            .ffmpeg_output(&["-h", &format!("filter={name}")])
+            .await?;
+        parse_filter_help(name, &output)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown filter `{name}`")))
+    }
+
+    /// 运行 ffmpeg 并返回 stdout，FFmpeg 以非零状态退出时返回 [`Error::CommandExecution`]
+    pub(crate) async fn ffmpeg_output(&self, args: &[&str]) -> Result<String> {
+        let path = self.get_ffmpeg_executable_path()?;
+        if !path.exists() {
+            return Err(Error::FfmpegNotFound);
+        }
+
+        let output = tokio::process::Command::new(path)
+            .arg("-hide_banner")
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(Error::CommandExecution(stderr_tail(
+                &String::from_utf8_lossy(&output.stderr),
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// 解析 `-formats`、`-muxers`、`-demuxers` 的输出
+///
+/// 说明部分（` D. = Demuxing supported`）给出标志列的宽度，FFmpeg 7.0 起多了表示设备的第三列；
+/// `--` 之后每行为 ` <标志> <名称> <说明>`。
+fn parse_formats(output: &str) -> Vec<FormatInfo> {
+    let Some((legend, entries)) = split_legend(output) else {
+        return Vec::new();
+    };
+    let width = legend
+        .lines()
+        .find_map(|line| line.split_once(" = "))
+        .map_or(2, |(flags, _)| flags.trim().len());
+
+    entries
+        .lines()
+        .filter_map(|line| {
+            let flags = line.get(1..1 + width)?;
+            let (name, description) = split_name(line.get(1 + width..)?)?;
+            Some(FormatInfo {
+                name: name.to_string(),
+                description: description.to_string(),
+                demux: flags.contains('D'),
+                mux: flags.contains('E'),
+                device: flags.contains('d'),
+            })
+        })
+        .collect()
+}
+
+/// 解析 `-protocols` 的输出：`Input:` 和 `Output:` 之后每行一个缩进的协议名
+fn parse_protocols(output: &str) -> ProtocolsInfo {
+    let mut protocols = ProtocolsInfo::default();
+    let mut section = None;
+    for line in output.lines() {
+        match line.trim() {
+            "Input:" => section = Some(&mut protocols.input),
+            "Output:" => section = Some(&mut protocols.output),
+            "" => {}
+            name if line.starts_with(char::is_whitespace) => {
+                if let Some(list) = section.as_mut() {
+                    list.push(name.to_string());
+                }
+            }
+            _ => section = None,
+        }
+    }
+    protocols
+}
+
+/// 解析 `-pix_fmts` 的输出：`-----` 之后每行为 `<标志> <名称> <分量数> <每像素位数> [<各分量位深>]`
+///
+/// 标志依次为 `I`（输入）、`O`（输出）、`H`（硬件）、`P`（调色板）、`B`（位流），不支持时为 `.`。
+fn parse_pix_fmts(output: &str) -> Vec<PixFmtInfo> {
+    let Some((_, entries)) = split_legend(output) else {
+        return Vec::new();
+    };
+
+    entries
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let flags: Vec<char> = columns.next()?.chars().collect();
+            let name = columns.next()?;
+            let components = columns.next()?.parse().ok()?;
+            let bits_per_pixel = columns.next()?.parse().ok()?;
+            let bit_depths = columns
+                .next()
+                .map(|depths| {
+                    depths
+                        .split('-')
+                        .filter_map(|depth| depth.parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let flag = |index: usize, c: char| flags.get(index) == Some(&c);
+            Some(PixFmtInfo {
+                name: name.to_string(),
+                components,
+                bits_per_pixel,
+                bit_depths,
+                input: flag(0, 'I'),
+                output: flag(1, 'O'),
+                hardware: flag(2, 'H'),
+                paletted: flag(3, 'P'),
+                bitstream: flag(4, 'B'),
+            })
+        })
+        .collect()
+}
+
+/// 解析 `-sample_fmts` 的输出：表头 `name depth` 之后每行为 `<名称> <位数>`
+fn parse_sample_fmts(output: &str) -> Vec<SampleFmtInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let name = columns.next()?;
+            let depth = columns.next()?.parse().ok()?;
+            Some(SampleFmtInfo {
+                name: name.to_string(),
+                depth,
+                // 平面格式以 `p` 结尾，如 `fltp`、`s16p`
+                planar: name.ends_with('p'),
+            })
+        })
+        .collect()
+}
+
+/// 解析 `-h filter=<名称>` 的输出
+///
+/// 第一行为 `Filter <名称>`，下一行为说明；`<名称> AVOptions:` 之后缩进的行为选项
+/// `<名称> <<类型>> <标志> <说明>`，缩进更深的行为上一个选项的常量 `<名称> [<数值>] <标志> <说明>`。
+/// 没有该滤镜时 FFmpeg 只输出 `Unknown filter '<名称>'.`，返回 None。
+fn parse_filter_help(name: &str, output: &str) -> Option<FilterHelp> {
+    let header = format!("Filter {name}");
+    let mut lines = output.lines();
+    lines.find(|line| line.trim() == header)?;
+
+    let mut help = FilterHelp {
+        name: name.to_string(),
+        description: lines.next().unwrap_or_default().trim().to_string(),
+        options: Vec::new(),
+        timeline: false,
+    };
+    let mut in_options = false;
+    let mut option_indent = 0;
+    for line in lines {
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            in_options = trimmed.ends_with("AVOptions:");
+            option_indent = 0;
+            help.timeline |= trimmed.contains("support for timeline");
+            continue;
+        }
+        if !in_options {
+            continue;
+        }
+        if option_indent == 0 || indent <= option_indent {
+            if let Some(option) = parse_filter_option(trimmed) {
+                option_indent = indent;
+                help.options.push(option);
+            }
+        } else if let Some(option) = help.options.last_mut() {
+            option.constants.extend(parse_filter_constant(trimmed));
+        }
+    }
+    Some(help)
+}
+
+/// 解析选项行 `<名称> <<类型>> <标志> <说明>`
+fn parse_filter_option(line: &str) -> Option<FilterOption> {
+    let (name, rest) = split_name(line)?;
+    let (kind, rest) = split_name(rest)?;
+    let kind = kind.strip_prefix('<')?.strip_suffix('>')?;
+    let (flags, description) = split_name(rest).unwrap_or_default();
+
+    let (description, default) = take_parenthesized(description, "(default ");
+    let (description, range) = take_parenthesized(description, "(from ");
+    let (min, max) = range
+        .and_then(|range| range.split_once(" to "))
+        .map_or((None, None), |(min, max)| {
+            (Some(min.to_string()), Some(max.to_string()))
+        });
+
+    Some(FilterOption {
+        name: name.to_string(),
+        kind: kind.to_string(),
+        description: description.to_string(),
+        default: default.map(|default| default.trim_matches('"').to_string()),
+        min,
+        max,
+        runtime: is_option_flags(flags) && flags.contains('T'),
+        constants: Vec::new(),
+    })
+}
+
+/// 解析常量行 `<名称> [<数值>] <标志> <说明>`
+fn parse_filter_constant(line: &str) -> Option<FilterOptionConstant> {
+    let (name, rest) = split_name(line)?;
+    let (second, after) = split_name(rest).unwrap_or_default();
+    let (value, description) = if is_option_flags(second) {
+        (None, after)
+    } else {
+        let (_, description) = split_name(after).unwrap_or_default();
+        (Some(second.to_string()), description)
+    };
+
+    Some(FilterOptionConstant {
+        name: name.to_string(),
+        value,
+        description: description.to_string(),
+    })
+}
+
+/// 选项标志列，如 `..FV.....T.`
+fn is_option_flags(token: &str) -> bool {
+    token.len() >= 8
+        && token.contains('.')
+        && token.chars().all(|c| c == '.' || c.is_ascii_uppercase())
+}
+
+/// 取出说明末尾以 `prefix` 开头的括号内容，如 `(default auto)` 中的 `auto`
+fn take_parenthesized<'a>(description: &'a str, prefix: &str) -> (&'a str, Option<&'a str>) {
+    match description.rfind(prefix) {
+        Some(start) if description.ends_with(')') => (
+            description[..start].trim_end(),
+            Some(&description[start + prefix.len()..description.len() - 1]),
+        ),
+        _ => (description, None),
+    }
+}
+
+/// 以只由 `-` 组成的分隔行把输出分成说明和条目两部分
+fn split_legend(output: &str) -> Option<(&str, &str)> {
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && trimmed.chars().all(|c| c == '-') {
+            return Some((&output[..offset], &output[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 拆分 `<名称> <说明>`，名称为空时返回 None
+fn split_name(rest: &str) -> Option<(&str, &str)> {
+    let rest = rest.trim();
+    let (name, description) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    (!name.is_empty()).then(|| (name, description.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// FFmpeg 7.1 `-hide_banner -formats` 的节选
+    const FORMATS_7: &str = "\
+Formats:
+ D.. = Demuxing supported
+ .E. = Muxing supported
+ ..d = Is a device
+ ---
+ D   3dostr          3DO STR
+  E  3g2             3GP2 (3GPP2 file format)
+ DE  ac3             raw AC-3
+ DEd alsa            ALSA audio output
+ D d avfoundation    AVFoundation input device
+ D d lavfi           Libavfilter virtual input device
+ D   matroska,webm   Matroska / WebM
+  E  matroska        Matroska
+  E  mp4             MP4 (MPEG-4 Part 14)
+ D   mov,mp4,m4a,3gp,3g2,mj2 QuickTime / MOV
+";
+
+    /// FFmpeg 6.0 `-hide_banner -formats` 的节选，没有设备列
+    const FORMATS_6: &str = "\
+File formats:
+ D. = Demuxing supported
+ .E = Muxing supported
+ --
+ D  3dostr          3DO STR
+  E 3g2             3GP2 (3GPP2 file format)
+ DE ac3             raw AC-3
+ D  lavfi           Libavfilter virtual input device
+";
+
+    /// FFmpeg 7.1 `-hide_banner -muxers` 的节选
+    const MUXERS_7: &str = "\
+Muxers:
+ D.. = Demuxing supported
+ .E. = Muxing supported
+ ..d = Is a device
+ ---
+  E  3g2             3GP2 (3GPP2 file format)
+  Ed alsa            ALSA audio output
+  E  webm            WebM
+";
+
+    /// FFmpeg 7.1 `-hide_banner -protocols` 的节选
+    const PROTOCOLS: &str = "\
+Supported file protocols:
+Input:
+  async
+  cache
+  concat
+  file
+  http
+  https
+  pipe
+  rtmp
+  srt
+Output:
+  file
+  http
+  pipe
+  rtmp
+";
+
+    /// FFmpeg 7.1 `-hide_banner -pix_fmts` 的节选
+    const PIX_FMTS_7: &str = "\
+Pixel formats:
+I.... = Supported Input  format for conversion
+.O... = Supported Output format for conversion
+..H.. = Hardware accelerated format
+...P. = Paletted format
+....B = Bitstream format
+FLAGS NAME            NB_COMPONENTS BITS_PER_PIXEL BIT_DEPTHS
+-----
+IO... yuv420p                3             12      8-8-8
+IO..B monob                  1              1      1
+IO.P. pal8                   1              8      8
+..H.. vaapi                  0              0      0
+IO... yuv420p10le            3             15      10-10-10
+..H.. videotoolbox_vld       0              0      0
+I.... bayer_rggb16le         3             16      4-8-4
+IO... yuva444p16le           4             64      16-16-16-16
+";
+
+    /// FFmpeg 5.1 `-hide_banner -pix_fmts` 的节选，没有各分量位深
+    const PIX_FMTS_5: &str = "\
+Pixel formats:
+I.... = Supported Input  format for conversion
+.O... = Supported Output format for conversion
+..H.. = Hardware accelerated format
+...P. = Paletted format
+....B = Bitstream format
+FLAGS NAME            NB_COMPONENTS BITS_PER_PIXEL
+-----
+IO... yuv420p                3            12
+..H.. cuda                   0             0
+";
+
+    /// FFmpeg 7.1 `-hide_banner -sample_fmts` 的完整输出
+    const SAMPLE_FMTS: &str = "\
+name   depth
+u8        8 
+s16      16 
+s32      32 
+flt      32 
+dbl      64 
+u8p       8 
+s16p     16 
+s32p     32 
+fltp     32 
+dblp     64 
+s64      64 
+s64p     64 
+";
+
+    /// FFmpeg 7.1 `-hide_banner -h filter=eq` 的完整输出
+    const EQ_HELP: &str = "\
+Filter eq
+  Adjust brightness, contrast, gamma, and saturation.
+    Inputs:
+       #0: default (video)
+    Outputs:
+       #0: default (video)
+eq AVOptions:
+   contrast          <string>     ..FV.....T. set the contrast adjustment, negative values give a negative image (default \"1.0\")
+   brightness        <string>     ..FV.....T. set the brightness adjustment (default \"0.0\")
+   saturation        <string>     ..FV.....T. set the saturation adjustment (default \"1.0\")
+   gamma             <string>     ..FV.....T. set the initial gamma value (default \"1.0\")
+   gamma_r           <string>     ..FV.....T. gamma value for red (default \"1.0\")
+   gamma_g           <string>     ..FV.....T. gamma value for green (default \"1.0\")
+   gamma_b           <string>     ..FV.....T. gamma value for blue (default \"1.0\")
+   gamma_weight      <string>     ..FV.....T. set the gamma weight which reduces the effect of gamma on bright areas (default \"1.0\")
+   eval              <int>        ..FV....... specify when to evaluate expressions (from 0 to 1) (default init)
+     init            0            ..FV....... eval expressions once during initialization
+     frame           1            ..FV....... eval expressions per-frame
+
+This filter has support for timeline through the 'enable' option.
+";
+
+    /// FFmpeg 7.1 `-hide_banner -h filter=scale` 的节选，包括 libswscale 的选项
+    const SCALE_HELP: &str = "\
+Filter scale
+  Scale the input video size and/or convert the image format.
+    Inputs:
+       #0: default (video)
+    Outputs:
+       #0: default (video)
+scale AVOptions:
+   w                 <string>     ..FV.....T. Output video width
+   interl            <boolean>    ..FV....... set interlacing (default false)
+   in_color_matrix   <int>        ..FV.....T. set input YCbCr type (from -1 to 17) (default auto)
+     auto            -1           ..FV.....T.
+     bt601           5            ..FV.....T.
+     bt709           1            ..FV.....T.
+   param0            <double>     ..FV....... Scaler param 0 (from -DBL_MAX to DBL_MAX) (default DBL_MAX)
+
+SWScaler AVOptions:
+  sws_flags         <flags>      E..V....... swscale flags (default bicubic)
+     fast_bilinear                E..V....... fast bilinear
+     bicubic                      E..V....... bicubic
+
+";
+
+    #[test]
+    fn formats_with_device_column() {
+        let formats = parse_formats(FORMATS_7);
+        assert_eq!(formats.len(), 10);
+
+        let ac3 = &formats[2];
+        assert_eq!(
+            (ac3.name.as_str(), ac3.description.as_str()),
+            ("ac3", "raw AC-3")
+        );
+        assert!(ac3.demux && ac3.mux && !ac3.device);
+
+        let alsa = &formats[3];
+        assert!(alsa.demux && alsa.mux && alsa.device);
+        let avfoundation = &formats[4];
+        assert_eq!(avfoundation.name, "avfoundation");
+        assert!(avfoundation.demux && !avfoundation.mux && avfoundation.device);
+
+        assert_eq!(formats[7].name, "matroska");
+        assert!(!formats[7].demux && formats[7].mux);
+        // 名称比说明列宽时只以空白分隔
+        assert_eq!(formats[9].name, "mov,mp4,m4a,3gp,3g2,mj2");
+        assert_eq!(formats[9].description, "QuickTime / MOV");
+    }
+
+    #[test]
+    fn formats_without_device_column() {
+        let formats = parse_formats(FORMATS_6);
+        let names: Vec<_> = formats.iter().map(|format| format.name.as_str()).collect();
+        assert_eq!(names, ["3dostr", "3g2", "ac3", "lavfi"]);
+        assert!(formats[0].demux && !formats[0].mux);
+        assert!(!formats[1].demux && formats[1].mux);
+        assert_eq!(formats[1].description, "3GP2 (3GPP2 file format)");
+        assert!(formats.iter().all(|format| !format.device));
+    }
+
+    #[test]
+    fn muxers() {
+        let muxers = parse_formats(MUXERS_7);
+        assert_eq!(muxers.len(), 3);
+        assert!(muxers.iter().all(|muxer| muxer.mux && !muxer.demux));
+        assert!(muxers[1].device);
+        assert_eq!(muxers[2].name, "webm");
+    }
+
+    #[test]
+    fn protocols() {
+        let protocols = parse_protocols(PROTOCOLS);
+        assert_eq!(
+            protocols.input,
+            ["async", "cache", "concat", "file", "http", "https", "pipe", "rtmp", "srt"]
+        );
+        assert_eq!(protocols.output, ["file", "http", "pipe", "rtmp"]);
+
+        // 没有输出协议的构建
+        let protocols = parse_protocols("Supported file protocols:\nInput:\n  file\nOutput:\n");
+        assert_eq!(protocols.input, ["file"]);
+        assert!(protocols.output.is_empty());
+        assert!(parse_protocols("").input.is_empty());
+    }
+
+    #[test]
+    fn pix_fmts_with_bit_depths() {
+        let pix_fmts = parse_pix_fmts(PIX_FMTS_7);
+        assert_eq!(pix_fmts.len(), 8);
+
+        let yuv420p = &pix_fmts[0];
+        assert_eq!(yuv420p.name, "yuv420p");
+        assert_eq!((yuv420p.components, yuv420p.bits_per_pixel), (3, 12));
+        assert_eq!(yuv420p.bit_depths, [8, 8, 8]);
+        assert!(yuv420p.input && yuv420p.output && !yuv420p.hardware);
+
+        assert!(pix_fmts[1].bitstream && !pix_fmts[1].paletted);
+        assert!(pix_fmts[2].paletted && !pix_fmts[2].bitstream);
+        let vaapi = &pix_fmts[3];
+        assert!(vaapi.hardware && !vaapi.input && !vaapi.output);
+        assert_eq!(vaapi.bit_depths, [0]);
+        assert_eq!(pix_fmts[4].bit_depths, [10, 10, 10]);
+        assert!(pix_fmts[6].input && !pix_fmts[6].output);
+        assert_eq!(pix_fmts[7].components, 4);
+        assert_eq!(pix_fmts[7].bit_depths, [16, 16, 16, 16]);
+    }
+
+    #[test]
+    fn pix_fmts_without_bit_depths() {
+        let pix_fmts = parse_pix_fmts(PIX_FMTS_5);
+        assert_eq!(pix_fmts.len(), 2);
+        assert_eq!(pix_fmts[0].bits_per_pixel, 12);
+        assert!(pix_fmts.iter().all(|pix_fmt| pix_fmt.bit_depths.is_empty()));
+        assert!(pix_fmts[1].hardware);
+    }
+
+    #[test]
+    fn sample_fmts() {
+        let sample_fmts = parse_sample_fmts(SAMPLE_FMTS);
+        assert_eq!(sample_fmts.len(), 12);
+        let planar: Vec<_> = sample_fmts
+            .iter()
+            .filter(|sample_fmt| sample_fmt.planar)
+            .map(|sample_fmt| sample_fmt.name.as_str())
+            .collect();
+        assert_eq!(planar, ["u8p", "s16p", "s32p", "fltp", "dblp", "s64p"]);
+        assert_eq!(sample_fmts[0].depth, 8);
+        assert_eq!(sample_fmts[11].depth, 64);
+    }
+
+    #[test]
+    fn filter_help_with_constants() {
+        let help = parse_filter_help("eq", EQ_HELP).unwrap();
+        assert_eq!(help.name, "eq");
+        assert_eq!(
+            help.description,
+            "Adjust brightness, contrast, gamma, and saturation."
+        );
+        assert!(help.timeline);
+        assert_eq!(help.options.len(), 9);
+
+        let contrast = &help.options[0];
+        assert_eq!(contrast.name, "contrast");
+        assert_eq!(contrast.kind, "string");
+        assert_eq!(
+            contrast.description,
+            "set the contrast adjustment, negative values give a negative image"
+        );
+        assert_eq!(contrast.default.as_deref(), Some("1.0"));
+        assert!(contrast.runtime && contrast.constants.is_empty());
+
+        let eval = &help.options[8];
+        assert_eq!(eval.kind, "int");
+        assert_eq!(eval.description, "specify when to evaluate expressions");
+        assert_eq!(
+            (eval.min.as_deref(), eval.max.as_deref()),
+            (Some("0"), Some("1"))
+        );
+        assert_eq!(eval.default.as_deref(), Some("init"));
+        assert!(!eval.runtime);
+        let constants: Vec<_> = eval
+            .constants
+            .iter()
+            .map(|c| (c.name.as_str(), c.value.as_deref(), c.description.as_str()))
+            .collect();
+        assert_eq!(
+            constants,
+            [
+                (
+                    "init",
+                    Some("0"),
+                    "eval expressions once during initialization"
+                ),
+                ("frame", Some("1"), "eval expressions per-frame"),
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_help_with_child_options() {
+        let help = parse_filter_help("scale", SCALE_HELP).unwrap();
+        assert!(!help.timeline);
+        let names: Vec<_> = help.options.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["w", "interl", "in_color_matrix", "param0", "sws_flags"]
+        );
+
+        assert_eq!(help.options[0].description, "Output video width");
+        assert_eq!(help.options[0].default, None);
+        assert_eq!(help.options[1].default.as_deref(), Some("false"));
+
+        let matrix = &help.options[2];
+        assert_eq!(
+            (matrix.min.as_deref(), matrix.max.as_deref()),
+            (Some("-1"), Some("17"))
+        );
+        assert_eq!(matrix.constants.len(), 3);
+        assert_eq!(matrix.constants[0].value.as_deref(), Some("-1"));
+        assert_eq!(matrix.constants[0].description, "");
+
+        let param0 = &help.options[3];
+        assert_eq!(param0.min.as_deref(), Some("-DBL_MAX"));
+        assert_eq!(param0.default.as_deref(), Some("DBL_MAX"));
+
+        // `flags` 类型的常量没有数值
+        let flags = &help.options[4];
+        assert_eq!(flags.kind, "flags");
+        assert_eq!(flags.constants[0].name, "fast_bilinear");
+        assert_eq!(flags.constants[0].value, None);
+        assert_eq!(flags.constants[0].description, "fast bilinear");
+    }
+
+    #[test]
+    fn unknown_filter() {
+        assert!(parse_filter_help("nosuch", "Unknown filter 'nosuch'.\n").is_none());
+        // 输出的是其他滤镜时同样视为未找到
+        assert!(parse_filter_help("eq2", EQ_HELP).is_none());
+    }
+
+    #[test]
+    fn output_without_legend_is_empty() {
+        assert!(parse_formats("").is_empty());
+        assert!(parse_formats("Unrecognized option 'formats'.\n").is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! 音频采集（麦克风和系统声音）
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::record::STOP_TIMEOUT;
+use super::{stderr_tail, to_args, HelperOptions, HelperOutput};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::ExecuteRequest;
+
+/// 音频采集源
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioCaptureOptions {
+    /// 输入设备，未设置时使用系统默认设备（Windows 采集麦克风时必须设置）
+    ///
+    /// macOS 为 AVFoundation 的音频设备序号或名称，Windows 为 DirectShow 设备名称，
+    /// Linux 为 PulseAudio / PipeWire 的 source 名称（采集系统声音时也可以是 sink 名称）。
+    pub device: Option<String>,
+    /// 采集系统正在播放的声音而不是麦克风
+    ///
+    /// - Windows：DirectShow 设备 `virtual-audio-capturer`（由 screen-capture-recorder 提供，内部使用 WASAPI loopback）
+    /// - Linux：PulseAudio / PipeWire 的 monitor source，默认为 `@DEFAULT_MONITOR@`
+    /// - macOS：系统不提供 loopback 设备，需要安装 BlackHole 等虚拟声卡，默认使用 `BlackHole 2ch`
+    #[serde(default)]
+    pub loopback: bool,
+}
+
+/// 录音请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordAudioRequest {
+    /// 输出文件，格式由扩展名决定
+    pub output: String,
+    #[serde(flatten)]
+    pub capture: AudioCaptureOptions,
+    /// 录制时长（秒），未设置时一直录制到任务被取消
+    pub duration_secs: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 从麦克风或系统声音录音
+    ///
+    /// 达到 `duration_secs` 或任务被取消时结束。取消时等待 FFmpeg 写完文件再返回，已录制的内容会被保留。
+    pub async fn record_audio(&self, request: RecordAudioRequest) -> Result<HelperOutput> {
+        let mut args = to_args(["-hide_banner", "-nostats"]);
+        args.extend(audio_input_args(&request.capture)?);
+        if let Some(duration) = request.duration_secs {
+            args.extend(to_args(["-t".to_string(), format!("{duration:.3}")]));
+        }
+        args.push(request.output.clone());
+
+        let job = self.register_job(&ExecuteRequest {
+            args: args.clone(),
+            job_id: request.job.job_id.clone(),
+            overwrite: request.job.overwrite,
+            tags: request.job.tags.clone(),
+            ..Default::default()
+        })?;
+
+        let mut child = self
+            .spawn_raw(ExecuteRequest {
+                args,
+                wait_for_install: request.job.wait_for_install,
+                overwrite: request.job.overwrite,
+                ..Default::default()
+            })
+            .await?;
+        let mut stdin = child.stdin();
+        let mut stderr = child.stderr().expect("stderr is piped");
+        let errors = tokio::spawn(async move {
+            let mut errors = Vec::new();
+            let _ = stderr.read_to_end(&mut errors).await;
+            String::from_utf8_lossy(&errors).to_string()
+        });
+
+        let status = tokio::select! {
+            status = child.wait() => Some(status?),
+            _ = job.cancelled() => None,
+        };
+
+        let success = match status {
+            Some(status) => status.success(),
+            None => {
+                // 发送 `q` 让 FFmpeg 写完剩余数据和文件头，超时后强制终止
+                if let Some(stdin) = stdin.as_mut() {
+                    let _ = stdin.write_all(b"q\n").await;
+                    let _ = stdin.flush().await;
+                }
+                let stopped = tokio::time::timeout(STOP_TIMEOUT, child.wait()).await;
+                if stopped.is_err() {
+                    child.kill().await?;
+                }
+                Path::new(&request.output).is_file()
+            }
+        };
+
+        if !success {
+            let errors = errors.await.unwrap_or_default();
+            return Err(Error::CommandExecution(stderr_tail(&errors)));
+        }
+
+        Ok(HelperOutput {
+            job_id: job.info().id.clone(),
+            path: request.output,
+        })
+    }
+}
+
+/// 生成采集音频的输入参数（包括 `-i`）
+pub(crate) fn audio_input_args(capture: &AudioCaptureOptions) -> Result<Vec<String>> {
+    let device = capture.device.as_deref();
+
+    #[cfg(target_os = "macos")]
+    return {
+        let default = if capture.loopback {
+            "BlackHole 2ch"
+        } else {
+            "default"
+        };
+        Ok(to_args([
+            "-f",
+            "avfoundation",
+            "-i",
+            &format!(":{}", device.unwrap_or(default)),
+        ]))
+    };
+
+    #[cfg(target_os = "windows")]
+    return match (device, capture.loopback) {
+        (Some(device), _) => Ok(to_args(["-f", "dshow", "-i", &format!("audio={device}")])),
+        (None, true) => Ok(to_args([
+            "-f",
+            "dshow",
+            "-i",
+            "audio=virtual-audio-capturer",
+        ])),
+        (None, false) => Err(Error::InvalidArgument(
+            "An audio device name is required on Windows".to_string(),
+        )),
+    };
+
+    #[cfg(target_os = "linux")]
+    return {
+        // 采集系统声音时，sink 名称需要加上 `.monitor` 才是对应的 source
+        let source = match (device, capture.loopback) {
+            (Some(device), true) if !device.ends_with(".monitor") && !device.starts_with('@') => {
+                format!("{device}.monitor")
+            }
+            (Some(device), _) => device.to_string(),
+            (None, true) => "@DEFAULT_MONITOR@".to_string(),
+            (None, false) => "default".to_string(),
+        };
+        Ok(to_args(["-f", "pulse", "-i", &source]))
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = device;
+        Err(Error::UnsupportedPlatform)
+    }
+}
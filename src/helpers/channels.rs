@@ -0,0 +1,171 @@
+//! 声道操作（拆分、缩混、交换、提取）
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{suffixed_path, to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+
+/// -3 dB 的缩混系数
+const MINUS_3DB: &str = "0.707";
+
+/// 声道操作
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ChannelOp {
+    /// 每个声道输出为单独的单声道文件（`<输出>_FL.wav` 等）
+    SplitMono,
+    /// 缩混为立体声，中置和环绕声道按 -3 dB 混入，LFE 丢弃
+    DownmixStereo,
+    /// 缩混为单声道
+    DownmixMono,
+    /// 交换左右声道
+    Swap,
+    /// 提取单个声道（如 `FL`、`FC`）为单声道
+    Extract { channel: String },
+}
+
+/// 声道操作请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelOpsRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出文件，`split-mono` 时作为文件名模板
+    pub output: String,
+    /// 声道操作
+    pub op: ChannelOp,
+    /// 输入的声道布局（如 `stereo`、`5.1`），默认 `stereo`，`downmix-stereo` 默认 `5.1`
+    pub source_layout: Option<String>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 声道操作结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelOpsResponse {
+    /// 任务 ID
+    pub job_id: String,
+    /// 生成的文件，`split-mono` 时每个声道一个
+    pub outputs: Vec<String>,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 对音频声道进行拆分、缩混、交换或提取，视频流（如果有）直接复制
+    pub async fn channel_ops(&self, request: ChannelOpsRequest) -> Result<ChannelOpsResponse> {
+        let default_layout = match request.op {
+            ChannelOp::DownmixStereo => "5.1",
+            _ => "stereo",
+        };
+        let layout = request.source_layout.as_deref().unwrap_or(default_layout);
+        let channels = layout_channels(layout).ok_or_else(|| {
+            Error::CommandExecution(format!("Unsupported channel layout `{layout}`"))
+        })?;
+
+        let mut args = to_args(["-i", &request.input]);
+        let mut outputs = Vec::new();
+
+        match &request.op {
+            ChannelOp::SplitMono => {
+                let labels: String = channels.iter().map(|c| format!("[{c}]")).collect();
+                args.extend(to_args([
+                    "-filter_complex".to_string(),
+                    format!("[0:a]channelsplit=channel_layout={layout}{labels}"),
+                ]));
+                for channel in channels {
+                    let output = suffixed_path(&request.output, channel);
+                    args.extend(to_args([
+                        "-map".to_string(),
+                        format!("[{channel}]"),
+                        output.clone(),
+                    ]));
+                    outputs.push(output);
+                }
+            }
+            op => {
+                let filter = match op {
+                    ChannelOp::DownmixStereo => downmix_stereo(channels),
+                    ChannelOp::DownmixMono => downmix_mono(channels),
+                    ChannelOp::Swap => "pan=stereo|c0=c1|c1=c0".to_string(),
+                    ChannelOp::Extract { channel } => {
+                        if !channels.contains(&channel.as_str()) {
+                            return Err(Error::InvalidArgument(format!(
+                                "channel `{channel}` not found in layout `{layout}`"
+                            )));
+                        }
+                        format!("pan=mono|c0={channel}")
+                    }
+                    ChannelOp::SplitMono => unreachable!(),
+                };
+                args.extend(to_args(["-af", &filter, "-c:v", "copy", &request.output]));
+                outputs.push(request.output.clone());
+            }
+        }
+
+        let response = self.run_helper(args, &request.job).await?;
+
+        Ok(ChannelOpsResponse {
+            job_id: response.job_id,
+            outputs,
+        })
+    }
+}
+
+/// 常见声道布局包含的声道
+fn layout_channels(layout: &str) -> Option<&'static [&'static str]> {
+    Some(match layout {
+        "mono" => &["FC"],
+        "stereo" => &["FL", "FR"],
+        "2.1" => &["FL", "FR", "LFE"],
+        "3.0" => &["FL", "FR", "FC"],
+        "quad" => &["FL", "FR", "BL", "BR"],
+        "5.0" => &["FL", "FR", "FC", "BL", "BR"],
+        "5.1" => &["FL", "FR", "FC", "LFE", "BL", "BR"],
+        "5.0(side)" => &["FL", "FR", "FC", "SL", "SR"],
+        "5.1(side)" => &["FL", "FR", "FC", "LFE", "SL", "SR"],
+        "7.1" => &["FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR"],
+        _ => return None,
+    })
+}
+
+/// 按 ITU-R BS.775 系数缩混为立体声，`<` 让 pan 归一化增益避免削波
+fn downmix_stereo(channels: &[&str]) -> String {
+    let side = |name: &str, surround: &[&str]| {
+        let mut terms = vec![name.to_string()];
+        if channels.contains(&"FC") {
+            terms.push(format!("{MINUS_3DB}*FC"));
+        }
+        for channel in surround {
+            if channels.contains(channel) {
+                terms.push(format!("{MINUS_3DB}*{channel}"));
+            }
+        }
+        terms.join("+")
+    };
+
+    if !channels.contains(&"FL") {
+        return "pan=stereo|FL=FC|FR=FC".to_string();
+    }
+
+    format!(
+        "pan=stereo|FL<{}|FR<{}",
+        side("FL", &["BL", "SL"]),
+        side("FR", &["BR", "SR"])
+    )
+}
+
+/// 缩混为单声道，左右声道等权、中置和环绕声道 -3 dB，LFE 丢弃
+fn downmix_mono(channels: &[&str]) -> String {
+    let terms: Vec<String> = channels
+        .iter()
+        .filter(|channel| **channel != "LFE")
+        .map(|channel| match *channel {
+            "FL" | "FR" => channel.to_string(),
+            other => format!("{MINUS_3DB}*{other}"),
+        })
+        .collect();
+
+    format!("pan=mono|c0<{}", terms.join("+"))
+}
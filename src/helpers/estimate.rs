@@ -0,0 +1,337 @@
+//! 编码复杂度分析和输出体积估算
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::analyze::find_value;
+use super::{output_args, to_args, HelperOptions, TranscodeOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+
+/// 复杂度分析时尝试的 CRF
+const PROBE_CRFS: [u32; 4] = [16, 22, 28, 34];
+
+/// 复杂度分析请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeComplexityRequest {
+    /// 输入文件
+    pub input: String,
+    /// 目标画质（SSIM，0-1），默认 0.98
+    pub target_ssim: Option<f64>,
+    /// 视频编码器，默认 `libx264`
+    pub video_codec: Option<String>,
+    /// 试编码使用的预设，默认 `veryfast`
+    pub preset: Option<String>,
+    /// 采样片段数，默认 3
+    pub samples: Option<u32>,
+    /// 每个片段的时长（秒），默认 4
+    pub sample_seconds: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 单个 CRF 的试编码结果
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexityProbe {
+    pub crf: u32,
+    /// 平均视频码率（kbps）
+    pub bitrate_kbps: f64,
+    /// 与原片相比的平均 SSIM
+    pub ssim: f64,
+}
+
+/// 复杂度分析结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexityReport {
+    /// 达到目标画质的最大 CRF
+    pub crf: u32,
+    /// 该 CRF 下预计的视频码率（kbps），可用于两遍编码或码率上限
+    pub bitrate_kbps: f64,
+    /// 该 CRF 下预计的 SSIM
+    pub ssim: f64,
+    /// 各 CRF 的试编码结果，按 CRF 从小到大排列
+    pub probes: Vec<ComplexityProbe>,
+}
+
+/// 体积估算请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateOutputSizeRequest {
+    /// 输入文件
+    pub input: String,
+    /// 与 `transcode` 相同的转码选项
+    #[serde(flatten)]
+    pub options: TranscodeOptions,
+    /// 采样片段数，默认 5
+    pub samples: Option<u32>,
+    /// 每个片段的时长（秒），默认 5
+    pub sample_seconds: Option<f64>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 体积估算结果
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeEstimate {
+    /// 预计大小（字节），按所有片段的平均码率推算
+    pub bytes: u64,
+    /// 下限，按码率最低的片段推算
+    pub low_bytes: u64,
+    /// 上限，按码率最高的片段推算
+    pub high_bytes: u64,
+    /// 输入时长（秒）
+    pub duration: f64,
+    /// 预计的平均码率（kbps）
+    pub bitrate_kbps: f64,
+}
+
+/// 采样片段
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    start: f64,
+    duration: f64,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 对采样片段用不同 CRF 试编码并计算 SSIM，推荐达到目标画质的 CRF 和码率
+    ///
+    /// 每次试编码单独运行，`jobId` 不会用于这些任务。
+    pub async fn analyze_complexity(
+        &self,
+        request: AnalyzeComplexityRequest,
+    ) -> Result<ComplexityReport> {
+        let target = request.target_ssim.unwrap_or(0.98).clamp(0.0, 1.0);
+        let codec = request.video_codec.as_deref().unwrap_or("libx264");
+        let preset = request.preset.as_deref().unwrap_or("veryfast");
+        let samples = self
+            .sample_windows(
+                &request.input,
+                request.samples.unwrap_or(3),
+                request.sample_seconds.unwrap_or(4.0),
+            )
+            .await;
+        let job = sub_job(&request.job);
+
+        let mut probes = Vec::with_capacity(PROBE_CRFS.len());
+        for crf in PROBE_CRFS {
+            let encode_args = to_args([
+                "-an".to_string(),
+                "-c:v".to_string(),
+                codec.to_string(),
+                "-preset".to_string(),
+                preset.to_string(),
+                "-crf".to_string(),
+                crf.to_string(),
+            ]);
+
+            let mut bytes = 0;
+            let mut seconds = 0.0;
+            let mut ssim = 0.0;
+            for sample in &samples {
+                let temp = self.temp_file("probe", "mkv")?;
+                let path = temp.path();
+                async {
+                    bytes += self
+                        .encode_sample(&request.input, *sample, &encode_args, path, &job)
+                        .await?;
+                    ssim += self
+                        .sample_ssim(&request.input, *sample, path, &job)
+                        .await?
+                        * sample.duration;
+                    seconds += sample.duration;
+                    Ok::<_, Error>(())
+                }
+                .await?;
+            }
+
+            probes.push(ComplexityProbe {
+                crf,
+                bitrate_kbps: bytes as f64 * 8.0 / seconds / 1000.0,
+                ssim: ssim / seconds,
+            });
+        }
+
+        Ok(recommend(&probes, target))
+    }
+
+    /// 按转码选项编码几个采样片段，推算完整输出的大小
+    ///
+    /// 每个片段单独运行，`jobId` 不会用于这些任务。
+    pub async fn estimate_output_size(
+        &self,
+        request: EstimateOutputSizeRequest,
+    ) -> Result<SizeEstimate> {
+        let duration = self
+            .media_duration(&request.input)
+            .await
+            .ok_or_else(|| Error::CommandExecution("Unable to read input duration".to_string()))?;
+        let samples = self
+            .sample_windows(
+                &request.input,
+                request.samples.unwrap_or(5),
+                request.sample_seconds.unwrap_or(5.0),
+            )
+            .await;
+        let job = sub_job(&request.job);
+        let (options, crop) = self
+            .prepare_transcode(&request.input, &request.options, &job)
+            .await?;
+        let encode_args = output_args(&options, crop);
+
+        let mut rates = Vec::with_capacity(samples.len());
+        let mut bytes = 0;
+        let mut seconds = 0.0;
+        for sample in &samples {
+            let temp = self.temp_file("probe", "mkv")?;
+            let size = self
+                .encode_sample(&request.input, *sample, &encode_args, temp.path(), &job)
+                .await?;
+
+            rates.push(size as f64 / sample.duration);
+            bytes += size;
+            seconds += sample.duration;
+        }
+
+        let rate = bytes as f64 / seconds;
+        let low = rates.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = rates.iter().copied().fold(0.0, f64::max);
+
+        Ok(SizeEstimate {
+            bytes: (rate * duration) as u64,
+            low_bytes: (low * duration) as u64,
+            high_bytes: (high * duration) as u64,
+            duration,
+            bitrate_kbps: rate * 8.0 / 1000.0,
+        })
+    }
+
+    /// 在整个文件中均匀选取采样片段，时长未知或过短时只取开头
+    async fn sample_windows(&self, input: &str, count: u32, seconds: f64) -> Vec<Sample> {
+        let seconds = seconds.max(1.0);
+        let count = count.max(1);
+
+        match self.media_duration(input).await {
+            Some(duration) if duration > seconds * count as f64 => (0..count)
+                .map(|i| Sample {
+                    start: duration * (i as f64 + 0.5) / count as f64 - seconds / 2.0,
+                    duration: seconds,
+                })
+                .collect(),
+            Some(duration) => vec![Sample {
+                start: 0.0,
+                duration: duration.min(seconds * count as f64),
+            }],
+            None => vec![Sample {
+                start: 0.0,
+                duration: seconds,
+            }],
+        }
+    }
+
+    /// 编码一个采样片段，返回输出文件的大小（字节）
+    async fn encode_sample(
+        &self,
+        input: &str,
+        sample: Sample,
+        encode_args: &[String],
+        path: &Path,
+        job: &HelperOptions,
+    ) -> Result<u64> {
+        let mut args = to_args([
+            "-hide_banner".to_string(),
+            "-ss".to_string(),
+            sample.start.to_string(),
+            "-t".to_string(),
+            sample.duration.to_string(),
+            "-i".to_string(),
+            input.to_string(),
+        ]);
+        args.extend(encode_args.iter().cloned());
+        args.push(path.to_string_lossy().to_string());
+
+        self.run_helper(args, job).await?;
+        Ok(std::fs::metadata(path)?.len())
+    }
+
+    /// 计算编码后的片段与原片对应位置的 SSIM
+    async fn sample_ssim(
+        &self,
+        input: &str,
+        sample: Sample,
+        encoded: &Path,
+        job: &HelperOptions,
+    ) -> Result<f64> {
+        let args = to_args([
+            "-hide_banner".to_string(),
+            "-i".to_string(),
+            encoded.to_string_lossy().to_string(),
+            "-ss".to_string(),
+            sample.start.to_string(),
+            "-t".to_string(),
+            sample.duration.to_string(),
+            "-i".to_string(),
+            input.to_string(),
+            "-lavfi".to_string(),
+            "[0:v][1:v]ssim".to_string(),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ]);
+
+        let response = self.run_helper(args, job).await?;
+        response
+            .stderr
+            .lines()
+            .filter(|line| line.contains("SSIM"))
+            .find_map(|line| find_value(line, "All"))
+            .ok_or_else(|| Error::CommandExecution("ssim produced no result".to_string()))
+    }
+}
+
+/// 内部任务只继承等待下载的设置
+fn sub_job(job: &HelperOptions) -> HelperOptions {
+    HelperOptions {
+        wait_for_install: job.wait_for_install,
+        ..Default::default()
+    }
+}
+
+/// 在相邻的两个试编码结果之间插值，找出刚好达到目标 SSIM 的 CRF
+///
+/// 码率按对数插值，CRF 每增加 6 码率大约减半。
+fn recommend(probes: &[ComplexityProbe], target: f64) -> ComplexityReport {
+    let report = |crf: f64, bitrate_kbps: f64, ssim: f64| ComplexityReport {
+        crf: crf.floor() as u32,
+        bitrate_kbps,
+        ssim,
+        probes: probes.to_vec(),
+    };
+
+    let first = probes[0];
+    if first.ssim < target {
+        return report(first.crf as f64, first.bitrate_kbps, first.ssim);
+    }
+
+    for pair in probes.windows(2) {
+        let (low, high) = (pair[0], pair[1]);
+        if high.ssim >= target {
+            continue;
+        }
+
+        let t = ((low.ssim - target) / (low.ssim - high.ssim)).clamp(0.0, 1.0);
+        let crf = low.crf as f64 + t * (high.crf as f64 - low.crf as f64);
+        let bitrate = (low.bitrate_kbps.max(f64::EPSILON).ln() * (1.0 - t)
+            + high.bitrate_kbps.max(f64::EPSILON).ln() * t)
+            .exp();
+        return report(crf, bitrate, target);
+    }
+
+    let last = probes[probes.len() - 1];
+    report(last.crf as f64, last.bitrate_kbps, last.ssim)
+}
@@ -0,0 +1,519 @@
+//! 内存中的图像帧与视频之间的转换
+
+use std::fs;
+use std::path::Path;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::Runtime;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::ChildStdout;
+use tokio::task::JoinHandle;
+
+use super::{stderr_tail, to_args, HelperOptions, HelperOutput, ProbeRequest};
+use crate::child::FfmpegChild;
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::ExecuteRequest;
+
+/// 通过 Channel 发送的每一帧前附加的头部长度：帧序号（u64）、宽度（u32）、高度（u32），均为小端序
+const FRAME_HEADER_LEN: usize = 16;
+
+/// 一帧图像的数据，可以是字节数组，也可以是 base64 字符串（允许带 `data:image/png;base64,` 前缀）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum FrameData {
+    Bytes(Vec<u8>),
+    Base64(String),
+}
+
+impl FrameData {
+    fn decode(self) -> Result<Vec<u8>> {
+        match self {
+            FrameData::Bytes(bytes) => Ok(bytes),
+            FrameData::Base64(text) => {
+                let data = match text.split_once(',') {
+                    Some((prefix, data)) if prefix.starts_with("data:") => data,
+                    _ => &text,
+                };
+                base64::engine::general_purpose::STANDARD
+                    .decode(data.trim())
+                    .map_err(|e| Error::InvalidArgument(format!("Invalid base64 frame: {e}")))
+            }
+        }
+    }
+}
+
+/// 图像帧编码选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodeFromBytesOptions {
+    /// 帧率，默认 10
+    pub fps: Option<f64>,
+    /// 输出格式（`-f`），未设置时由扩展名决定
+    pub format: Option<String>,
+}
+
+/// 图像帧编码请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodeFromBytesRequest {
+    /// 按顺序排列的图像帧（PNG、JPEG 等 FFmpeg 能识别的格式）
+    pub frames: Vec<FrameData>,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: EncodeFromBytesOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 将内存中的图像帧通过标准输入（`image2pipe`）编码为 GIF 或视频，不写入临时图片
+    ///
+    /// GIF 会先生成调色板以保证画质，其他格式输出 `yuv420p`，并将尺寸裁剪为偶数以兼容 H.264。
+    /// 任务出现在 `list_jobs` 中并可以通过 `cancel` 取消，失败或取消时删除写了一半的输出文件。
+    pub async fn encode_from_bytes(&self, request: EncodeFromBytesRequest) -> Result<HelperOutput> {
+        if request.frames.is_empty() {
+            return Err(Error::InvalidArgument("No frames to encode".to_string()));
+        }
+        let frames = request
+            .frames
+            .into_iter()
+            .map(FrameData::decode)
+            .collect::<Result<Vec<_>>>()?;
+
+        let fps = request.options.fps.unwrap_or(10.0);
+        if fps.is_nan() || fps <= 0.0 {
+            return Err(Error::InvalidArgument("`fps` must be positive".to_string()));
+        }
+
+        let format = request.options.format.clone().or_else(|| {
+            Path::new(&request.output)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+        });
+        let mut args = to_args([
+            "-hide_banner",
+            "-nostats",
+            "-f",
+            "image2pipe",
+            "-framerate",
+            &fps.to_string(),
+            "-i",
+            "pipe:0",
+        ]);
+        match format.as_deref() {
+            Some("gif") => args.extend(to_args([
+                "-filter_complex",
+                "split[a][b];[a]palettegen[p];[b][p]paletteuse",
+                "-loop",
+                "0",
+            ])),
+            Some("apng" | "webp") => {}
+            _ => args.extend(to_args([
+                "-vf",
+                "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+                "-pix_fmt",
+                "yuv420p",
+            ])),
+        }
+        if let Some(format) = &request.options.format {
+            args.extend(to_args(["-f", format]));
+        }
+        args.push(request.output.clone());
+
+        let job = self.register_job(&ExecuteRequest {
+            args: args.clone(),
+            job_id: request.job.job_id.clone(),
+            overwrite: request.job.overwrite,
+            tags: request.job.tags.clone(),
+            ..Default::default()
+        })?;
+
+        let existed = Path::new(&request.output).exists();
+        let mut child = self
+            .spawn_raw(ExecuteRequest {
+                args,
+                wait_for_install: request.job.wait_for_install,
+                overwrite: request.job.overwrite,
+                ..Default::default()
+            })
+            .await?;
+        let mut stdin = child.stdin().expect("stdin is piped");
+        let mut stderr = child.stderr().expect("stderr is piped");
+
+        // FFmpeg 提前退出时写入会失败，此时以退出状态为准
+        let write = async move {
+            for frame in &frames {
+                if stdin.write_all(frame).await.is_err() {
+                    break;
+                }
+            }
+        };
+        let mut errors = Vec::new();
+        let run = async {
+            let (_, read) = tokio::join!(write, stderr.read_to_end(&mut errors));
+            read?;
+            child.wait().await
+        };
+
+        let status = tokio::select! {
+            status = run => Some(status?),
+            _ = job.cancelled() => None,
+        };
+
+        let result = match status {
+            Some(status) if status.success() => Ok(HelperOutput {
+                job_id: job.info().id.clone(),
+                path: request.output.clone(),
+            }),
+            Some(_) => Err(Error::CommandExecution(stderr_tail(
+                &String::from_utf8_lossy(&errors),
+            ))),
+            None => {
+                child.kill().await?;
+                Err(Error::Cancelled)
+            }
+        };
+
+        if result.is_err() && !existed {
+            let _ = fs::remove_file(&request.output);
+        }
+        result
+    }
+}
+
+/// 解码输出的原始像素格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RawPixelFormat {
+    #[default]
+    Rgb24,
+    Bgr24,
+    Rgba,
+    Bgra,
+    Gray,
+    Yuv420p,
+    Nv12,
+}
+
+impl RawPixelFormat {
+    fn as_str(self) -> &'static str {
+        match self {
+            RawPixelFormat::Rgb24 => "rgb24",
+            RawPixelFormat::Bgr24 => "bgr24",
+            RawPixelFormat::Rgba => "rgba",
+            RawPixelFormat::Bgra => "bgra",
+            RawPixelFormat::Gray => "gray",
+            RawPixelFormat::Yuv420p => "yuv420p",
+            RawPixelFormat::Nv12 => "nv12",
+        }
+    }
+
+    /// 一帧的字节数，YUV 4:2:0 格式要求宽高为偶数
+    pub fn frame_size(self, width: u32, height: u32) -> usize {
+        let pixels = width as usize * height as usize;
+        match self {
+            RawPixelFormat::Rgb24 | RawPixelFormat::Bgr24 => pixels * 3,
+            RawPixelFormat::Rgba | RawPixelFormat::Bgra => pixels * 4,
+            RawPixelFormat::Gray => pixels,
+            RawPixelFormat::Yuv420p | RawPixelFormat::Nv12 => pixels * 3 / 2,
+        }
+    }
+
+    fn is_subsampled(self) -> bool {
+        matches!(self, RawPixelFormat::Yuv420p | RawPixelFormat::Nv12)
+    }
+}
+
+/// 帧解码选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodeFramesOptions {
+    /// 输出帧率，未设置时输出每一帧
+    pub fps: Option<f64>,
+    /// 像素格式，默认 `rgb24`
+    pub pix_fmt: Option<RawPixelFormat>,
+    /// 输出宽度，只设置宽高之一时按比例计算另一个，都未设置时使用原始尺寸
+    pub width: Option<u32>,
+    /// 输出高度
+    pub height: Option<u32>,
+}
+
+/// 帧解码请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodeFramesRequest {
+    /// 输入文件
+    pub input: String,
+    #[serde(flatten)]
+    pub options: DecodeFramesOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 解码出的一帧
+#[derive(Debug, Clone)]
+pub struct DecodedFrame {
+    /// 帧序号，从 0 开始
+    pub index: u64,
+    /// 设置了 `fps` 时为该帧的时间（秒）
+    pub time: Option<f64>,
+    pub width: u32,
+    pub height: u32,
+    pub pix_fmt: RawPixelFormat,
+    /// 紧密排列的像素数据（没有行填充）
+    pub data: Vec<u8>,
+}
+
+/// 帧解码结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodeFramesSummary {
+    /// 任务 ID
+    pub job_id: String,
+    /// 发送的帧数
+    pub frames: u64,
+    pub width: u32,
+    pub height: u32,
+    pub pix_fmt: RawPixelFormat,
+    /// 每帧的字节数（不包括头部）
+    pub frame_size: usize,
+}
+
+/// 按帧读取 FFmpeg 解码输出的原始像素
+///
+/// 被释放时终止 FFmpeg 进程。
+pub struct FrameReader {
+    child: FfmpegChild,
+    stdout: ChildStdout,
+    errors: Option<JoinHandle<String>>,
+    fps: Option<f64>,
+    width: u32,
+    height: u32,
+    pix_fmt: RawPixelFormat,
+    index: u64,
+}
+
+impl FrameReader {
+    /// 输出宽度
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// 输出高度
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// 像素格式
+    pub fn pix_fmt(&self) -> RawPixelFormat {
+        self.pix_fmt
+    }
+
+    /// 每帧的字节数
+    pub fn frame_size(&self) -> usize {
+        self.pix_fmt.frame_size(self.width, self.height)
+    }
+
+    /// 读取下一帧，所有帧读完后返回 None，FFmpeg 出错时返回 [`Error::CommandExecution`]
+    pub async fn next_frame(&mut self) -> Result<Option<DecodedFrame>> {
+        let mut data = vec![0u8; self.frame_size()];
+        let mut filled = 0;
+        while filled < data.len() {
+            match self.stdout.read(&mut data[filled..]).await? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+
+        if filled < data.len() {
+            let status = self.child.wait().await?;
+            let errors = match self.errors.take() {
+                Some(errors) => errors.await.unwrap_or_default(),
+                None => String::new(),
+            };
+            if !status.success() {
+                return Err(Error::CommandExecution(stderr_tail(&errors)));
+            }
+            return Ok(None);
+        }
+
+        let index = self.index;
+        self.index += 1;
+        Ok(Some(DecodedFrame {
+            index,
+            time: self.fps.map(|fps| index as f64 / fps),
+            width: self.width,
+            height: self.height,
+            pix_fmt: self.pix_fmt,
+            data,
+        }))
+    }
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 解码视频并按帧返回原始 RGB / YUV 像素，供 Rust 端的计算机视觉或机器学习代码直接使用
+    ///
+    /// 尺寸按 ffprobe 报告的原始尺寸计算，因此不应用旋转元数据（`-noautorotate`）。
+    /// 进程不经过任务队列，由返回的 [`FrameReader`] 管理。
+    pub async fn decode_frames(&self, request: DecodeFramesRequest) -> Result<FrameReader> {
+        let options = &request.options;
+        if options.fps.is_some_and(|fps| fps.is_nan() || fps <= 0.0) {
+            return Err(Error::InvalidArgument("`fps` must be positive".to_string()));
+        }
+        let pix_fmt = options.pix_fmt.unwrap_or_default();
+        let (width, height) = self
+            .decode_size(&request.input, options.width, options.height, pix_fmt)
+            .await?;
+
+        let mut filters = Vec::new();
+        if let Some(fps) = options.fps {
+            filters.push(format!("fps={fps}"));
+        }
+        filters.push(format!("scale={width}:{height}"));
+
+        let args = to_args([
+            "-hide_banner",
+            "-nostats",
+            "-loglevel",
+            "error",
+            "-noautorotate",
+            "-i",
+            &request.input,
+            "-map",
+            "0:v:0",
+            "-vf",
+            &filters.join(","),
+            "-pix_fmt",
+            pix_fmt.as_str(),
+            "-f",
+            "rawvideo",
+            "pipe:1",
+        ]);
+
+        let mut child = self
+            .spawn_raw(ExecuteRequest {
+                args,
+                wait_for_install: request.job.wait_for_install,
+                ..Default::default()
+            })
+            .await?;
+        drop(child.stdin());
+        let stdout = child.stdout().expect("stdout is piped");
+        let mut stderr = child.stderr().expect("stderr is piped");
+        let errors = tokio::spawn(async move {
+            let mut errors = Vec::new();
+            let _ = stderr.read_to_end(&mut errors).await;
+            String::from_utf8_lossy(&errors).to_string()
+        });
+
+        Ok(FrameReader {
+            child,
+            stdout,
+            errors: Some(errors),
+            fps: options.fps,
+            width,
+            height,
+            pix_fmt,
+            index: 0,
+        })
+    }
+
+    /// 解码视频并通过 Channel 逐帧发送给前端，供 `decode_frames` 命令使用
+    ///
+    /// 每条消息为 16 字节的头部加上一帧像素。任务出现在 `list_jobs` 中，可以通过 `cancel` 终止。
+    pub(crate) async fn send_frames(
+        &self,
+        request: DecodeFramesRequest,
+        channel: Channel<InvokeResponseBody>,
+    ) -> Result<DecodeFramesSummary> {
+        let job = self.register_job(&ExecuteRequest {
+            args: to_args(["-i", &request.input]),
+            job_id: request.job.job_id.clone(),
+            tags: request.job.tags.clone(),
+            ..Default::default()
+        })?;
+        let mut reader = self.decode_frames(request).await?;
+        let mut frames = 0;
+
+        loop {
+            let frame = tokio::select! {
+                frame = reader.next_frame() => frame?,
+                _ = job.cancelled() => return Err(Error::Cancelled),
+            };
+            let Some(frame) = frame else {
+                break;
+            };
+
+            let mut message = Vec::with_capacity(FRAME_HEADER_LEN + frame.data.len());
+            message.extend_from_slice(&frame.index.to_le_bytes());
+            message.extend_from_slice(&frame.width.to_le_bytes());
+            message.extend_from_slice(&frame.height.to_le_bytes());
+            message.extend_from_slice(&frame.data);
+            channel
+                .send(InvokeResponseBody::Raw(message))
+                .map_err(|e| Error::CommandExecution(e.to_string()))?;
+            frames += 1;
+        }
+
+        Ok(DecodeFramesSummary {
+            job_id: job.info().id.clone(),
+            frames,
+            width: reader.width(),
+            height: reader.height(),
+            pix_fmt: reader.pix_fmt(),
+            frame_size: reader.frame_size(),
+        })
+    }
+
+    /// 计算输出尺寸，只设置宽高之一时按原始比例计算另一个
+    async fn decode_size(
+        &self,
+        input: &str,
+        width: Option<u32>,
+        height: Option<u32>,
+        pix_fmt: RawPixelFormat,
+    ) -> Result<(u32, u32)> {
+        let (width, height) = match (width, height) {
+            (Some(width), Some(height)) => (width, height),
+            (width, height) => {
+                let probe = self
+                    .probe(ProbeRequest {
+                        input: input.to_string(),
+                    })
+                    .await?;
+                let video = probe
+                    .video()
+                    .ok_or_else(|| Error::InvalidArgument(format!("No video stream in {input}")))?;
+                let (Some(source_width), Some(source_height)) = (video.width, video.height) else {
+                    return Err(Error::InvalidArgument(format!(
+                        "Unable to read video size of {input}"
+                    )));
+                };
+                let scale = |value: u32, from: u32, to: u32| {
+                    (value as f64 * to as f64 / from as f64).round() as u32
+                };
+                match (width, height) {
+                    (Some(width), None) => (width, scale(width, source_width, source_height)),
+                    (None, Some(height)) => (scale(height, source_height, source_width), height),
+                    _ => (source_width, source_height),
+                }
+            }
+        };
+
+        let (width, height) = if pix_fmt.is_subsampled() {
+            (width & !1, height & !1)
+        } else {
+            (width, height)
+        };
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid output size {width}x{height}"
+            )));
+        }
+        Ok((width, height))
+    }
+}
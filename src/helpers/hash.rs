@@ -0,0 +1,105 @@
+//! 媒体内容哈希
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+
+/// 哈希算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    #[default]
+    Sha256,
+    Sha512,
+    Crc32,
+}
+
+impl HashAlgorithm {
+    /// hash 封装器使用的名称
+    fn ffmpeg_name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha160",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+            HashAlgorithm::Crc32 => "crc32",
+        }
+    }
+}
+
+/// 参与计算的流
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashStreams {
+    /// FFmpeg 默认选择的视频流和音频流
+    #[default]
+    All,
+    /// 只计算第一个视频流
+    Video,
+    /// 只计算第一个音频流
+    Audio,
+}
+
+/// 内容哈希请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaHashRequest {
+    /// 输入文件
+    pub input: String,
+    /// 哈希算法，默认 `sha256`
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+    /// 参与计算的流，默认视频和音频
+    #[serde(default)]
+    pub streams: HashStreams,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 内容哈希结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaHash {
+    pub algorithm: HashAlgorithm,
+    /// 十六进制哈希值
+    pub hash: String,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 对解码后的画面和声音计算哈希，与封装格式无关
+    ///
+    /// 同一内容重新封装为不同格式（如 MKV 和 MP4）时哈希相同，可用于去重。
+    pub async fn media_hash(&self, request: MediaHashRequest) -> Result<MediaHash> {
+        let mut args = to_args(["-hide_banner", "-i", &request.input]);
+        match request.streams {
+            HashStreams::All => {}
+            HashStreams::Video => args.extend(to_args(["-map", "0:v:0"])),
+            HashStreams::Audio => args.extend(to_args(["-map", "0:a:0"])),
+        }
+        args.extend(to_args([
+            "-f",
+            "hash",
+            "-hash",
+            request.algorithm.ffmpeg_name(),
+            "-",
+        ]));
+
+        let response = self.run_helper(args, &request.job).await?;
+        let hash = response
+            .stdout
+            .lines()
+            .find_map(|line| line.trim().split_once('='))
+            .map(|(_, hash)| hash.to_string())
+            .ok_or_else(|| Error::CommandExecution("hash muxer produced no result".to_string()))?;
+
+        Ok(MediaHash {
+            algorithm: request.algorithm,
+            hash,
+        })
+    }
+}
@@ -0,0 +1,230 @@
+//! 输入电平表
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader, Lines};
+use tokio::process::ChildStdout;
+
+use super::capture::{audio_input_args, AudioCaptureOptions};
+use super::{stderr_tail, to_args, HelperOptions};
+use crate::child::FfmpegChild;
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::events;
+use crate::jobs::JobGuard;
+use crate::models::{ExecuteRequest, JobTags};
+use crate::FfmpegExt;
+
+/// 电平计算使用的采样率
+const SAMPLE_RATE: u32 = 48_000;
+
+/// 静音时报告的电平（dBFS）
+const SILENCE_DB: f64 = -100.0;
+
+/// 启动时等待第一个电平的时间，期间 FFmpeg 退出视为无法打开设备
+const START_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 电平表请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelMeterRequest {
+    #[serde(flatten)]
+    pub capture: AudioCaptureOptions,
+    /// 电平事件的间隔（毫秒），默认 100
+    pub interval_ms: Option<u32>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 已启动的电平表
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelMeter {
+    /// 任务 ID，通过 `cancel` 停止
+    pub job_id: String,
+}
+
+/// 输入电平（`use-ffmpeg://audio-level` 事件）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevel {
+    /// 任务 ID
+    pub job_id: String,
+    /// 本次间隔内的 RMS 电平（dBFS），静音时为 -100
+    pub rms_db: f64,
+    /// 本次间隔内的峰值电平（dBFS），静音时为 -100
+    pub peak_db: f64,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 电平表停止（`use-ffmpeg://level-meter-stopped` 事件）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelMeterStopped {
+    /// 任务 ID
+    pub job_id: String,
+    /// 设备断开等原因导致 FFmpeg 退出时的错误信息，被取消时为 None
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 从输入设备采集音频并持续发送 `use-ffmpeg://audio-level` 事件，用于录制前的输入电平显示
+    ///
+    /// 确认设备可以打开后立即返回，电平表在后台运行，直到通过 `cancel` 取消任务。
+    /// 电平由 `astats` 滤镜计算，不写入任何文件。
+    pub async fn start_level_meter(&self, request: LevelMeterRequest) -> Result<LevelMeter> {
+        let interval_ms = request.interval_ms.unwrap_or(100).clamp(20, 5000);
+        let samples = SAMPLE_RATE * interval_ms / 1000;
+
+        let mut args = to_args(["-hide_banner", "-nostats", "-loglevel", "error"]);
+        args.extend(audio_input_args(&request.capture)?);
+        args.extend(to_args([
+            "-af",
+            &format!(
+                "aresample={SAMPLE_RATE},asetnsamples=n={samples}:p=0,\
+                 astats=metadata=1:reset=1:measure_perchannel=none:measure_overall=Peak_level+RMS_level,\
+                 ametadata=mode=print:file=-:direct=1"
+            ),
+            "-f",
+            "null",
+            "-",
+        ]));
+
+        let job = self.register_job(&ExecuteRequest {
+            args: args.clone(),
+            job_id: request.job.job_id.clone(),
+            tags: request.job.tags.clone(),
+            ..Default::default()
+        })?;
+
+        let mut child = self
+            .spawn_raw(ExecuteRequest {
+                args,
+                wait_for_install: request.job.wait_for_install,
+                ..Default::default()
+            })
+            .await?;
+        drop(child.stdin());
+        let mut lines = BufReader::new(child.stdout().expect("stdout is piped")).lines();
+        let mut stderr = child.stderr().expect("stderr is piped");
+        let errors = tokio::spawn(async move {
+            let mut errors = Vec::new();
+            let _ = stderr.read_to_end(&mut errors).await;
+            String::from_utf8_lossy(&errors).to_string()
+        });
+
+        // 设备无法打开时 FFmpeg 会立即退出，此时直接返回错误
+        let first = match tokio::time::timeout(START_TIMEOUT, lines.next_line()).await {
+            Ok(Ok(Some(line))) => Some(line),
+            Ok(_) => {
+                let _ = child.wait().await;
+                let errors = errors.await.unwrap_or_default();
+                return Err(Error::CommandExecution(stderr_tail(&errors)));
+            }
+            Err(_) => None,
+        };
+
+        let meter = LevelMeter {
+            job_id: job.info().id.clone(),
+        };
+
+        let app = self.app().clone();
+        tauri::async_runtime::spawn(async move {
+            app.ffmpeg()
+                .run_level_meter(job, child, lines, first, errors)
+                .await;
+        });
+
+        Ok(meter)
+    }
+
+    /// 在后台读取电平直到任务被取消或 FFmpeg 退出
+    async fn run_level_meter(
+        &self,
+        job: JobGuard,
+        mut child: FfmpegChild,
+        mut lines: Lines<BufReader<ChildStdout>>,
+        first: Option<String>,
+        errors: tokio::task::JoinHandle<String>,
+    ) {
+        let mut parser = LevelParser::default();
+        if let Some(line) = first {
+            self.feed_level(&job, &mut parser, &line);
+        }
+
+        let cancelled = loop {
+            tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => self.feed_level(&job, &mut parser, &line),
+                    _ => break false,
+                },
+                _ = job.cancelled() => break true,
+            }
+        };
+
+        let error = if cancelled {
+            let _ = child.kill().await;
+            None
+        } else {
+            let _ = child.wait().await;
+            Some(stderr_tail(&errors.await.unwrap_or_default()))
+        };
+
+        let stopped = LevelMeterStopped {
+            job_id: job.info().id.clone(),
+            error,
+            tags: job.info().tags.clone(),
+        };
+        events::emit(self.app(), events::LEVEL_METER_STOPPED, &stopped);
+    }
+
+    fn feed_level(&self, job: &JobGuard, parser: &mut LevelParser, line: &str) {
+        if let Some((rms_db, peak_db)) = parser.feed(line) {
+            let level = AudioLevel {
+                job_id: job.info().id.clone(),
+                rms_db,
+                peak_db,
+                tags: job.info().tags.clone(),
+            };
+            events::emit(self.app(), events::AUDIO_LEVEL, &level);
+        }
+    }
+}
+
+/// 解析 `ametadata` 输出的 `astats` 电平，RMS 和峰值都读到后返回一组
+#[derive(Default)]
+struct LevelParser {
+    rms: Option<f64>,
+    peak: Option<f64>,
+}
+
+impl LevelParser {
+    fn feed(&mut self, line: &str) -> Option<(f64, f64)> {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value
+            .parse::<f64>()
+            .ok()
+            .filter(|value| !value.is_nan())
+            .map_or(SILENCE_DB, |value| value.max(SILENCE_DB));
+
+        match key {
+            "lavfi.astats.Overall.RMS_level" => self.rms = Some(value),
+            "lavfi.astats.Overall.Peak_level" => self.peak = Some(value),
+            _ => return None,
+        }
+
+        match (self.rms, self.peak) {
+            (Some(rms), Some(peak)) => {
+                self.rms = None;
+                self.peak = None;
+                Some((rms, peak))
+            }
+            _ => None,
+        }
+    }
+}
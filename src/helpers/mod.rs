@@ -0,0 +1,248 @@
+//! # Helpers
+//!
+//! 常用 FFmpeg 操作的封装。参数由插件生成，任务仍通过 `execute` 运行，
+//! 因此同样支持排队、进度事件和取消。
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use crate::args::{apply_map_metadata, apply_protocol_whitelist, first_input, output_paths};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::{ExecuteRequest, ExecuteResponse, JobTags, OverwritePolicy};
+use crate::progress::parse_time;
+
+mod analyze;
+mod audio;
+mod benchmark;
+mod capabilities;
+mod capture;
+mod channels;
+mod estimate;
+mod frames;
+mod hash;
+mod meter;
+mod pipeline;
+mod platforms;
+mod probe;
+mod profiles;
+mod proxies;
+mod record;
+mod remux;
+mod spherical;
+mod sync;
+mod text;
+mod thumbnail;
+mod transcode;
+mod verify;
+mod visualize;
+
+pub use analyze::*;
+pub use audio::*;
+pub use benchmark::*;
+pub use capabilities::*;
+pub use capture::*;
+pub use channels::*;
+pub use estimate::*;
+pub use frames::*;
+pub use hash::*;
+pub use meter::*;
+pub use pipeline::*;
+pub use platforms::*;
+pub use probe::*;
+pub use profiles::*;
+pub use proxies::*;
+pub use record::*;
+pub use remux::*;
+pub use spherical::*;
+pub use sync::*;
+pub use text::*;
+pub use thumbnail::*;
+pub use transcode::*;
+pub use verify::*;
+pub use visualize::*;
+
+/// 辅助函数共用的任务选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HelperOptions {
+    /// 可选的任务 ID，未提供时自动生成
+    pub job_id: Option<String>,
+    /// 输出文件已存在时的处理策略
+    pub overwrite: Option<OverwritePolicy>,
+    /// 下载正在进行时是否等待其完成
+    #[serde(default)]
+    pub wait_for_install: bool,
+    /// 复制第一个输入的容器元数据（包括 `creation_time`），并将输出文件的修改时间设为与输入相同
+    #[serde(default)]
+    pub preserve_timestamps: bool,
+    /// 所属批次
+    pub batch_id: Option<String>,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 生成单个文件的辅助函数的结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HelperOutput {
+    /// 任务 ID
+    pub job_id: String,
+    /// 输出文件路径
+    pub path: String,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 运行辅助函数生成的命令，FFmpeg 以非零状态退出时返回 [`Error::CommandExecution`]
+    pub(crate) async fn run_helper(
+        &self,
+        args: Vec<String>,
+        options: &HelperOptions,
+    ) -> Result<ExecuteResponse> {
+        let args = if options.preserve_timestamps {
+            apply_map_metadata(&args)
+        } else {
+            args
+        };
+        let outputs = output_paths(&args);
+        let modified = first_input(&args)
+            .filter(|_| options.preserve_timestamps)
+            .and_then(|input| fs::metadata(input).and_then(|m| m.modified()).ok());
+
+        let response = self
+            .execute(ExecuteRequest {
+                args,
+                job_id: options.job_id.clone(),
+                wait_for_install: options.wait_for_install,
+                cleanup_outputs_on_failure: true,
+                overwrite: options.overwrite,
+                batch_id: options.batch_id.clone(),
+                tags: options.tags.clone(),
+                ..Default::default()
+            })
+            .await?;
+
+        if !response.success {
+            return Err(Error::CommandExecution(stderr_tail(&response.stderr)));
+        }
+
+        if let Some(modified) = modified {
+            for output in outputs {
+                let _ = fs::File::options()
+                    .write(true)
+                    .open(output)
+                    .and_then(|file| file.set_modified(modified));
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 判断 FFmpeg 是否以指定的 `--enable-*` 选项编译（如 `libsoxr`）
+    pub(crate) async fn has_build_option(&self, option: &str) -> bool {
+        let Ok(path) = self.get_ffmpeg_executable_path() else {
+            return false;
+        };
+        let Ok(output) = tokio::process::Command::new(path)
+            .args(["-hide_banner", "-buildconf"])
+            .output()
+            .await
+        else {
+            return false;
+        };
+
+        let flag = format!("--enable-{option}");
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .any(|arg| arg == flag)
+    }
+
+    /// 读取输入的时长（秒），从 `ffmpeg -i` 输出的 `Duration:` 行解析，未知时为 None
+    ///
+    /// 输入使用了 `allowedProtocols` 之外的协议时同样为 None，之后的任务会因此被拒绝。
+    pub(crate) async fn media_duration(&self, input: &str) -> Option<f64> {
+        let input = self.input_args(input).ok()?;
+        let path = self.get_ffmpeg_executable_path().ok()?;
+        let output = tokio::process::Command::new(path)
+            .arg("-hide_banner")
+            .args(input)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await
+            .ok()?;
+
+        String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Duration:"))
+            .and_then(|rest| rest.split(',').next())
+            .and_then(|duration| parse_time(duration.trim()))
+            .filter(|duration| *duration > 0.0)
+    }
+
+    /// 辅助函数在任务之外直接读取输入时使用的 `-i <input>`，按 `allowedProtocols` 检查并注入
+    /// `-protocol_whitelist`
+    pub(crate) fn input_args(&self, input: &str) -> Result<Vec<String>> {
+        apply_protocol_whitelist(
+            &to_args(["-i", input]),
+            self.config().allowed_protocols.as_deref(),
+        )
+    }
+}
+
+/// stderr 的最后一行非空内容，通常是 FFmpeg 的错误原因
+pub(crate) fn stderr_tail(stderr: &str) -> String {
+    stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("FFmpeg exited with an error")
+        .to_string()
+}
+
+/// 转义滤镜选项值，结果可以直接放入 `-vf` / `-af` / `-filter_complex`
+///
+/// 先按选项值规则转义 `\`、`'`、`:`，再按滤镜图规则转义 `\`、`'`、`[`、`]`、`,`、`;`。
+pub(crate) fn escape_filter_value(value: &str) -> String {
+    let escape = |value: &str, special: &[char]| {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    };
+
+    let option = escape(value, &['\\', '\'', ':']);
+    escape(&option, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// 在文件名（扩展名之前）加上后缀，如 `voice.wav` + `FL` -> `voice_FL.wav`
+pub(crate) fn suffixed_path(path: &str, suffix: &str) -> String {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}_{suffix}.{}", ext.to_string_lossy()),
+        None => format!("{stem}_{suffix}"),
+    };
+    path.with_file_name(name).to_string_lossy().to_string()
+}
+
+/// 将参数列表转换为 `Vec<String>`
+pub(crate) fn to_args<I, S>(args: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    args.into_iter().map(Into::into).collect()
+}
@@ -0,0 +1,205 @@
+//! 多个 FFmpeg 进程组成的管道
+
+use std::process::Stdio;
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+use tokio::io::AsyncReadExt;
+use tokio_util::sync::CancellationToken;
+
+use super::{stderr_tail, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::events;
+use crate::models::ExecuteRequest;
+use crate::progress::ProgressParser;
+
+/// 管道请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRequest {
+    /// 按顺序连接的各个阶段，前一阶段的标准输出（`pipe:1`）作为后一阶段的标准输入（`pipe:0`）
+    pub stages: Vec<ExecuteRequest>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 管道结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineOutput {
+    /// 任务 ID
+    pub job_id: String,
+    /// 各阶段的退出码，被终止的阶段为 None
+    pub exit_codes: Vec<Option<i32>>,
+}
+
+/// 一个阶段的结束状态
+struct StageExit {
+    index: usize,
+    /// 退出码，被信号终止时为 None
+    code: Option<i32>,
+    success: bool,
+    /// 是否因其他阶段失败而被终止
+    killed: bool,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 运行多个 FFmpeg 进程，通过操作系统管道依次连接，例如解码 → 滤镜 → 编码
+    ///
+    /// 整个管道是一个任务，可以通过 `cancel` 同时终止所有阶段。进度由第一个阶段（读取原始输入）报告。
+    /// 任一阶段失败时终止其余阶段并返回该阶段的错误；后面的阶段已经成功结束时，
+    /// 前面的阶段因管道关闭而退出不视为失败。
+    pub async fn run_pipeline(&self, request: PipelineRequest) -> Result<PipelineOutput> {
+        let Some(last) = request.stages.last() else {
+            return Err(Error::InvalidArgument(
+                "`stages` must not be empty".to_string(),
+            ));
+        };
+
+        let job = self.register_job(&ExecuteRequest {
+            args: last.args.clone(),
+            job_id: request.job.job_id.clone(),
+            overwrite: last.overwrite,
+            tags: request.job.tags.clone(),
+            ..Default::default()
+        })?;
+
+        let stop = CancellationToken::new();
+        let mut stdin = Stdio::null();
+        let mut waits = FuturesUnordered::new();
+        let mut errors = Vec::new();
+
+        for (index, stage) in request.stages.iter().enumerate() {
+            let child = self
+                .spawn_with_stdin(
+                    ExecuteRequest {
+                        args: stage.args.clone(),
+                        overwrite: stage.overwrite,
+                        wait_for_install: request.job.wait_for_install,
+                        ..Default::default()
+                    },
+                    stdin,
+                )
+                .await;
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    // 终止已经启动的阶段
+                    stop.cancel();
+                    while waits.next().await.is_some() {}
+                    return Err(e);
+                }
+            };
+
+            let stdout = child.stdout().expect("stdout is piped");
+            stdin = if index + 1 < request.stages.len() {
+                stdout.try_into()?
+            } else {
+                // 最后一个阶段通常写入文件，丢弃它的标准输出，避免管道写满后阻塞
+                tokio::spawn(drain(stdout));
+                Stdio::null()
+            };
+
+            let mut stderr = child.stderr().expect("stderr is piped");
+            let mut parser = (index == 0).then(|| {
+                ProgressParser::new(job.info().id.clone(), job.info().tags.clone(), &stage.args)
+            });
+            let app = self.app().clone();
+            errors.push(tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let n = match stderr.read(&mut chunk).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => n,
+                    };
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(parser) = parser.as_mut() {
+                        for progress in parser.feed(&chunk[..n]) {
+                            events::emit(&app, events::JOB_PROGRESS, &progress);
+                        }
+                    }
+                }
+                String::from_utf8_lossy(&buf).to_string()
+            }));
+
+            let stop = stop.clone();
+            waits.push(tokio::spawn(async move {
+                tokio::select! {
+                    status = child.wait() => {
+                        let status = status.ok();
+                        StageExit {
+                            index,
+                            code: status.and_then(|status| status.code()),
+                            success: status.is_some_and(|status| status.success()),
+                            killed: false,
+                        }
+                    }
+                    _ = stop.cancelled() => {
+                        let _ = child.kill().await;
+                        StageExit { index, code: None, success: false, killed: true }
+                    }
+                }
+            }));
+        }
+
+        let mut exit_codes = vec![None; request.stages.len()];
+        let mut succeeded: Option<usize> = None;
+        let mut failed: Option<usize> = None;
+        let mut cancelled = false;
+
+        loop {
+            let exit = tokio::select! {
+                exit = waits.next() => match exit {
+                    Some(Ok(exit)) => exit,
+                    Some(Err(_)) => continue,
+                    None => break,
+                },
+                _ = job.cancelled(), if !cancelled => {
+                    cancelled = true;
+                    stop.cancel();
+                    continue;
+                }
+            };
+
+            exit_codes[exit.index] = exit.code;
+            if exit.success {
+                succeeded = Some(succeeded.map_or(exit.index, |index| index.max(exit.index)));
+            } else if !exit.killed
+                && failed.is_none()
+                && succeeded.map_or(true, |index| index < exit.index)
+            {
+                failed = Some(exit.index);
+                stop.cancel();
+            }
+        }
+
+        if cancelled {
+            return Err(Error::Cancelled);
+        }
+        if let Some(index) = failed {
+            let stderr = errors.swap_remove(index).await.unwrap_or_default();
+            return Err(Error::CommandExecution(format!(
+                "Pipeline stage {index} failed: {}",
+                stderr_tail(&stderr)
+            )));
+        }
+
+        Ok(PipelineOutput {
+            job_id: job.info().id.clone(),
+            exit_codes,
+        })
+    }
+}
+
+/// 读取并丢弃所有数据
+async fn drain(mut reader: impl tokio::io::AsyncRead + Unpin) {
+    let mut chunk = [0u8; 4096];
+    while let Ok(n) = reader.read(&mut chunk).await {
+        if n == 0 {
+            break;
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! 社交平台导出预设和约束检查
+//!
+//! 约束参考各平台公开的上传要求，平台规则变化时需要同步更新。
+
+use serde::{Deserialize, Serialize};
+
+use super::ProbeResult;
+
+/// 目标平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Platform {
+    Youtube,
+    YoutubeShorts,
+    InstagramReels,
+    #[serde(rename = "tiktok")]
+    TikTok,
+    X,
+}
+
+/// 平台的上传约束，None 表示不限制
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformConstraints {
+    /// 最短时长（秒）
+    pub min_duration: Option<f64>,
+    /// 最长时长（秒）
+    pub max_duration: Option<f64>,
+    /// 最大文件大小（字节）
+    pub max_size: Option<u64>,
+    /// 长边的最大像素数
+    pub max_long_edge: Option<u32>,
+    /// 短边的最大像素数
+    pub max_short_edge: Option<u32>,
+    /// 宽高比（宽 / 高）的允许范围
+    pub aspect_ratio: Option<(f64, f64)>,
+    /// 最大帧率
+    pub max_frame_rate: Option<f64>,
+    /// 最大总码率（kbps）
+    pub max_bitrate_kbps: Option<u64>,
+    /// 允许的封装格式（ffprobe 的格式名）
+    pub containers: &'static [&'static str],
+    /// 允许的视频编码，为空时不限制
+    pub video_codecs: &'static [&'static str],
+    /// 允许的音频编码，为空时不限制
+    pub audio_codecs: &'static [&'static str],
+}
+
+/// 平台导出预设
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformPreset {
+    pub platform: Platform,
+    /// 推荐使用的内置转码配置（见 `list_builtin_profiles`）
+    pub profile: &'static str,
+    pub constraints: PlatformConstraints,
+}
+
+/// 违反的约束
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Constraint {
+    Duration,
+    Size,
+    Resolution,
+    AspectRatio,
+    FrameRate,
+    Bitrate,
+    Container,
+    VideoCodec,
+    AudioCodec,
+}
+
+/// 单条违规
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintViolation {
+    pub constraint: Constraint,
+    /// 可直接展示给用户的说明
+    pub message: String,
+}
+
+/// 约束检查结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub platform: Platform,
+    /// 没有任何违规时为 true
+    pub valid: bool,
+    pub violations: Vec<ConstraintViolation>,
+}
+
+/// 约束检查请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateForRequest {
+    pub platform: Platform,
+    /// `probe` 的结果
+    pub probe: ProbeResult,
+}
+
+const MP4_MOV: &[&str] = &["mp4", "mov"];
+const H264_HEVC: &[&str] = &["h264", "hevc"];
+
+impl Platform {
+    /// 所有支持的平台
+    pub const ALL: [Platform; 5] = [
+        Platform::Youtube,
+        Platform::YoutubeShorts,
+        Platform::InstagramReels,
+        Platform::TikTok,
+        Platform::X,
+    ];
+
+    /// 平台的导出预设
+    pub fn preset(self) -> PlatformPreset {
+        let (profile, constraints) = match self {
+            Platform::Youtube => (
+                "web-1080p-h264",
+                PlatformConstraints {
+                    min_duration: None,
+                    max_duration: Some(12.0 * 3600.0),
+                    max_size: Some(256_000_000_000),
+                    max_long_edge: Some(7680),
+                    max_short_edge: Some(4320),
+                    aspect_ratio: None,
+                    max_frame_rate: Some(60.0),
+                    max_bitrate_kbps: None,
+                    containers: &["mp4", "mov", "matroska", "webm", "avi", "flv"],
+                    video_codecs: &[],
+                    audio_codecs: &[],
+                },
+            ),
+            Platform::YoutubeShorts => (
+                "social-vertical-9x16",
+                PlatformConstraints {
+                    min_duration: None,
+                    max_duration: Some(180.0),
+                    max_size: None,
+                    max_long_edge: None,
+                    max_short_edge: None,
+                    aspect_ratio: Some((0.0, 1.0)),
+                    max_frame_rate: Some(60.0),
+                    max_bitrate_kbps: None,
+                    containers: &["mp4", "mov", "matroska", "webm"],
+                    video_codecs: &[],
+                    audio_codecs: &[],
+                },
+            ),
+            Platform::InstagramReels => (
+                "social-vertical-9x16",
+                PlatformConstraints {
+                    min_duration: Some(3.0),
+                    max_duration: Some(180.0),
+                    max_size: Some(4_000_000_000),
+                    max_long_edge: Some(1920),
+                    max_short_edge: Some(1080),
+                    aspect_ratio: Some((0.5, 1.0)),
+                    max_frame_rate: Some(60.0),
+                    max_bitrate_kbps: Some(25_000),
+                    containers: MP4_MOV,
+                    video_codecs: H264_HEVC,
+                    audio_codecs: &["aac"],
+                },
+            ),
+            Platform::TikTok => (
+                "social-vertical-9x16",
+                PlatformConstraints {
+                    min_duration: Some(3.0),
+                    max_duration: Some(600.0),
+                    max_size: Some(287_600_000),
+                    max_long_edge: Some(1920),
+                    max_short_edge: Some(1080),
+                    aspect_ratio: None,
+                    max_frame_rate: Some(60.0),
+                    max_bitrate_kbps: None,
+                    containers: MP4_MOV,
+                    video_codecs: H264_HEVC,
+                    audio_codecs: &["aac", "mp3"],
+                },
+            ),
+            Platform::X => (
+                "web-1080p-h264",
+                PlatformConstraints {
+                    min_duration: Some(0.5),
+                    max_duration: Some(140.0),
+                    max_size: Some(512_000_000),
+                    max_long_edge: Some(1920),
+                    max_short_edge: Some(1200),
+                    aspect_ratio: Some((1.0 / 3.0, 3.0)),
+                    max_frame_rate: Some(60.0),
+                    max_bitrate_kbps: Some(25_000),
+                    containers: MP4_MOV,
+                    video_codecs: &["h264"],
+                    audio_codecs: &["aac"],
+                },
+            ),
+        };
+
+        PlatformPreset {
+            platform: self,
+            profile,
+            constraints,
+        }
+    }
+}
+
+/// 所有平台的导出预设
+pub fn platform_presets() -> Vec<PlatformPreset> {
+    Platform::ALL.into_iter().map(Platform::preset).collect()
+}
+
+/// 检查文件是否满足平台的上传约束，缺少的信息不视为违规
+pub fn validate_for(platform: Platform, probe: &ProbeResult) -> ValidationReport {
+    let limits = platform.preset().constraints;
+    let mut violations = Vec::new();
+    let mut violate = |constraint, message: String| {
+        violations.push(ConstraintViolation {
+            constraint,
+            message,
+        })
+    };
+
+    if let Some(duration) = probe.duration {
+        if let Some(max) = limits.max_duration.filter(|max| duration > *max) {
+            violate(
+                Constraint::Duration,
+                format!("Duration {duration:.1} s exceeds the {max} s limit"),
+            );
+        }
+        if let Some(min) = limits.min_duration.filter(|min| duration < *min) {
+            violate(
+                Constraint::Duration,
+                format!("Duration {duration:.1} s is shorter than the {min} s minimum"),
+            );
+        }
+    }
+
+    if let Some(max) = limits
+        .max_size
+        .filter(|max| probe.size.is_some_and(|size| size > *max))
+    {
+        violate(
+            Constraint::Size,
+            format!(
+                "File size {} MB exceeds the {} MB limit",
+                probe.size.unwrap_or_default() / 1_000_000,
+                max / 1_000_000
+            ),
+        );
+    }
+
+    if let Some(max) = limits
+        .max_bitrate_kbps
+        .filter(|max| probe.bit_rate.is_some_and(|rate| rate / 1000 > *max))
+    {
+        violate(
+            Constraint::Bitrate,
+            format!(
+                "Bitrate {} kbps exceeds the {max} kbps limit",
+                probe.bit_rate.unwrap_or_default() / 1000
+            ),
+        );
+    }
+
+    let formats: Vec<&str> = probe.format_name.split(',').collect();
+    if !limits
+        .containers
+        .iter()
+        .any(|container| formats.contains(container))
+    {
+        violate(
+            Constraint::Container,
+            format!(
+                "Container `{}` is not supported, use one of: {}",
+                probe.format_name,
+                limits.containers.join(", ")
+            ),
+        );
+    }
+
+    if let Some(video) = probe.video() {
+        if let (Some(width), Some(height)) = (video.width, video.height) {
+            let (long, short) = (width.max(height), width.min(height));
+            let too_large = limits.max_long_edge.is_some_and(|max| long > max)
+                || limits.max_short_edge.is_some_and(|max| short > max);
+            if too_large {
+                violate(
+                    Constraint::Resolution,
+                    format!(
+                        "Resolution {width}x{height} exceeds {}x{}",
+                        limits.max_long_edge.unwrap_or(long),
+                        limits.max_short_edge.unwrap_or(short)
+                    ),
+                );
+            }
+
+            let ratio = width as f64 / height.max(1) as f64;
+            if let Some((min, max)) = limits.aspect_ratio {
+                // 允许 1% 的误差，避免 1080x1921 之类的尺寸被误判
+                if ratio < min * 0.99 || ratio > max * 1.01 {
+                    violate(
+                        Constraint::AspectRatio,
+                        format!("Aspect ratio {ratio:.2} is outside {min:.2}-{max:.2}"),
+                    );
+                }
+            }
+        }
+
+        if let Some(max) = limits
+            .max_frame_rate
+            .filter(|max| video.frame_rate.is_some_and(|fps| fps > max + 0.01))
+        {
+            violate(
+                Constraint::FrameRate,
+                format!(
+                    "Frame rate {:.2} fps exceeds the {max} fps limit",
+                    video.frame_rate.unwrap_or_default()
+                ),
+            );
+        }
+
+        check_codec(
+            &mut violate,
+            Constraint::VideoCodec,
+            "Video",
+            video.codec_name.as_deref(),
+            limits.video_codecs,
+        );
+    }
+
+    if let Some(audio) = probe.audio() {
+        check_codec(
+            &mut violate,
+            Constraint::AudioCodec,
+            "Audio",
+            audio.codec_name.as_deref(),
+            limits.audio_codecs,
+        );
+    }
+
+    ValidationReport {
+        platform,
+        valid: violations.is_empty(),
+        violations,
+    }
+}
+
+fn check_codec(
+    violate: &mut impl FnMut(Constraint, String),
+    constraint: Constraint,
+    kind: &str,
+    codec: Option<&str>,
+    allowed: &[&str],
+) {
+    let Some(codec) = codec else {
+        return;
+    };
+    if !allowed.is_empty() && !allowed.contains(&codec) {
+        violate(
+            constraint,
+            format!(
+                "{kind} codec `{codec}` is not supported, use one of: {}",
+                allowed.join(", ")
+            ),
+        );
+    }
+}
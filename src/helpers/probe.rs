@@ -0,0 +1,235 @@
+//! 媒体信息读取（ffprobe）
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::stderr_tail;
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::Tool;
+
+/// 媒体信息读取请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeRequest {
+    /// 输入文件
+    pub input: String,
+}
+
+/// 媒体信息
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeResult {
+    /// 封装格式，ffprobe 以逗号分隔的别名列表（如 `mov,mp4,m4a,3gp,3g2,mj2`）
+    pub format_name: String,
+    /// 时长（秒）
+    pub duration: Option<f64>,
+    /// 文件大小（字节）
+    pub size: Option<u64>,
+    /// 总码率（bps）
+    pub bit_rate: Option<u64>,
+    /// 容器级元数据（如 `creation_time`）
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub streams: Vec<ProbeStream>,
+}
+
+/// [`Ffmpeg::probe_typed`] 返回的媒体信息
+pub type MediaInfo = ProbeResult;
+
+/// 单个流的信息
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeStream {
+    pub index: u32,
+    /// `video`、`audio`、`subtitle`、`data` 等
+    pub codec_type: String,
+    pub codec_name: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// 像素格式（如 `yuv420p10le`）
+    pub pixel_format: Option<String>,
+    /// 色彩原色（如 `bt709`、`bt2020`）
+    pub color_primaries: Option<String>,
+    /// 传递特性（如 `smpte2084` 表示 PQ，`arib-std-b67` 表示 HLG）
+    pub color_transfer: Option<String>,
+    /// 色彩矩阵（如 `bt2020nc`）
+    pub color_space: Option<String>,
+    /// 平均帧率
+    pub frame_rate: Option<f64>,
+    /// 码率（bps）
+    pub bit_rate: Option<u64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    /// 时长（秒）
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl ProbeResult {
+    /// 第一个视频流
+    pub fn video(&self) -> Option<&ProbeStream> {
+        self.streams
+            .iter()
+            .find(|stream| stream.codec_type == "video")
+    }
+
+    /// 第一个音频流
+    pub fn audio(&self) -> Option<&ProbeStream> {
+        self.streams
+            .iter()
+            .find(|stream| stream.codec_type == "audio")
+    }
+}
+
+/// ffprobe `-of json` 的输出，数值字段以字符串表示
+#[derive(Deserialize)]
+struct RawProbe {
+    #[serde(default)]
+    format: RawFormat,
+    #[serde(default)]
+    streams: Vec<RawStream>,
+}
+
+#[derive(Default, Deserialize)]
+struct RawFormat {
+    #[serde(default)]
+    format_name: String,
+    duration: Option<String>,
+    size: Option<String>,
+    bit_rate: Option<String>,
+    #[serde(default)]
+    tags: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct RawStream {
+    index: u32,
+    #[serde(default)]
+    codec_type: String,
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    pix_fmt: Option<String>,
+    color_primaries: Option<String>,
+    color_transfer: Option<String>,
+    color_space: Option<String>,
+    avg_frame_rate: Option<String>,
+    r_frame_rate: Option<String>,
+    bit_rate: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+    duration: Option<String>,
+    #[serde(default)]
+    tags: BTreeMap<String, serde_json::Value>,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 使用 ffprobe 读取封装格式和各个流的信息
+    pub async fn probe(&self, request: ProbeRequest) -> Result<ProbeResult> {
+        let raw: RawProbe = self
+            .ffprobe_json(&["-show_format", "-show_streams"], &request.input)
+            .await?;
+        Ok(raw.into())
+    }
+
+    /// 读取媒体信息，供 Rust 端直接使用
+    pub async fn probe_typed(&self, path: impl AsRef<Path>) -> Result<MediaInfo> {
+        self.probe(ProbeRequest {
+            input: path.as_ref().to_string_lossy().to_string(),
+        })
+        .await
+    }
+
+    /// 运行 ffprobe 读取 `input`，并解析 JSON 输出
+    ///
+    /// 与任务相同，`input` 受 `allowedProtocols` 限制。
+    pub(crate) async fn ffprobe_json<T: DeserializeOwned>(
+        &self,
+        args: &[&str],
+        input: &str,
+    ) -> Result<T> {
+        let input = self.input_args(input)?;
+        let ffprobe = self.get_tool_executable_path(Tool::Ffprobe)?;
+        if !ffprobe.exists() {
+            return Err(Error::CommandExecution("ffprobe not found".to_string()));
+        }
+
+        let output = tokio::process::Command::new(ffprobe)
+            .args(["-v", "error", "-of", "json"])
+            .args(args)
+            .args(input)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(Error::CommandExecution(stderr_tail(
+                &String::from_utf8_lossy(&output.stderr),
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| Error::CommandExecution(e.to_string()))
+    }
+}
+
+impl From<RawProbe> for ProbeResult {
+    fn from(raw: RawProbe) -> Self {
+        Self {
+            format_name: raw.format.format_name,
+            duration: parse_number(&raw.format.duration),
+            size: parse_number(&raw.format.size),
+            bit_rate: parse_number(&raw.format.bit_rate),
+            tags: string_tags(raw.format.tags),
+            streams: raw
+                .streams
+                .into_iter()
+                .map(|stream| ProbeStream {
+                    index: stream.index,
+                    codec_type: stream.codec_type,
+                    codec_name: stream.codec_name,
+                    width: stream.width,
+                    height: stream.height,
+                    pixel_format: stream.pix_fmt,
+                    color_primaries: stream.color_primaries.filter(|value| value != "unknown"),
+                    color_transfer: stream.color_transfer.filter(|value| value != "unknown"),
+                    color_space: stream.color_space.filter(|value| value != "unknown"),
+                    frame_rate: parse_rate(&stream.avg_frame_rate)
+                        .or_else(|| parse_rate(&stream.r_frame_rate)),
+                    bit_rate: parse_number(&stream.bit_rate),
+                    sample_rate: parse_number(&stream.sample_rate),
+                    channels: stream.channels,
+                    duration: parse_number(&stream.duration),
+                    tags: string_tags(stream.tags),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &Option<String>) -> Option<T> {
+    value.as_deref()?.parse().ok()
+}
+
+/// 解析 `30000/1001` 形式的帧率，`0/0` 表示未知
+fn parse_rate(value: &Option<String>) -> Option<f64> {
+    let (num, den) = value.as_deref()?.split_once('/')?;
+    let (num, den) = (num.parse::<f64>().ok()?, den.parse::<f64>().ok()?);
+    (num > 0.0 && den > 0.0).then(|| num / den)
+}
+
+/// 元数据的值通常是字符串，个别版本会输出数字
+fn string_tags(tags: BTreeMap<String, serde_json::Value>) -> BTreeMap<String, String> {
+    tags.into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect()
+}
@@ -0,0 +1,174 @@
+//! 内置转码配置
+
+use serde::Serialize;
+
+use super::{MovFlags, ScaleFit, TranscodeOptions};
+use crate::error::{Error, Result};
+
+/// 内置转码配置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltinProfile {
+    /// 配置名称，用于 `TranscodeOptions::profile`
+    pub name: &'static str,
+    /// 用途说明
+    pub description: &'static str,
+    /// 推荐的输出扩展名
+    pub extension: &'static str,
+    /// 配置对应的转码选项
+    pub options: TranscodeOptions,
+}
+
+/// 所有内置配置
+pub fn builtin_profiles() -> Vec<BuiltinProfile> {
+    vec![
+        BuiltinProfile {
+            name: "web-1080p-h264",
+            description: "H.264 1080p for web playback, compatible with every browser",
+            extension: "mp4",
+            options: TranscodeOptions {
+                video_codec: Some("libx264".to_string()),
+                crf: Some(23),
+                preset: Some("medium".to_string()),
+                height: Some(1080),
+                pixel_format: Some("yuv420p".to_string()),
+                audio_codec: Some("aac".to_string()),
+                audio_bitrate: Some("160k".to_string()),
+                movflags: Some(MovFlags::Faststart),
+                ..Default::default()
+            },
+        },
+        BuiltinProfile {
+            name: "web-720p-h264",
+            description: "H.264 720p for web playback on slower connections",
+            extension: "mp4",
+            options: TranscodeOptions {
+                video_codec: Some("libx264".to_string()),
+                crf: Some(23),
+                preset: Some("medium".to_string()),
+                height: Some(720),
+                pixel_format: Some("yuv420p".to_string()),
+                audio_codec: Some("aac".to_string()),
+                audio_bitrate: Some("128k".to_string()),
+                movflags: Some(MovFlags::Faststart),
+                ..Default::default()
+            },
+        },
+        BuiltinProfile {
+            name: "web-1080p-hevc",
+            description:
+                "HEVC 1080p, about half the size of H.264, plays in Safari and modern devices",
+            extension: "mp4",
+            options: TranscodeOptions {
+                video_codec: Some("libx265".to_string()),
+                crf: Some(26),
+                preset: Some("medium".to_string()),
+                height: Some(1080),
+                pixel_format: Some("yuv420p".to_string()),
+                audio_codec: Some("aac".to_string()),
+                audio_bitrate: Some("160k".to_string()),
+                movflags: Some(MovFlags::Faststart),
+                extra_args: ["-tag:v", "hvc1"].map(String::from).to_vec(),
+                ..Default::default()
+            },
+        },
+        BuiltinProfile {
+            name: "archive-av1",
+            description: "SVT-AV1 10-bit at source resolution for long-term storage",
+            extension: "mkv",
+            options: TranscodeOptions {
+                video_codec: Some("libsvtav1".to_string()),
+                crf: Some(28),
+                preset: Some("6".to_string()),
+                pixel_format: Some("yuv420p10le".to_string()),
+                audio_codec: Some("libopus".to_string()),
+                audio_bitrate: Some("160k".to_string()),
+                extra_args: ["-g", "240", "-svtav1-params", "tune=0"]
+                    .map(String::from)
+                    .to_vec(),
+                ..Default::default()
+            },
+        },
+        BuiltinProfile {
+            name: "archive-hevc",
+            description: "x265 10-bit near-transparent quality with lossless audio",
+            extension: "mkv",
+            options: TranscodeOptions {
+                video_codec: Some("libx265".to_string()),
+                crf: Some(20),
+                preset: Some("slow".to_string()),
+                pixel_format: Some("yuv420p10le".to_string()),
+                audio_codec: Some("flac".to_string()),
+                ..Default::default()
+            },
+        },
+        BuiltinProfile {
+            name: "social-vertical-9x16",
+            description: "1080x1920 vertical H.264, cropped to fill the frame",
+            extension: "mp4",
+            options: TranscodeOptions {
+                video_codec: Some("libx264".to_string()),
+                crf: Some(21),
+                preset: Some("medium".to_string()),
+                width: Some(1080),
+                height: Some(1920),
+                fit: Some(ScaleFit::Cover),
+                fps: Some(30.0),
+                pixel_format: Some("yuv420p".to_string()),
+                audio_codec: Some("aac".to_string()),
+                audio_bitrate: Some("128k".to_string()),
+                movflags: Some(MovFlags::Faststart),
+                ..Default::default()
+            },
+        },
+    ]
+}
+
+impl TranscodeOptions {
+    /// 将 `profile` 指定的内置配置与显式设置的选项合并
+    ///
+    /// 显式设置的选项优先，`extra_args` 追加在配置的参数之后。
+    pub fn resolve_profile(&self) -> Result<TranscodeOptions> {
+        let Some(name) = &self.profile else {
+            return Ok(self.clone());
+        };
+        let base = builtin_profiles()
+            .into_iter()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown profile `{name}`")))?
+            .options;
+
+        Ok(TranscodeOptions {
+            profile: None,
+            video_codec: self.video_codec.clone().or(base.video_codec),
+            audio_codec: self.audio_codec.clone().or(base.audio_codec),
+            crf: self.crf.or(base.crf),
+            preset: self.preset.clone().or(base.preset),
+            video_bitrate: self.video_bitrate.clone().or(base.video_bitrate),
+            audio_bitrate: self.audio_bitrate.clone().or(base.audio_bitrate),
+            width: self.width.or(base.width),
+            height: self.height.or(base.height),
+            fit: self.fit.or(base.fit),
+            fps: self.fps.or(base.fps),
+            pixel_format: self.pixel_format.clone().or(base.pixel_format),
+            deinterlace: self.deinterlace.or(base.deinterlace),
+            denoise: self.denoise.or(base.denoise),
+            deband: self.deband || base.deband,
+            auto_crop: self.auto_crop || base.auto_crop,
+            tonemap_to_sdr: self.tonemap_to_sdr || base.tonemap_to_sdr,
+            preserve_hdr: self.preserve_hdr || base.preserve_hdr,
+            color_primaries: self.color_primaries.clone().or(base.color_primaries),
+            color_trc: self.color_trc.clone().or(base.color_trc),
+            colorspace: self.colorspace.clone().or(base.colorspace),
+            preserve_spherical: self.preserve_spherical || base.preserve_spherical,
+            spherical: self.spherical.clone().or(base.spherical),
+            movflags: self.movflags.or(base.movflags),
+            frag_duration: self.frag_duration.or(base.frag_duration),
+            extra_args: base
+                .extra_args
+                .into_iter()
+                .chain(self.extra_args.iter().cloned())
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,191 @@
+//! 剪辑代理文件生成
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::jobs::now_millis;
+
+/// 清单文件名，保存在输出目录中
+const MANIFEST_NAME: &str = "proxy-manifest.json";
+
+/// 代理文件编码
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyCodec {
+    /// ProRes 422 Proxy（MOV），剪辑软件解码负担最小
+    #[default]
+    ProresProxy,
+    /// DNxHR LB（MOV）
+    Dnxhr,
+    /// H.264 短 GOP（MP4），体积最小
+    H264,
+}
+
+impl ProxyCodec {
+    fn extension(self) -> &'static str {
+        match self {
+            ProxyCodec::ProresProxy | ProxyCodec::Dnxhr => "mov",
+            ProxyCodec::H264 => "mp4",
+        }
+    }
+
+    fn args(self) -> Vec<String> {
+        let args: &[&str] = match self {
+            ProxyCodec::ProresProxy => &[
+                "-c:v",
+                "prores_ks",
+                "-profile:v",
+                "0",
+                "-pix_fmt",
+                "yuv422p10le",
+                "-c:a",
+                "pcm_s16le",
+            ],
+            ProxyCodec::Dnxhr => &[
+                "-c:v",
+                "dnxhd",
+                "-profile:v",
+                "dnxhr_lb",
+                "-pix_fmt",
+                "yuv422p",
+                "-c:a",
+                "pcm_s16le",
+            ],
+            // 短 GOP 便于在时间线上快速定位
+            ProxyCodec::H264 => &[
+                "-c:v", "libx264", "-preset", "veryfast", "-crf", "23", "-g", "15", "-pix_fmt",
+                "yuv420p", "-c:a", "aac", "-b:a", "128k",
+            ],
+        };
+        to_args(args.iter().copied())
+    }
+}
+
+/// 代理生成选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyOptions {
+    #[serde(default)]
+    pub codec: ProxyCodec,
+    /// 相对原片的缩放比例，默认 0.5
+    pub scale: Option<f64>,
+}
+
+/// 代理生成请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateProxiesRequest {
+    /// 原始文件
+    pub inputs: Vec<String>,
+    /// 输出目录，不存在时自动创建
+    pub output_dir: String,
+    #[serde(flatten)]
+    pub options: ProxyOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 清单中的一项
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyEntry {
+    /// 原始文件
+    pub original: String,
+    /// 代理文件
+    pub proxy: String,
+    /// 生成失败时的原因，成功时为 None
+    pub error: Option<String>,
+}
+
+/// 代理清单，同时写入输出目录的 `proxy-manifest.json`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyManifest {
+    pub codec: ProxyCodec,
+    pub scale: f64,
+    /// 生成时间（Unix 毫秒）
+    pub created_at: u64,
+    pub entries: Vec<ProxyEntry>,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 批量生成剪辑代理，任务通过调度器并发运行，单个文件失败不影响其他文件
+    ///
+    /// 每个文件单独运行，`jobId` 不会用于这些任务。
+    pub async fn generate_proxies(&self, request: GenerateProxiesRequest) -> Result<ProxyManifest> {
+        let codec = request.options.codec;
+        let scale = request.options.scale.unwrap_or(0.5).clamp(0.05, 1.0);
+        let output_dir = PathBuf::from(&request.output_dir);
+        fs::create_dir_all(&output_dir)?;
+
+        let proxies = proxy_paths(&request.inputs, &output_dir, codec);
+        let job = HelperOptions {
+            job_id: None,
+            ..request.job.clone()
+        };
+        let filter = format!("scale=trunc(iw*{scale}/2)*2:trunc(ih*{scale}/2)*2");
+
+        let results = join_all(request.inputs.iter().zip(&proxies).map(|(input, proxy)| {
+            let mut args = to_args(["-i", input, "-vf", &filter]);
+            args.extend(codec.args());
+            args.push(proxy.clone());
+            self.run_helper(args, &job)
+        }))
+        .await;
+
+        let entries = request
+            .inputs
+            .iter()
+            .zip(proxies)
+            .zip(results)
+            .map(|((original, proxy), result)| ProxyEntry {
+                original: original.clone(),
+                proxy,
+                error: result.err().map(|e| crate::messages::error_message(&e)),
+            })
+            .collect();
+
+        let manifest = ProxyManifest {
+            codec,
+            scale,
+            created_at: now_millis(),
+            entries,
+        };
+        let data = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| Error::CommandExecution(e.to_string()))?;
+        fs::write(output_dir.join(MANIFEST_NAME), data)?;
+
+        Ok(manifest)
+    }
+}
+
+/// 代理文件路径 `<文件名>_proxy.<扩展名>`，文件名重复时追加序号
+fn proxy_paths(inputs: &[String], output_dir: &Path, codec: ProxyCodec) -> Vec<String> {
+    let mut used = HashSet::new();
+
+    inputs
+        .iter()
+        .map(|input| {
+            let stem = Path::new(input)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "proxy".to_string());
+
+            let mut name = format!("{stem}_proxy.{}", codec.extension());
+            let mut index = 2;
+            while !used.insert(name.clone()) {
+                name = format!("{stem}_proxy_{index}.{}", codec.extension());
+                index += 1;
+            }
+            output_dir.join(name).to_string_lossy().to_string()
+        })
+        .collect()
+}
@@ -0,0 +1,76 @@
+//! 重封装（不重新编码）
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{suffixed_path, to_args, HelperOptions, HelperOutput};
+use crate::desktop::Ffmpeg;
+use crate::error::Result;
+use crate::models::OverwritePolicy;
+
+/// faststart 请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaststartRequest {
+    /// 输入的 MP4 / MOV 文件
+    pub input: String,
+    /// 输出文件，未设置时原地替换输入
+    pub output: Option<String>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 直接复制所有流并将 `moov` 移到文件开头，使文件可以边下载边播放
+    ///
+    /// 原地处理时先写入同目录的临时文件，成功后再替换输入，失败时输入保持不变。
+    pub async fn faststart(&self, request: FaststartRequest) -> Result<HelperOutput> {
+        let (output, job) = match &request.output {
+            Some(output) => (output.clone(), request.job.clone()),
+            None => (
+                suffixed_path(&request.input, "faststart-tmp"),
+                HelperOptions {
+                    overwrite: Some(OverwritePolicy::Always),
+                    ..request.job.clone()
+                },
+            ),
+        };
+
+        let args = to_args([
+            "-i",
+            &request.input,
+            "-map",
+            "0",
+            "-c",
+            "copy",
+            "-ignore_unknown",
+            "-movflags",
+            "+faststart",
+            &output,
+        ]);
+        let response = match self.run_helper(args, &job).await {
+            Ok(response) => response,
+            Err(err) => {
+                if request.output.is_none() {
+                    let _ = fs::remove_file(&output);
+                }
+                return Err(err);
+            }
+        };
+
+        let path = match request.output {
+            Some(output) => output,
+            None => {
+                fs::rename(&output, &request.input)?;
+                request.input
+            }
+        };
+
+        Ok(HelperOutput {
+            job_id: response.job_id,
+            path,
+        })
+    }
+}
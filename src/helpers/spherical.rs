@@ -0,0 +1,618 @@
+//! 全景视频元数据（Spherical Video V2）
+//!
+//! FFmpeg 只能在 `-strict unofficial` 下保留输入已有的 `st3d` / `sv3d` 盒子，无法从头写入，
+//! 因此注入时在编码完成后直接修改 MP4 / MOV 的 `moov`。
+
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// 投影方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Projection {
+    /// 等距柱状投影，大多数 360 相机的默认输出
+    #[default]
+    Equirectangular,
+    /// 立方体贴图
+    Cubemap,
+}
+
+/// 立体模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StereoMode {
+    #[default]
+    Mono,
+    /// 上下排列，左眼在上
+    TopBottom,
+    /// 左右排列，左眼在左
+    LeftRight,
+}
+
+/// 要写入的全景元数据
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SphericalMetadata {
+    #[serde(default)]
+    pub projection: Projection,
+    #[serde(default)]
+    pub stereo: StereoMode,
+    /// 初始视角的偏航角（度）
+    pub yaw: Option<f64>,
+    /// 初始视角的俯仰角（度）
+    pub pitch: Option<f64>,
+    /// 初始视角的翻滚角（度）
+    pub roll: Option<f64>,
+}
+
+/// 可以注入全景元数据的输出格式
+pub(crate) fn supports_spherical(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ["mp4", "mov", "m4v"]
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// 在视频轨道的采样描述中写入 `st3d` 和 `sv3d`，已有的同名盒子会被替换
+///
+/// `moov` 位于 `mdat` 之前时同时修正块偏移。先写入临时文件再替换原文件。
+pub(crate) fn inject_spherical(path: &Path, metadata: &SphericalMetadata) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let (moov_start, moov_end) = find_moov(&mut file, file_len)?;
+
+    let mut moov = vec![0; (moov_end - moov_start) as usize];
+    file.seek(SeekFrom::Start(moov_start))?;
+    file.read_exact(&mut moov)?;
+
+    let mut root = parse_atoms(&moov)?;
+    let moov = root.first_mut().ok_or_else(|| invalid("moov is empty"))?;
+    let sample_entry = video_sample_entry(moov)?;
+    if let Payload::Container { children, .. } = &mut sample_entry.payload {
+        children.retain(|atom| &atom.kind != b"st3d" && &atom.kind != b"sv3d");
+        children.push(st3d(metadata.stereo));
+        children.push(sv3d(metadata));
+    }
+
+    let old_len = moov_end - moov_start;
+    let new_len = moov.encoded_len();
+    if new_len != old_len {
+        shift_chunk_offsets(moov, moov_end, new_len as i64 - old_len as i64)?;
+    }
+    let mut encoded = Vec::with_capacity(new_len as usize);
+    moov.write(&mut encoded);
+
+    let temp_path = path.with_extension("spherical.tmp");
+    let result = (|| {
+        let mut out = File::create(&temp_path)?;
+        file.seek(SeekFrom::Start(0))?;
+        io::copy(&mut (&mut file).take(moov_start), &mut out)?;
+        out.write_all(&encoded)?;
+        file.seek(SeekFrom::Start(moov_end))?;
+        io::copy(&mut file, &mut out)?;
+        out.sync_all()
+    })();
+    drop(file);
+
+    match result {
+        Ok(()) => fs::rename(&temp_path, path),
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+/// 顶层 `moov` 的起止位置
+fn find_moov(file: &mut File, file_len: u64) -> io::Result<(u64, u64)> {
+    let mut offset = 0;
+
+    while offset + 8 <= file_len {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0; 16];
+        file.read_exact(&mut header[..8])?;
+
+        let size = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            0 => file_len - offset,
+            1 => {
+                file.read_exact(&mut header[8..])?;
+                u64::from_be_bytes(header[8..].try_into().unwrap())
+            }
+            size => size as u64,
+        };
+        if size < 8 || offset + size > file_len {
+            return Err(invalid("truncated box"));
+        }
+        if &header[4..8] == b"moov" {
+            return Ok((offset, offset + size));
+        }
+        offset += size;
+    }
+
+    Err(invalid("moov not found"))
+}
+
+/// 盒子
+struct Atom {
+    kind: [u8; 4],
+    payload: Payload,
+}
+
+enum Payload {
+    Raw(Vec<u8>),
+    /// `prefix` 是子盒子之前的固定字段
+    Container {
+        prefix: Vec<u8>,
+        children: Vec<Atom>,
+    },
+}
+
+fn parse_atoms(mut data: &[u8]) -> io::Result<Vec<Atom>> {
+    let mut atoms = Vec::new();
+
+    while data.len() >= 8 {
+        let (header, size) = match u32::from_be_bytes(data[..4].try_into().unwrap()) {
+            0 => (8, data.len()),
+            1 if data.len() >= 16 => (
+                16,
+                u64::from_be_bytes(data[8..16].try_into().unwrap()) as usize,
+            ),
+            1 => return Err(invalid("truncated box")),
+            size => (8, size as usize),
+        };
+        if size < header || size > data.len() {
+            return Err(invalid("truncated box"));
+        }
+
+        let kind = data[4..8].try_into().unwrap();
+        atoms.push(Atom::parse(kind, &data[header..size])?);
+        data = &data[size..];
+    }
+
+    Ok(atoms)
+}
+
+impl Atom {
+    fn parse(kind: [u8; 4], body: &[u8]) -> io::Result<Self> {
+        let prefix = match &kind {
+            b"moov" | b"trak" | b"mdia" | b"minf" | b"stbl" => 0,
+            // version/flags + entry_count
+            b"stsd" => 8,
+            _ => {
+                return Ok(Self {
+                    kind,
+                    payload: Payload::Raw(body.to_vec()),
+                })
+            }
+        };
+        Self::container(kind, body, prefix)
+    }
+
+    fn container(kind: [u8; 4], body: &[u8], prefix: usize) -> io::Result<Self> {
+        if body.len() < prefix {
+            return Err(invalid("truncated box"));
+        }
+        Ok(Self {
+            kind,
+            payload: Payload::Container {
+                prefix: body[..prefix].to_vec(),
+                children: parse_atoms(&body[prefix..])?,
+            },
+        })
+    }
+
+    fn raw(kind: &[u8; 4], body: Vec<u8>) -> Self {
+        Self {
+            kind: *kind,
+            payload: Payload::Raw(body),
+        }
+    }
+
+    fn children(&self) -> &[Atom] {
+        match &self.payload {
+            Payload::Container { children, .. } => children,
+            Payload::Raw(_) => &[],
+        }
+    }
+
+    fn children_mut(&mut self) -> &mut [Atom] {
+        match &mut self.payload {
+            Payload::Container { children, .. } => children,
+            Payload::Raw(_) => &mut [],
+        }
+    }
+
+    fn child(&self, kind: &[u8; 4]) -> Option<&Atom> {
+        self.children().iter().find(|atom| &atom.kind == kind)
+    }
+
+    fn child_mut(&mut self, kind: &[u8; 4]) -> Option<&mut Atom> {
+        self.children_mut()
+            .iter_mut()
+            .find(|atom| &atom.kind == kind)
+    }
+
+    fn body_len(&self) -> u64 {
+        match &self.payload {
+            Payload::Raw(body) => body.len() as u64,
+            Payload::Container { prefix, children } => {
+                prefix.len() as u64 + children.iter().map(Atom::encoded_len).sum::<u64>()
+            }
+        }
+    }
+
+    fn encoded_len(&self) -> u64 {
+        let body = self.body_len();
+        if body + 8 > u32::MAX as u64 {
+            body + 16
+        } else {
+            body + 8
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        let size = self.encoded_len();
+        if size > u32::MAX as u64 {
+            out.extend(1u32.to_be_bytes());
+            out.extend(self.kind);
+            out.extend(size.to_be_bytes());
+        } else {
+            out.extend((size as u32).to_be_bytes());
+            out.extend(self.kind);
+        }
+
+        match &self.payload {
+            Payload::Raw(body) => out.extend(body),
+            Payload::Container { prefix, children } => {
+                out.extend(prefix);
+                for child in children {
+                    child.write(out);
+                }
+            }
+        }
+    }
+}
+
+/// 第一个视频轨道的第一个采样描述，解析为容器以便添加子盒子
+fn video_sample_entry(moov: &mut Atom) -> io::Result<&mut Atom> {
+    let trak = moov
+        .children_mut()
+        .iter_mut()
+        .find(|trak| {
+            &trak.kind == b"trak"
+                && trak
+                    .child(b"mdia")
+                    .and_then(|mdia| mdia.child(b"hdlr"))
+                    .is_some_and(|hdlr| {
+                        matches!(&hdlr.payload, Payload::Raw(body) if body.get(8..12) == Some(b"vide"))
+                    })
+        })
+        .ok_or_else(|| invalid("no video track"))?;
+
+    let entry = trak
+        .child_mut(b"mdia")
+        .and_then(|mdia| mdia.child_mut(b"minf"))
+        .and_then(|minf| minf.child_mut(b"stbl"))
+        .and_then(|stbl| stbl.child_mut(b"stsd"))
+        .and_then(|stsd| stsd.children_mut().first_mut())
+        .ok_or_else(|| invalid("no video sample description"))?;
+
+    if let Payload::Raw(body) = &entry.payload {
+        // VisualSampleEntry 在子盒子之前有 78 字节的固定字段
+        *entry = Atom::container(entry.kind, body, 78)?;
+    }
+    Ok(entry)
+}
+
+/// `moov` 变长后，位于其后的媒体数据整体后移，需要修正所有轨道的 `stco` / `co64`
+fn shift_chunk_offsets(moov: &mut Atom, moov_end: u64, delta: i64) -> io::Result<()> {
+    for trak in moov
+        .children_mut()
+        .iter_mut()
+        .filter(|atom| &atom.kind == b"trak")
+    {
+        let Some(stbl) = trak
+            .child_mut(b"mdia")
+            .and_then(|mdia| mdia.child_mut(b"minf"))
+            .and_then(|minf| minf.child_mut(b"stbl"))
+        else {
+            continue;
+        };
+
+        for table in stbl.children_mut() {
+            let width = match &table.kind {
+                b"stco" => 4,
+                b"co64" => 8,
+                _ => continue,
+            };
+            let Payload::Raw(body) = &mut table.payload else {
+                continue;
+            };
+
+            for entry in body
+                .get_mut(8..)
+                .unwrap_or_default()
+                .chunks_exact_mut(width)
+            {
+                let offset = match width {
+                    4 => u32::from_be_bytes(entry.try_into().unwrap()) as u64,
+                    _ => u64::from_be_bytes(entry.try_into().unwrap()),
+                };
+                if offset < moov_end {
+                    continue;
+                }
+
+                let shifted = offset
+                    .checked_add_signed(delta)
+                    .ok_or_else(|| invalid("bad offset"))?;
+                if width == 4 {
+                    let shifted =
+                        u32::try_from(shifted).map_err(|_| invalid("chunk offset overflow"))?;
+                    entry.copy_from_slice(&shifted.to_be_bytes());
+                } else {
+                    entry.copy_from_slice(&shifted.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 立体模式盒子
+fn st3d(stereo: StereoMode) -> Atom {
+    let mode = match stereo {
+        StereoMode::Mono => 0,
+        StereoMode::TopBottom => 1,
+        StereoMode::LeftRight => 2,
+    };
+    Atom::raw(b"st3d", vec![0, 0, 0, 0, mode])
+}
+
+/// 全景视频盒子：`svhd` + `proj`（`prhd` + `equi` / `cbmp`）
+fn sv3d(metadata: &SphericalMetadata) -> Atom {
+    let mut svhd = vec![0; 4];
+    svhd.extend(b"tauri-plugin-use-ffmpeg\0");
+
+    // 角度为 16.16 定点数
+    let fixed =
+        |degrees: Option<f64>| ((degrees.unwrap_or(0.0) * 65536.0).round() as i32).to_be_bytes();
+    let mut prhd = vec![0; 4];
+    prhd.extend(fixed(metadata.yaw));
+    prhd.extend(fixed(metadata.pitch));
+    prhd.extend(fixed(metadata.roll));
+
+    let projection = match metadata.projection {
+        // 四个方向的裁剪边界均为 0，表示完整球面
+        Projection::Equirectangular => Atom::raw(b"equi", vec![0; 4 + 16]),
+        // 默认布局，无边距
+        Projection::Cubemap => Atom::raw(b"cbmp", vec![0; 4 + 8]),
+    };
+
+    Atom {
+        kind: *b"sv3d",
+        payload: Payload::Container {
+            prefix: Vec::new(),
+            children: vec![
+                Atom::raw(b"svhd", svhd),
+                Atom {
+                    kind: *b"proj",
+                    payload: Payload::Container {
+                        prefix: Vec::new(),
+                        children: vec![Atom::raw(b"prhd", prhd), projection],
+                    },
+                },
+            ],
+        },
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `mdat` 中的两个块：视频块 `video-chunk-` 和音频块 `audio-chunk`
+    const SAMPLES: &[u8] = b"video-chunk-audio-chunk";
+    const AUDIO_CHUNK: u64 = 12;
+
+    fn boxed(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend(kind);
+        out.extend(body);
+        out
+    }
+
+    /// 音频轨道在前并使用 `co64`，视频轨道使用 `stco`，`avc1` 中带有 `extra` 子盒子
+    fn moov(video_offset: u64, audio_offset: u64, extra: &[u8]) -> Vec<u8> {
+        let track = |handler: &[u8; 4], entry: Vec<u8>, table: Vec<u8>| {
+            let hdlr = [&[0; 8][..], handler, &[0; 12]].concat();
+            let stsd = [vec![0, 0, 0, 0, 0, 0, 0, 1], entry].concat();
+            let stbl = [boxed(b"stsd", &stsd), table].concat();
+            let mdia = [
+                boxed(b"hdlr", &hdlr),
+                boxed(b"minf", &boxed(b"stbl", &stbl)),
+            ]
+            .concat();
+            boxed(b"trak", &boxed(b"mdia", &mdia))
+        };
+        let stco = [
+            &[0, 0, 0, 0, 0, 0, 0, 1][..],
+            &(video_offset as u32).to_be_bytes(),
+        ]
+        .concat();
+        let co64 = [&[0, 0, 0, 0, 0, 0, 0, 1][..], &audio_offset.to_be_bytes()].concat();
+        let body = [
+            track(b"soun", boxed(b"mp4a", &[0; 28]), boxed(b"co64", &co64)),
+            track(
+                b"vide",
+                boxed(b"avc1", &[&[0; 78][..], extra].concat()),
+                boxed(b"stco", &stco),
+            ),
+        ]
+        .concat();
+        boxed(b"moov", &body)
+    }
+
+    /// 最小的 MP4：`ftyp`，以及按 `moov_first` 排列的 `moov` 和 `mdat`
+    ///
+    /// `moov` 在后时 `mdat` 使用 64 位大小，与超过 4 GB 的录像相同。
+    fn mp4(moov_first: bool, extra: &[u8]) -> Vec<u8> {
+        let ftyp = boxed(b"ftyp", b"isom\0\0\0\0isom");
+        let mdat = if moov_first {
+            boxed(b"mdat", SAMPLES)
+        } else {
+            let size = (SAMPLES.len() as u64 + 16).to_be_bytes();
+            [&1u32.to_be_bytes()[..], b"mdat", &size, SAMPLES].concat()
+        };
+        let moov_len = moov(0, 0, extra).len();
+        let data = ftyp.len() + mdat.len() - SAMPLES.len() + if moov_first { moov_len } else { 0 };
+        let moov = moov(data as u64, data as u64 + AUDIO_CHUNK, extra);
+
+        if moov_first {
+            [ftyp, moov, mdat].concat()
+        } else {
+            [ftyp, mdat, moov].concat()
+        }
+    }
+
+    fn write_temp(data: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("spherical-{}.mp4", uuid::Uuid::new_v4()));
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn parse_moov(data: &[u8]) -> Atom {
+        parse_atoms(data)
+            .unwrap()
+            .into_iter()
+            .find(|atom| &atom.kind == b"moov")
+            .unwrap()
+    }
+
+    /// 按轨道顺序列出的块偏移
+    fn chunk_offsets(moov: &Atom) -> Vec<u64> {
+        let mut offsets = Vec::new();
+        for trak in moov.children() {
+            let stbl = trak
+                .child(b"mdia")
+                .and_then(|mdia| mdia.child(b"minf"))
+                .and_then(|minf| minf.child(b"stbl"))
+                .unwrap();
+            for table in stbl.children() {
+                let Payload::Raw(body) = &table.payload else {
+                    continue;
+                };
+                match &table.kind {
+                    b"stco" => {
+                        offsets.push(u32::from_be_bytes(body[8..12].try_into().unwrap()) as u64)
+                    }
+                    b"co64" => offsets.push(u64::from_be_bytes(body[8..16].try_into().unwrap())),
+                    _ => {}
+                }
+            }
+        }
+        offsets
+    }
+
+    /// 注入后视频采样描述的子盒子
+    fn sample_entry_children(moov: &mut Atom) -> Vec<([u8; 4], Vec<u8>)> {
+        let entry = video_sample_entry(moov).unwrap();
+        let mut children = Vec::new();
+        for child in entry.children() {
+            let mut encoded = Vec::new();
+            child.write(&mut encoded);
+            children.push((child.kind, encoded[8..].to_vec()));
+        }
+        children
+    }
+
+    /// 块偏移仍然指向原来的视频块和音频块
+    fn assert_chunks(data: &[u8]) {
+        let offsets = chunk_offsets(&parse_moov(data));
+        assert_eq!(offsets.len(), 2);
+        let (audio, video) = (offsets[0] as usize, offsets[1] as usize);
+        assert_eq!(&data[video..video + AUDIO_CHUNK as usize], b"video-chunk-");
+        assert_eq!(&data[audio..audio + 11], b"audio-chunk");
+    }
+
+    fn inject(data: &[u8], stereo: StereoMode) -> Vec<u8> {
+        let path = write_temp(data);
+        let metadata = SphericalMetadata {
+            stereo,
+            yaw: Some(90.0),
+            ..Default::default()
+        };
+        inject_spherical(&path, &metadata).unwrap();
+        let result = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        result
+    }
+
+    #[test]
+    fn moov_before_mdat_shifts_chunk_offsets() {
+        let original = mp4(true, &[]);
+        assert_chunks(&original);
+
+        let injected = inject(&original, StereoMode::Mono);
+        assert!(injected.len() > original.len());
+        assert_chunks(&injected);
+
+        let kinds: Vec<_> = sample_entry_children(&mut parse_moov(&injected))
+            .into_iter()
+            .map(|(kind, _)| kind)
+            .collect();
+        assert_eq!(kinds, [*b"st3d", *b"sv3d"]);
+    }
+
+    #[test]
+    fn moov_after_mdat_keeps_chunk_offsets() {
+        let original = mp4(false, &[]);
+        let injected = inject(&original, StereoMode::LeftRight);
+
+        assert_eq!(
+            chunk_offsets(&parse_moov(&injected)),
+            chunk_offsets(&parse_moov(&original))
+        );
+        assert_chunks(&injected);
+        // `moov` 之前的内容原样保留
+        let moov_start = original.len() - moov(0, 0, &[]).len();
+        assert_eq!(injected[..moov_start], original[..moov_start]);
+    }
+
+    #[test]
+    fn existing_spherical_boxes_are_replaced() {
+        let extra = [
+            boxed(b"pasp", &[0, 0, 0, 1, 0, 0, 0, 1]),
+            boxed(b"st3d", &[0, 0, 0, 0, 0]),
+            boxed(b"sv3d", &boxed(b"svhd", &[0; 5])),
+        ]
+        .concat();
+        let original = mp4(true, &extra);
+
+        let first = inject(&original, StereoMode::TopBottom);
+        assert_chunks(&first);
+        let children = sample_entry_children(&mut parse_moov(&first));
+        let kinds: Vec<_> = children.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, [*b"pasp", *b"st3d", *b"sv3d"]);
+        assert_eq!(children[1].1, [0, 0, 0, 0, 1]);
+
+        // 再次注入只替换，不会重复添加
+        let second = inject(&first, StereoMode::LeftRight);
+        assert_eq!(second.len(), first.len());
+        assert_chunks(&second);
+        let children = sample_entry_children(&mut parse_moov(&second));
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[1].1, [0, 0, 0, 0, 2]);
+    }
+}
@@ -0,0 +1,295 @@
+//! 音画同步：偏移测量和替换音轨
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+use tokio::io::AsyncReadExt;
+
+use super::{stderr_tail, to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::{ExecuteRequest, ExecuteResponse};
+
+/// 解码采样率
+const SAMPLE_RATE: usize = 8000;
+/// 粗略搜索的包络采样率（10 ms 精度）
+const COARSE_RATE: usize = 100;
+/// 精细搜索的包络采样率（1 ms 精度）
+const FINE_RATE: usize = 1000;
+
+/// 同步偏移测量请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasureSyncRequest {
+    /// 参考文件（通常是带现场声音的视频）
+    pub reference: String,
+    /// 需要对齐的文件（通常是单独录制的音频）
+    pub target: String,
+    /// 从第几秒开始分析，默认 0
+    pub start_seconds: Option<f64>,
+    /// 分析的时长（秒），默认 60
+    pub window_seconds: Option<f64>,
+    /// 搜索的最大偏移（毫秒），默认 10000
+    pub max_offset_ms: Option<u32>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 同步偏移测量结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOffset {
+    /// 需要施加在 `target` 上的偏移（毫秒），正数表示延后，可以直接用于 `replace_audio` 的 `offset_ms`
+    pub offset_ms: i64,
+    /// 相关系数（0-1），越高越可信，低于 0.3 时结果通常不可靠
+    pub confidence: f32,
+}
+
+/// 替换音轨选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceAudioOptions {
+    /// 音频偏移（毫秒），正数表示延后，负数表示提前（裁掉音频开头），可以直接使用 `measure_sync_offset` 的结果
+    pub offset_ms: Option<i64>,
+    /// 以较短的一路为准结束输出
+    #[serde(default)]
+    pub trim_to_shortest: bool,
+    /// 音频编码器，默认 `aac`，传入 `copy` 可直接复制
+    pub audio_codec: Option<String>,
+}
+
+/// 替换音轨请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceAudioRequest {
+    /// 视频文件，其中的音轨会被丢弃
+    pub video: String,
+    /// 新的音频文件
+    pub audio: String,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: ReplaceAudioOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 用外部音频替换视频的音轨，视频流直接复制
+    pub async fn replace_audio(&self, request: ReplaceAudioRequest) -> Result<ExecuteResponse> {
+        let options = &request.options;
+        let mut args = to_args(["-i", &request.video]);
+
+        // 延后用 -itsoffset，提前则直接跳过音频开头，避免产生负时间戳
+        match options.offset_ms.unwrap_or(0) {
+            0 => {}
+            offset if offset > 0 => {
+                args.extend(to_args(["-itsoffset".to_string(), seconds(offset)]))
+            }
+            offset => args.extend(to_args(["-ss".to_string(), seconds(-offset)])),
+        }
+        args.extend(to_args([
+            "-i",
+            &request.audio,
+            "-map",
+            "0:v",
+            "-map",
+            "1:a:0",
+            "-c:v",
+            "copy",
+            "-c:a",
+            options.audio_codec.as_deref().unwrap_or("aac"),
+        ]));
+        if options.trim_to_shortest {
+            args.push("-shortest".to_string());
+        }
+        args.push(request.output.clone());
+
+        self.run_helper(args, &request.job).await
+    }
+
+    /// 将两个输入解码为 PCM，对音量包络做互相关，估算两者之间的同步偏移
+    pub async fn measure_sync_offset(&self, request: MeasureSyncRequest) -> Result<SyncOffset> {
+        let start = request.start_seconds.unwrap_or(0.0).max(0.0);
+        let window = request.window_seconds.unwrap_or(60.0).max(1.0);
+        let max_offset_ms = request.max_offset_ms.unwrap_or(10_000) as i64;
+
+        // 目标多解码最大偏移的长度，保证两个方向都能对齐
+        let margin = max_offset_ms as f64 / 1000.0;
+        let (reference, target) = tokio::try_join!(
+            self.decode_pcm(&request.reference, start, window, &request.job),
+            self.decode_pcm(
+                &request.target,
+                (start - margin).max(0.0),
+                window + margin * 2.0,
+                &request.job,
+            ),
+        )?;
+        let target_start_ms = (((start - margin).max(0.0) - start) * 1000.0).round() as i64;
+
+        tokio::task::spawn_blocking(move || {
+            estimate_offset(&reference, &target, target_start_ms, max_offset_ms)
+        })
+        .await
+        .map_err(|e| Error::CommandExecution(e.to_string()))?
+        .ok_or_else(|| Error::CommandExecution("Not enough audio to measure sync".to_string()))
+    }
+
+    /// 解码为 8 kHz 单声道 16 位 PCM
+    async fn decode_pcm(
+        &self,
+        input: &str,
+        start: f64,
+        duration: f64,
+        job: &HelperOptions,
+    ) -> Result<Vec<i16>> {
+        let start = start.to_string();
+        let duration = duration.to_string();
+        let sample_rate = SAMPLE_RATE.to_string();
+        let args = to_args([
+            "-nostdin",
+            "-v",
+            "error",
+            "-ss",
+            &start,
+            "-t",
+            &duration,
+            "-i",
+            input,
+            "-vn",
+            "-ac",
+            "1",
+            "-ar",
+            &sample_rate,
+            "-f",
+            "s16le",
+            "pipe:1",
+        ]);
+
+        let mut child = self
+            .spawn_raw(ExecuteRequest {
+                args,
+                wait_for_install: job.wait_for_install,
+                ..Default::default()
+            })
+            .await?;
+        drop(child.stdin());
+
+        let mut stdout = child.stdout().expect("stdout is piped");
+        let mut stderr = child.stderr().expect("stderr is piped");
+        let mut pcm = Vec::new();
+        let mut errors = Vec::new();
+        let (read_stdout, read_stderr) = tokio::join!(
+            stdout.read_to_end(&mut pcm),
+            stderr.read_to_end(&mut errors)
+        );
+        read_stdout?;
+        read_stderr?;
+
+        if !child.wait().await?.success() {
+            return Err(Error::CommandExecution(stderr_tail(
+                &String::from_utf8_lossy(&errors),
+            )));
+        }
+
+        Ok(pcm
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect())
+    }
+}
+
+/// 毫秒转换为 FFmpeg 的时间参数
+fn seconds(ms: i64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// 先以 10 ms 精度搜索整个范围，再在峰值附近以 1 ms 精度细化
+///
+/// `target_start_ms` 是目标片段相对参考片段的起始时间（通常为负数），
+/// 返回的偏移已换算为在目标文件上需要施加的偏移。
+fn estimate_offset(
+    reference: &[i16],
+    target: &[i16],
+    target_start_ms: i64,
+    max_offset_ms: i64,
+) -> Option<SyncOffset> {
+    let coarse_scale = (1000 / COARSE_RATE) as i64;
+    let coarse_ref = envelope(reference, SAMPLE_RATE / COARSE_RATE);
+    let coarse_target = envelope(target, SAMPLE_RATE / COARSE_RATE);
+
+    // lag 为参考片段第 i 个包络点对应目标片段的第 i + lag 个点
+    let center = -target_start_ms / coarse_scale;
+    let radius = max_offset_ms / coarse_scale;
+    let (coarse_lag, _) = best_lag(
+        &coarse_ref,
+        &coarse_target,
+        center - radius,
+        center + radius,
+    )?;
+
+    let fine_ref = envelope(reference, SAMPLE_RATE / FINE_RATE);
+    let fine_target = envelope(target, SAMPLE_RATE / FINE_RATE);
+    let fine_center = coarse_lag * coarse_scale;
+    let (fine_lag, confidence) = best_lag(
+        &fine_ref,
+        &fine_target,
+        fine_center - coarse_scale * 2,
+        fine_center + coarse_scale * 2,
+    )?;
+
+    // 目标中的内容比参考晚 delay 毫秒出现，需要提前同样的时间
+    let delay_ms = fine_lag + target_start_ms;
+
+    Some(SyncOffset {
+        offset_ms: -delay_ms,
+        confidence: confidence.max(0.0),
+    })
+}
+
+/// 计算音量包络并标准化（零均值、单位方差）
+fn envelope(samples: &[i16], block: usize) -> Vec<f32> {
+    let mut envelope: Vec<f32> = samples
+        .chunks(block)
+        .map(|chunk| chunk.iter().map(|s| (*s as f32).abs()).sum::<f32>() / chunk.len() as f32)
+        .collect();
+
+    let len = envelope.len().max(1) as f32;
+    let mean = envelope.iter().sum::<f32>() / len;
+    let variance = envelope.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / len;
+    let std = variance.sqrt().max(f32::EPSILON);
+
+    for value in &mut envelope {
+        *value = (*value - mean) / std;
+    }
+    envelope
+}
+
+/// 在 `[min_lag, max_lag]` 内寻找相关系数最高的位移
+fn best_lag(reference: &[f32], target: &[f32], min_lag: i64, max_lag: i64) -> Option<(i64, f32)> {
+    let mut best: Option<(i64, f32)> = None;
+    // 重叠太少时相关系数不可信
+    let min_overlap = (reference.len() / 2).max(1);
+
+    for lag in min_lag..=max_lag {
+        let mut sum = 0.0;
+        let mut overlap = 0;
+        for (i, value) in reference.iter().enumerate() {
+            let j = i as i64 + lag;
+            if j < 0 || j >= target.len() as i64 {
+                continue;
+            }
+            sum += value * target[j as usize];
+            overlap += 1;
+        }
+        if overlap < min_overlap {
+            continue;
+        }
+
+        let score = sum / overlap as f32;
+        if best.map_or(true, |(_, best)| score > best) {
+            best = Some((lag, score));
+        }
+    }
+
+    best
+}
@@ -0,0 +1,215 @@
+//! 文字叠加（drawtext）
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{escape_filter_value, to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::Result;
+use crate::models::ExecuteResponse;
+
+/// 各平台常见的默认字体，按顺序查找第一个存在的文件
+#[cfg(target_os = "macos")]
+const DEFAULT_FONTS: &[&str] = &[
+    "/System/Library/Fonts/Helvetica.ttc",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/System/Library/Fonts/PingFang.ttc",
+];
+
+#[cfg(target_os = "windows")]
+const DEFAULT_FONTS: &[&str] = &[
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\msyh.ttc",
+];
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/liberation-sans/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/local/share/fonts/dejavu/DejaVuSans.ttf",
+];
+
+/// 文字位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextPosition {
+    TopLeft,
+    Top,
+    TopRight,
+    Center,
+    BottomLeft,
+    #[default]
+    Bottom,
+    BottomRight,
+}
+
+/// 文字背景框
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextBox {
+    /// 背景颜色，默认 `black@0.5`
+    pub color: Option<String>,
+    /// 文字与边框的间距（像素），默认 10
+    pub padding: Option<u32>,
+}
+
+/// 文字叠加选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrawTextOptions {
+    /// 要绘制的文字，不会展开 `%{...}` 等占位符
+    pub text: String,
+    /// 字体文件路径或字体名称（需要 FFmpeg 支持 fontconfig），未设置时使用插件配置或系统默认字体
+    pub font: Option<String>,
+    /// 字号，默认 48
+    pub size: Option<u32>,
+    /// 文字颜色，默认 `white`
+    pub color: Option<String>,
+    /// 预设位置，默认底部居中
+    pub position: Option<TextPosition>,
+    /// 与画面边缘的距离（像素），默认 20
+    pub margin: Option<u32>,
+    /// 自定义横坐标表达式，覆盖 `position`
+    pub x: Option<String>,
+    /// 自定义纵坐标表达式，覆盖 `position`
+    pub y: Option<String>,
+    /// 背景框，未设置时不绘制
+    #[serde(rename = "box")]
+    pub text_box: Option<TextBox>,
+}
+
+/// 文字叠加请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrawTextRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: DrawTextOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 字体来源
+enum Font {
+    File(PathBuf),
+    Family(String),
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 在视频上叠加文字，音频直接复制
+    pub async fn draw_text(&self, request: DrawTextRequest) -> Result<ExecuteResponse> {
+        let filter = drawtext_filter(&request.options, self.resolve_font(&request.options));
+
+        let args = to_args([
+            "-i",
+            &request.input,
+            "-vf",
+            &filter,
+            "-c:a",
+            "copy",
+            &request.output,
+        ]);
+
+        self.run_helper(args, &request.job).await
+    }
+
+    /// 查找字体：请求中的字体 > 插件配置的 `defaultFont` > 系统默认字体 > fontconfig 的 `Sans`
+    fn resolve_font(&self, options: &DrawTextOptions) -> Font {
+        let font = options
+            .font
+            .as_deref()
+            .or(self.config().default_font.as_deref());
+
+        match font {
+            Some(font) if is_font_file(font) => Font::File(PathBuf::from(font)),
+            Some(font) => Font::Family(font.to_string()),
+            None => DEFAULT_FONTS
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.is_file())
+                .map(Font::File)
+                .unwrap_or_else(|| Font::Family("Sans".to_string())),
+        }
+    }
+}
+
+/// 判断字体参数是文件路径还是字体名称
+fn is_font_file(font: &str) -> bool {
+    let path = Path::new(font);
+    path.is_file()
+        || path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ["ttf", "otf", "ttc"]
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            })
+}
+
+/// 生成 drawtext 滤镜
+fn drawtext_filter(options: &DrawTextOptions, font: Font) -> String {
+    let margin = options.margin.unwrap_or(20);
+    let (x, y) = position_expr(options.position.unwrap_or_default(), margin);
+    let x = options.x.clone().unwrap_or(x);
+    let y = options.y.clone().unwrap_or(y);
+
+    let mut params = vec![match font {
+        Font::File(path) => format!("fontfile={}", escape_filter_value(&path.to_string_lossy())),
+        Font::Family(family) => format!("font={}", escape_filter_value(&family)),
+    }];
+
+    params.push(format!("text={}", escape_filter_value(&options.text)));
+    params.push("expansion=none".to_string());
+    params.push(format!("fontsize={}", options.size.unwrap_or(48)));
+    params.push(format!(
+        "fontcolor={}",
+        escape_filter_value(options.color.as_deref().unwrap_or("white"))
+    ));
+    params.push(format!("x={}", escape_filter_value(&x)));
+    params.push(format!("y={}", escape_filter_value(&y)));
+
+    if let Some(text_box) = &options.text_box {
+        params.push("box=1".to_string());
+        params.push(format!(
+            "boxcolor={}",
+            escape_filter_value(text_box.color.as_deref().unwrap_or("black@0.5"))
+        ));
+        params.push(format!("boxborderw={}", text_box.padding.unwrap_or(10)));
+    }
+
+    format!("drawtext={}", params.join(":"))
+}
+
+/// 预设位置对应的坐标表达式
+fn position_expr(position: TextPosition, margin: u32) -> (String, String) {
+    let left = margin.to_string();
+    let center_x = "(w-text_w)/2".to_string();
+    let right = format!("w-text_w-{margin}");
+    let top = margin.to_string();
+    let center_y = "(h-text_h)/2".to_string();
+    let bottom = format!("h-text_h-{margin}");
+
+    match position {
+        TextPosition::TopLeft => (left, top),
+        TextPosition::Top => (center_x, top),
+        TextPosition::TopRight => (right, top),
+        TextPosition::Center => (center_x, center_y),
+        TextPosition::BottomLeft => (left, bottom),
+        TextPosition::Bottom => (center_x, bottom),
+        TextPosition::BottomRight => (right, bottom),
+    }
+}
@@ -0,0 +1,210 @@
+//! 缩略图：自动挑选有代表性的画面
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+use tokio::io::AsyncReadExt;
+
+use super::{stderr_tail, to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::ExecuteRequest;
+
+/// 评分时缩小到的分辨率
+const SCORE_WIDTH: usize = 160;
+const SCORE_HEIGHT: usize = 90;
+/// 平均亮度低于该值视为黑屏
+const MIN_BRIGHTNESS: f64 = 24.0;
+/// 平均亮度高于该值视为白屏
+const MAX_BRIGHTNESS: f64 = 232.0;
+
+/// 智能缩略图选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartThumbnailOptions {
+    /// 候选画面数量，默认 20
+    pub samples: Option<u32>,
+    /// 输出宽度（像素），高度按比例计算，未设置时使用原始尺寸
+    pub width: Option<u32>,
+    /// 跳过开头和结尾的比例（0-0.5），避开片头和片尾，默认 0.05
+    pub margin: Option<f64>,
+}
+
+/// 智能缩略图请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartThumbnailRequest {
+    /// 输入视频
+    pub input: String,
+    /// 输出图片（如 `.jpg`、`.png`、`.webp`）
+    pub output: String,
+    #[serde(flatten)]
+    pub options: SmartThumbnailOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 智能缩略图结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartThumbnail {
+    pub job_id: String,
+    /// 输出图片路径
+    pub path: String,
+    /// 选中画面的时间（秒）
+    pub time: f64,
+}
+
+/// 候选画面的评分
+struct FrameScore {
+    time: f64,
+    /// 是否不是黑屏/白屏/纯色画面
+    usable: bool,
+    score: f64,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 在均匀分布的候选画面中选出最清晰、非黑屏的一帧作为缩略图
+    ///
+    /// 每个候选画面缩小为灰度图后在 Rust 中评分：排除过暗、过亮和几乎纯色的画面，
+    /// 再按拉普拉斯方差（清晰度）和对比度排序。
+    pub async fn smart_thumbnail(&self, request: SmartThumbnailRequest) -> Result<SmartThumbnail> {
+        let options = &request.options;
+        let duration = self
+            .media_duration(&request.input)
+            .await
+            .ok_or_else(|| Error::CommandExecution("Unable to read input duration".to_string()))?;
+
+        let samples = options.samples.unwrap_or(20).max(1);
+        let margin = options.margin.unwrap_or(0.05).clamp(0.0, 0.49);
+        let start = duration * margin;
+        let span = duration * (1.0 - 2.0 * margin);
+
+        let mut best: Option<FrameScore> = None;
+        for index in 0..samples {
+            let time = start + span * (index as f64 + 0.5) / samples as f64;
+            let Some(frame) = self.score_frame(&request.input, time, &request.job).await? else {
+                continue;
+            };
+            let better = best.as_ref().map_or(true, |best| {
+                (frame.usable, frame.score) > (best.usable, best.score)
+            });
+            if better {
+                best = Some(frame);
+            }
+        }
+
+        let time = best.map(|frame| frame.time).unwrap_or(start);
+        let seek = time.to_string();
+        let mut args = to_args(["-ss", &seek, "-i", &request.input, "-frames:v", "1"]);
+        if let Some(width) = options.width {
+            args.extend(to_args(["-vf", &format!("scale={width}:-2")]));
+        }
+        args.extend(to_args(["-update", "1", &request.output]));
+
+        let response = self.run_helper(args, &request.job).await?;
+
+        Ok(SmartThumbnail {
+            job_id: response.job_id,
+            path: request.output,
+            time,
+        })
+    }
+
+    /// 解码指定时间的画面并评分，超出文件末尾没有画面时返回 None
+    async fn score_frame(
+        &self,
+        input: &str,
+        time: f64,
+        job: &HelperOptions,
+    ) -> Result<Option<FrameScore>> {
+        let seek = time.to_string();
+        let filter = format!("scale={SCORE_WIDTH}:{SCORE_HEIGHT},format=gray");
+        let args = to_args([
+            "-nostdin",
+            "-v",
+            "error",
+            "-ss",
+            &seek,
+            "-i",
+            input,
+            "-frames:v",
+            "1",
+            "-vf",
+            &filter,
+            "-f",
+            "rawvideo",
+            "pipe:1",
+        ]);
+
+        let mut child = self
+            .spawn_raw(ExecuteRequest {
+                args,
+                wait_for_install: job.wait_for_install,
+                ..Default::default()
+            })
+            .await?;
+        drop(child.stdin());
+
+        let mut stdout = child.stdout().expect("stdout is piped");
+        let mut stderr = child.stderr().expect("stderr is piped");
+        let mut pixels = Vec::new();
+        let mut errors = Vec::new();
+        let (read_stdout, read_stderr) = tokio::join!(
+            stdout.read_to_end(&mut pixels),
+            stderr.read_to_end(&mut errors)
+        );
+        read_stdout?;
+        read_stderr?;
+
+        if !child.wait().await?.success() {
+            return Err(Error::CommandExecution(stderr_tail(
+                &String::from_utf8_lossy(&errors),
+            )));
+        }
+
+        if pixels.len() < SCORE_WIDTH * SCORE_HEIGHT {
+            return Ok(None);
+        }
+
+        Ok(Some(score_pixels(
+            time,
+            &pixels[..SCORE_WIDTH * SCORE_HEIGHT],
+        )))
+    }
+}
+
+/// 根据亮度、对比度和清晰度为灰度画面评分
+fn score_pixels(time: f64, pixels: &[u8]) -> FrameScore {
+    let count = pixels.len() as f64;
+    let mean = pixels.iter().map(|&p| p as f64).sum::<f64>() / count;
+    let contrast = (pixels
+        .iter()
+        .map(|&p| (p as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count)
+        .sqrt();
+
+    // 拉普拉斯方差，数值越大画面越清晰
+    let mut laplacians = Vec::with_capacity((SCORE_WIDTH - 2) * (SCORE_HEIGHT - 2));
+    for y in 1..SCORE_HEIGHT - 1 {
+        for x in 1..SCORE_WIDTH - 1 {
+            let at = |dx: isize, dy: isize| {
+                let index = (y as isize + dy) as usize * SCORE_WIDTH + (x as isize + dx) as usize;
+                pixels[index] as f64
+            };
+            laplacians.push(at(-1, 0) + at(1, 0) + at(0, -1) + at(0, 1) - 4.0 * at(0, 0));
+        }
+    }
+    let lap_mean = laplacians.iter().sum::<f64>() / laplacians.len() as f64;
+    let sharpness = laplacians
+        .iter()
+        .map(|value| (value - lap_mean).powi(2))
+        .sum::<f64>()
+        / laplacians.len() as f64;
+
+    FrameScore {
+        time,
+        usable: (MIN_BRIGHTNESS..=MAX_BRIGHTNESS).contains(&mean) && contrast >= 10.0,
+        score: sharpness.sqrt() + contrast,
+    }
+}
@@ -0,0 +1,570 @@
+//! 转码
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::spherical::{inject_spherical, supports_spherical};
+use super::{to_args, CropRect, DetectCropRequest, HelperOptions, ProbeRequest, SphericalMetadata};
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::models::ExecuteResponse;
+
+/// 去隔行滤镜
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Deinterlace {
+    /// 速度快，适合大多数素材
+    Yadif,
+    /// 质量更好，速度稍慢
+    Bwdif,
+}
+
+/// 视频降噪滤镜
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoDenoise {
+    /// 速度快的时空降噪
+    Hqdn3d,
+    /// 非局部均值降噪，质量高但很慢
+    Nlmeans,
+}
+
+/// 同时设置宽高时的缩放方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScaleFit {
+    /// 直接拉伸到目标尺寸
+    #[default]
+    Stretch,
+    /// 保持比例缩放到目标尺寸以内，空白处补黑边
+    Contain,
+    /// 保持比例缩放到铺满目标尺寸，超出部分裁掉
+    Cover,
+}
+
+/// MP4 / MOV 的 `-movflags` 预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MovFlags {
+    /// 将 `moov` 移到文件开头，便于边下载边播放
+    Faststart,
+    /// 分片 MP4，可直接用于 `<video>` 的 MSE 播放
+    Fragmented,
+    /// 符合 CMAF 的分片 MP4，用于 HLS / DASH
+    Cmaf,
+}
+
+impl MovFlags {
+    fn value(self) -> &'static str {
+        match self {
+            MovFlags::Faststart => "+faststart",
+            MovFlags::Fragmented => "+frag_keyframe+empty_moov+default_base_moof",
+            MovFlags::Cmaf => "+cmaf+frag_keyframe+empty_moov+default_base_moof",
+        }
+    }
+}
+
+/// 转码选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodeOptions {
+    /// 内置配置的名称（见 `list_builtin_profiles`），显式设置的选项优先于配置
+    pub profile: Option<String>,
+    /// 视频编码器（如 `libx264`、`libx265`、`libsvtav1`），`copy` 表示直接复制
+    pub video_codec: Option<String>,
+    /// 音频编码器（如 `aac`、`libopus`），`copy` 表示直接复制
+    pub audio_codec: Option<String>,
+    /// 恒定质量（`-crf`）
+    pub crf: Option<u32>,
+    /// 编码器预设（`-preset`）
+    pub preset: Option<String>,
+    /// 视频码率（如 `5M`）
+    pub video_bitrate: Option<String>,
+    /// 音频码率（如 `192k`）
+    pub audio_bitrate: Option<String>,
+    /// 输出宽度，只设置宽或高时按比例缩放
+    pub width: Option<u32>,
+    /// 输出高度
+    pub height: Option<u32>,
+    /// 同时设置宽高时的缩放方式，默认拉伸
+    pub fit: Option<ScaleFit>,
+    /// 输出帧率
+    pub fps: Option<f64>,
+    /// 像素格式（如 `yuv420p`）
+    pub pixel_format: Option<String>,
+    /// 去隔行
+    pub deinterlace: Option<Deinterlace>,
+    /// 降噪
+    pub denoise: Option<VideoDenoise>,
+    /// 去色带（deband）
+    #[serde(default)]
+    pub deband: bool,
+    /// 自动检测并裁掉黑边
+    #[serde(default)]
+    pub auto_crop: bool,
+    /// 将 HDR（PQ / HLG）色调映射为 SDR BT.709，需要 FFmpeg 编译了 libzimg
+    #[serde(default)]
+    pub tonemap_to_sdr: bool,
+    /// 保留 HDR：沿用输入的色彩标记，使用 `libx265` 时同时写入 HDR10 母版显示和亮度元数据
+    #[serde(default)]
+    pub preserve_hdr: bool,
+    /// 输出的色彩原色标记（`-color_primaries`，如 `bt709`、`bt2020`）
+    pub color_primaries: Option<String>,
+    /// 输出的传递特性标记（`-color_trc`，如 `bt709`、`smpte2084`、`arib-std-b67`）
+    pub color_trc: Option<String>,
+    /// 输出的色彩矩阵标记（`-colorspace`，如 `bt709`、`bt2020nc`）
+    pub colorspace: Option<String>,
+    /// 保留输入中的全景视频元数据（`-strict unofficial`）
+    #[serde(default)]
+    pub preserve_spherical: bool,
+    /// 编码完成后写入全景视频元数据，只支持 MP4 / MOV 输出，设置后忽略 `preserve_spherical`
+    pub spherical: Option<SphericalMetadata>,
+    /// MP4 / MOV 的 `-movflags` 预设，`extra_args` 中再设置 `-movflags` 会覆盖预设
+    pub movflags: Option<MovFlags>,
+    /// 分片时长（秒），配合 `fragmented` / `cmaf` 使用，在达到时长后的第一个关键帧处切分
+    pub frag_duration: Option<f64>,
+    /// 追加在输出文件之前的自定义参数
+    ///
+    /// 只能从 Rust 端设置，前端传入的 `extraArgs` 被忽略，只有 `helpers` 权限的窗口无法借此传入任意参数。
+    #[serde(default, skip_deserializing)]
+    pub extra_args: Vec<String>,
+}
+
+/// 转码请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodeRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: TranscodeOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 多输出转码中的一个输出
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodeOutput {
+    /// 输出文件
+    pub output: String,
+    #[serde(flatten)]
+    pub options: TranscodeOptions,
+}
+
+/// 多输出转码请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodeMultiRequest {
+    /// 输入文件
+    pub input: String,
+    /// 各个输出及其转码选项
+    pub outputs: Vec<TranscodeOutput>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 按选项转码，预处理滤镜按去隔行、裁剪、色调映射、降噪、去色带、缩放的顺序应用
+    pub async fn transcode(&self, request: TranscodeRequest) -> Result<ExecuteResponse> {
+        self.transcode_multi(TranscodeMultiRequest {
+            input: request.input,
+            outputs: vec![TranscodeOutput {
+                output: request.output,
+                options: request.options,
+            }],
+            job: request.job,
+        })
+        .await
+    }
+
+    /// 在一次 FFmpeg 调用中生成多个输出，输入只解码一次
+    ///
+    /// 需要检测黑边时只检测一次，所有设置了 `auto_crop` 的输出共用结果。
+    pub async fn transcode_multi(&self, request: TranscodeMultiRequest) -> Result<ExecuteResponse> {
+        if request.outputs.is_empty() {
+            return Err(Error::InvalidArgument("outputs is empty".to_string()));
+        }
+
+        let mut detected: Option<CropRect> = None;
+        let mut args = to_args(["-i", &request.input]);
+        let mut spherical = Vec::new();
+
+        for output in &request.outputs {
+            let mut options = output.options.resolve_profile()?;
+            self.apply_hdr_options(&request.input, &mut options).await?;
+
+            if options.spherical.is_some() && !supports_spherical(&output.output) {
+                return Err(Error::InvalidArgument(
+                    "spherical metadata can only be written to MP4 / MOV".to_string(),
+                ));
+            }
+
+            let crop = match detected {
+                Some(rect)
+                    if options.auto_crop && options.video_codec.as_deref() != Some("copy") =>
+                {
+                    Some(rect)
+                }
+                _ => {
+                    let rect = self
+                        .auto_crop(&request.input, &options, &request.job)
+                        .await?;
+                    detected = detected.or(rect);
+                    rect
+                }
+            };
+
+            args.extend(output_args(&options, crop));
+            args.push(output.output.clone());
+            if let Some(metadata) = options.spherical {
+                spherical.push((PathBuf::from(&output.output), metadata));
+            }
+        }
+
+        let response = self.run_helper(args, &request.job).await?;
+
+        if !spherical.is_empty() {
+            tokio::task::spawn_blocking(move || {
+                spherical
+                    .iter()
+                    .try_for_each(|(path, metadata)| inject_spherical(path, metadata))
+            })
+            .await
+            .map_err(|e| Error::CommandExecution(e.to_string()))??;
+        }
+
+        Ok(response)
+    }
+
+    /// 合并内置配置、补全 HDR 相关选项并检测黑边
+    pub(crate) async fn prepare_transcode(
+        &self,
+        input: &str,
+        options: &TranscodeOptions,
+        job: &HelperOptions,
+    ) -> Result<(TranscodeOptions, Option<CropRect>)> {
+        let mut options = options.resolve_profile()?;
+        self.apply_hdr_options(input, &mut options).await?;
+        let crop = self.auto_crop(input, &options, job).await?;
+        Ok((options, crop))
+    }
+
+    /// 色调映射时默认标记为 BT.709，保留 HDR 时沿用输入的色彩标记
+    async fn apply_hdr_options(&self, input: &str, options: &mut TranscodeOptions) -> Result<()> {
+        if options.video_codec.as_deref() == Some("copy") {
+            return Ok(());
+        }
+
+        if options.tonemap_to_sdr {
+            if !self.has_build_option("libzimg").await {
+                return Err(Error::InvalidArgument(
+                    "tonemapToSdr requires FFmpeg built with libzimg".to_string(),
+                ));
+            }
+            options
+                .color_primaries
+                .get_or_insert_with(|| "bt709".to_string());
+            options.color_trc.get_or_insert_with(|| "bt709".to_string());
+            options
+                .colorspace
+                .get_or_insert_with(|| "bt709".to_string());
+            options
+                .pixel_format
+                .get_or_insert_with(|| "yuv420p".to_string());
+            return Ok(());
+        }
+
+        if !options.preserve_hdr {
+            return Ok(());
+        }
+
+        let probe = self
+            .probe(ProbeRequest {
+                input: input.to_string(),
+            })
+            .await?;
+        if let Some(video) = probe.video() {
+            if options.color_primaries.is_none() {
+                options.color_primaries = video.color_primaries.clone();
+            }
+            if options.color_trc.is_none() {
+                options.color_trc = video.color_transfer.clone();
+            }
+            if options.colorspace.is_none() {
+                options.colorspace = video.color_space.clone();
+            }
+        }
+        options
+            .pixel_format
+            .get_or_insert_with(|| "yuv420p10le".to_string());
+
+        if options.video_codec.as_deref() == Some("libx265") {
+            let mut params = vec!["repeat-headers=1".to_string()];
+            params.extend(self.hdr10_params(input).await);
+            options
+                .extra_args
+                .extend(["-x265-params".to_string(), params.join(":")]);
+        }
+
+        Ok(())
+    }
+
+    /// 从第一帧的附加数据读取 HDR10 静态元数据，转换为 x265 的参数
+    ///
+    /// 色度坐标以 0.00002 为单位，亮度以 0.0001 cd/m² 为单位。
+    async fn hdr10_params(&self, input: &str) -> Vec<String> {
+        let Ok(frames) = self
+            .ffprobe_json::<serde_json::Value>(
+                &[
+                    "-select_streams",
+                    "v:0",
+                    "-read_intervals",
+                    "%+#1",
+                    "-show_entries",
+                    "frame=side_data_list",
+                ],
+                input,
+            )
+            .await
+        else {
+            return Vec::new();
+        };
+
+        let side_data = frames["frames"][0]["side_data_list"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let mut params = Vec::new();
+
+        for data in &side_data {
+            let value = |key: &str| data.get(key).and_then(rational);
+            match data["side_data_type"].as_str() {
+                Some("Mastering display metadata") => {
+                    let chroma: Option<Vec<u64>> = [
+                        "green_x",
+                        "green_y",
+                        "blue_x",
+                        "blue_y",
+                        "red_x",
+                        "red_y",
+                        "white_point_x",
+                        "white_point_y",
+                    ]
+                    .iter()
+                    .map(|key| value(key).map(|v| (v * 50000.0).round() as u64))
+                    .collect();
+                    let luma = |key: &str| value(key).map(|v| (v * 10000.0).round() as u64);
+
+                    if let (Some(c), Some(max), Some(min)) =
+                        (chroma, luma("max_luminance"), luma("min_luminance"))
+                    {
+                        params.push("hdr10=1".to_string());
+                        params.push(format!(
+                            "master-display=G({},{})B({},{})R({},{})WP({},{})L({max},{min})",
+                            c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]
+                        ));
+                    }
+                }
+                Some("Content light level metadata") => {
+                    if let (Some(max_content), Some(max_average)) =
+                        (value("max_content"), value("max_average"))
+                    {
+                        params.push(format!("max-cll={max_content},{max_average}"));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        params
+    }
+
+    /// 设置了 `auto_crop` 时检测黑边
+    pub(crate) async fn auto_crop(
+        &self,
+        input: &str,
+        options: &TranscodeOptions,
+        job: &HelperOptions,
+    ) -> Result<Option<CropRect>> {
+        if !options.auto_crop || options.video_codec.as_deref() == Some("copy") {
+            return Ok(None);
+        }
+
+        let rect = self
+            .detect_crop(DetectCropRequest {
+                input: input.to_string(),
+                sample_seconds: None,
+                job: HelperOptions {
+                    wait_for_install: job.wait_for_install,
+                    ..Default::default()
+                },
+            })
+            .await?;
+
+        Ok(Some(rect))
+    }
+}
+
+/// 输入和输出文件之间的编码参数
+pub(crate) fn output_args(options: &TranscodeOptions, crop: Option<CropRect>) -> Vec<String> {
+    let mut args = Vec::new();
+
+    let filters = video_filters(options, crop);
+    let copy_video = options.video_codec.as_deref() == Some("copy");
+    if !filters.is_empty() && !copy_video {
+        args.extend(to_args(["-vf".to_string(), filters.join(",")]));
+    }
+
+    if let Some(codec) = &options.video_codec {
+        args.extend(to_args(["-c:v", codec]));
+    }
+    if !copy_video {
+        if let Some(crf) = options.crf {
+            args.extend(to_args(["-crf".to_string(), crf.to_string()]));
+        }
+        if let Some(preset) = &options.preset {
+            args.extend(to_args(["-preset", preset]));
+        }
+        if let Some(bitrate) = &options.video_bitrate {
+            args.extend(to_args(["-b:v", bitrate]));
+        }
+        if let Some(fps) = options.fps {
+            args.extend(to_args(["-r".to_string(), fps.to_string()]));
+        }
+        if let Some(format) = &options.pixel_format {
+            args.extend(to_args(["-pix_fmt", format]));
+        }
+        if let Some(primaries) = &options.color_primaries {
+            args.extend(to_args(["-color_primaries", primaries]));
+        }
+        if let Some(trc) = &options.color_trc {
+            args.extend(to_args(["-color_trc", trc]));
+        }
+        if let Some(colorspace) = &options.colorspace {
+            args.extend(to_args(["-colorspace", colorspace]));
+        }
+    }
+
+    // MOV 封装器默认不写入 st3d / sv3d
+    if options.preserve_spherical && options.spherical.is_none() {
+        args.extend(to_args(["-strict", "unofficial"]));
+    }
+
+    if let Some(codec) = &options.audio_codec {
+        args.extend(to_args(["-c:a", codec]));
+    }
+    if let Some(bitrate) = &options.audio_bitrate {
+        args.extend(to_args(["-b:a", bitrate]));
+    }
+
+    if let Some(flags) = options.movflags {
+        args.extend(to_args(["-movflags", flags.value()]));
+    }
+    if let Some(duration) = options.frag_duration {
+        let micros = (duration * 1_000_000.0).round() as u64;
+        args.extend(to_args(["-frag_duration".to_string(), micros.to_string()]));
+    }
+
+    args.extend(options.extra_args.iter().cloned());
+    args
+}
+
+/// 预处理和缩放滤镜，顺序：去隔行 -> 裁剪 -> 色调映射 -> 降噪 -> 去色带 -> 缩放
+fn video_filters(options: &TranscodeOptions, crop: Option<CropRect>) -> Vec<String> {
+    let mut filters = Vec::new();
+
+    match options.deinterlace {
+        Some(Deinterlace::Yadif) => {
+            filters.push("yadif=mode=send_frame:deint=interlaced".to_string())
+        }
+        Some(Deinterlace::Bwdif) => {
+            filters.push("bwdif=mode=send_frame:deint=interlaced".to_string())
+        }
+        None => {}
+    }
+
+    if let Some(crop) = crop {
+        filters.push(crop.filter());
+    }
+
+    // 先转换到线性光再映射，避免直接缩放 PQ / HLG 信号导致画面发灰
+    if options.tonemap_to_sdr {
+        filters.push(
+            "zscale=t=linear:npl=100,format=gbrpf32le,zscale=p=bt709,\
+             tonemap=tonemap=hable:desat=0,zscale=t=bt709:m=bt709:r=tv,format=yuv420p"
+                .to_string(),
+        );
+    }
+
+    match options.denoise {
+        Some(VideoDenoise::Hqdn3d) => filters.push("hqdn3d".to_string()),
+        Some(VideoDenoise::Nlmeans) => filters.push("nlmeans".to_string()),
+        None => {}
+    }
+
+    if options.deband {
+        filters.push("deband".to_string());
+    }
+
+    match (options.width, options.height) {
+        (Some(width), Some(height)) => filters.push(match options.fit.unwrap_or_default() {
+            ScaleFit::Stretch => format!("scale={width}:{height}"),
+            ScaleFit::Contain => format!(
+                "scale={width}:{height}:force_original_aspect_ratio=decrease,\
+                 pad={width}:{height}:(ow-iw)/2:(oh-ih)/2"
+            ),
+            ScaleFit::Cover => format!(
+                "scale={width}:{height}:force_original_aspect_ratio=increase,\
+                 crop={width}:{height}"
+            ),
+        }),
+        (Some(width), None) => filters.push(format!("scale={width}:-2")),
+        (None, Some(height)) => filters.push(format!("scale=-2:{height}")),
+        (None, None) => {}
+    }
+
+    filters
+}
+
+/// 读取 ffprobe 输出的数值，可能是数字或 `34000/50000` 形式的分数
+fn rational(value: &serde_json::Value) -> Option<f64> {
+    if let Some(number) = value.as_f64() {
+        return Some(number);
+    }
+    let text = value.as_str()?;
+    match text.split_once('/') {
+        Some((num, den)) => {
+            let den: f64 = den.parse().ok()?;
+            (den != 0.0).then_some(num.parse::<f64>().ok()? / den)
+        }
+        None => text.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_args_are_not_deserialized() {
+        let options: TranscodeOptions = serde_json::from_value(serde_json::json!({
+            "crf": 23,
+            "extraArgs": ["-filter_complex", "movie=http\\://internal/x"],
+        }))
+        .unwrap();
+        assert_eq!(options.crf, Some(23));
+        assert!(options.extra_args.is_empty());
+
+        let output: TranscodeOutput = serde_json::from_value(serde_json::json!({
+            "output": "out.mp4",
+            "extraArgs": ["-f", "flv", "rtmp://internal/x"],
+        }))
+        .unwrap();
+        assert!(output.options.extra_args.is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! 文件完整性检查
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{stderr_tail, to_args, HelperOptions};
+use crate::desktop::Ffmpeg;
+use crate::error::Result;
+use crate::models::ExecuteRequest;
+use crate::progress::parse_time;
+
+/// 报告中最多保留的错误条数，损坏严重的文件可能产生成千上万条
+const MAX_ERRORS: usize = 500;
+
+/// 完整性检查请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyFileRequest {
+    /// 输入文件
+    pub input: String,
+    /// 同时检查 CRC 和码流语法（`-err_detect`），更严格但会报告更多轻微问题
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 单条解码错误
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodeError {
+    /// 出错时的大致解码位置（秒），在第一次进度输出之前出错时为 None
+    pub time: Option<f64>,
+    /// FFmpeg 输出的错误信息
+    pub message: String,
+}
+
+/// 完整性检查结果
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptionReport {
+    /// 任务 ID
+    pub job_id: String,
+    /// 完整解码且没有任何错误
+    pub ok: bool,
+    /// FFmpeg 是否正常退出，无法打开或读取中断时为 false
+    pub completed: bool,
+    /// 错误总数
+    pub error_count: usize,
+    /// 错误列表，最多保留 500 条
+    pub errors: Vec<DecodeError>,
+    /// 成功解码到的位置（秒）
+    pub decoded_duration: Option<f64>,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 将整个文件解码到 null 封装器，收集解码错误及其大致位置
+    ///
+    /// 文件损坏不会返回错误，而是体现在报告中。
+    pub async fn verify_file(&self, request: VerifyFileRequest) -> Result<CorruptionReport> {
+        let mut args = to_args(["-hide_banner", "-v", "error", "-stats"]);
+        if request.strict {
+            args.extend(to_args(["-err_detect", "crccheck+bitstream+buffer"]));
+        }
+        args.extend(to_args(["-i", &request.input, "-f", "null", "-"]));
+
+        let response = self
+            .execute(ExecuteRequest {
+                args,
+                job_id: request.job.job_id.clone(),
+                wait_for_install: request.job.wait_for_install,
+                batch_id: request.job.batch_id.clone(),
+                tags: request.job.tags.clone(),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut errors = Vec::new();
+        let mut error_count = 0;
+        let mut time = None;
+
+        // 进度行以 \r 结尾，错误行插在进度行之间，出错位置取最近一次进度的时间
+        for line in response.stderr.split(['\r', '\n']).map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with("frame=") || line.starts_with("size=") {
+                if let Some(parsed) = line
+                    .split_once("time=")
+                    .and_then(|(_, rest)| rest.split_whitespace().next())
+                    .and_then(parse_time)
+                {
+                    time = Some(parsed);
+                }
+                continue;
+            }
+
+            error_count += 1;
+            if errors.len() < MAX_ERRORS {
+                errors.push(DecodeError {
+                    time,
+                    message: line.to_string(),
+                });
+            }
+        }
+
+        if !response.success && error_count == 0 {
+            error_count = 1;
+            errors.push(DecodeError {
+                time,
+                message: stderr_tail(&response.stderr),
+            });
+        }
+
+        Ok(CorruptionReport {
+            job_id: response.job_id,
+            ok: response.success && error_count == 0,
+            completed: response.success,
+            error_count,
+            errors,
+            decoded_duration: time,
+        })
+    }
+}
@@ -0,0 +1,189 @@
+//! 音频可视化（波形视频、频谱图）
+
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+
+use super::{escape_filter_value, to_args, HelperOptions, HelperOutput};
+use crate::desktop::Ffmpeg;
+use crate::error::Result;
+use crate::models::ExecuteResponse;
+
+/// 波形视频样式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WaveformStyle {
+    /// 每个采样一条竖线（showwaves `line`）
+    Line,
+    /// 每个采样一个点（showwaves `point`）
+    Point,
+    /// 相邻采样连线（showwaves `p2p`）
+    P2p,
+    /// 居中的竖线（showwaves `cline`）
+    #[default]
+    Centered,
+    /// 滚动频谱（showspectrum）
+    Spectrum,
+}
+
+/// 波形视频选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaveformVideoOptions {
+    /// 样式，默认 `centered`
+    pub style: Option<WaveformStyle>,
+    /// 分辨率，默认 `1280x720`
+    pub resolution: Option<String>,
+    /// 波形颜色，默认 `white`（`spectrum` 样式不使用）
+    pub color: Option<String>,
+    /// 帧率，默认 30
+    pub frame_rate: Option<u32>,
+}
+
+/// 波形视频请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaveformVideoRequest {
+    /// 输入音频
+    pub audio: String,
+    /// 输出视频
+    pub output: String,
+    #[serde(flatten)]
+    pub options: WaveformVideoOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 频谱图幅度刻度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpectrogramScale {
+    Lin,
+    Sqrt,
+    Cbrt,
+    #[default]
+    Log,
+    #[serde(rename = "4thrt")]
+    FourthRoot,
+    #[serde(rename = "5thrt")]
+    FifthRoot,
+}
+
+impl SpectrogramScale {
+    fn as_str(self) -> &'static str {
+        match self {
+            SpectrogramScale::Lin => "lin",
+            SpectrogramScale::Sqrt => "sqrt",
+            SpectrogramScale::Cbrt => "cbrt",
+            SpectrogramScale::Log => "log",
+            SpectrogramScale::FourthRoot => "4thrt",
+            SpectrogramScale::FifthRoot => "5thrt",
+        }
+    }
+}
+
+/// 频谱图选项
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectrogramOptions {
+    /// 图片尺寸（不含坐标轴），默认 `1024x512`
+    pub size: Option<String>,
+    /// 幅度刻度，默认 `log`
+    pub scale: Option<SpectrogramScale>,
+    /// 配色方案（showspectrumpic 的 `color`，如 `intensity`、`viridis`、`magma`），默认 `intensity`
+    pub color_scheme: Option<String>,
+    /// 是否绘制坐标轴和图例，默认 true
+    pub legend: Option<bool>,
+}
+
+/// 频谱图请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectrogramRequest {
+    /// 输入文件
+    pub input: String,
+    /// 输出图片
+    pub output: String,
+    #[serde(flatten)]
+    pub options: SpectrogramOptions,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 将音频渲染为带波形或频谱动画的视频（音频图），视频为 H.264、音频为 AAC
+    pub async fn render_waveform_video(
+        &self,
+        request: WaveformVideoRequest,
+    ) -> Result<ExecuteResponse> {
+        let options = &request.options;
+        let size = escape_filter_value(options.resolution.as_deref().unwrap_or("1280x720"));
+        let rate = options.frame_rate.unwrap_or(30);
+
+        let visual = match options.style.unwrap_or_default() {
+            WaveformStyle::Spectrum => format!(
+                "showspectrum=s={size}:mode=combined:color=intensity:slide=scroll:fps={rate}"
+            ),
+            style => {
+                let mode = match style {
+                    WaveformStyle::Line => "line",
+                    WaveformStyle::Point => "point",
+                    WaveformStyle::P2p => "p2p",
+                    _ => "cline",
+                };
+                let color = escape_filter_value(options.color.as_deref().unwrap_or("white"));
+                format!("showwaves=s={size}:mode={mode}:colors={color}:rate={rate}")
+            }
+        };
+        let filter = format!("[0:a]{visual},format=yuv420p[v]");
+
+        let args = to_args([
+            "-i",
+            &request.audio,
+            "-filter_complex",
+            &filter,
+            "-map",
+            "[v]",
+            "-map",
+            "0:a",
+            "-c:v",
+            "libx264",
+            "-c:a",
+            "aac",
+            "-shortest",
+            &request.output,
+        ]);
+
+        self.run_helper(args, &request.job).await
+    }
+
+    /// 生成整段音频的频谱图，返回图片路径
+    pub async fn spectrogram(&self, request: SpectrogramRequest) -> Result<HelperOutput> {
+        let options = &request.options;
+        let filter = format!(
+            "showspectrumpic=s={}:scale={}:color={}:legend={}",
+            escape_filter_value(options.size.as_deref().unwrap_or("1024x512")),
+            options.scale.unwrap_or_default().as_str(),
+            escape_filter_value(options.color_scheme.as_deref().unwrap_or("intensity")),
+            u8::from(options.legend.unwrap_or(true)),
+        );
+
+        let args = to_args([
+            "-i",
+            &request.input,
+            "-lavfi",
+            &filter,
+            "-frames:v",
+            "1",
+            "-update",
+            "1",
+            &request.output,
+        ]);
+
+        let response = self.run_helper(args, &request.job).await?;
+
+        Ok(HelperOutput {
+            job_id: response.job_id,
+            path: request.output,
+        })
+    }
+}
@@ -0,0 +1,93 @@
+//! # Hooks
+//!
+//! 任务结束时调用的回调和 Webhook，便于在没有前端参与的情况下集成插件。
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Runtime};
+
+use crate::events::Versioned;
+use crate::metrics::{DownloadMetrics, JobMetrics, MetricsSink};
+use crate::models::{JobFinished, JobInfo};
+
+/// 任务结束回调
+pub(crate) type JobFinishedHook<R> = Arc<dyn Fn(&AppHandle<R>, &JobFinished) + Send + Sync>;
+
+/// 任务成功回调，参数为任务信息和已写入的输出文件
+pub(crate) type JobSuccessHook = Arc<dyn Fn(&JobInfo, &[PathBuf]) + Send + Sync>;
+
+/// 已注册的回调
+pub(crate) struct Hooks<R: Runtime> {
+    pub job_finished: Vec<JobFinishedHook<R>>,
+    pub job_success: Vec<JobSuccessHook>,
+    pub metrics: Vec<Arc<dyn MetricsSink>>,
+}
+
+impl<R: Runtime> Default for Hooks<R> {
+    fn default() -> Self {
+        Self {
+            job_finished: Vec::new(),
+            job_success: Vec::new(),
+            metrics: Vec::new(),
+        }
+    }
+}
+
+impl<R: Runtime> Hooks<R> {
+    /// 在阻塞线程中依次调用任务成功回调，全部返回后才继续
+    pub async fn job_success(&self, job: &JobInfo, outputs: Vec<PathBuf>) {
+        if self.job_success.is_empty() {
+            return;
+        }
+
+        let hooks = self.job_success.clone();
+        let job = job.clone();
+        let _ = tokio::task::spawn_blocking(move || {
+            for hook in &hooks {
+                hook(&job, &outputs);
+            }
+        })
+        .await;
+    }
+
+    /// 调用任务结束回调，并将结果 POST 到给定的 Webhook 地址
+    pub fn job_finished(&self, app: &AppHandle<R>, finished: &JobFinished, webhooks: Vec<String>) {
+        for hook in &self.job_finished {
+            hook(app, finished);
+        }
+
+        for url in webhooks {
+            let finished = finished.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = post_webhook(&url, &finished).await;
+            });
+        }
+    }
+
+    /// 将下载指标发送给所有已注册的 [`MetricsSink`]
+    pub fn download_metrics(&self, metrics: &DownloadMetrics) {
+        for sink in &self.metrics {
+            sink.download_finished(metrics);
+        }
+    }
+
+    /// 将任务指标发送给所有已注册的 [`MetricsSink`]
+    pub fn job_metrics(&self, metrics: &JobMetrics) {
+        for sink in &self.metrics {
+            sink.job_finished(metrics);
+        }
+    }
+}
+
+/// 发送 Webhook 请求，失败时不重试
+async fn post_webhook(url: &str, finished: &JobFinished) -> reqwest::Result<()> {
+    reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(&Versioned(finished)).unwrap_or_default())
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! # Install
+//!
+//! 跟踪 FFmpeg 的下载/安装状态，多个下载可以同时进行，各自通过下载 ID 区分。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{Error, Result};
+use crate::models::DownloadProgress;
+
+/// Rust 端传入的下载进度回调
+pub(crate) type ProgressCallback<'a> = &'a (dyn Fn(&DownloadProgress) + Send + Sync);
+
+/// 安装状态
+pub(crate) struct InstallState {
+    /// 正在进行的下载数量
+    active: watch::Sender<usize>,
+    downloads: Mutex<HashMap<String, CancellationToken>>,
+    /// 因离线失败、等待网络恢复后自动重试的下载
+    pending: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl Default for InstallState {
+    fn default() -> Self {
+        Self {
+            active: watch::Sender::new(0),
+            downloads: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl InstallState {
+    /// 标记下载开始，返回的 [`InstallGuard`] 被释放时标记该下载结束
+    ///
+    /// 同一 ID 的下载正在进行时返回 [`Error::DownloadExists`]。`on_progress` 在每次发送进度事件时调用。
+    pub fn begin<'a>(
+        &'a self,
+        download_id: &str,
+        on_progress: Option<ProgressCallback<'a>>,
+    ) -> Result<InstallGuard<'a>> {
+        let mut downloads = self.downloads.lock().unwrap();
+        if downloads.contains_key(download_id) || self.is_pending(download_id) {
+            return Err(Error::DownloadExists(download_id.to_string()));
+        }
+
+        let cancel = CancellationToken::new();
+        downloads.insert(download_id.to_string(), cancel.clone());
+        self.active.send_modify(|active| *active += 1);
+
+        Ok(InstallGuard {
+            state: self,
+            download_id: download_id.to_string(),
+            cancel,
+            downloaded: AtomicU64::new(0),
+            on_progress,
+        })
+    }
+
+    /// 登记等待网络恢复的下载，返回的令牌在下载被取消时触发
+    pub fn add_pending(&self, download_id: &str) -> Result<CancellationToken> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.contains_key(download_id) {
+            return Err(Error::DownloadExists(download_id.to_string()));
+        }
+
+        let cancel = CancellationToken::new();
+        pending.insert(download_id.to_string(), cancel.clone());
+        Ok(cancel)
+    }
+
+    /// 注销等待网络恢复的下载
+    pub fn remove_pending(&self, download_id: &str) {
+        self.pending.lock().unwrap().remove(download_id);
+    }
+
+    fn is_pending(&self, download_id: &str) -> bool {
+        self.pending.lock().unwrap().contains_key(download_id)
+    }
+
+    /// 取消指定的下载，未指定 ID 时取消所有正在进行和等待网络恢复的下载
+    pub fn cancel(&self, download_id: Option<&str>) -> Result<()> {
+        let downloads = self.downloads.lock().unwrap();
+        let pending = self.pending.lock().unwrap();
+        match download_id {
+            Some(download_id) => match downloads.get(download_id).or(pending.get(download_id)) {
+                Some(cancel) => {
+                    cancel.cancel();
+                    Ok(())
+                }
+                None => Err(Error::DownloadNotFound(download_id.to_string())),
+            },
+            None if downloads.is_empty() && pending.is_empty() => Err(Error::NoInstallInProgress),
+            None => {
+                for cancel in downloads.values().chain(pending.values()) {
+                    cancel.cancel();
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 是否有下载正在进行
+    pub fn is_installing(&self) -> bool {
+        *self.active.borrow() > 0
+    }
+
+    /// 等待所有正在进行的下载结束
+    pub async fn wait(&self) {
+        let mut rx = self.active.subscribe();
+        let _ = rx.wait_for(|active| *active == 0).await;
+    }
+}
+
+/// 下载句柄，释放时标记该下载结束
+pub(crate) struct InstallGuard<'a> {
+    state: &'a InstallState,
+    download_id: String,
+    cancel: CancellationToken,
+    /// 本次下载累计下载的字节数
+    downloaded: AtomicU64,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl InstallGuard<'_> {
+    /// 下载被取消时触发
+    pub async fn cancelled(&self) {
+        self.cancel.cancelled().await
+    }
+
+    /// 记录已下载的字节数
+    pub fn add_downloaded(&self, bytes: u64) {
+        self.downloaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// 调用 Rust 端传入的进度回调
+    pub fn report_progress(&self, progress: &DownloadProgress) {
+        if let Some(on_progress) = self.on_progress {
+            on_progress(progress);
+        }
+    }
+
+    /// 本次下载累计下载的字节数（包括补丁）
+    pub fn downloaded(&self) -> u64 {
+        self.downloaded.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for InstallGuard<'_> {
+    fn drop(&mut self) {
+        self.state
+            .downloads
+            .lock()
+            .unwrap()
+            .remove(&self.download_id);
+        self.state.active.send_modify(|active| *active -= 1);
+    }
+}
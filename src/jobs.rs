@@ -0,0 +1,251 @@
+//! # Jobs
+//!
+//! 跟踪正在运行的 FFmpeg 进程。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::process::ChildStdin;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{Error, Result};
+use crate::models::{JobInfo, JobStatus};
+
+/// 任务的标准输入，仅交互式任务在运行时存在
+pub(crate) type JobStdin = Arc<tokio::sync::Mutex<ChildStdin>>;
+
+/// 正在运行的任务
+struct RunningJob {
+    info: JobInfo,
+    cancel: CancellationToken,
+    stdin: Option<JobStdin>,
+}
+
+/// 任务注册表
+#[derive(Default)]
+pub(crate) struct JobRegistry {
+    jobs: Mutex<HashMap<String, RunningJob>>,
+    /// 有任务注销时通知 [`JobRegistry::kill_running`]
+    idle: Notify,
+}
+
+impl JobRegistry {
+    /// 注册任务，返回的 [`JobGuard`] 被释放时自动注销
+    pub fn register(self: &Arc<Self>, info: JobInfo) -> Result<JobGuard> {
+        let mut jobs = self.jobs.lock().unwrap();
+
+        if jobs.contains_key(&info.id) {
+            return Err(Error::JobExists(info.id));
+        }
+
+        let cancel = CancellationToken::new();
+
+        jobs.insert(
+            info.id.clone(),
+            RunningJob {
+                info: info.clone(),
+                cancel: cancel.clone(),
+                stdin: None,
+            },
+        );
+
+        Ok(JobGuard {
+            registry: self.clone(),
+            info,
+            cancel,
+        })
+    }
+
+    /// 列出正在运行的任务
+    pub fn list(&self) -> Vec<JobInfo> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut list: Vec<JobInfo> = jobs.values().map(|job| job.info.clone()).collect();
+        list.sort_by_key(|info| info.queued_at);
+        list
+    }
+
+    /// 终止指定任务
+    pub fn cancel(&self, id: &str) -> Result<()> {
+        match self.jobs.lock().unwrap().get(id) {
+            Some(job) => {
+                job.cancel.cancel();
+                Ok(())
+            }
+            None => Err(Error::JobNotFound(id.to_string())),
+        }
+    }
+
+    /// 获取交互式任务的标准输入
+    pub fn stdin(&self, id: &str) -> Result<JobStdin> {
+        match self.jobs.lock().unwrap().get(id) {
+            Some(job) => job
+                .stdin
+                .clone()
+                .ok_or_else(|| Error::JobNotInteractive(id.to_string())),
+            None => Err(Error::JobNotFound(id.to_string())),
+        }
+    }
+
+    /// 是否有任务正在运行，计划中、排队中和等待安装的任务不算在内
+    pub fn has_running(&self) -> bool {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .any(|job| job.info.status == JobStatus::Running)
+    }
+
+    /// 终止正在运行的任务并等待它们退出，尚未开始的任务保持不变
+    pub async fn kill_running(&self) {
+        for job in self.jobs.lock().unwrap().values() {
+            if job.info.status == JobStatus::Running {
+                job.cancel.cancel();
+            }
+        }
+
+        loop {
+            let idle = self.idle.notified();
+            if !self.has_running() {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    fn set_status(&self, id: &str, status: JobStatus) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.get_mut(id)?;
+        job.info.status = status;
+        if status == JobStatus::Running {
+            job.info.started_at = Some(now_millis());
+        }
+        Some(job.info.clone())
+    }
+
+    fn set_stdin(&self, id: &str, stdin: ChildStdin) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            job.stdin = Some(Arc::new(tokio::sync::Mutex::new(stdin)));
+        }
+    }
+
+    fn unregister(&self, id: &str) {
+        self.jobs.lock().unwrap().remove(id);
+        self.idle.notify_waiters();
+    }
+}
+
+/// 任务句柄，释放时从注册表中注销
+pub(crate) struct JobGuard {
+    registry: Arc<JobRegistry>,
+    info: JobInfo,
+    cancel: CancellationToken,
+}
+
+impl JobGuard {
+    /// 任务信息
+    pub fn info(&self) -> &JobInfo {
+        &self.info
+    }
+
+    /// 更新任务状态
+    pub fn set_status(&mut self, status: JobStatus) {
+        if let Some(info) = self.registry.set_status(&self.info.id, status) {
+            self.info = info;
+        }
+    }
+
+    /// 保存交互式任务的标准输入，供 `send_command` 写入
+    pub fn set_stdin(&self, stdin: ChildStdin) {
+        self.registry.set_stdin(&self.info.id, stdin);
+    }
+
+    /// 任务被终止时触发
+    pub async fn cancelled(&self) {
+        self.cancel.cancelled().await
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        self.registry.unregister(&self.info.id);
+    }
+}
+
+/// 生成新的任务 ID
+pub(crate) fn new_job_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// 当前 Unix 时间戳（毫秒）
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, status: JobStatus) -> JobInfo {
+        JobInfo {
+            id: id.to_string(),
+            args: Vec::new(),
+            status,
+            queued_at: now_millis(),
+            started_at: None,
+            not_before: None,
+            only_when_idle: false,
+            batch_id: None,
+            tags: Default::default(),
+        }
+    }
+
+    #[test]
+    fn scheduled_jobs_are_not_running() {
+        let registry = Arc::new(JobRegistry::default());
+        let scheduled = registry
+            .register(job("scheduled", JobStatus::Scheduled))
+            .unwrap();
+        let mut running = registry
+            .register(job("running", JobStatus::Queued))
+            .unwrap();
+        assert!(!registry.has_running());
+
+        running.set_status(JobStatus::Running);
+        assert!(registry.has_running());
+        drop(running);
+        assert!(!registry.has_running());
+
+        // 没有正在运行的任务时立即返回，计划中的任务不被终止
+        tauri::async_runtime::block_on(registry.kill_running());
+        assert!(!scheduled.cancel.is_cancelled());
+        assert_eq!(registry.list().len(), 1);
+    }
+
+    #[test]
+    fn kill_running_waits_for_running_jobs() {
+        let registry = Arc::new(JobRegistry::default());
+        let scheduled = registry
+            .register(job("scheduled", JobStatus::Scheduled))
+            .unwrap();
+        let mut running = registry
+            .register(job("running", JobStatus::Queued))
+            .unwrap();
+        running.set_status(JobStatus::Running);
+
+        tauri::async_runtime::block_on(async {
+            let job = tokio::spawn(async move {
+                running.cancelled().await;
+                drop(running);
+            });
+            registry.kill_running().await;
+            job.await.unwrap();
+        });
+        assert!(!registry.has_running());
+        assert!(!scheduled.cancel.is_cancelled());
+    }
+}
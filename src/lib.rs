@@ -6,7 +6,7 @@
 //!
 //! - ✅ 无需预装 FFmpeg
 //! - ✅ 自动下载并解压 FFmpeg
-//! - ✅ 支持桌面平台：macOS、Windows、Linux
+//! - ✅ 支持桌面平台：macOS、Windows、Linux，以及 FreeBSD、OpenBSD（需配置下载源或使用系统 FFmpeg）
 //! - ✅ 实时下载进度监听
 //! - ✅ FFmpeg 可用性检查（包含路径和版本信息）
 //! - ✅ 执行任意 FFmpeg 命令
@@ -28,6 +28,23 @@
 //! }
 //! ```
 //!
+//! ### 在 Rust 中使用
+//!
+//! 所有前端命令和辅助函数都是 [`Ffmpeg`] 上的公开方法，可以在 Rust 端直接编排媒体处理：
+//!
+//! ```rust,ignore
+//! use tauri_plugin_use_ffmpeg::{EnqueueRequest, ExecuteRequest, FfmpegExt};
+//!
+//! let job = app.ffmpeg().enqueue(EnqueueRequest {
+//!     execute: ExecuteRequest {
+//!         args: vec!["-i".into(), "input.mp4".into(), "output.webm".into()],
+//!         ..Default::default()
+//!     },
+//!     ..Default::default()
+//! })?;
+//! println!("queued {}", job.id);
+//! ```
+//!
 //! ### 权限配置
 //!
 //! 在 `src-tauri/capabilities/default.json` 中添加：
@@ -40,22 +57,84 @@
 //!   ]
 //! }
 //! ```
+//!
+//! ### 插件配置
+//!
+//! 可选，在 `tauri.conf.json` 的 `plugins.use-ffmpeg` 中配置，详见 [`Config`]。
+//!
+//! ```json
+//! {
+//!   "plugins": {
+//!     "use-ffmpeg": {
+//!       "systemFfmpeg": true
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use tauri::{
-    plugin::{Builder, TauriPlugin},
-    Manager, Runtime,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    AppHandle, Manager, RunEvent, Runtime, WindowEvent,
 };
 
 pub use models::*;
 
+mod archive;
+mod args;
+mod audit;
+mod batch;
+mod child;
 mod commands;
+mod config;
 mod desktop;
 mod error;
+mod events;
+mod helpers;
+mod hooks;
+mod install;
+mod jobs;
+mod lock;
+mod messages;
+mod metrics;
 mod models;
+#[cfg(feature = "notification")]
+mod notification;
+mod patch;
+mod power;
+mod progress;
+mod providers;
+mod queue;
+mod quota;
+mod scheduler;
+mod signature;
+#[cfg(feature = "simulate")]
+mod simulate;
+mod stats;
+mod storage;
+mod stream;
+mod temp;
+mod template;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+mod version;
 
+pub use audit::{AuditAction, AuditEntry, AuditLogRequest};
+pub use child::FfmpegChild;
+pub use config::{BatteryAction, Config, EvermeetChannel, PowerAwareConfig, ProviderConfig};
 pub use error::{Error, Result};
+pub use events::EVENT_SCHEMA_VERSION;
+pub use helpers::*;
+pub use messages::MessageCode;
+pub use metrics::{DownloadMetrics, FailureCategory, JobMetrics, MetricsSink};
+pub use signature::SignatureInfo;
+pub use stream::OutputChunk;
+pub use template::{CommandTemplate, RunTemplateRequest, TemplateParam};
 
-use desktop::Ffmpeg;
+pub use desktop::Ffmpeg;
 
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the ffmpeg APIs.
 pub trait FfmpegExt<R: Runtime> {
@@ -82,18 +161,189 @@ impl<R: Runtime, T: Manager<R>> crate::FfmpegExt<R> for T {
 ///         .expect("error while running tauri application");
 /// }
 /// ```
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("use-ffmpeg")
-        .invoke_handler(tauri::generate_handler![
-            commands::check,
-            commands::download,
-            commands::execute,
-            commands::remove
-        ])
-        .setup(|app, api| {
-            let ffmpeg = desktop::init(app, api)?;
-            app.manage(ffmpeg);
-            Ok(())
-        })
-        .build()
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
+    Builder::new().build()
+}
+
+/// 插件构建器，用于注册 Rust 回调
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use tauri_plugin_use_ffmpeg::Builder;
+///
+/// fn main() {
+///     tauri::Builder::default()
+///         .plugin(
+///             Builder::new()
+///                 .on_job_finished(|_app, finished| {
+///                     println!("{} finished: {}", finished.job_id, finished.success);
+///                 })
+///                 .build(),
+///         )
+///         .run(tauri::generate_context!())
+///         .expect("error while running tauri application");
+/// }
+/// ```
+pub struct Builder<R: Runtime> {
+    hooks: hooks::Hooks<R>,
+    templates: HashMap<String, CommandTemplate>,
+}
+
+impl<R: Runtime> Default for Builder<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Runtime> Builder<R> {
+    pub fn new() -> Self {
+        Self {
+            hooks: hooks::Hooks::default(),
+            templates: HashMap::new(),
+        }
+    }
+
+    /// 注册任务结束回调，每个任务（包括失败和被取消的任务）结束时调用一次
+    pub fn on_job_finished<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&AppHandle<R>, &JobFinished) + Send + Sync + 'static,
+    {
+        self.hooks.job_finished.push(Arc::new(hook));
+        self
+    }
+
+    /// 注册任务成功回调，参数为任务信息和已写入的本地输出文件
+    ///
+    /// 回调在阻塞线程中运行，可以直接移动输出文件、计算校验和或上传，
+    /// 全部回调返回后才会发送 `use-ffmpeg://job-finished` 事件。
+    pub fn on_job_success<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&JobInfo, &[PathBuf]) + Send + Sync + 'static,
+    {
+        self.hooks.job_success.push(Arc::new(hook));
+        self
+    }
+
+    /// 注册指标接收器，用于将下载和任务的耗时、大小、失败原因等接入应用自己的统计系统
+    ///
+    /// 插件本身不收集或上报任何数据。
+    pub fn metrics_sink<S: MetricsSink>(mut self, sink: S) -> Self {
+        self.hooks.metrics.push(Arc::new(sink));
+        self
+    }
+
+    /// 注册命令模板，前端通过 `run_template` 以模板名称和参数运行
+    ///
+    /// 只授予 `use-ffmpeg:templates` 权限集时，前端不能运行任意命令，只能运行这里注册的模板，
+    /// 参数按声明的类型校验。同名的模板会被覆盖。
+    pub fn command_template(mut self, name: impl Into<String>, template: CommandTemplate) -> Self {
+        self.templates.insert(name.into(), template);
+        self
+    }
+
+    pub fn build(self) -> TauriPlugin<R, Option<Config>> {
+        let hooks = self.hooks;
+        let templates = self.templates;
+
+        PluginBuilder::<R, Option<Config>>::new("use-ffmpeg")
+            .invoke_handler(tauri::generate_handler![
+                commands::check,
+                commands::download,
+                commands::cancel_download,
+                commands::execute,
+                commands::enqueue,
+                commands::restore_queue,
+                commands::cancel,
+                commands::send_command,
+                commands::list_jobs,
+                commands::remove,
+                commands::draw_text,
+                commands::render_waveform_video,
+                commands::spectrogram,
+                commands::channel_ops,
+                commands::resample,
+                commands::denoise_audio,
+                commands::transcode,
+                commands::detect_crop,
+                commands::detect_interlacing,
+                commands::detect_black,
+                commands::detect_freeze,
+                commands::measure_sync_offset,
+                commands::replace_audio,
+                commands::analyze_complexity,
+                commands::estimate_output_size,
+                commands::list_builtin_profiles,
+                commands::probe,
+                commands::validate_for,
+                commands::list_platform_presets,
+                commands::transcode_multi,
+                commands::generate_proxies,
+                commands::trim_silence,
+                commands::prepare_for_transcription,
+                commands::media_hash,
+                commands::verify_file,
+                commands::faststart,
+                commands::smart_thumbnail,
+                commands::record_rtsp,
+                commands::start_level_meter,
+                commands::record_audio,
+                commands::encode_from_bytes,
+                commands::decode_frames,
+                commands::run_pipeline,
+                commands::purge_temp,
+                commands::get_quota_status,
+                commands::run_template,
+                commands::get_audit_log,
+                commands::reinstall,
+                commands::execute_str,
+                commands::recheck,
+                commands::is_at_least,
+                commands::list_formats,
+                commands::list_protocols,
+                commands::list_pix_fmts,
+                commands::list_sample_fmts,
+                commands::filter_help,
+                commands::benchmark,
+                commands::ready_report
+            ])
+            .setup(move |app, api| {
+                let ffmpeg = desktop::init(app, api, hooks, templates)?;
+                let power_aware = ffmpeg.config().power_aware.clone();
+                let restore_queue = ffmpeg.config().restore_queue.unwrap_or(false);
+                app.manage(ffmpeg);
+
+                if restore_queue {
+                    app.ffmpeg().restore_queue();
+                }
+
+                if let Some(config) = power_aware {
+                    power::spawn_monitor(app.clone(), config);
+                }
+
+                temp::spawn_gc(app.clone());
+
+                let handle = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let report = handle.ffmpeg().ready_report().await;
+                    events::emit(&handle, events::READY, &report);
+                });
+
+                Ok(())
+            })
+            .on_event(|app, event| {
+                if let RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::Destroyed,
+                    ..
+                } = event
+                {
+                    let ffmpeg = app.ffmpeg();
+                    if ffmpeg.config().kill_jobs_on_window_close {
+                        ffmpeg.cancel_window_jobs(label);
+                    }
+                }
+            })
+            .build()
+    }
 }
@@ -0,0 +1,83 @@
+//! # Lock
+//!
+//! 共享安装目录的跨进程建议锁，避免多个应用同时写入同一份 FFmpeg。
+//!
+//! 使用操作系统的文件锁（Unix 的 `flock`、Windows 的 `LockFileEx`），持有锁的进程退出时由系统释放，
+//! 无论安装耗时多久都不会被其他应用当作失效的锁抢走。锁文件放在安装目录旁边而不是目录中，
+//! 删除整个安装目录时仍然持有锁，锁文件本身从不删除。
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use fs4::fs_std::FileExt;
+
+/// 等待锁时的检查间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 已持有的锁，释放时解锁
+pub(crate) struct DirLock {
+    file: File,
+}
+
+impl DirLock {
+    /// 等待并获取目录锁，锁文件为目录旁边的 `<目录名>.lock`
+    pub async fn acquire(dir: &Path) -> io::Result<Self> {
+        let (Some(parent), Some(name)) = (dir.parent(), dir.file_name()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot lock {}", dir.display()),
+            ));
+        };
+        fs::create_dir_all(parent)?;
+
+        let mut lock_name = name.to_os_string();
+        lock_name.push(".lock");
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(parent.join(lock_name))?;
+
+        while !file.try_lock_exclusive()? {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        Ok(Self { file })
+    }
+}
+
+impl Drop for DirLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        let dir = std::env::temp_dir()
+            .join(format!("use-ffmpeg-lock-{}", uuid::Uuid::new_v4()))
+            .join("linux-0123456789ab");
+
+        tauri::async_runtime::block_on(async {
+            let lock = DirLock::acquire(&dir).await.unwrap();
+            let waiting = tokio::time::timeout(Duration::from_millis(500), DirLock::acquire(&dir));
+            assert!(waiting.await.is_err());
+
+            // 持有锁时可以删除整个安装目录
+            fs::create_dir_all(&dir).unwrap();
+            fs::remove_dir_all(&dir).unwrap();
+
+            drop(lock);
+            let waiting = tokio::time::timeout(Duration::from_secs(5), DirLock::acquire(&dir));
+            assert!(waiting.await.unwrap().is_ok());
+        });
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+}
@@ -0,0 +1,146 @@
+//! # Messages
+//!
+//! 返回给前端的文字的消息代码和翻译。
+//!
+//! 未配置 `locale` 时错误仍以英文字符串返回，与之前的版本保持一致；配置后错误以
+//! `{ code, message }` 对象返回，`message` 为对应语言的文字。
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::models::Tool;
+
+/// 配置的语言，未配置时为 None
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Locale {
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    /// 解析语言标签，只比较主语言（`en-US` 视为 `en`，`zh`、`zh-CN`、`zh-Hans` 视为简体中文）
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next().unwrap_or_default();
+        match primary.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::En),
+            "zh" => Some(Self::ZhCn),
+            _ => None,
+        }
+    }
+}
+
+/// 设置语言，只在插件初始化时调用一次
+pub(crate) fn init(locale: Option<&str>) {
+    if let Some(locale) = locale.and_then(Locale::parse) {
+        let _ = LOCALE.set(locale);
+    }
+}
+
+/// 错误是否以 `{ code, message }` 对象返回
+pub(crate) fn structured_errors() -> bool {
+    LOCALE.get().is_some()
+}
+
+fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or(Locale::En)
+}
+
+/// 响应中的消息代码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageCode {
+    /// 下载并安装完成
+    Downloaded,
+    /// 通过增量补丁更新完成
+    Patched,
+    /// 没有已安装的 FFmpeg，无需删除
+    NotInstalled,
+    /// 删除完成
+    Removed,
+    /// 缓存或日志已清除
+    Cleared,
+}
+
+/// 响应消息的文字
+pub(crate) fn message(code: MessageCode, tool: Tool) -> String {
+    let tool = tool_label(tool);
+    match (locale(), code) {
+        (Locale::En, MessageCode::Downloaded) => format!("{tool} downloaded successfully"),
+        (Locale::En, MessageCode::Patched) => format!("{tool} updated with patch"),
+        (Locale::En, MessageCode::NotInstalled) => "FFmpeg directory does not exist".to_string(),
+        (Locale::En, MessageCode::Removed) => format!("{tool} deleted successfully"),
+        (Locale::En, MessageCode::Cleared) => "Files cleared successfully".to_string(),
+        (Locale::ZhCn, MessageCode::Downloaded) => format!("{tool} 下载完成"),
+        (Locale::ZhCn, MessageCode::Patched) => format!("{tool} 已通过增量补丁更新"),
+        (Locale::ZhCn, MessageCode::NotInstalled) => "FFmpeg 目录不存在".to_string(),
+        (Locale::ZhCn, MessageCode::Removed) => format!("{tool} 已删除"),
+        (Locale::ZhCn, MessageCode::Cleared) => "文件已清除".to_string(),
+    }
+}
+
+/// 工具在消息中的名称
+fn tool_label(tool: Tool) -> &'static str {
+    match tool {
+        Tool::Ffmpeg => "FFmpeg",
+        Tool::Ffprobe => "ffprobe",
+        Tool::Ffplay => "ffplay",
+    }
+}
+
+/// 错误的文字
+pub(crate) fn error_message(error: &Error) -> String {
+    match locale() {
+        Locale::En => error.to_string(),
+        Locale::ZhCn => zh_cn_error(error),
+    }
+}
+
+fn zh_cn_error(error: &Error) -> String {
+    match error {
+        Error::Io(e) => format!("文件读写失败：{e}"),
+        Error::Download(detail) => format!("下载失败：{detail}"),
+        Error::FfmpegNotFound => "未找到 FFmpeg".to_string(),
+        Error::Extraction(detail) => format!("解压失败：{detail}"),
+        Error::NotAnArchive(excerpt) => {
+            format!("下载的文件不是压缩包，可能是错误页面，文件开头为：{excerpt}")
+        }
+        Error::Http(e) => format!("网络请求失败：{e}"),
+        Error::BlockedByAntivirus(detail) => format!(
+            "FFmpeg 被杀毒软件拦截：{detail}。请从隔离区恢复文件或将安装目录加入排除列表，然后重新安装"
+        ),
+        Error::UntrustedBinary(detail) => format!("可执行文件签名验证失败：{detail}"),
+        Error::Zip(e) => format!("压缩包错误：{e}"),
+        Error::CommandExecution(detail) => format!("命令执行失败：{detail}"),
+        Error::UnsupportedPlatform => "不支持当前平台".to_string(),
+        Error::NoDefaultSource(platform) => {
+            format!("平台 `{platform}` 没有默认下载源，请在插件配置中设置 `defaultDownload`")
+        }
+        Error::SystemFfmpegMode => "系统 FFmpeg 模式下由系统管理 FFmpeg".to_string(),
+        Error::ProvisionedMode => "FFmpeg 由 `ffmpegPath` 指定，无法下载或删除".to_string(),
+        Error::JobsRunning => "仍有 FFmpeg 任务在运行，传入 `force: true` 可先终止它们".to_string(),
+        Error::InstallInProgress => "FFmpeg 正在下载".to_string(),
+        Error::NoInstallInProgress => "没有正在进行的 FFmpeg 下载".to_string(),
+        Error::DownloadExists(id) => format!("下载 `{id}` 已存在"),
+        Error::DownloadNotFound(id) => format!("下载 `{id}` 不存在"),
+        Error::DownloadPending(id) => format!("网络未连接，下载 `{id}` 将在网络恢复后自动开始"),
+        Error::Cancelled => "操作已取消".to_string(),
+        Error::JobExists(id) => format!("任务 `{id}` 已存在"),
+        Error::QueueFull(len) => format!("任务队列已满（{len} 个任务等待中）"),
+        Error::QuotaExceeded { used, limit } => {
+            format!("存储空间超出配额（已使用 {used} 字节，上限 {limit} 字节）")
+        }
+        Error::JobNotFound(id) => format!("任务 `{id}` 不存在"),
+        Error::JobNotInteractive(id) => {
+            format!("任务 `{id}` 不是交互式任务，请以 `interactive: true` 启动")
+        }
+        Error::InvalidConfig(detail) => format!("插件配置无效：{detail}"),
+        Error::InvalidArgument(detail) => format!("参数无效：{detail}"),
+        Error::OutputExists(path) => format!("输出文件已存在：{path}"),
+        Error::UnknownVersion(line) => format!("无法从 `{line}` 识别 FFmpeg 的版本号"),
+    }
+}
@@ -0,0 +1,116 @@
+//! # Metrics
+//!
+//! 供宿主应用接入自己的统计系统的埋点接口，插件本身不收集或上报任何数据。
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::models::{JobInfo, JobResourceStats};
+
+/// 接收插件指标的接口，通过 [`crate::Builder::metrics_sink`] 注册
+///
+/// 方法在插件内部的异步任务中同步调用，耗时的处理（如网络上报）应自行转到后台。
+pub trait MetricsSink: Send + Sync + 'static {
+    /// 每次 `download` 结束（成功、失败或被取消）时调用
+    fn download_finished(&self, _metrics: &DownloadMetrics) {}
+
+    /// 每个任务结束（成功、失败或被取消）时调用
+    fn job_finished(&self, _metrics: &JobMetrics) {}
+}
+
+/// 失败原因分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    /// 被取消
+    Cancelled,
+    /// 网络或 HTTP 错误
+    Network,
+    /// 解压、补丁或签名校验失败
+    Extraction,
+    /// 文件读写失败或被杀毒软件拦截
+    Io,
+    /// 找不到 FFmpeg
+    NotInstalled,
+    /// 参数或配置无效、输出文件已存在等调用方错误
+    InvalidInput,
+    /// 下载正在进行、任务正在运行等状态冲突
+    Busy,
+    /// FFmpeg 以非零状态退出或无法启动
+    Ffmpeg,
+    /// 其他错误
+    Other,
+}
+
+impl FailureCategory {
+    /// 错误对应的分类
+    pub fn of(error: &Error) -> Self {
+        match error {
+            Error::Cancelled => Self::Cancelled,
+            Error::Download(_) | Error::Http(_) | Error::DownloadPending(_) => Self::Network,
+            Error::Extraction(_)
+            | Error::NotAnArchive(_)
+            | Error::Zip(_)
+            | Error::UntrustedBinary(_) => Self::Extraction,
+            Error::Io(_) | Error::QuotaExceeded { .. } | Error::BlockedByAntivirus(_) => Self::Io,
+            Error::FfmpegNotFound => Self::NotInstalled,
+            Error::InvalidArgument(_)
+            | Error::InvalidConfig(_)
+            | Error::OutputExists(_)
+            | Error::NoDefaultSource(_)
+            | Error::JobNotFound(_)
+            | Error::JobNotInteractive(_)
+            | Error::DownloadNotFound(_)
+            | Error::SystemFfmpegMode
+            | Error::ProvisionedMode => Self::InvalidInput,
+            Error::InstallInProgress
+            | Error::NoInstallInProgress
+            | Error::JobsRunning
+            | Error::JobExists(_)
+            | Error::QueueFull(_)
+            | Error::DownloadExists(_) => Self::Busy,
+            Error::CommandExecution(_) => Self::Ffmpeg,
+            Error::UnsupportedPlatform | Error::UnknownVersion(_) => Self::Other,
+        }
+    }
+}
+
+/// 下载指标
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadMetrics {
+    /// 下载 ID
+    pub download_id: String,
+    /// 下载 URL，使用默认配置且未能确定时为 None
+    pub url: Option<String>,
+    /// 实际下载的字节数（包括补丁）
+    pub bytes: u64,
+    /// 从开始到结束的耗时（毫秒），包括解压
+    pub duration_ms: u64,
+    /// 是否通过增量补丁完成
+    pub patched: bool,
+    /// 是否成功
+    pub success: bool,
+    /// 失败原因分类
+    pub failure: Option<FailureCategory>,
+}
+
+/// 任务指标
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobMetrics {
+    /// 任务信息
+    pub job: JobInfo,
+    /// 从提交到开始运行的等待时间（毫秒），未开始运行时为 None
+    pub wait_ms: Option<u64>,
+    /// 运行耗时（毫秒），未开始运行时为 None
+    pub duration_ms: Option<u64>,
+    /// 是否成功
+    pub success: bool,
+    /// 退出码
+    pub exit_code: Option<i32>,
+    /// 资源占用汇总
+    pub stats: Option<JobResourceStats>,
+    /// 失败原因分类，FFmpeg 以非零状态退出时为 `ffmpeg`
+    pub failure: Option<FailureCategory>,
+}
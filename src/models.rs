@@ -4,6 +4,12 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::args::split_command;
+use crate::config::ProviderConfig;
+use crate::error::Result;
+use crate::messages::MessageCode;
+use crate::signature::SignatureInfo;
+
 /// FFmpeg 下载配置
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,26 +18,242 @@ pub struct DownloadConfig {
     pub url: String,
     /// 解压后 FFmpeg 可执行文件的相对路径
     pub executable_path: String,
+    /// `executable_path` 对应的工具，默认 `ffmpeg`（同时解压同目录下的 ffprobe、ffplay）
+    ///
+    /// 设置为 `ffprobe` 或 `ffplay` 时只安装该工具，可以与 FFmpeg 的下载同时进行。
+    pub tool: Option<Tool>,
+    /// 从已安装版本升级的增量补丁，没有匹配的补丁或打补丁失败时自动改为完整下载
+    #[serde(default)]
+    pub patches: Vec<DownloadPatch>,
+    /// 下载文件的 SHA-256（十六进制），设置后下载完成时校验，不一致时安装失败
+    pub sha256: Option<String>,
+}
+
+/// 增量补丁（`zstd --patch-from=<旧可执行文件> <新可执行文件>` 生成）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadPatch {
+    /// 补丁适用的已安装版本，与 `<tool> -version` 输出中 `version` 之后的版本号比较（如 `n7.1`）
+    pub from_version: String,
+    /// 补丁 URL
+    pub url: String,
+    /// 补丁对应的工具，默认 `ffmpeg`
+    pub tool: Option<Tool>,
+    /// 解码得到的新可执行文件的 SHA-256（十六进制），不一致时不运行该文件并改为完整下载
+    pub sha256: String,
 }
 
 /// 下载请求
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadRequest {
+    /// 可选的下载 ID，未提供时自动生成，用于区分同时进行的下载
+    pub download_id: Option<String>,
     /// 可选的下载配置，如果为 None 则使用默认配置
     pub config: Option<DownloadConfig>,
+    /// 使用默认配置时的构建渠道，覆盖插件配置中的 `provider.channel`
+    pub channel: Option<BuildChannel>,
+    /// 要安装的工具，默认安装 `config.tool` 指定的工具（`ffmpeg` 时同时安装同目录下的 ffprobe、ffplay）
+    ///
+    /// 例如 `["ffprobe"]` 只安装 ffprobe，适合只需要读取媒体信息的应用。已安装的其他工具保持不变。
+    pub tools: Option<Vec<Tool>>,
+    /// 有任务正在运行时是否先终止它们再覆盖安装
+    #[serde(default)]
+    pub force: bool,
+    /// 因网络未连接而失败时，是否在网络恢复后自动重试
+    ///
+    /// 此时返回 [`Error::DownloadPending`](crate::Error::DownloadPending)，重试开始时发送
+    /// `use-ffmpeg://download-resumed` 事件，之后与普通下载一样发送进度和结束事件。
+    #[serde(default)]
+    pub retry_when_online: bool,
 }
 
 /// 下载响应
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadResponse {
+    /// 下载 ID
+    pub download_id: String,
     /// 是否成功
     pub success: bool,
+    /// 是否通过增量补丁完成升级
+    #[serde(default)]
+    pub patched: bool,
+    /// 消息代码，不随语言变化
+    pub message_code: Option<MessageCode>,
     /// 下载后的文件路径
     pub path: Option<String>,
     /// 消息
     pub message: Option<String>,
+    /// 跟随重定向后实际下载的地址，通过增量补丁升级时为 None
+    pub final_url: Option<String>,
+    /// 实际提供文件的服务器（响应头 `Server`），服务器未提供时为 None
+    pub server: Option<String>,
+}
+
+/// 内置下载源的构建渠道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuildChannel {
+    /// 正式发布版本
+    #[default]
+    Release,
+    /// git master 分支的每日或每周构建，包含最新的编码器，但未经充分测试
+    GitMaster,
+}
+
+impl BuildChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BuildChannel::Release => "release",
+            BuildChannel::GitMaster => "git-master",
+        }
+    }
+}
+
+/// FFmpeg 套件中的工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tool {
+    Ffmpeg,
+    Ffprobe,
+    Ffplay,
+}
+
+impl Tool {
+    /// 所有工具
+    pub const ALL: [Tool; 3] = [Tool::Ffmpeg, Tool::Ffprobe, Tool::Ffplay];
+
+    /// 随 FFmpeg 一起分发的配套工具
+    pub const COMPANIONS: [Tool; 2] = [Tool::Ffprobe, Tool::Ffplay];
+
+    /// 当前平台下的可执行文件名
+    pub fn executable_name(&self) -> &'static str {
+        #[cfg(target_os = "windows")]
+        return match self {
+            Tool::Ffmpeg => "ffmpeg.exe",
+            Tool::Ffprobe => "ffprobe.exe",
+            Tool::Ffplay => "ffplay.exe",
+        };
+
+        #[cfg(not(target_os = "windows"))]
+        return match self {
+            Tool::Ffmpeg => "ffmpeg",
+            Tool::Ffprobe => "ffprobe",
+            Tool::Ffplay => "ffplay",
+        };
+    }
+}
+
+/// 单个工具的检查结果
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatus {
+    /// 是否可用
+    pub available: bool,
+    /// 可执行文件路径
+    pub path: Option<String>,
+    /// 版本信息
+    pub version: Option<String>,
+    /// 可执行文件是否被杀毒软件拦截而无法运行，可以在加入排除列表后调用 `reinstall`
+    #[serde(default)]
+    pub blocked_by_antivirus: bool,
+    /// `-version` 是否在 `timeout_ms` 内没有完成，此时 `available` 为 false，稍后可以重新检查
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Authenticode 签名信息，仅 Windows，文件没有签名时为 None
+    pub signature: Option<SignatureInfo>,
+}
+
+/// 各工具的检查结果
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolsStatus {
+    pub ffmpeg: ToolStatus,
+    pub ffprobe: ToolStatus,
+    pub ffplay: ToolStatus,
+}
+
+/// 检查请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckRequest {
+    /// 等待每个工具 `-version` 完成的最长时间（毫秒），默认不限制
+    pub timeout_ms: Option<u64>,
+    /// 超过该时间（毫秒）仍未完成时发送 `use-ffmpeg://check-slow` 事件，默认为 1000
+    pub slow_threshold_ms: Option<u64>,
+}
+
+/// FFmpeg 的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FfmpegSource {
+    /// 由插件下载和管理
+    Managed,
+    /// 使用 PATH 中的系统 FFmpeg（`systemFfmpeg`）
+    System,
+    /// 外部部署的 FFmpeg（`ffmpegPath`）
+    Provisioned,
+    /// 开发调试用的路径（`TAURI_FFMPEG_PATH` 或 `pathOverride`）
+    Override,
+}
+
+/// 就绪报告中的策略配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyPolicies {
+    pub max_concurrent_jobs: Option<usize>,
+    pub max_queue_length: Option<usize>,
+    pub allowed_protocols: Option<Vec<String>>,
+    pub storage_quota_mb: Option<u64>,
+    pub require_signed_binaries: bool,
+    pub kill_jobs_on_window_close: bool,
+    pub audit_log: bool,
+    pub power_aware: bool,
+    pub shared: bool,
+    pub compress_binaries: bool,
+    pub simulate: bool,
+}
+
+/// 就绪报告（`use-ffmpeg://ready` 事件），插件初始化后发送一次
+///
+/// `install` 为初始化时的检查结果，之后的安装状态变化需要通过 `check` 或下载、删除的事件获得。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyReport {
+    /// 插件版本
+    pub plugin_version: String,
+    /// 平台（`macos`、`windows`、`linux` 等），不支持的平台为 None
+    pub platform: Option<String>,
+    /// CPU 架构（`x86_64`、`aarch64` 等）
+    pub arch: String,
+    /// FFmpeg 的来源
+    pub source: FfmpegSource,
+    /// 初始化时的检查结果，检查失败时为 None
+    pub install: Option<CheckResponse>,
+    /// 插件配置中的 `defaultDownload.url`，未设置时使用内置下载源
+    pub default_download_url: Option<String>,
+    /// 内置下载源的选项
+    pub provider: ProviderConfig,
+    pub policies: ReadyPolicies,
+}
+
+/// 版本比较请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsAtLeastRequest {
+    /// 要求的最低版本，例如 `6.0`
+    pub version: String,
+}
+
+/// 检查较慢事件，`check` 超过 `slow_threshold_ms` 仍未完成时发送一次
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckSlow {
+    /// 已经等待的时间（毫秒）
+    pub elapsed_ms: u64,
+    /// 本次检查的超时时间（毫秒），未设置时为 None
+    pub timeout_ms: Option<u64>,
 }
 
 /// 检查响应
@@ -44,20 +266,95 @@ pub struct CheckResponse {
     pub path: Option<String>,
     /// FFmpeg 版本信息
     pub version: Option<String>,
+    /// 各工具（ffmpeg、ffprobe、ffplay）的检查结果
+    pub tools: ToolsStatus,
+}
+
+/// 输出文件已存在时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    /// 直接覆盖（`-y`）
+    Always,
+    /// 不覆盖，任务以失败结束（`-n`）
+    Never,
+    /// 不覆盖，并返回 [`crate::Error::OutputExists`]（`-n`）
+    Error,
+}
+
+/// 调用方附加在任务上的标签，原样出现在该任务的所有进度和生命周期事件中
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobTags {
+    /// 任务标签
+    pub tag: Option<String>,
+    /// 任意 JSON 元数据
+    pub meta: Option<serde_json::Value>,
+    /// 发起任务的窗口标签，由插件根据调用来源设置，不能由前端指定；Rust 端发起的任务为 None
+    #[serde(default, skip_deserializing)]
+    pub window: Option<String>,
 }
 
 /// 执行请求
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecuteRequest {
     /// FFmpeg 命令参数（不包含 ffmpeg 本身）
+    #[serde(default)]
     pub args: Vec<String>,
+    /// 可选的任务 ID，未提供时自动生成
+    pub job_id: Option<String>,
+    /// 下载正在进行时是否等待其完成，否则返回错误
+    #[serde(default)]
+    pub wait_for_install: bool,
+    /// 任务失败或被取消时是否删除写了一半的输出文件
+    #[serde(default)]
+    pub cleanup_outputs_on_failure: bool,
+    /// 输出文件已存在时的处理策略，未设置时不注入 `-y` / `-n`，与 `args` 中的标志矛盾时返回错误
+    pub overwrite: Option<OverwritePolicy>,
+    /// 编码线程数（`-threads`），覆盖插件配置中的 `defaultThreads`
+    pub threads: Option<u32>,
+    /// 任务结束时将 `JobFinished` 以 JSON POST 到该地址，源必须在插件配置的 `allowedWebhookOrigins` 中
+    pub webhook: Option<String>,
+    /// 任务结束时发送系统通知（需要启用 `notification` feature 并注册通知插件）
+    #[serde(default)]
+    pub notify_on_complete: bool,
+    /// 是否将标准输入保持为管道，以便通过 `send_command` 发送交互命令
+    #[serde(default)]
+    pub interactive: bool,
+    /// 所属批次，同一批次的任务会汇总发送 `use-ffmpeg://batch-progress` 和 `use-ffmpeg://batch-finished` 事件
+    pub batch_id: Option<String>,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 以 shell 风格的命令行执行的请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteStrRequest {
+    /// 命令行，如 `ffmpeg -i "my video.mp4" -vf scale=1280:-2 out.mp4`
+    pub command: String,
+    /// 执行选项，其中的 `args` 被忽略
+    #[serde(flatten)]
+    pub execute: ExecuteRequest,
+}
+
+impl ExecuteStrRequest {
+    /// 解析命令行，生成执行请求
+    pub(crate) fn into_request(self) -> Result<ExecuteRequest> {
+        Ok(ExecuteRequest {
+            args: split_command(&self.command)?,
+            ..self.execute
+        })
+    }
 }
 
 /// 执行响应
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecuteResponse {
+    /// 任务 ID
+    pub job_id: String,
     /// 是否成功
     pub success: bool,
     /// 标准输出
@@ -66,12 +363,45 @@ pub struct ExecuteResponse {
     pub stderr: String,
     /// 退出码
     pub exit_code: Option<i32>,
+    /// 资源占用汇总
+    pub stats: Option<JobResourceStats>,
+    /// 不影响运行、但很可能有误的参数（如没有输入）
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// 发送交互命令的请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendCommandRequest {
+    /// 任务 ID
+    pub job_id: String,
+    /// 写入标准输入的文本，未以换行结尾时自动补上
+    pub text: String,
+}
+
+/// 加入后台队列的请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnqueueRequest {
+    /// 执行请求
+    #[serde(flatten)]
+    pub execute: ExecuteRequest,
+    /// 延迟开始的毫秒数
+    pub start_after: Option<u64>,
+    /// 最早开始时间（Unix 时间戳，毫秒）
+    pub not_before: Option<u64>,
+    /// 是否只在没有其他任务运行时开始
+    #[serde(default)]
+    pub only_when_idle: bool,
 }
 
 /// 下载进度
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadProgress {
+    /// 下载 ID
+    pub download_id: String,
     /// 已下载字节数
     pub downloaded: u64,
     /// 总字节数（如果已知）
@@ -80,12 +410,337 @@ pub struct DownloadProgress {
     pub percentage: Option<f64>,
 }
 
+/// 下载开始事件（`use-ffmpeg://download-started`）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadStarted {
+    /// 下载 ID
+    pub download_id: String,
+    /// 下载 URL
+    pub url: String,
+}
+
+/// 下载即将开始传输事件（`use-ffmpeg://download-starting`），在确认地址可以访问之后、传输文件内容之前发送
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadStarting {
+    /// 下载 ID
+    pub download_id: String,
+    /// 下载 URL
+    pub url: String,
+    /// 文件总大小（字节），服务器未提供时为 None
+    pub total_bytes: Option<u64>,
+}
+
+/// 等待网络恢复的下载开始重试事件（`use-ffmpeg://download-resumed`）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadResumed {
+    /// 下载 ID
+    pub download_id: String,
+}
+
+/// 下载结束事件（`use-ffmpeg://download-finished`）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadFinished {
+    /// 下载 ID
+    pub download_id: String,
+    /// 是否成功
+    pub success: bool,
+    /// 是否被取消
+    pub cancelled: bool,
+    /// 失败时的错误信息
+    pub error: Option<String>,
+    /// 失败时的错误代码，不随语言变化
+    pub error_code: Option<String>,
+}
+
+/// 删除请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveRequest {
+    /// 有任务正在运行时是否先终止它们再删除
+    #[serde(default)]
+    pub force: bool,
+    /// 只删除指定的工具，`target` 为 `all` 时有效，默认删除整个安装目录
+    pub tools: Option<Vec<Tool>>,
+    /// 要删除的内容，默认 `all`
+    pub target: Option<RemoveTarget>,
+}
+
+/// `remove` 删除的内容
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoveTarget {
+    /// 只删除 ffmpeg 可执行文件
+    Ffmpeg,
+    /// 只删除 ffprobe 可执行文件
+    Ffprobe,
+    /// 可以重新生成的缓存：临时文件和压缩存储模式下解压的可执行文件
+    Cache,
+    /// FFmpeg 的 `-report` 日志
+    Logs,
+    /// 安装目录、缓存和日志
+    #[default]
+    All,
+}
+
 /// 删除响应
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteResponse {
     /// 是否成功
     pub success: bool,
+    /// 消息代码，不随语言变化
+    pub message_code: Option<MessageCode>,
     /// 消息
     pub message: Option<String>,
 }
+
+/// 临时文件清理结果
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeTempResponse {
+    /// 删除的文件数
+    pub removed: usize,
+    /// 释放的字节数
+    pub freed_bytes: u64,
+}
+
+/// 存储配额状态
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaStatus {
+    /// 配额（字节），未设置 `storageQuotaMb` 时为 None
+    pub limit_bytes: Option<u64>,
+    /// 已使用的字节数
+    pub used_bytes: u64,
+    /// 文件数
+    pub files: usize,
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// 等待计划的开始时间
+    Scheduled,
+    /// 排队等待运行名额
+    Queued,
+    /// 正在运行
+    Running,
+}
+
+/// 任务信息
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    /// 任务 ID
+    pub id: String,
+    /// FFmpeg 命令参数
+    pub args: Vec<String>,
+    /// 任务状态
+    pub status: JobStatus,
+    /// 提交时间（Unix 时间戳，毫秒）
+    pub queued_at: u64,
+    /// 开始运行时间（Unix 时间戳，毫秒）
+    pub started_at: Option<u64>,
+    /// 计划的最早开始时间（Unix 时间戳，毫秒）
+    pub not_before: Option<u64>,
+    /// 是否只在没有其他任务运行时开始
+    pub only_when_idle: bool,
+    /// 所属批次
+    pub batch_id: Option<String>,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 任务结束事件
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFinished {
+    /// 任务 ID
+    pub job_id: String,
+    /// 是否成功
+    pub success: bool,
+    /// 退出码
+    pub exit_code: Option<i32>,
+    /// 是否被取消
+    pub cancelled: bool,
+    /// 资源占用汇总
+    pub stats: Option<JobResourceStats>,
+    /// 任务未能运行或运行出错时的错误信息
+    pub error: Option<String>,
+    /// 任务未能运行或运行出错时的错误代码，不随语言变化
+    pub error_code: Option<String>,
+    /// 所属批次
+    pub batch_id: Option<String>,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 队列排空事件，后台队列从低水位以上减少到低水位以下时发送
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueDrained {
+    /// 队列中剩余的尚未开始的任务数
+    pub queued: usize,
+    /// 队列容量
+    pub max_queue_length: Option<usize>,
+}
+
+/// 恢复后台队列时被拒绝的任务（`use-ffmpeg://queue-job-rejected` 事件）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueJobRejected {
+    /// 任务 ID
+    pub job_id: String,
+    /// 错误代码
+    pub error_code: String,
+    /// 被拒绝的原因
+    pub error: String,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 批次进度事件
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    /// 批次 ID
+    pub batch_id: String,
+    /// 已提交的任务数
+    pub total: usize,
+    /// 已结束的任务数
+    pub completed: usize,
+    /// 失败或被取消的任务数
+    pub failed: usize,
+    /// 所有任务的平均进度百分比，已结束的任务按 100 计算，进度未知的任务按 0 计算
+    pub percentage: f64,
+}
+
+/// 批次中第一个失败的任务
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFailure {
+    /// 任务 ID
+    pub job_id: String,
+    /// 退出码
+    pub exit_code: Option<i32>,
+    /// 任务未能运行或运行出错时的错误信息
+    pub error: Option<String>,
+    /// 任务未能运行或运行出错时的错误代码
+    pub error_code: Option<String>,
+}
+
+/// 批次结束事件，批次中所有已提交的任务都结束后发送
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFinished {
+    /// 批次 ID
+    pub batch_id: String,
+    /// 任务总数
+    pub total: usize,
+    /// 成功的任务数
+    pub succeeded: usize,
+    /// 失败的任务数
+    pub failed: usize,
+    /// 被取消的任务数
+    pub cancelled: usize,
+    /// 是否所有任务都成功
+    pub success: bool,
+    /// 第一个失败（不包括被取消）的任务
+    pub first_failure: Option<BatchFailure>,
+}
+
+/// 任务资源占用采样（`use-ffmpeg://job-stats` 事件）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatsSample {
+    /// 任务 ID
+    pub job_id: String,
+    /// CPU 占用百分比（多核时可能超过 100）
+    pub cpu_percent: f32,
+    /// 常驻内存（字节）
+    pub rss_bytes: u64,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 任务资源占用汇总
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobResourceStats {
+    /// 采样次数
+    pub samples: u32,
+    /// 平均 CPU 占用百分比
+    pub avg_cpu_percent: f32,
+    /// 峰值 CPU 占用百分比
+    pub peak_cpu_percent: f32,
+    /// 峰值常驻内存（字节）
+    pub peak_rss_bytes: u64,
+}
+
+/// 取消任务请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRequest {
+    /// 任务 ID
+    pub job_id: String,
+}
+
+/// 取消下载请求
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelDownloadRequest {
+    /// 下载 ID，未设置时取消所有正在进行的下载
+    pub download_id: Option<String>,
+}
+
+/// 任务进度
+///
+/// 输入没有时长（直播源、管道）时 `percentage` 为空、`indeterminate` 为 `true`，
+/// 此时可以使用 `size_bytes` / `out_time_secs` 展示已处理的数据量。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodeProgress {
+    /// 任务 ID
+    pub job_id: String,
+    /// 已处理帧数
+    pub frame: Option<u64>,
+    /// 处理帧率
+    pub fps: Option<f64>,
+    /// 已输出字节数
+    pub size_bytes: Option<u64>,
+    /// 已输出时长（秒）
+    pub out_time_secs: Option<f64>,
+    /// 预期输出时长（秒，如果已知）
+    pub duration_secs: Option<f64>,
+    /// 进度百分比（如果已知，单调不减）
+    pub percentage: Option<f64>,
+    /// 平滑后的处理速度（相对实时的倍数）
+    pub speed: Option<f64>,
+    /// 已耗时（秒）
+    pub elapsed_secs: f64,
+    /// 预计剩余时间（秒，如果已知）
+    pub eta_secs: Option<f64>,
+    /// 是否无法计算百分比
+    pub indeterminate: bool,
+    #[serde(flatten)]
+    pub tags: JobTags,
+}
+
+/// 电源感知的限流状态（`use-ffmpeg://throttle-changed` 事件）
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottleState {
+    /// 是否在使用电池
+    pub on_battery: bool,
+    /// 系统是否报告过热
+    pub thermal_pressure: bool,
+    /// 队列是否暂停
+    pub paused: bool,
+    /// 限流后的最大并发数
+    pub max_concurrent_jobs: Option<usize>,
+}
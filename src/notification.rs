@@ -0,0 +1,43 @@
+//! # Notification
+//!
+//! 任务结束时通过 `tauri-plugin-notification` 发送系统通知（需要启用 `notification` feature）。
+
+use std::path::Path;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_notification::Notification;
+
+use crate::args::output_targets;
+use crate::models::JobFinished;
+
+/// 发送任务结束通知，应用未注册通知插件时不做处理
+pub(crate) fn job_finished<R: Runtime>(
+    app: &AppHandle<R>,
+    finished: &JobFinished,
+    args: &[String],
+) {
+    let Some(notification) = app.try_state::<Notification<R>>() else {
+        return;
+    };
+
+    let title = if finished.cancelled {
+        "Export cancelled"
+    } else if finished.success {
+        "Export finished"
+    } else {
+        "Export failed"
+    };
+
+    // 优先显示输出文件名，没有时显示任务 ID
+    let body = output_targets(args)
+        .last()
+        .map(|target| {
+            Path::new(target)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| target.clone())
+        })
+        .unwrap_or_else(|| finished.job_id.clone());
+
+    let _ = notification.builder().title(title).body(body).show();
+}
@@ -0,0 +1,46 @@
+//! # Patch
+//!
+//! 基于 `zstd --patch-from` 的增量升级：以已安装的可执行文件为字典解码补丁，得到新版本的可执行文件。
+//!
+//! 补丁的生成方式：
+//!
+//! ```sh
+//! zstd --patch-from=old/ffmpeg new/ffmpeg -o ffmpeg-n7.1-to-n8.0.zst --long=31 -19
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// 解码时允许的最大窗口（与 `--long=31` 对应）
+const WINDOW_LOG_MAX: u32 = 31;
+
+/// 从 `<tool> -version` 的第一行中提取版本号，例如 `ffmpeg version n8.0 Copyright ...` 中的 `n8.0`
+pub(crate) fn version_number(version_line: &str) -> Option<&str> {
+    let mut words = version_line.split_whitespace();
+    words.find(|word| *word == "version")?;
+    words.next()
+}
+
+/// 以 `old` 为字典解码补丁 `patch`，写入 `output`
+pub(crate) fn apply_patch(old: &Path, patch: &Path, output: &Path) -> io::Result<()> {
+    let dictionary = fs::read(old)?;
+    let mut decoder = zstd::stream::read::Decoder::with_dictionary(
+        io::BufReader::new(fs::File::open(patch)?),
+        &dictionary,
+    )?;
+    decoder.window_log_max(WINDOW_LOG_MAX)?;
+
+    let mut file = fs::File::create(output)?;
+    io::copy(&mut decoder, &mut file)?;
+    file.sync_all()
+}
+
+/// 文件的 SHA-256（小写十六进制）
+pub(crate) fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,184 @@
+//! # Power
+//!
+//! 读取电源和散热状态，在使用电池或系统过热时限制任务并发。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime};
+
+use crate::config::{BatteryAction, PowerAwareConfig};
+use crate::events;
+use crate::models::ThrottleState;
+use crate::FfmpegExt;
+
+/// 电源状态，无法获取的字段为 None
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct PowerStatus {
+    pub on_battery: Option<bool>,
+    pub thermal_pressure: Option<bool>,
+}
+
+/// 在后台定期检查电源状态并调整调度器
+pub(crate) fn spawn_monitor<R: Runtime>(app: AppHandle<R>, config: PowerAwareConfig) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            config.poll_interval_secs.unwrap_or(30).max(1),
+        ));
+        let mut last: Option<ThrottleState> = None;
+
+        loop {
+            interval.tick().await;
+
+            let status = tokio::task::spawn_blocking(read_power_status)
+                .await
+                .unwrap_or_default();
+            let state = throttle_state(&config, status);
+
+            if last.as_ref() != Some(&state) {
+                app.ffmpeg().apply_throttle(&state);
+                events::emit(&app, events::THROTTLE_CHANGED, &state);
+                last = Some(state);
+            }
+        }
+    });
+}
+
+/// 根据电源状态计算限流状态
+fn throttle_state(config: &PowerAwareConfig, status: PowerStatus) -> ThrottleState {
+    let on_battery = status.on_battery.unwrap_or(false);
+    let thermal_pressure = status.thermal_pressure.unwrap_or(false);
+
+    let mut paused = thermal_pressure && config.pause_on_thermal_pressure;
+    let mut max_concurrent_jobs = None;
+
+    if on_battery {
+        match config.on_battery {
+            BatteryAction::Pause => paused = true,
+            BatteryAction::Reduce => {
+                max_concurrent_jobs = Some(config.battery_max_concurrent_jobs.unwrap_or(1))
+            }
+            BatteryAction::Ignore => {}
+        }
+    }
+
+    ThrottleState {
+        on_battery,
+        thermal_pressure,
+        paused,
+        max_concurrent_jobs,
+    }
+}
+
+/// 读取当前平台的电源状态
+pub(crate) fn read_power_status() -> PowerStatus {
+    #[cfg(target_os = "linux")]
+    return linux::read();
+
+    #[cfg(target_os = "macos")]
+    return macos::read();
+
+    #[cfg(target_os = "windows")]
+    return windows::read();
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    return PowerStatus::default();
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+
+    use super::PowerStatus;
+
+    /// 通过 `/sys/class/power_supply` 判断是否在使用电池
+    pub fn read() -> PowerStatus {
+        let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+            return PowerStatus::default();
+        };
+
+        let mut has_battery = false;
+        let mut mains_online = false;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let kind = fs::read_to_string(path.join("type")).unwrap_or_default();
+            match kind.trim() {
+                "Battery" => has_battery = true,
+                "Mains" | "USB" => {
+                    let online = fs::read_to_string(path.join("online")).unwrap_or_default();
+                    mains_online |= online.trim() == "1";
+                }
+                _ => {}
+            }
+        }
+
+        PowerStatus {
+            on_battery: has_battery.then_some(!mains_online),
+            thermal_pressure: None,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::process::Command;
+
+    use super::PowerStatus;
+
+    /// 通过 `pmset` 读取电源和散热状态
+    pub fn read() -> PowerStatus {
+        let battery = pmset(&["-g", "batt"]);
+        let thermal = pmset(&["-g", "therm"]);
+
+        PowerStatus {
+            on_battery: battery.map(|output| output.contains("'Battery Power'")),
+            thermal_pressure: thermal.map(|output| {
+                let speed_limited = output
+                    .lines()
+                    .filter_map(|line| line.trim().strip_prefix("CPU_Speed_Limit"))
+                    .filter_map(|rest| {
+                        rest.trim_start_matches([' ', '='])
+                            .trim()
+                            .parse::<u32>()
+                            .ok()
+                    })
+                    .any(|limit| limit < 100);
+                speed_limited || output.contains("Thermal warning level set to")
+            }),
+        }
+    }
+
+    fn pmset(args: &[&str]) -> Option<String> {
+        let output = Command::new("pmset").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    use super::PowerStatus;
+
+    /// 通过 `GetSystemPowerStatus` 判断是否在使用电池
+    pub fn read() -> PowerStatus {
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return PowerStatus::default();
+        }
+
+        PowerStatus {
+            // 0 = 使用电池，1 = 接通电源，255 = 未知
+            on_battery: match status.ACLineStatus {
+                0 => Some(true),
+                1 => Some(false),
+                _ => None,
+            },
+            thermal_pressure: None,
+        }
+    }
+}
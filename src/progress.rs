@@ -0,0 +1,253 @@
+//! # Progress
+//!
+//! 解析 FFmpeg 的 stderr 输出，生成任务进度。
+
+use std::time::Instant;
+
+use crate::models::{EncodeProgress, JobTags};
+
+/// 速度平滑系数（指数移动平均）
+const SPEED_SMOOTHING: f64 = 0.3;
+
+/// 任务进度解析器
+pub(crate) struct ProgressParser {
+    job_id: String,
+    tags: JobTags,
+    /// 输入时长（秒），从 `Duration:` 行解析
+    input_duration: Option<f64>,
+    /// 通过 `-t` 限制的输出时长（秒）
+    limit: Option<f64>,
+    started: Instant,
+    last_percentage: f64,
+    smoothed_speed: Option<f64>,
+    lines: LineBuffer,
+}
+
+impl ProgressParser {
+    pub fn new(job_id: String, tags: JobTags, args: &[String]) -> Self {
+        let limit = args
+            .windows(2)
+            .rev()
+            .filter(|pair| pair[0] == "-t")
+            .find_map(|pair| parse_time(&pair[1]));
+
+        Self {
+            job_id,
+            tags,
+            input_duration: None,
+            limit,
+            started: Instant::now(),
+            last_percentage: 0.0,
+            smoothed_speed: None,
+            lines: LineBuffer::default(),
+        }
+    }
+
+    /// 预期的输出时长
+    fn duration(&self) -> Option<f64> {
+        match (self.input_duration, self.limit) {
+            (Some(input), Some(limit)) => Some(input.min(limit)),
+            (input, limit) => input.or(limit),
+        }
+        .filter(|duration| *duration > 0.0)
+    }
+
+    /// 输入一段 stderr 字节，返回其中解析出的进度
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<EncodeProgress> {
+        let Some(text) = self.lines.push(bytes) else {
+            return Vec::new();
+        };
+
+        text.split(['\r', '\n'])
+            .filter_map(|line| self.parse_line(line))
+            .collect()
+    }
+
+    fn parse_line(&mut self, line: &str) -> Option<EncodeProgress> {
+        let line = line.trim();
+
+        if self.input_duration.is_none() {
+            if let Some(rest) = line.strip_prefix("Duration:") {
+                self.input_duration = rest.split(',').next().and_then(|d| parse_time(d.trim()));
+                return None;
+            }
+        }
+
+        if !(line.starts_with("frame=") || line.starts_with("size=")) || !line.contains("time=") {
+            return None;
+        }
+
+        let out_time = field(line, "time").and_then(parse_time);
+        let speed = field(line, "speed")
+            .and_then(|speed| speed.trim_end_matches('x').parse::<f64>().ok())
+            .filter(|speed| *speed > 0.0);
+
+        if let Some(speed) = speed {
+            self.smoothed_speed = Some(match self.smoothed_speed {
+                Some(prev) => prev + SPEED_SMOOTHING * (speed - prev),
+                None => speed,
+            });
+        }
+
+        let duration = self.duration();
+        let percentage = match (out_time, duration) {
+            (Some(time), Some(duration)) => {
+                // 流复制时时间戳会跳跃，保证进度单调不减
+                let percentage = (time / duration * 100.0).clamp(0.0, 100.0);
+                self.last_percentage = self.last_percentage.max(percentage);
+                Some(self.last_percentage)
+            }
+            _ => None,
+        };
+
+        let eta_secs = match (out_time, duration, self.smoothed_speed) {
+            (Some(time), Some(duration), Some(speed)) => Some(((duration - time) / speed).max(0.0)),
+            _ => None,
+        };
+
+        Some(EncodeProgress {
+            job_id: self.job_id.clone(),
+            frame: field(line, "frame").and_then(|v| v.parse().ok()),
+            fps: field(line, "fps").and_then(|v| v.parse().ok()),
+            size_bytes: field(line, "size").and_then(parse_size),
+            out_time_secs: out_time,
+            duration_secs: duration,
+            percentage,
+            speed: self.smoothed_speed,
+            elapsed_secs: self.started.elapsed().as_secs_f64(),
+            eta_secs,
+            indeterminate: percentage.is_none(),
+            tags: self.tags.clone(),
+        })
+    }
+}
+
+/// 按 `\r` / `\n` 切分 stderr 字节流，只解码完整的行，避免多字节字符被截断
+#[derive(Default)]
+pub(crate) struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// 追加一段字节，返回其中所有完整的行（保留行尾的 `\r` / `\n`）
+    pub fn push(&mut self, bytes: &[u8]) -> Option<String> {
+        self.pending.extend_from_slice(bytes);
+
+        let end = self
+            .pending
+            .iter()
+            .rposition(|b| matches!(b, b'\r' | b'\n'))?
+            + 1;
+        let rest = self.pending.split_off(end);
+        let lines = std::mem::replace(&mut self.pending, rest);
+        Some(String::from_utf8_lossy(&lines).into_owned())
+    }
+
+    /// 取出剩余的不完整行
+    pub fn finish(&mut self) -> Option<String> {
+        (!self.pending.is_empty())
+            .then(|| String::from_utf8_lossy(&std::mem::take(&mut self.pending)).into_owned())
+    }
+}
+
+/// 读取 `key=value` 形式的字段（`=` 后可能有空格）
+pub(crate) fn field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("{}=", key);
+    let mut search = 0;
+
+    while let Some(offset) = line[search..].find(&pattern) {
+        let index = search + offset;
+        if index == 0 || line[..index].ends_with(' ') {
+            let value = line[index + pattern.len()..].trim_start();
+            let value = value.split_whitespace().next()?;
+            return (value != "N/A").then_some(value);
+        }
+        search = index + pattern.len();
+    }
+
+    None
+}
+
+/// 解析 FFmpeg 时间格式（`HH:MM:SS.ms` 或秒数）
+pub(crate) fn parse_time(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+
+    let mut secs = 0.0;
+    for part in value.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+
+    Some(if negative { -secs } else { secs })
+}
+
+/// 解析大小字段（如 `1024kB`、`2MiB`）
+fn parse_size(value: &str) -> Option<u64> {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match unit {
+        "" | "B" => 1.0,
+        "kB" | "KiB" => 1024.0,
+        "MB" | "MiB" => 1024.0 * 1024.0,
+        "GB" | "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    Some((number * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> ProgressParser {
+        ProgressParser::new("job".into(), JobTags::default(), &[])
+    }
+
+    #[test]
+    fn multibyte_characters_split_across_chunks() {
+        let text = "Input #0, from '视频.mp4':\n".as_bytes();
+        let split = text.iter().position(|b| !b.is_ascii()).unwrap() + 1;
+
+        let mut lines = LineBuffer::default();
+        assert_eq!(lines.push(&text[..split]), None);
+        assert_eq!(
+            lines.push(&text[split..]).as_deref(),
+            Some("Input #0, from '视频.mp4':\n")
+        );
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn incomplete_line_is_kept_until_finish() {
+        let mut lines = LineBuffer::default();
+        assert_eq!(
+            lines.push(b"frame=1\rframe=2").as_deref(),
+            Some("frame=1\r")
+        );
+        assert_eq!(lines.finish().as_deref(), Some("frame=2"));
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn progress_line_split_across_chunks() {
+        let mut parser = parser();
+        assert!(parser
+            .feed(b"  Duration: 00:00:10.00, start: 0.000000\nframe=  25 fps=25 size=  256kB ti")
+            .is_empty());
+
+        let progress = parser.feed(b"me=00:00:05.00 bitrate=N/A speed=2.0x\r");
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].frame, Some(25));
+        assert_eq!(progress[0].out_time_secs, Some(5.0));
+        assert_eq!(progress[0].percentage, Some(50.0));
+        assert_eq!(progress[0].size_bytes, Some(256 * 1024));
+    }
+}
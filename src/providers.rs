@@ -0,0 +1,256 @@
+//! # Providers
+//!
+//! 内置下载源。下载前通过各下载源的 API 解析出具体的发布文件和校验和，
+//! 避免文件命名变化后固定的下载地址失效。
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::{EvermeetChannel, ProviderConfig};
+use crate::error::{Error, Result};
+use crate::models::{BuildChannel, DownloadConfig, Tool};
+
+/// 请求下载源 API 的超时时间
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// BtbN/FFmpeg-Builds 的 GitHub 仓库
+const BTBN_REPO: &str = "BtbN/FFmpeg-Builds";
+
+/// BtbN 每次构建都会更新的滚动发布
+const BTBN_DEFAULT_TAG: &str = "latest";
+
+/// BtbN 发布中的校验和清单
+const BTBN_CHECKSUMS: &str = "checksums.sha256";
+
+/// evermeet.cx 的版本信息 API
+const EVERMEET_API: &str = "https://evermeet.cx/ffmpeg/info";
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// `/ffmpeg/info/<tool>/<channel>` 的响应
+#[derive(Deserialize)]
+struct EvermeetInfo {
+    version: String,
+    download: EvermeetDownloads,
+}
+
+#[derive(Deserialize)]
+struct EvermeetDownloads {
+    zip: Option<EvermeetDownload>,
+}
+
+#[derive(Deserialize)]
+struct EvermeetDownload {
+    url: String,
+}
+
+/// 请求下载源 API 使用的 HTTP 客户端，GitHub API 要求设置 User-Agent
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+pub(crate) fn api_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(API_TIMEOUT)
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()?)
+}
+
+/// 解析 BtbN 发布中当前平台的 GPL 静态构建，并从校验和清单中取出 SHA-256
+///
+/// `release` 渠道在同一发布中有多个 FFmpeg 版本的构建（如 `n7.1` 和 `n8.0`）时选择版本最高的一个，
+/// `git-master` 渠道选择 master 分支的构建。
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) async fn btbn(
+    client: &reqwest::Client,
+    config: &ProviderConfig,
+    channel: BuildChannel,
+) -> Result<DownloadConfig> {
+    let tag = config.btbn_tag.as_deref().unwrap_or(BTBN_DEFAULT_TAG);
+    let body = client
+        .get(format!(
+            "https://api.github.com/repos/{BTBN_REPO}/releases/tags/{tag}"
+        ))
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| Error::Download(format!("Failed to resolve BtbN release `{tag}`: {e}")))?
+        .text()
+        .await?;
+    let release: GithubRelease = serde_json::from_str(&body)
+        .map_err(|e| Error::Download(format!("Invalid GitHub release response: {e}")))?;
+
+    let target = if cfg!(target_arch = "aarch64") {
+        "winarm64"
+    } else {
+        "win64"
+    };
+    let asset = match channel {
+        BuildChannel::Release => release
+            .assets
+            .iter()
+            .filter_map(|asset| {
+                btbn_release_version(&asset.name, target).map(|version| (version, asset))
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, asset)| asset),
+        BuildChannel::GitMaster => release
+            .assets
+            .iter()
+            .find(|asset| is_btbn_master(&asset.name, target)),
+    }
+    .ok_or_else(|| {
+        Error::Download(format!(
+            "BtbN release `{}` has no {target} GPL {} build",
+            release.tag_name,
+            channel.as_str()
+        ))
+    })?;
+
+    let checksums = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == BTBN_CHECKSUMS)
+        .ok_or_else(|| {
+            Error::Download(format!(
+                "BtbN release `{}` has no {BTBN_CHECKSUMS}",
+                release.tag_name
+            ))
+        })?;
+    let manifest = client
+        .get(&checksums.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let sha256 = checksum_for(&manifest, &asset.name).ok_or_else(|| {
+        Error::Download(format!("{BTBN_CHECKSUMS} has no entry for {}", asset.name))
+    })?;
+
+    Ok(DownloadConfig {
+        url: asset.browser_download_url.clone(),
+        executable_path: "bin/ffmpeg.exe".to_string(),
+        tool: None,
+        patches: Vec::new(),
+        sha256: Some(sha256),
+    })
+}
+
+/// 通过 evermeet.cx 的 API 解析 macOS 构建当前的版本和下载地址
+///
+/// evermeet.cx 为每个工具提供单独的压缩包，不提供校验和，下载通过 HTTPS 进行。
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) async fn evermeet(
+    client: &reqwest::Client,
+    config: &ProviderConfig,
+    channel: BuildChannel,
+    tool: Tool,
+) -> Result<DownloadConfig> {
+    let evermeet_channel = config.evermeet_channel.unwrap_or(match channel {
+        BuildChannel::Release => EvermeetChannel::Release,
+        BuildChannel::GitMaster => EvermeetChannel::Snapshot,
+    });
+    let channel = match evermeet_channel {
+        EvermeetChannel::Release => "release",
+        EvermeetChannel::Snapshot => "snapshot",
+    };
+    let name = tool.executable_name();
+    let body = client
+        .get(format!("{EVERMEET_API}/{name}/{channel}"))
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| {
+            Error::Download(format!(
+                "Failed to resolve evermeet.cx {name} {channel}: {e}"
+            ))
+        })?
+        .text()
+        .await?;
+    let info: EvermeetInfo = serde_json::from_str(&body)
+        .map_err(|e| Error::Download(format!("Invalid evermeet.cx response: {e}")))?;
+    let zip = info.download.zip.ok_or_else(|| {
+        Error::Download(format!(
+            "evermeet.cx {name} {channel} {} has no zip download",
+            info.version
+        ))
+    })?;
+
+    Ok(DownloadConfig {
+        url: zip.url,
+        executable_path: name.to_string(),
+        tool: Some(tool),
+        patches: Vec::new(),
+        sha256: None,
+    })
+}
+
+/// johnvansickle.com 的 Linux 静态构建，`git-master` 渠道为每周更新的 git 构建
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn johnvansickle(channel: BuildChannel) -> DownloadConfig {
+    let arch = if cfg!(target_arch = "aarch64") {
+        "arm64"
+    } else {
+        "amd64"
+    };
+    let url = match channel {
+        BuildChannel::Release => {
+            format!("https://johnvansickle.com/ffmpeg/releases/ffmpeg-release-{arch}-static.tar.xz")
+        }
+        BuildChannel::GitMaster => {
+            format!("https://johnvansickle.com/ffmpeg/builds/ffmpeg-git-{arch}-static.tar.xz")
+        }
+    };
+
+    DownloadConfig {
+        url,
+        executable_path: "ffmpeg".to_string(),
+        tool: None,
+        patches: Vec::new(),
+        sha256: None,
+    }
+}
+
+/// 是否为 master 分支的构建（`ffmpeg-master-latest-win64-gpl.zip` 或自动构建中的 `ffmpeg-N-...-win64-gpl.zip`）
+fn is_btbn_master(name: &str, target: &str) -> bool {
+    (name.starts_with("ffmpeg-master-") || name.starts_with("ffmpeg-N-"))
+        && name.ends_with(&format!("-{target}-gpl.zip"))
+}
+
+/// 从 `ffmpeg-n8.0-latest-win64-gpl-8.0.zip` 形式的文件名中取出发布版本号（`[8, 0]`）
+///
+/// 只接受指定平台的静态 GPL 构建，跳过 `master`、`shared` 和 `lgpl` 构建。
+fn btbn_release_version(name: &str, target: &str) -> Option<Vec<u32>> {
+    if !name.starts_with("ffmpeg-n") {
+        return None;
+    }
+    let (_, version) = name
+        .strip_suffix(".zip")?
+        .split_once(&format!("-{target}-gpl-"))?;
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// 从 `sha256sum` 格式的清单中查找文件的校验和
+fn checksum_for(manifest: &str, file_name: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        // 二进制模式下文件名前带有 `*`
+        let name = name.trim().trim_start_matches('*');
+        (name == file_name).then(|| hash.to_ascii_lowercase())
+    })
+}
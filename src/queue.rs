@@ -0,0 +1,176 @@
+//! # Queue
+//!
+//! 将尚未开始的后台任务保存到磁盘，应用崩溃或更新重启后可以恢复。
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::models::EnqueueRequest;
+
+/// `queue.json` 中的一项
+///
+/// 发起任务的窗口（`JobTags::window`）不接受反序列化，单独保存在由插件写入的字段中，
+/// 恢复后 `killJobsOnWindowClose` 仍然对这些任务生效。
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredJob {
+    #[serde(flatten)]
+    request: EnqueueRequest,
+    #[serde(default)]
+    owner_window: Option<String>,
+}
+
+/// 已保存的后台任务
+pub(crate) struct QueueStore {
+    /// 保存位置，无法获取应用数据目录时为 None（只保存在内存中）
+    path: Option<PathBuf>,
+    /// 任务数上限，None 表示不限制
+    capacity: Option<usize>,
+    /// 低于该长度时视为已排空
+    low_watermark: Option<usize>,
+    jobs: Mutex<QueuedJobs>,
+}
+
+struct QueuedJobs {
+    jobs: Vec<EnqueueRequest>,
+    /// 上次排空后队列长度是否达到过低水位
+    above_watermark: bool,
+}
+
+impl QueueStore {
+    /// 从磁盘加载上次保存的任务，文件不存在或损坏时为空
+    pub fn load(
+        path: Option<PathBuf>,
+        capacity: Option<usize>,
+        low_watermark: Option<usize>,
+    ) -> Self {
+        let stored: Vec<StoredJob> = path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        let jobs: Vec<EnqueueRequest> = stored
+            .into_iter()
+            .map(|mut stored| {
+                stored.request.execute.tags.window = stored.owner_window;
+                stored.request
+            })
+            .collect();
+        let low_watermark = low_watermark.or(capacity.map(|capacity| (capacity / 2).max(1)));
+
+        Self {
+            path,
+            capacity,
+            low_watermark,
+            jobs: Mutex::new(QueuedJobs {
+                above_watermark: low_watermark.is_some_and(|low| jobs.len() >= low),
+                jobs,
+            }),
+        }
+    }
+
+    /// 保存任务，请求中的 `job_id` 和 `not_before` 必须已确定
+    ///
+    /// 队列已满时返回 [`Error::QueueFull`]，替换同一 ID 的任务不受限制。
+    pub fn insert(&self, request: EnqueueRequest) -> Result<()> {
+        let mut queued = self.jobs.lock().unwrap();
+        queued
+            .jobs
+            .retain(|job| job.execute.job_id != request.execute.job_id);
+
+        if let Some(capacity) = self.capacity {
+            if queued.jobs.len() >= capacity {
+                return Err(Error::QueueFull(queued.jobs.len()));
+            }
+        }
+
+        queued.jobs.push(request);
+        if self
+            .low_watermark
+            .is_some_and(|low| queued.jobs.len() >= low)
+        {
+            queued.above_watermark = true;
+        }
+        self.save(&queued.jobs);
+        Ok(())
+    }
+
+    /// 移除已开始或已结束的任务，队列因此降到低水位以下时返回剩余的任务数
+    pub fn remove(&self, job_id: &str) -> Option<usize> {
+        let mut queued = self.jobs.lock().unwrap();
+        let len = queued.jobs.len();
+        queued
+            .jobs
+            .retain(|job| job.execute.job_id.as_deref() != Some(job_id));
+        if queued.jobs.len() == len {
+            return None;
+        }
+        self.save(&queued.jobs);
+
+        let len = queued.jobs.len();
+        if queued.above_watermark && self.low_watermark.is_some_and(|low| len < low) {
+            queued.above_watermark = false;
+            return Some(len);
+        }
+        None
+    }
+
+    /// 所有已保存的任务，按加入顺序排列
+    pub fn snapshot(&self) -> Vec<EnqueueRequest> {
+        self.jobs.lock().unwrap().jobs.clone()
+    }
+
+    /// 先写入临时文件再替换，避免写入过程中崩溃损坏队列文件
+    fn save(&self, jobs: &[EnqueueRequest]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let stored: Vec<StoredJob> = jobs
+            .iter()
+            .map(|request| StoredJob {
+                owner_window: request.execute.tags.window.clone(),
+                request: request.clone(),
+            })
+            .collect();
+        let Ok(data) = serde_json::to_vec(&stored) else {
+            return;
+        };
+
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+
+        let temp_path = path.with_extension("json.tmp");
+        if fs::write(&temp_path, data).is_ok() {
+            let _ = fs::rename(&temp_path, path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_window_survives_reload() {
+        let dir = std::env::temp_dir().join(format!("use-ffmpeg-queue-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("queue.json");
+
+        let mut request = EnqueueRequest::default();
+        request.execute.job_id = Some("job".to_string());
+        request.execute.tags.window = Some("editor-1".to_string());
+        QueueStore::load(Some(path.clone()), None, None)
+            .insert(request)
+            .unwrap();
+
+        let jobs = QueueStore::load(Some(path), None, None).snapshot();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].execute.tags.window.as_deref(), Some("editor-1"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,96 @@
+//! # Quota
+//!
+//! 限制插件自行管理的可再生数据（临时目录、压缩存储模式下解压的可执行文件）的磁盘占用，
+//! 超出时按最近使用时间删除最久未用的文件。
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::{Error, Result};
+use crate::models::QuotaStatus;
+
+/// 受配额限制的一个文件
+struct StoredFile {
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// 统计各目录的占用
+pub(crate) fn status(dirs: &[PathBuf], limit: Option<u64>) -> QuotaStatus {
+    let files = scan(dirs);
+    QuotaStatus {
+        limit_bytes: limit,
+        used_bytes: files.iter().map(|file| file.size).sum(),
+        files: files.len(),
+    }
+}
+
+/// 确保在现有占用之外还能写入 `incoming` 字节，必要时删除最久未用的文件
+///
+/// `protected` 中的文件（正在使用的临时文件等）不会被删除，删除其余文件后仍然放不下时返回
+/// [`Error::QuotaExceeded`]。
+pub(crate) fn reserve(
+    dirs: &[PathBuf],
+    limit: u64,
+    incoming: u64,
+    protected: &HashSet<PathBuf>,
+) -> Result<()> {
+    let mut files = scan(dirs);
+    let mut used: u64 = files.iter().map(|file| file.size).sum();
+    if used.saturating_add(incoming) <= limit {
+        return Ok(());
+    }
+
+    files.sort_by_key(|file| file.last_used);
+    for file in files {
+        if used.saturating_add(incoming) <= limit {
+            break;
+        }
+        if protected.contains(&file.path) {
+            continue;
+        }
+        if fs::remove_file(&file.path).is_ok() {
+            used -= file.size;
+        }
+    }
+
+    if used.saturating_add(incoming) > limit {
+        return Err(Error::QuotaExceeded { used, limit });
+    }
+    Ok(())
+}
+
+fn scan(dirs: &[PathBuf]) -> Vec<StoredFile> {
+    let mut files = Vec::new();
+    for dir in dirs {
+        scan_dir(dir, &mut files);
+    }
+    files
+}
+
+fn scan_dir(dir: &Path, files: &mut Vec<StoredFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            scan_dir(&entry.path(), files);
+        } else if metadata.is_file() {
+            // 很多系统不更新访问时间，此时以修改时间为准
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let accessed = metadata.accessed().unwrap_or(modified);
+            files.push(StoredFile {
+                path: entry.path(),
+                size: metadata.len(),
+                last_used: accessed.max(modified),
+            });
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! # Scheduler
+//!
+//! 限制同时运行的 FFmpeg 进程数量，超出的任务按先后顺序排队。
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+
+/// 任务调度器
+pub(crate) struct Scheduler {
+    state: Mutex<SchedulerState>,
+    changed: Notify,
+}
+
+struct SchedulerState {
+    /// 最大并发数，None 表示不限制
+    limit: Option<usize>,
+    /// 限流时的最大并发数
+    throttle_limit: Option<usize>,
+    /// 是否暂停启动新任务
+    paused: bool,
+    running: usize,
+    waiting: VecDeque<u64>,
+    next_ticket: u64,
+}
+
+impl SchedulerState {
+    fn has_capacity(&self) -> bool {
+        if self.paused {
+            return false;
+        }
+
+        let limit = match (self.limit, self.throttle_limit) {
+            (Some(limit), Some(throttle)) => Some(limit.min(throttle)),
+            (limit, throttle) => limit.or(throttle),
+        };
+
+        match limit {
+            Some(limit) => self.running < limit,
+            None => true,
+        }
+    }
+}
+
+impl Scheduler {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            state: Mutex::new(SchedulerState {
+                limit: limit.map(|limit| limit.max(1)),
+                throttle_limit: None,
+                paused: false,
+                running: 0,
+                waiting: VecDeque::new(),
+                next_ticket: 0,
+            }),
+            changed: Notify::new(),
+        }
+    }
+
+    /// 排队等待运行名额，返回的 [`SchedulerPermit`] 被释放时归还名额
+    ///
+    /// `only_when_idle` 为 true 时还需等待没有其他任务在运行。
+    pub async fn acquire(&self, only_when_idle: bool) -> SchedulerPermit<'_> {
+        let ticket = {
+            let mut state = self.state.lock().unwrap();
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiting.push_back(ticket);
+            ticket
+        };
+        let mut waiter = Waiter {
+            scheduler: self,
+            ticket,
+            admitted: false,
+        };
+
+        loop {
+            let changed = self.changed.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                let idle = !only_when_idle || state.running == 0;
+                if state.waiting.front() == Some(&ticket) && state.has_capacity() && idle {
+                    state.waiting.pop_front();
+                    state.running += 1;
+                    waiter.admitted = true;
+                    drop(state);
+                    // 让下一个排队的任务重新检查
+                    self.changed.notify_waiters();
+                    return SchedulerPermit { scheduler: self };
+                }
+            }
+            changed.await;
+        }
+    }
+
+    /// 设置限流状态，只影响尚未开始的任务
+    pub fn set_throttle(&self, paused: bool, limit: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = paused;
+        state.throttle_limit = limit.map(|limit| limit.max(1));
+        drop(state);
+        self.changed.notify_waiters();
+    }
+
+    fn release(&self) {
+        self.state.lock().unwrap().running -= 1;
+        self.changed.notify_waiters();
+    }
+}
+
+/// 排队中的任务，未获得名额就被取消时从队列中移除
+struct Waiter<'a> {
+    scheduler: &'a Scheduler,
+    ticket: u64,
+    admitted: bool,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        if self.admitted {
+            return;
+        }
+        let mut state = self.scheduler.state.lock().unwrap();
+        state.waiting.retain(|ticket| *ticket != self.ticket);
+        drop(state);
+        self.scheduler.changed.notify_waiters();
+    }
+}
+
+/// 运行名额
+pub(crate) struct SchedulerPermit<'a> {
+    scheduler: &'a Scheduler,
+}
+
+impl Drop for SchedulerPermit<'_> {
+    fn drop(&mut self) {
+        self.scheduler.release();
+    }
+}
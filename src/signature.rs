@@ -0,0 +1,168 @@
+//! # Signature
+//!
+//! 验证 Windows 可执行文件的 Authenticode 签名（`WinVerifyTrust`），并读取签名者信息。
+//! 其他平台的可执行文件没有对应的签名机制，不进行验证。
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// `WinVerifyTrust` 在文件没有签名时返回的结果
+#[cfg_attr(not(windows), allow(dead_code))]
+const TRUST_E_NOSIGNATURE: i32 = 0x800B_0100_u32 as i32;
+
+/// 可执行文件的签名信息
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureInfo {
+    /// 签名是否有效且证书链到受信任的根证书
+    pub trusted: bool,
+    /// 签名者证书的名称
+    pub signer: Option<String>,
+    /// 签名者证书的颁发者
+    pub issuer: Option<String>,
+    /// `WinVerifyTrust` 的结果（HRESULT），有效时为 0
+    pub status: i32,
+}
+
+impl SignatureInfo {
+    /// 签名是否可信，设置了 `trusted_signers` 时签名者还必须是其中之一
+    pub(crate) fn accepted_by(&self, trusted_signers: Option<&[String]>) -> bool {
+        self.trusted
+            && trusted_signers.map_or(true, |signers| {
+                self.signer
+                    .as_ref()
+                    .is_some_and(|signer| signers.iter().any(|trusted| trusted == signer))
+            })
+    }
+}
+
+/// 验证文件的 Authenticode 签名，文件没有签名或不在 Windows 上时返回 None
+///
+/// 不检查证书吊销，避免验证时访问网络。
+pub(crate) fn verify(path: &Path) -> Option<SignatureInfo> {
+    #[cfg(windows)]
+    {
+        let (status, signer, issuer) = windows::verify(path);
+        if status == TRUST_E_NOSIGNATURE {
+            return None;
+        }
+        Some(SignatureInfo {
+            trusted: status == 0,
+            signer,
+            issuer,
+            status,
+        })
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+    use std::mem::size_of;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::Security::Cryptography::{
+        CertGetNameStringW, CERT_CONTEXT, CERT_NAME_ISSUER_FLAG, CERT_NAME_SIMPLE_DISPLAY_TYPE,
+    };
+    use windows_sys::Win32::Security::WinTrust::{
+        WTHelperGetProvSignerFromChain, WTHelperProvDataFromStateData, WinVerifyTrust,
+        WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0, WINTRUST_FILE_INFO,
+        WTD_CHOICE_FILE, WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY,
+        WTD_UI_NONE,
+    };
+
+    /// 返回 `WinVerifyTrust` 的结果，以及签名者证书的名称和颁发者
+    pub(super) fn verify(path: &Path) -> (i32, Option<String>, Option<String>) {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut file = WINTRUST_FILE_INFO {
+            cbStruct: size_of::<WINTRUST_FILE_INFO>() as u32,
+            pcwszFilePath: wide.as_ptr(),
+            ..Default::default()
+        };
+        let mut data = WINTRUST_DATA {
+            cbStruct: size_of::<WINTRUST_DATA>() as u32,
+            dwUIChoice: WTD_UI_NONE,
+            fdwRevocationChecks: WTD_REVOKE_NONE,
+            dwUnionChoice: WTD_CHOICE_FILE,
+            Anonymous: WINTRUST_DATA_0 { pFile: &mut file },
+            dwStateAction: WTD_STATEACTION_VERIFY,
+            ..Default::default()
+        };
+        let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+
+        // SAFETY: `data` 和 `file` 在两次调用期间有效，`wide` 以 0 结尾；
+        // 第二次调用释放第一次调用分配的状态数据
+        unsafe {
+            let status = WinVerifyTrust(
+                ptr::null_mut(),
+                &mut action,
+                &mut data as *mut WINTRUST_DATA as *mut c_void,
+            );
+            let (signer, issuer) = signer_names(data.hWVTStateData);
+
+            data.dwStateAction = WTD_STATEACTION_CLOSE;
+            WinVerifyTrust(
+                ptr::null_mut(),
+                &mut action,
+                &mut data as *mut WINTRUST_DATA as *mut c_void,
+            );
+
+            (status, signer, issuer)
+        }
+    }
+
+    /// 从验证状态中取出第一个签名者证书的名称和颁发者
+    unsafe fn signer_names(state: HANDLE) -> (Option<String>, Option<String>) {
+        if state.is_null() {
+            return (None, None);
+        }
+        let provider = WTHelperProvDataFromStateData(state);
+        if provider.is_null() {
+            return (None, None);
+        }
+        let signer = WTHelperGetProvSignerFromChain(provider, 0, 0, 0);
+        if signer.is_null() || (*signer).csCertChain == 0 || (*signer).pasCertChain.is_null() {
+            return (None, None);
+        }
+        let cert = (*(*signer).pasCertChain).pCert;
+
+        (cert_name(cert, 0), cert_name(cert, CERT_NAME_ISSUER_FLAG))
+    }
+
+    unsafe fn cert_name(cert: *const CERT_CONTEXT, flags: u32) -> Option<String> {
+        if cert.is_null() {
+            return None;
+        }
+        let len = CertGetNameStringW(
+            cert,
+            CERT_NAME_SIMPLE_DISPLAY_TYPE,
+            flags,
+            ptr::null(),
+            ptr::null_mut(),
+            0,
+        );
+        // 长度包括结尾的 0，为 1 时表示名称为空
+        if len <= 1 {
+            return None;
+        }
+        let mut buf = vec![0u16; len as usize];
+        CertGetNameStringW(
+            cert,
+            CERT_NAME_SIMPLE_DISPLAY_TYPE,
+            flags,
+            ptr::null(),
+            buf.as_mut_ptr(),
+            len,
+        );
+        Some(String::from_utf16_lossy(&buf[..len as usize - 1]))
+    }
+}
@@ -0,0 +1,100 @@
+//! # Simulate
+//!
+//! 前端开发用的模拟模式，需要启用 `simulate` feature 并设置 `simulate: true`。
+//! 任务不运行 FFmpeg，而是按 FFmpeg 的 stderr 格式生成输出，经过与真实任务相同的进度解析器发送
+//! `use-ffmpeg://job-progress` 事件，没有安装 FFmpeg 的机器也可以开发进度界面。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime};
+
+use crate::desktop::emit_job_progress;
+use crate::error::{Error, Result};
+use crate::jobs::JobGuard;
+use crate::models::ExecuteResponse;
+use crate::progress::{parse_time, ProgressParser};
+
+/// 参数中没有 `-t` 时模拟的媒体时长（秒）
+const DEFAULT_DURATION: f64 = 10.0;
+
+/// 输出进度的间隔，与 FFmpeg 默认的 `-stats_period` 相近
+const TICK: Duration = Duration::from_millis(250);
+
+/// 平均编码速度（倍速），实际速度在其上下波动
+const BASE_SPEED: f64 = 2.0;
+
+/// 模拟的帧率和码率（kbit/s）
+const FRAME_RATE: f64 = 25.0;
+const BITRATE: f64 = 2000.0;
+
+/// 运行模拟任务：逐步输出进度直到完成，可以被取消，不写入任何输出文件
+pub(crate) async fn run<R: Runtime>(app: &AppHandle<R>, job: &JobGuard) -> Result<ExecuteResponse> {
+    let info = job.info();
+    let mut parser = ProgressParser::new(info.id.clone(), info.tags.clone(), &info.args);
+    let duration = info
+        .args
+        .windows(2)
+        .rev()
+        .filter(|pair| pair[0] == "-t")
+        .find_map(|pair| parse_time(&pair[1]))
+        .filter(|duration| *duration > 0.0)
+        .unwrap_or(DEFAULT_DURATION);
+
+    let mut stderr = format!(
+        "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'simulated':\n  Duration: {}, start: 0.000000, bitrate: {BITRATE} kb/s\n",
+        format_time(duration)
+    );
+    feed(app, &mut parser, &stderr);
+
+    let mut out_time = 0.0;
+    let mut tick = 0u32;
+    while out_time < duration {
+        tokio::select! {
+            _ = tokio::time::sleep(TICK) => {}
+            _ = job.cancelled() => return Err(Error::Cancelled),
+        }
+        tick += 1;
+        // 速度随时间平滑波动，使 ETA 和速度显示更接近真实情况
+        let speed = BASE_SPEED * (1.0 + 0.25 * (f64::from(tick) * 0.7).sin());
+        out_time = (out_time + TICK.as_secs_f64() * speed).min(duration);
+
+        let line = format!(
+            "frame={:>5} fps={:.1} q=28.0 size={:>8}kB time={} bitrate={BITRATE:.1}kbits/s speed={speed:.2}x\r",
+            (out_time * FRAME_RATE) as u64,
+            FRAME_RATE * speed,
+            (out_time * BITRATE / 8.0) as u64,
+            format_time(out_time),
+        );
+        feed(app, &mut parser, &line);
+        stderr.push_str(&line);
+    }
+    stderr.push_str("\n[simulated] no output was written\n");
+
+    Ok(ExecuteResponse {
+        job_id: info.id.clone(),
+        success: true,
+        stdout: String::new(),
+        stderr,
+        exit_code: Some(0),
+        stats: None,
+        warnings: Vec::new(),
+    })
+}
+
+fn feed<R: Runtime>(app: &AppHandle<R>, parser: &mut ProgressParser, text: &str) {
+    for progress in parser.feed(text.as_bytes()) {
+        emit_job_progress(app, &progress);
+    }
+}
+
+/// 格式化为 FFmpeg 的 `HH:MM:SS.cc`
+fn format_time(secs: f64) -> String {
+    let centis = (secs * 100.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:02}",
+        centis / 360_000,
+        centis / 6_000 % 60,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
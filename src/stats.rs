@@ -0,0 +1,83 @@
+//! # Stats
+//!
+//! 采样 FFmpeg 进程的资源占用。
+
+use std::time::Duration;
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Runtime};
+use tokio_util::sync::CancellationToken;
+
+use crate::events;
+use crate::models::{JobResourceStats, JobStatsSample, JobTags};
+
+/// 采样间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 资源占用采样器
+pub(crate) struct StatsSampler {
+    stop: CancellationToken,
+    task: tokio::task::JoinHandle<JobResourceStats>,
+}
+
+impl StatsSampler {
+    /// 开始采样指定进程，每次采样发送一次 `use-ffmpeg://job-stats` 事件
+    pub fn spawn<R: Runtime>(app: AppHandle<R>, job_id: String, tags: JobTags, pid: u32) -> Self {
+        let stop = CancellationToken::new();
+        let stopped = stop.clone();
+
+        let task = tokio::spawn(async move {
+            let pid = Pid::from_u32(pid);
+            let refresh_kind = ProcessRefreshKind::nothing().with_cpu().with_memory();
+            let mut system = System::new();
+            let mut totals = JobResourceStats::default();
+            let mut cpu_sum = 0.0;
+            let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+
+            // CPU 占用需要两次刷新之间的差值，先刷新一次作为基准
+            interval.tick().await;
+            system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh_kind);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stopped.cancelled() => break,
+                }
+
+                system.refresh_processes_specifics(
+                    ProcessesToUpdate::Some(&[pid]),
+                    true,
+                    refresh_kind,
+                );
+                let Some(process) = system.process(pid) else {
+                    break;
+                };
+
+                let sample = JobStatsSample {
+                    job_id: job_id.clone(),
+                    cpu_percent: process.cpu_usage(),
+                    rss_bytes: process.memory(),
+                    tags: tags.clone(),
+                };
+
+                totals.samples += 1;
+                cpu_sum += sample.cpu_percent as f64;
+                totals.avg_cpu_percent = (cpu_sum / totals.samples as f64) as f32;
+                totals.peak_cpu_percent = totals.peak_cpu_percent.max(sample.cpu_percent);
+                totals.peak_rss_bytes = totals.peak_rss_bytes.max(sample.rss_bytes);
+
+                events::emit(&app, events::JOB_STATS, &sample);
+            }
+
+            totals
+        });
+
+        Self { stop, task }
+    }
+
+    /// 停止采样并返回汇总
+    pub async fn finish(self) -> JobResourceStats {
+        self.stop.cancel();
+        self.task.await.unwrap_or_default()
+    }
+}
@@ -0,0 +1,82 @@
+//! # Storage
+//!
+//! 压缩存储模式：安装后可执行文件以 zstd 压缩保存在应用数据目录，首次使用时解压到缓存目录。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 压缩级别，兼顾压缩率和安装耗时
+const LEVEL: i32 = 19;
+
+/// 压缩后的文件路径（`<可执行文件>.zst`）
+pub(crate) fn stored_path(raw: &Path) -> PathBuf {
+    let mut name = raw.file_name().unwrap_or_default().to_os_string();
+    name.push(".zst");
+    raw.with_file_name(name)
+}
+
+/// 压缩可执行文件并删除原文件，先写入临时文件再替换，避免留下不完整的压缩文件
+pub(crate) fn compress(raw: &Path) -> io::Result<()> {
+    let stored = stored_path(raw);
+    let temp = temp_path(&stored);
+
+    let mut input = fs::File::open(raw)?;
+    let output = fs::File::create(&temp)?;
+    let result = (|| {
+        let mut encoder = zstd::stream::write::Encoder::new(output, LEVEL)?;
+        encoder.long_distance_matching(true)?;
+        io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?.sync_all()
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    fs::rename(&temp, &stored)?;
+    fs::remove_file(raw)
+}
+
+/// 将压缩的可执行文件解压到 `runtime`，并行调用时后完成的一方直接替换
+pub(crate) fn decompress(stored: &Path, runtime: &Path) -> io::Result<()> {
+    if let Some(parent) = runtime.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = temp_path(runtime);
+
+    let result = (|| {
+        let mut decoder = zstd::stream::read::Decoder::new(fs::File::open(stored)?)?;
+        let mut output = fs::File::create(&temp)?;
+        io::copy(&mut decoder, &mut output)?;
+        output.sync_all()?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp, fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    fs::rename(&temp, runtime)
+}
+
+/// 解压副本是否存在且不比压缩文件旧
+pub(crate) fn is_fresh(stored: &Path, runtime: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(stored), modified(runtime)) {
+        (Ok(stored), Ok(runtime)) => runtime >= stored,
+        (_, runtime) => runtime.is_ok(),
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    path.with_file_name(name)
+}
@@ -0,0 +1,127 @@
+//! # Stream
+//!
+//! 以异步流的形式返回 FFmpeg 的输出，供 Rust 端直接消费，不经过事件和 JSON。
+
+use futures_util::Stream;
+use tauri::Runtime;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+
+use crate::desktop::Ffmpeg;
+use crate::error::Result;
+use crate::models::{EncodeProgress, ExecuteRequest};
+use crate::progress::{LineBuffer, ProgressParser};
+
+/// 读取标准输出时每块的最大字节数
+const STDOUT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 尚未被消费的输出块数量上限，超过后暂停读取，FFmpeg 随之阻塞
+const CHANNEL_CAPACITY: usize = 16;
+
+/// `execute_stream` 输出的一块数据
+#[derive(Debug, Clone)]
+pub enum OutputChunk {
+    /// 标准输出的原始字节（如 `pipe:1` 的输出）
+    Stdout(Vec<u8>),
+    /// 标准错误输出的文本，总是以完整的行为单位（最后一行可能没有换行符）
+    Stderr(String),
+    /// 从标准错误输出解析出的进度
+    Progress(Box<EncodeProgress>),
+    /// 进程已退出，总是最后一项
+    Exited {
+        success: bool,
+        /// 退出码，被信号终止时为 None
+        exit_code: Option<i32>,
+        /// 是否因任务被取消或流被释放而终止
+        cancelled: bool,
+    },
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 执行 FFmpeg 命令，以流的形式返回标准输出、标准错误输出和进度
+    ///
+    /// 任务出现在 `list_jobs` 中并可以通过 `cancel` 终止，但不经过调度器，也不发送任务事件。
+    /// 流被释放时进程会被终止；消费速度跟不上时 FFmpeg 会被阻塞，不会无限占用内存。
+    pub async fn execute_stream(
+        &self,
+        request: ExecuteRequest,
+    ) -> Result<impl Stream<Item = OutputChunk> + Send + Unpin + 'static> {
+        let job = self.register_job(&request)?;
+        let mut parser = ProgressParser::new(
+            job.info().id.clone(),
+            job.info().tags.clone(),
+            &job.info().args,
+        );
+
+        let mut child = self.spawn_raw(request).await?;
+        drop(child.stdin());
+        let mut stdout = child.stdout().expect("stdout is piped");
+        let mut stderr = child.stderr().expect("stderr is piped");
+
+        let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+        tauri::async_runtime::spawn(async move {
+            let mut out = vec![0u8; STDOUT_CHUNK_SIZE];
+            let mut err = [0u8; 4096];
+            let mut lines = LineBuffer::default();
+            let (mut out_open, mut err_open) = (true, true);
+
+            let cancelled = loop {
+                if !out_open && !err_open {
+                    break false;
+                }
+
+                let mut chunks = Vec::new();
+                tokio::select! {
+                    read = stdout.read(&mut out), if out_open => match read {
+                        Ok(0) | Err(_) => out_open = false,
+                        Ok(n) => chunks.push(OutputChunk::Stdout(out[..n].to_vec())),
+                    },
+                    read = stderr.read(&mut err), if err_open => match read {
+                        Ok(0) | Err(_) => {
+                            err_open = false;
+                            chunks.extend(lines.finish().map(OutputChunk::Stderr));
+                        }
+                        Ok(n) => {
+                            // 只输出完整的行，避免多字节字符被截断
+                            chunks.extend(lines.push(&err[..n]).map(OutputChunk::Stderr));
+                            chunks.extend(
+                                parser
+                                    .feed(&err[..n])
+                                    .into_iter()
+                                    .map(|progress| OutputChunk::Progress(Box::new(progress))),
+                            );
+                        }
+                    },
+                    _ = job.cancelled() => break true,
+                    _ = tx.closed() => break true,
+                }
+
+                let mut closed = false;
+                for chunk in chunks {
+                    if tx.send(chunk).await.is_err() {
+                        closed = true;
+                        break;
+                    }
+                }
+                if closed {
+                    break true;
+                }
+            };
+
+            if cancelled {
+                let _ = child.kill().await;
+            }
+            let status = child.wait().await.ok();
+            let _ = tx
+                .send(OutputChunk::Exited {
+                    success: !cancelled && status.is_some_and(|status| status.success()),
+                    exit_code: status.and_then(|status| status.code()),
+                    cancelled,
+                })
+                .await;
+            drop(job);
+        });
+
+        Ok(futures_util::stream::poll_fn(move |cx| rx.poll_recv(cx)))
+    }
+}
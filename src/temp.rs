@@ -0,0 +1,140 @@
+//! # Temp
+//!
+//! 辅助函数产生的中间文件（试编码结果、调色板、concat 列表等）统一放在插件的临时目录中，
+//! 使用结束后删除，进程崩溃等原因遗留的文件超过保留时间后自动清理。
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Runtime};
+
+use crate::error::Result;
+use crate::models::PurgeTempResponse;
+use crate::FfmpegExt;
+
+/// 遗留文件的默认保留时间（小时）
+pub(crate) const DEFAULT_TTL_HOURS: u64 = 24;
+
+/// 自动清理的最长间隔
+const GC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 临时文件登记表
+pub(crate) struct TempRegistry {
+    dir: PathBuf,
+    ttl: Duration,
+    /// 正在使用的文件，清理时跳过
+    active: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+/// 已登记的临时文件，被释放时删除
+pub(crate) struct TempFile {
+    path: PathBuf,
+    active: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl TempFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        self.active.lock().unwrap().remove(&self.path);
+    }
+}
+
+impl TempRegistry {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self {
+            dir,
+            ttl,
+            active: Arc::default(),
+        }
+    }
+
+    /// 临时目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 正在使用的文件
+    pub fn active(&self) -> HashSet<PathBuf> {
+        self.active.lock().unwrap().clone()
+    }
+
+    /// 在临时目录中登记一个新文件 `<prefix>-<uuid>.<extension>`，文件本身由调用方创建
+    pub fn create(&self, prefix: &str, extension: &str) -> Result<TempFile> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self
+            .dir
+            .join(format!("{prefix}-{}.{extension}", uuid::Uuid::new_v4()));
+        self.active.lock().unwrap().insert(path.clone());
+        Ok(TempFile {
+            path,
+            active: self.active.clone(),
+        })
+    }
+
+    /// 删除超过保留时间的遗留文件
+    pub fn gc(&self) -> PurgeTempResponse {
+        let cutoff = SystemTime::now()
+            .checked_sub(self.ttl)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.remove_where(|modified| modified < cutoff)
+    }
+
+    /// 删除所有未在使用的临时文件
+    pub fn purge(&self) -> PurgeTempResponse {
+        self.remove_where(|_| true)
+    }
+
+    fn remove_where(&self, expired: impl Fn(SystemTime) -> bool) -> PurgeTempResponse {
+        let mut response = PurgeTempResponse::default();
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return response;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() || self.active.lock().unwrap().contains(&path) {
+                continue;
+            }
+            if !expired(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)) {
+                continue;
+            }
+            if fs::remove_file(&path).is_ok() {
+                response.removed += 1;
+                response.freed_bytes += metadata.len();
+            }
+        }
+
+        response
+    }
+
+    /// 自动清理的间隔，不超过保留时间
+    fn gc_interval(&self) -> Duration {
+        self.ttl.min(GC_INTERVAL)
+    }
+}
+
+/// 启动时清理一次遗留文件，之后定期清理
+pub(crate) fn spawn_gc<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(app.ffmpeg().temp().gc_interval());
+
+        loop {
+            interval.tick().await;
+
+            let app = app.clone();
+            let _ = tokio::task::spawn_blocking(move || app.ffmpeg().temp().gc()).await;
+        }
+    });
+}
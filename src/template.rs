@@ -0,0 +1,402 @@
+//! # Template
+//!
+//! 由宿主应用在插件构建器中注册的命令模板。前端只能通过 `run_template` 以模板名称和参数运行，
+//! 参数经过类型校验后整体替换到占位符中，不会被拆分成多个参数，也不能注入额外的选项。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::Runtime;
+
+use crate::args::target_protocol;
+use crate::desktop::Ffmpeg;
+use crate::error::{Error, Result};
+use crate::helpers::HelperOptions;
+use crate::models::ExecuteResponse;
+
+/// 文本参数的最大长度
+const MAX_TEXT_LEN: usize = 256;
+
+/// 模板参数的类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateParam {
+    /// 本地文件路径，不能以 `-` 开头，也不能是 `http:`、`concat:`、`subfile,,start,0,:` 等带协议的地址
+    Path,
+    /// 数字，可以限制取值范围
+    Number { min: Option<f64>, max: Option<f64> },
+    /// 整数，可以限制取值范围
+    Integer { min: Option<i64>, max: Option<i64> },
+    /// 限定的取值之一，如编码器名称
+    OneOf(Vec<String>),
+    /// 只包含字母、数字、空格和 `_`、`-`、`.` 的短文本，不能以 `-` 开头
+    Text,
+}
+
+/// 命令模板
+///
+/// 参数中的 `{name}` 为占位符，可以占据整个参数（如 `"{input}"`）或其中一部分（如 `"scale={width}:-2"`），
+/// `{{` 和 `}}` 表示字面的大括号。
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use tauri_plugin_use_ffmpeg::{Builder, CommandTemplate, TemplateParam};
+///
+/// let plugin = Builder::new()
+///     .command_template(
+///         "thumbnail",
+///         CommandTemplate::new(["-ss", "{time}", "-i", "{input}", "-frames:v", "1", "{output}"])
+///             .param("time", TemplateParam::Number { min: Some(0.0), max: None })
+///             .param("input", TemplateParam::Path)
+///             .param("output", TemplateParam::Path),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandTemplate {
+    args: Vec<String>,
+    params: HashMap<String, TemplateParam>,
+}
+
+/// 运行命令模板的请求
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTemplateRequest {
+    /// 模板名称
+    pub name: String,
+    /// 模板参数
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    #[serde(flatten)]
+    pub job: HelperOptions,
+}
+
+/// 参数中的一段
+enum Segment<'a> {
+    Literal(String),
+    Placeholder(&'a str),
+}
+
+impl CommandTemplate {
+    /// 以参数数组创建模板，参数不包含 ffmpeg 本身
+    pub fn new<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+            params: HashMap::new(),
+        }
+    }
+
+    /// 声明占位符参数的类型，模板中的每个占位符都必须声明
+    pub fn param(mut self, name: impl Into<String>, kind: TemplateParam) -> Self {
+        self.params.insert(name.into(), kind);
+        self
+    }
+
+    /// 检查模板本身，返回未声明的占位符
+    pub(crate) fn undeclared(&self) -> Vec<String> {
+        let mut undeclared = Vec::new();
+        for arg in &self.args {
+            for segment in segments(arg) {
+                if let Segment::Placeholder(name) = segment {
+                    if !self.params.contains_key(name) && !undeclared.iter().any(|n| n == name) {
+                        undeclared.push(name.to_string());
+                    }
+                }
+            }
+        }
+        undeclared
+    }
+
+    /// 校验参数并生成命令参数
+    pub(crate) fn render(&self, params: &HashMap<String, Value>) -> Result<Vec<String>> {
+        if let Some(name) = params.keys().find(|name| !self.params.contains_key(*name)) {
+            return Err(Error::InvalidArgument(format!(
+                "Unknown template parameter `{name}`"
+            )));
+        }
+
+        let mut values = HashMap::new();
+        for (name, kind) in &self.params {
+            let value = params.get(name).ok_or_else(|| {
+                Error::InvalidArgument(format!("Missing template parameter `{name}`"))
+            })?;
+            values.insert(name.as_str(), validate(name, kind, value)?);
+        }
+
+        Ok(self
+            .args
+            .iter()
+            .map(|arg| {
+                segments(arg)
+                    .into_iter()
+                    .map(|segment| match segment {
+                        Segment::Literal(text) => text,
+                        Segment::Placeholder(name) => values[name].clone(),
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// 将参数拆分为字面文本和占位符
+fn segments(arg: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = arg;
+
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            literal.push(c);
+            rest = &rest[2..];
+        } else if c == '{' {
+            match rest.find('}') {
+                Some(end) => {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(&rest[1..end]));
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    literal.push_str(rest);
+                    rest = "";
+                }
+            }
+        } else {
+            literal.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    segments
+}
+
+/// 校验单个参数，返回替换到占位符中的文本
+fn validate(name: &str, kind: &TemplateParam, value: &Value) -> Result<String> {
+    let invalid =
+        |reason: &str| Error::InvalidArgument(format!("Template parameter `{name}` {reason}"));
+
+    match kind {
+        TemplateParam::Path => {
+            let path = value.as_str().ok_or_else(|| invalid("must be a string"))?;
+            if path.is_empty() || path.starts_with('-') {
+                return Err(invalid("must be a file path"));
+            }
+            // 与 FFmpeg 判断协议的方式相同，`subfile,,start,0,:` 等无法确定协议的前缀同样拒绝
+            if target_protocol(path) != Some("file") {
+                return Err(invalid("must be a local file path, not a URL or protocol"));
+            }
+            Ok(path.to_string())
+        }
+        TemplateParam::Number { min, max } => {
+            let number = value
+                .as_f64()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| invalid("must be a number"))?;
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                return Err(invalid("is out of range"));
+            }
+            Ok(number.to_string())
+        }
+        TemplateParam::Integer { min, max } => {
+            let number = value
+                .as_i64()
+                .ok_or_else(|| invalid("must be an integer"))?;
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                return Err(invalid("is out of range"));
+            }
+            Ok(number.to_string())
+        }
+        TemplateParam::OneOf(allowed) => {
+            let text = value.as_str().ok_or_else(|| invalid("must be a string"))?;
+            if !allowed.iter().any(|allowed| allowed == text) {
+                return Err(invalid(&format!("must be one of {}", allowed.join(", "))));
+            }
+            Ok(text.to_string())
+        }
+        TemplateParam::Text => {
+            let text = value.as_str().ok_or_else(|| invalid("must be a string"))?;
+            let allowed = |c: char| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.');
+            if text.len() > MAX_TEXT_LEN || text.starts_with('-') || !text.chars().all(allowed) {
+                return Err(invalid(
+                    "may only contain letters, digits, spaces, `_`, `-` and `.`",
+                ));
+            }
+            Ok(text.to_string())
+        }
+    }
+}
+
+impl<R: Runtime> Ffmpeg<R> {
+    /// 以经过校验的参数运行注册的命令模板
+    ///
+    /// 未注册的模板名称、未声明或缺少的参数、类型不符的参数都返回 [`Error::InvalidArgument`]。
+    pub async fn run_template(&self, request: RunTemplateRequest) -> Result<ExecuteResponse> {
+        let template = self.template(&request.name).ok_or_else(|| {
+            Error::InvalidArgument(format!("Unknown command template `{}`", request.name))
+        })?;
+        let args = template.render(&request.params)?;
+
+        self.run_helper(args, &request.job).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn template() -> CommandTemplate {
+        CommandTemplate::new([
+            "-ss",
+            "{time}",
+            "-i",
+            "{input}",
+            "-c:v",
+            "{codec}",
+            "-vf",
+            "scale={width}:-2",
+            "{output}",
+        ])
+        .param(
+            "time",
+            TemplateParam::Number {
+                min: Some(0.0),
+                max: None,
+            },
+        )
+        .param("input", TemplateParam::Path)
+        .param(
+            "codec",
+            TemplateParam::OneOf(vec!["libx264".into(), "libx265".into()]),
+        )
+        .param(
+            "width",
+            TemplateParam::Integer {
+                min: Some(16),
+                max: Some(7680),
+            },
+        )
+        .param("output", TemplateParam::Path)
+    }
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn valid() -> Value {
+        json!({
+            "time": 1.5,
+            "input": "in.mp4",
+            "codec": "libx264",
+            "width": 1280,
+            "output": "C:\\Videos\\out.mp4",
+        })
+    }
+
+    fn render_with(key: &str, value: Value) -> Result<Vec<String>> {
+        let mut params = params(valid());
+        params.insert(key.to_string(), value);
+        template().render(&params)
+    }
+
+    fn is_rejected(result: Result<Vec<String>>) -> bool {
+        matches!(result, Err(Error::InvalidArgument(_)))
+    }
+
+    #[test]
+    fn params_are_substituted() {
+        assert_eq!(
+            template().render(&params(valid())).unwrap(),
+            [
+                "-ss",
+                "1.5",
+                "-i",
+                "in.mp4",
+                "-c:v",
+                "libx264",
+                "-vf",
+                "scale=1280:-2",
+                "C:\\Videos\\out.mp4",
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_braces_are_kept() {
+        let template = CommandTemplate::new(["-metadata", "comment={{{text}}}"])
+            .param("text", TemplateParam::Text);
+        assert_eq!(
+            template
+                .render(&params(json!({ "text": "hello" })))
+                .unwrap(),
+            ["-metadata", "comment={hello}"]
+        );
+        assert_eq!(CommandTemplate::new(["{a}", "{{b}}"]).undeclared(), ["a"]);
+    }
+
+    #[test]
+    fn missing_and_extra_params_are_rejected() {
+        let mut missing = params(valid());
+        missing.remove("input");
+        assert!(is_rejected(template().render(&missing)));
+        assert!(is_rejected(render_with("extra", json!("x"))));
+    }
+
+    #[test]
+    fn paths_must_be_local_files() {
+        assert!(render_with("input", json!("file:My Video: 1.mp4")).is_ok());
+        assert!(render_with("input", json!("./a:b.mp4")).is_ok());
+        for path in [
+            "",
+            "-f",
+            "-",
+            "http://internal/a.mp4",
+            "concat:a.mp4|b.mp4",
+            "pipe:0",
+            "subfile,,start,0,end,0,,:http://internal/",
+            "[f=flv]rtmp://internal/x",
+            "a.mp4|rtmp://internal/x",
+        ] {
+            assert!(is_rejected(render_with("input", json!(path))), "{path}");
+        }
+        assert!(is_rejected(render_with("input", json!(1))));
+    }
+
+    #[test]
+    fn numbers_are_range_checked() {
+        assert!(render_with("time", json!(0)).is_ok());
+        assert!(is_rejected(render_with("time", json!(-1))));
+        assert!(is_rejected(render_with("time", json!("1"))));
+        assert!(is_rejected(render_with("width", json!(8))));
+        assert!(is_rejected(render_with("width", json!(1280.5))));
+    }
+
+    #[test]
+    fn enums_only_accept_listed_values() {
+        assert!(render_with("codec", json!("libx265")).is_ok());
+        assert!(is_rejected(render_with("codec", json!("libx264 -f"))));
+        assert!(is_rejected(render_with("codec", json!(["libx264"]))));
+    }
+
+    #[test]
+    fn text_is_restricted() {
+        let template = CommandTemplate::new(["-metadata", "title={title}"])
+            .param("title", TemplateParam::Text);
+        let render = |title: &str| template.render(&params(json!({ "title": title })));
+        assert!(render("My video 1.0").is_ok());
+        for title in ["-y", "a:b", "a,b", "a'b", &"a".repeat(MAX_TEXT_LEN + 1)] {
+            assert!(is_rejected(render(title)), "{title}");
+        }
+    }
+}
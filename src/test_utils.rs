@@ -0,0 +1,364 @@
+//! # Test utils
+//!
+//! 端到端测试工具，需要启用 `test-utils` feature。
+//!
+//! [`FakeServer`] 在本机启动一个 HTTP 服务器，提供一个假的 FFmpeg 压缩包，其中的可执行文件是一个
+//! shell 脚本（[`stub_script`]），因此可以在不联网、不安装真实 FFmpeg 的情况下测试下载、解压和执行的完整流程。
+//! [`mock_app`] 创建使用 Tauri 模拟运行时并注册了本插件的应用。下游应用也可以用它测试自己基于插件的逻辑。
+//!
+//! 假的可执行文件依赖 `/bin/sh`，只能在 Unix 系统上运行。
+//!
+//! ```rust,ignore
+//! use tauri_plugin_use_ffmpeg::test_utils::{mock_app, FakeServer};
+//! use tauri_plugin_use_ffmpeg::{DownloadRequest, FfmpegExt};
+//!
+//! let server = FakeServer::start().unwrap();
+//! let app = mock_app();
+//! tauri::async_runtime::block_on(async {
+//!     app.ffmpeg()
+//!         .download(DownloadRequest {
+//!             config: Some(server.download_config()),
+//!             ..Default::default()
+//!         })
+//!         .await
+//!         .unwrap();
+//!     assert!(app.ffmpeg().check(Default::default()).await.unwrap().available);
+//! });
+//! ```
+
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Once};
+
+use sha2::{Digest, Sha256};
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::App;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::config::Config;
+use crate::models::DownloadConfig;
+use crate::Builder;
+
+/// 假的可执行文件 `-version` 输出的第一行（ffmpeg）
+pub const STUB_VERSION: &str = "ffmpeg version n0.0-stub Copyright (c) the test harness";
+
+/// 假压缩包在服务器上的路径
+pub const ARCHIVE_PATH: &str = "/ffmpeg.zip";
+
+/// 假的可执行文件的参数为该值时以非零状态退出
+pub const FAIL_ARG: &str = "stub-fail";
+
+/// 测试应用的数据目录都在该目录下
+static INIT_ENV: Once = Once::new();
+
+/// 假的 ffmpeg / ffprobe
+///
+/// - `-version` 输出 `<工具名> version n0.0-stub ...`
+/// - `-hide_banner -formats` 输出与 FFmpeg 7 格式相同的三个封装格式
+/// - `-hide_banner -protocols` 输出输入协议 `file`、`https` 和输出协议 `file`
+/// - `-hide_banner -pix_fmts`、`-hide_banner -sample_fmts` 各输出两个格式
+/// - `-hide_banner -h filter=eq` 输出 `eq` 滤镜的部分选项，其他滤镜输出 `Unknown filter`
+/// - 任一参数为 [`FAIL_ARG`] 时向 stderr 输出错误并以状态 1 退出
+/// - 否则向 stderr 输出时长和一行进度，并在最后一个参数是文件路径时写入 `stub output`
+pub fn stub_script() -> String {
+    format!(
+        r#"#!/bin/sh
+name=$(basename "$0")
+if [ "$1" = "-version" ]; then
+  echo "$name version n0.0-stub Copyright (c) the test harness"
+  exit 0
+fi
+if [ "$1" = "-hide_banner" ] && [ "$2" = "-formats" ]; then
+  cat <<'EOF'
+File formats:
+ D.. = Demuxing supported
+ .E. = Muxing supported
+ ..d = Is a device
+ ---
+ D   matroska,webm   Matroska / WebM
+  E  mp4             MP4 (MPEG-4 Part 14)
+ DEd lavfi           Libavfilter virtual input device
+EOF
+  exit 0
+fi
+if [ "$1" = "-hide_banner" ] && [ "$2" = "-protocols" ]; then
+  printf 'Supported file protocols:\nInput:\n  file\n  https\nOutput:\n  file\n'
+  exit 0
+fi
+if [ "$1" = "-hide_banner" ] && [ "$2" = "-pix_fmts" ]; then
+  cat <<'EOF'
+Pixel formats:
+I.... = Supported Input  format for conversion
+.O... = Supported Output format for conversion
+..H.. = Hardware accelerated format
+...P. = Paletted format
+....B = Bitstream format
+FLAGS NAME            NB_COMPONENTS BITS_PER_PIXEL BIT_DEPTHS
+-----
+IO... yuv420p10le            3             15      10-10-10
+..H.. cuda                   0              0      0
+EOF
+  exit 0
+fi
+if [ "$1" = "-hide_banner" ] && [ "$2" = "-sample_fmts" ]; then
+  printf 'name   depth\ns16      16 \nfltp     32 \n'
+  exit 0
+fi
+if [ "$1" = "-hide_banner" ] && [ "$2" = "-h" ]; then
+  if [ "$3" != "filter=eq" ]; then
+    echo "Unknown filter '${{3#filter=}}'."
+    exit 0
+  fi
+  cat <<'EOF'
+Filter eq
+  Adjust brightness, contrast, gamma, and saturation.
+    Inputs:
+       #0: default (video)
+    Outputs:
+       #0: default (video)
+eq AVOptions:
+   contrast          <string>     ..FV.....T. set the contrast adjustment, negative values give a negative image (default "1.0")
+   gamma_weight      <float>      ..FV....... set weight (from 0 to 1) (default 1)
+   eval              <int>        ..FV....... specify when to evaluate expressions (from 0 to 1) (default init)
+     init            0            ..FV....... eval expressions once during initialization
+     frame           1            ..FV....... eval expressions per-frame
+
+This filter has support for timeline through the 'enable' option.
+EOF
+  exit 0
+fi
+last=""
+for arg in "$@"; do
+  if [ "$arg" = "{FAIL_ARG}" ]; then
+    echo "Stub failure requested" >&2
+    exit 1
+  fi
+  last="$arg"
+done
+echo "  Duration: 00:00:01.00, start: 0.000000, bitrate: 8 kb/s" >&2
+echo "frame=   25 fps=0.0 q=-1.0 size=       1kB time=00:00:01.00 bitrate=   8.0kbits/s speed=  10x" >&2
+case "$last" in
+  ""|-*|*:*) ;;
+  *) printf 'stub output' > "$last" ;;
+esac
+exit 0
+"#
+    )
+}
+
+/// 与 BtbN 构建结构相同的压缩包：`bin/ffmpeg` 和 `bin/ffprobe` 均为 [`stub_script`]
+pub fn stub_archive() -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .unix_permissions(0o755);
+    let script = stub_script();
+    for name in ["bin/ffmpeg", "bin/ffprobe"] {
+        zip.start_file(name, options).expect("write stub archive");
+        zip.write_all(script.as_bytes())
+            .expect("write stub archive");
+    }
+    zip.finish().expect("write stub archive").into_inner()
+}
+
+/// 与 johnvansickle.com 的 Linux 构建结构相同的 tar.xz：目录中的 `ffmpeg` 和 `ffprobe` 为 [`stub_script`]，
+/// 另有 `GPLv3.txt` 和 `manpages/ffmpeg.1`
+pub fn stub_tar_xz() -> Vec<u8> {
+    let mut tar = tar::Builder::new(xz2::write::XzEncoder::new(Vec::new(), 6));
+    let script = stub_script();
+    for (name, data, mode) in [
+        ("ffmpeg-0.0-amd64-static/GPLv3.txt", "license", 0o644),
+        ("ffmpeg-0.0-amd64-static/manpages/ffmpeg.1", "manual", 0o644),
+        ("ffmpeg-0.0-amd64-static/ffmpeg", script.as_str(), 0o755),
+        ("ffmpeg-0.0-amd64-static/ffprobe", script.as_str(), 0o755),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        tar.append_data(&mut header, name, data.as_bytes())
+            .expect("write stub archive");
+    }
+    tar.into_inner()
+        .and_then(|encoder| encoder.finish())
+        .expect("write stub archive")
+}
+
+/// 数据的 SHA-256（小写十六进制）
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// 本机的假下载服务器，被释放时停止
+///
+/// 支持插件下载时使用的 `HEAD` 和 `Range: bytes=0-0` 请求，未登记的路径返回 404。
+pub struct FakeServer {
+    port: u16,
+    requests: Arc<Mutex<HashMap<String, usize>>>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl FakeServer {
+    /// 启动服务器，在 [`ARCHIVE_PATH`] 提供 [`stub_archive`]
+    pub fn start() -> std::io::Result<Self> {
+        Self::with_files([(ARCHIVE_PATH.to_string(), stub_archive())])
+    }
+
+    /// 启动服务器，提供指定的文件（如 `("/error.zip", b"<html>...".to_vec())`）
+    pub fn with_files(files: impl IntoIterator<Item = (String, Vec<u8>)>) -> std::io::Result<Self> {
+        let files: Arc<HashMap<String, Vec<u8>>> = Arc::new(files.into_iter().collect());
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+        let requests = Arc::new(Mutex::new(HashMap::new()));
+
+        let counter = requests.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            let Ok(listener) = tokio::net::TcpListener::from_std(listener) else {
+                return;
+            };
+            while let Ok((stream, _)) = listener.accept().await {
+                let files = files.clone();
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, &files, &counter).await;
+                });
+            }
+        });
+
+        Ok(Self {
+            port,
+            requests,
+            task,
+        })
+    }
+
+    /// 服务器上某个路径的完整地址
+    pub fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{path}", self.port)
+    }
+
+    /// 下载 [`ARCHIVE_PATH`] 的配置，带有正确的 SHA-256
+    pub fn download_config(&self) -> DownloadConfig {
+        DownloadConfig {
+            url: self.url(ARCHIVE_PATH),
+            executable_path: "bin/ffmpeg".to_string(),
+            tool: None,
+            patches: Vec::new(),
+            sha256: Some(sha256_hex(&stub_archive())),
+        }
+    }
+
+    /// 某个路径收到的请求数（包括 `HEAD`）
+    pub fn requests(&self, path: &str) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .get(path)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 处理一个连接上的一个请求
+async fn serve(
+    mut stream: TcpStream,
+    files: &HashMap<String, Vec<u8>>,
+    requests: &Mutex<HashMap<String, usize>>,
+) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let ranged = lines.any(|line| line.eq_ignore_ascii_case("range: bytes=0-0"));
+    *requests.lock().unwrap().entry(path.clone()).or_default() += 1;
+
+    let (status, headers, body): (&str, String, &[u8]) = match files.get(&path) {
+        None => ("404 Not Found", "Content-Length: 0\r\n".to_string(), &[]),
+        Some(data) if ranged && !data.is_empty() => (
+            "206 Partial Content",
+            format!(
+                "Content-Length: 1\r\nContent-Range: bytes 0-0/{}\r\n",
+                data.len()
+            ),
+            &data[..1],
+        ),
+        Some(data) => (
+            "200 OK",
+            format!("Content-Length: {}\r\n", data.len()),
+            data.as_slice(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\n{headers}Server: use-ffmpeg-fake\r\nConnection: close\r\n\r\n"
+    );
+    stream.write_all(response.as_bytes()).await?;
+    if method != "HEAD" {
+        stream.write_all(body).await?;
+    }
+    stream.shutdown().await
+}
+
+/// 测试应用共用的根目录，首次调用时将 `HOME` 和 `XDG_*` 指向该目录，避免写入真实的用户目录
+///
+/// 环境变量对整个进程生效，每个测试应用使用不同的标识符，因此数据目录互不影响。
+pub fn test_root() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("use-ffmpeg-test-{}", std::process::id()));
+    INIT_ENV.call_once(|| {
+        let _ = std::fs::create_dir_all(&root);
+        std::env::set_var("HOME", &root);
+        for (key, dir) in [
+            ("XDG_DATA_HOME", "data"),
+            ("XDG_CACHE_HOME", "cache"),
+            ("XDG_CONFIG_HOME", "config"),
+        ] {
+            std::env::set_var(key, root.join(dir));
+        }
+    });
+    root
+}
+
+/// 创建注册了本插件的模拟应用，使用默认插件配置
+pub fn mock_app() -> App<MockRuntime> {
+    mock_app_with(Builder::new(), None)
+}
+
+/// 创建注册了本插件的模拟应用，可以指定构建器（模板、回调）和插件配置
+pub fn mock_app_with(builder: Builder<MockRuntime>, config: Option<Config>) -> App<MockRuntime> {
+    test_root();
+    let mut context = mock_context(noop_assets());
+    context.config_mut().identifier = format!("use-ffmpeg.test.{}", uuid::Uuid::new_v4());
+    if let Some(config) = config {
+        context.config_mut().plugins.0.insert(
+            "use-ffmpeg".to_string(),
+            serde_json::to_value(config).expect("serialize plugin config"),
+        );
+    }
+
+    mock_builder()
+        .plugin(builder.build())
+        .build(context)
+        .expect("failed to build mock app")
+}
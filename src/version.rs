@@ -0,0 +1,162 @@
+//! # Version
+//!
+//! 解析 FFmpeg 的版本号并按数值比较，例如 `n6.1.1`、`7.0.2-static`、`4.4.2-0ubuntu0.22.04.1`
+//! 都取前面的数字部分。从 master 分支构建的版本（BtbN 的 `N-117676-g...`、gyan.dev 的
+//! `2024-05-13-git-...`）没有发布版本号，视为比所有发布版本都新。
+
+use std::cmp::Ordering;
+
+use crate::error::{Error, Result};
+
+/// 解析后的 FFmpeg 版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FfmpegVersion {
+    /// 发布版本的主、次、修订版本号，缺少的部分为 0
+    Release([u32; 3]),
+    /// 从 master 分支构建的开发版本
+    Development,
+}
+
+impl FfmpegVersion {
+    /// 解析 `-version` 第一行中的版本号，无法识别时返回 None
+    pub(crate) fn parse(number: &str) -> Option<Self> {
+        if number.starts_with("N-") || number.contains("-git") || number.starts_with("git") {
+            return Some(Self::Development);
+        }
+        parse_release(number).map(Self::Release)
+    }
+
+    /// 是否不低于发布版本 `required`
+    pub(crate) fn is_at_least(&self, required: [u32; 3]) -> bool {
+        match self {
+            Self::Release(version) => version.cmp(&required) != Ordering::Less,
+            Self::Development => true,
+        }
+    }
+}
+
+/// 解析调用方要求的最低版本，例如 `6`、`6.0`、`n6.1.1`
+pub(crate) fn parse_required(version: &str) -> Result<[u32; 3]> {
+    parse_release(version.trim()).ok_or_else(|| {
+        Error::InvalidArgument(format!(
+            "`{version}` is not a version number, expected e.g. `6.0`"
+        ))
+    })
+}
+
+/// 取开头由 `.` 分隔的数字部分，忽略前缀 `n` 和之后的构建信息
+fn parse_release(number: &str) -> Option<[u32; 3]> {
+    let number = number.strip_prefix('n').unwrap_or(number);
+    let end = number
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(number.len());
+
+    let mut parts = [0u32; 3];
+    let digits = number[..end].split('.').take_while(|part| !part.is_empty());
+    let mut count = 0;
+    for (slot, part) in parts.iter_mut().zip(digits) {
+        *slot = part.parse().ok()?;
+        count += 1;
+    }
+    (count > 0).then_some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patch::version_number;
+
+    /// `-version` 第一行中的版本号
+    fn parse(line: &str) -> Option<FfmpegVersion> {
+        FfmpegVersion::parse(version_number(line)?)
+    }
+
+    #[test]
+    fn release_builds() {
+        let cases = [
+            // BtbN 的版本号构建
+            (
+                "ffmpeg version n7.1 Copyright (c) 2000-2024 the FFmpeg developers",
+                [7, 1, 0],
+            ),
+            // evermeet.cx、johnvansickle.com
+            (
+                "ffmpeg version 7.1.1 Copyright (c) 2000-2025 the FFmpeg developers",
+                [7, 1, 1],
+            ),
+            (
+                "ffmpeg version 7.0.2-static https://johnvansickle.com/ffmpeg/  Copyright (c) 2000-2024 the FFmpeg developers",
+                [7, 0, 2],
+            ),
+            // Ubuntu 22.04
+            (
+                "ffmpeg version 4.4.2-0ubuntu0.22.04.1 Copyright (c) 2000-2021 the FFmpeg developers",
+                [4, 4, 2],
+            ),
+            // gyan.dev 的正式版本
+            (
+                "ffmpeg version 7.1-full_build-www.gyan.dev Copyright (c) 2000-2024 the FFmpeg developers",
+                [7, 1, 0],
+            ),
+            (
+                "ffmpeg version n8.0-latest-win64-gpl-8.0 Copyright (c) 2000-2025 the FFmpeg developers",
+                [8, 0, 0],
+            ),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(
+                parse(line),
+                Some(FfmpegVersion::Release(expected)),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn development_builds() {
+        for line in [
+            "ffmpeg version N-117676-g6c3d6f2f0e-20241101 Copyright (c) 2000-2024 the FFmpeg developers",
+            "ffmpeg version 2024-05-13-git-37db0454e4-full_build-www.gyan.dev Copyright (c) 2000-2024 the FFmpeg developers",
+            "ffmpeg version git-2024-05-13-37db045 Copyright (c) 2000-2024 the FFmpeg developers",
+        ] {
+            let version = parse(line);
+            assert_eq!(version, Some(FfmpegVersion::Development), "{line}");
+            assert!(version.unwrap().is_at_least([99, 0, 0]));
+        }
+    }
+
+    #[test]
+    fn unknown_versions() {
+        assert_eq!(FfmpegVersion::parse("unknown"), None);
+        assert_eq!(parse("ffmpeg: command not found"), None);
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        let n7_1 = parse("ffmpeg version n7.1 Copyright").unwrap();
+        let v7_1_1 = parse("ffmpeg version 7.1.1 Copyright").unwrap();
+        assert!(n7_1.is_at_least(parse_required("7.1").unwrap()));
+        assert!(n7_1.is_at_least(parse_required("7").unwrap()));
+        assert!(!n7_1.is_at_least(parse_required("7.1.1").unwrap()));
+        assert!(v7_1_1.is_at_least(parse_required("n7.1").unwrap()));
+        assert!(!v7_1_1.is_at_least(parse_required("7.2").unwrap()));
+        // 按数值而不是字符串比较
+        let v10 = FfmpegVersion::parse("10.0").unwrap();
+        assert!(v10.is_at_least(parse_required("9.9.9").unwrap()));
+        assert!(!FfmpegVersion::parse("6.10")
+            .unwrap()
+            .is_at_least(parse_required("6.11").unwrap()));
+    }
+
+    #[test]
+    fn required_version_is_validated() {
+        assert_eq!(parse_required(" 6.0 ").unwrap(), [6, 0, 0]);
+        assert_eq!(parse_required("n6.1.1").unwrap(), [6, 1, 1]);
+        for version in ["", "latest", "v6", ".1"] {
+            assert!(
+                matches!(parse_required(version), Err(Error::InvalidArgument(_))),
+                "{version}"
+            );
+        }
+    }
+}
@@ -0,0 +1,449 @@
+//! 使用 `test-utils` 中的假下载服务器和假 FFmpeg 测试下载、解压、执行和删除的完整流程
+//!
+//! 运行：`cargo test --features test-utils --test end_to_end`
+
+#![cfg(unix)]
+
+use tauri_plugin_use_ffmpeg::test_utils::{
+    mock_app, mock_app_with, stub_tar_xz, test_root, FakeServer, ARCHIVE_PATH, FAIL_ARG,
+    STUB_VERSION,
+};
+use tauri_plugin_use_ffmpeg::{
+    BenchmarkRequest, DownloadConfig, DownloadRequest, Error, ExecuteRequest, ExecuteStrRequest,
+    FfmpegExt, FfmpegSource, ProbeRequest, RemoveRequest,
+};
+
+fn download_request(config: DownloadConfig) -> DownloadRequest {
+    DownloadRequest {
+        config: Some(config),
+        ..Default::default()
+    }
+}
+
+/// 从假下载服务器安装了 FFmpeg 的模拟应用
+fn installed_app(server: &FakeServer) -> tauri::App<tauri::test::MockRuntime> {
+    let app = mock_app();
+    tauri::async_runtime::block_on(
+        app.ffmpeg()
+            .download(download_request(server.download_config())),
+    )
+    .unwrap();
+    app
+}
+
+#[test]
+fn download_execute_and_remove() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        assert!(!ffmpeg.check(Default::default()).await.unwrap().available);
+
+        let response = ffmpeg
+            .download(download_request(server.download_config()))
+            .await
+            .unwrap();
+        assert!(response.success);
+        assert_eq!(response.final_url, Some(server.url(ARCHIVE_PATH)));
+        assert_eq!(response.server.as_deref(), Some("use-ffmpeg-fake"));
+
+        let check = ffmpeg.check(Default::default()).await.unwrap();
+        assert!(check.available);
+        assert_eq!(check.version.as_deref(), Some(STUB_VERSION));
+        assert!(check.tools.ffprobe.available);
+
+        let output = test_root().join(format!("{}.mp4", uuid::Uuid::new_v4()));
+        let response = ffmpeg
+            .execute(ExecuteRequest {
+                args: vec![
+                    "-i".to_string(),
+                    "input.mp4".to_string(),
+                    output.to_string_lossy().to_string(),
+                ],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(response.success);
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "stub output");
+
+        ffmpeg.remove(RemoveRequest::default()).await.unwrap();
+        assert!(!ffmpeg.check(Default::default()).await.unwrap().available);
+    });
+}
+
+#[test]
+fn ready_report_before_install() {
+    let app = mock_app();
+
+    let report = tauri::async_runtime::block_on(app.ffmpeg().ready_report());
+    assert_eq!(report.source, FfmpegSource::Managed);
+    assert_eq!(report.arch, std::env::consts::ARCH);
+    assert!(!report.install.unwrap().available);
+}
+
+#[test]
+fn installed_version_is_compared() {
+    let server = FakeServer::start().unwrap();
+    let app = installed_app(&server);
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        assert!(ffmpeg.is_at_least("0.0").await.unwrap());
+        assert!(!ffmpeg.is_at_least("6.0").await.unwrap());
+    });
+}
+
+#[test]
+fn formats_are_listed() {
+    let server = FakeServer::start().unwrap();
+    let app = installed_app(&server);
+
+    let formats = tauri::async_runtime::block_on(app.ffmpeg().list_formats()).unwrap();
+    let names: Vec<_> = formats.iter().map(|format| format.name.as_str()).collect();
+    assert_eq!(names, ["matroska,webm", "mp4", "lavfi"]);
+    assert!(formats[0].demux && !formats[0].mux);
+    assert_eq!(formats[1].description, "MP4 (MPEG-4 Part 14)");
+    assert!(formats[2].mux && formats[2].device);
+}
+
+#[test]
+fn protocols_are_listed() {
+    let server = FakeServer::start().unwrap();
+    let app = installed_app(&server);
+
+    let protocols = tauri::async_runtime::block_on(app.ffmpeg().list_protocols()).unwrap();
+    assert_eq!(protocols.input, ["file", "https"]);
+    assert_eq!(protocols.output, ["file"]);
+}
+
+#[test]
+fn pixel_and_sample_formats_are_listed() {
+    let server = FakeServer::start().unwrap();
+    let app = installed_app(&server);
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        let pix_fmts = ffmpeg.list_pix_fmts().await.unwrap();
+        assert_eq!(pix_fmts.len(), 2);
+        assert_eq!(pix_fmts[0].bit_depths, [10, 10, 10]);
+        assert!(pix_fmts[0].output && !pix_fmts[0].hardware);
+        assert!(pix_fmts[1].hardware && !pix_fmts[1].input);
+
+        let sample_fmts = ffmpeg.list_sample_fmts().await.unwrap();
+        assert_eq!(sample_fmts.len(), 2);
+        assert!(!sample_fmts[0].planar && sample_fmts[1].planar);
+        assert_eq!(sample_fmts[1].depth, 32);
+    });
+}
+
+#[test]
+fn filter_help_is_parsed() {
+    let server = FakeServer::start().unwrap();
+    let app = installed_app(&server);
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        let help = ffmpeg.filter_help("eq").await.unwrap();
+        assert!(help.timeline);
+        assert_eq!(help.options.len(), 3);
+        assert_eq!(help.options[0].default.as_deref(), Some("1.0"));
+        assert!(help.options[0].runtime);
+        assert_eq!(help.options[1].max.as_deref(), Some("1"));
+        assert_eq!(help.options[1].description, "set weight");
+        let constants: Vec<_> = help.options[2]
+            .constants
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(constants, ["init", "frame"]);
+
+        for name in ["nosuchfilter", "eq,scale"] {
+            let result = ffmpeg.filter_help(name).await;
+            assert!(
+                matches!(result, Err(Error::InvalidArgument(_))),
+                "{name}: {result:?}"
+            );
+        }
+    });
+}
+
+#[test]
+fn benchmark_reports_each_encoder() {
+    let server = FakeServer::start().unwrap();
+    let app = installed_app(&server);
+
+    let report = tauri::async_runtime::block_on(app.ffmpeg().benchmark(BenchmarkRequest {
+        encoders: vec!["libx264".to_string(), FAIL_ARG.to_string()],
+        seconds: Some(1.0),
+        ..Default::default()
+    }))
+    .unwrap();
+    assert_eq!(report.results[0].speed, Some(10.0));
+    assert!(report.results[1].error.is_some());
+    assert_eq!(report.fastest.as_deref(), Some("libx264"));
+}
+
+#[test]
+fn failing_command_is_reported() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        ffmpeg
+            .download(download_request(server.download_config()))
+            .await
+            .unwrap();
+
+        let response = ffmpeg
+            .execute(ExecuteRequest {
+                args: vec!["-i".to_string(), FAIL_ARG.to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(!response.success);
+        assert_eq!(response.exit_code, Some(1));
+        assert!(response.stderr.contains("Stub failure requested"));
+    });
+}
+
+#[test]
+fn arguments_are_validated_before_spawning() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        ffmpeg
+            .download(download_request(server.download_config()))
+            .await
+            .unwrap();
+
+        let execute = |args: &[&str]| {
+            ffmpeg.execute(ExecuteRequest {
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                ..Default::default()
+            })
+        };
+        for args in [
+            &["ffmpeg"][..],
+            &["-i input.mp4", "output.mp4"],
+            &["-i", "input.mp4\n", "output.mp4"],
+            &["-i", "input.mp4", "out\0put.mp4"],
+        ] {
+            let result = execute(args).await;
+            assert!(
+                matches!(result, Err(Error::InvalidArgument(_))),
+                "{args:?}: {result:?}"
+            );
+        }
+
+        // 开头误加的 ffmpeg 被去掉，没有输入时返回警告
+        let response = execute(&["/usr/bin/ffmpeg", "-version"]).await.unwrap();
+        assert!(response.stdout.contains(STUB_VERSION));
+        assert!(response.warnings.is_empty());
+        let output = test_root().join(format!("{}.mp4", uuid::Uuid::new_v4()));
+        let response = execute(&["ffmpeg", &output.to_string_lossy()])
+            .await
+            .unwrap();
+        assert_eq!(response.warnings.len(), 1);
+    });
+}
+
+#[test]
+fn command_line_is_split_like_a_shell() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+    let ffmpeg = app.ffmpeg();
+
+    tauri::async_runtime::block_on(async {
+        ffmpeg
+            .download(download_request(server.download_config()))
+            .await
+            .unwrap();
+
+        let output = test_root().join(format!("{} copy.mp4", uuid::Uuid::new_v4()));
+        let response = ffmpeg
+            .execute_str(ExecuteStrRequest {
+                command: format!("ffmpeg -i 'input file.mp4' \\\n  \"{}\"", output.display()),
+                execute: Default::default(),
+            })
+            .await
+            .unwrap();
+        assert!(response.success);
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "stub output");
+
+        for command in [
+            "ffmpeg -i \"unterminated.mp4",
+            "ffmpeg -i a.mp4 out.mp4 2>&1 | tee log",
+        ] {
+            let result = ffmpeg
+                .execute_str(ExecuteStrRequest {
+                    command: command.to_string(),
+                    execute: Default::default(),
+                })
+                .await;
+            assert!(
+                matches!(result, Err(Error::InvalidArgument(_))),
+                "{command}: {result:?}"
+            );
+        }
+    });
+}
+
+#[test]
+fn checksum_mismatch_is_rejected() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+
+    let result =
+        tauri::async_runtime::block_on(app.ffmpeg().download(download_request(DownloadConfig {
+            sha256: Some("0".repeat(64)),
+            ..server.download_config()
+        })));
+    assert!(matches!(result, Err(Error::Download(_))), "{result:?}");
+    let check = tauri::async_runtime::block_on(app.ffmpeg().check(Default::default()));
+    assert!(!check.unwrap().available);
+}
+
+#[test]
+fn tar_xz_is_extracted() {
+    let server = FakeServer::with_files([("/ffmpeg.tar.xz".to_string(), stub_tar_xz())]).unwrap();
+    let app = mock_app();
+
+    tauri::async_runtime::block_on(async {
+        app.ffmpeg()
+            .download(download_request(DownloadConfig {
+                url: server.url("/ffmpeg.tar.xz"),
+                executable_path: "ffmpeg".to_string(),
+                sha256: None,
+                ..server.download_config()
+            }))
+            .await
+            .unwrap();
+
+        let check = app.ffmpeg().check(Default::default()).await.unwrap();
+        assert_eq!(check.version.as_deref(), Some(STUB_VERSION));
+        assert!(check.tools.ffprobe.available);
+    });
+}
+
+#[test]
+fn empty_tools_are_rejected_before_download_starts() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tauri::Listener;
+
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+    let started = Arc::new(AtomicBool::new(false));
+    let flag = started.clone();
+    app.listen("use-ffmpeg://download-started", move |_| {
+        flag.store(true, Ordering::SeqCst);
+    });
+
+    let result = tauri::async_runtime::block_on(app.ffmpeg().download(DownloadRequest {
+        tools: Some(Vec::new()),
+        ..download_request(server.download_config())
+    }));
+    assert!(
+        matches!(result, Err(Error::InvalidArgument(_))),
+        "{result:?}"
+    );
+    assert!(!started.load(Ordering::SeqCst));
+}
+
+#[test]
+fn probe_input_is_checked_against_allowed_protocols() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app_with(
+        tauri_plugin_use_ffmpeg::Builder::new(),
+        Some(tauri_plugin_use_ffmpeg::Config {
+            allowed_protocols: Some(vec!["file".to_string()]),
+            ..Default::default()
+        }),
+    );
+
+    tauri::async_runtime::block_on(async {
+        app.ffmpeg()
+            .download(download_request(server.download_config()))
+            .await
+            .unwrap();
+
+        let result = app
+            .ffmpeg()
+            .probe(ProbeRequest {
+                input: "http://10.0.0.1/".to_string(),
+            })
+            .await;
+        assert!(
+            matches!(result, Err(Error::InvalidArgument(_))),
+            "{result:?}"
+        );
+    });
+}
+
+#[test]
+fn error_page_is_not_an_archive() {
+    let server = FakeServer::with_files([(
+        "/error.zip".to_string(),
+        b"<!DOCTYPE html><html><body>Forbidden</body></html>".to_vec(),
+    )])
+    .unwrap();
+    let app = mock_app();
+
+    let result =
+        tauri::async_runtime::block_on(app.ffmpeg().download(download_request(DownloadConfig {
+            url: server.url("/error.zip"),
+            sha256: None,
+            ..server.download_config()
+        })));
+    assert!(
+        matches!(&result, Err(Error::NotAnArchive(excerpt)) if excerpt.starts_with("<!DOCTYPE html>")),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn missing_file_fails_before_transfer() {
+    let server = FakeServer::start().unwrap();
+    let app = mock_app();
+
+    let result =
+        tauri::async_runtime::block_on(app.ffmpeg().download(download_request(DownloadConfig {
+            url: server.url("/missing.zip"),
+            ..server.download_config()
+        })));
+    assert!(matches!(result, Err(Error::Download(_))), "{result:?}");
+    // HEAD 和 Range 预检都失败后不再发送完整的 GET 请求
+    assert_eq!(server.requests("/missing.zip"), 2);
+}
+
+#[cfg(feature = "simulate")]
+#[test]
+fn simulated_job_runs_without_ffmpeg() {
+    let app = mock_app_with(
+        tauri_plugin_use_ffmpeg::Builder::new(),
+        Some(tauri_plugin_use_ffmpeg::Config {
+            simulate: true,
+            ..Default::default()
+        }),
+    );
+    let check = tauri::async_runtime::block_on(app.ffmpeg().check(Default::default()));
+    assert!(!check.unwrap().available);
+
+    let response = tauri::async_runtime::block_on(
+        app.ffmpeg().execute(ExecuteRequest {
+            args: ["-i", "input.mp4", "-t", "1", "output.mp4"]
+                .map(String::from)
+                .to_vec(),
+            ..Default::default()
+        }),
+    )
+    .unwrap();
+    assert!(response.success);
+    assert!(response.stderr.contains("time=00:00:01.00"));
+}